@@ -2,14 +2,17 @@ use anyhow::Result;
 use log::info;
 use std::time::Instant;
 
-use simpledb::rdbc::{
-    embedded::{
-        metadata::EmbeddedMetaData, resultset::EmbeddedResultSet, statement::EmbeddedStatement,
+use simpledb::{
+    query::constant::Constant,
+    rdbc::{
+        embedded::{
+            metadata::EmbeddedMetaData, resultset::EmbeddedResultSet, statement::EmbeddedStatement,
+        },
+        resultsetadapter::ResultSetAdapter,
+        resultsetmetadataadapter::DataType,
+        resultsetmetadataadapter::ResultSetMetaDataAdapter,
+        statementadapter::StatementAdapter,
     },
-    resultsetadapter::ResultSetAdapter,
-    resultsetmetadataadapter::DataType,
-    resultsetmetadataadapter::ResultSetMetaDataAdapter,
-    statementadapter::StatementAdapter,
 };
 
 fn print_record(results: &mut EmbeddedResultSet, meta: &EmbeddedMetaData) -> Result<()> {
@@ -18,6 +21,10 @@ fn print_record(results: &mut EmbeddedResultSet, meta: &EmbeddedMetaData) -> Res
         let w = meta
             .get_column_display_size(i)
             .expect("get column display size");
+        if results.is_null(fldname)? {
+            print!("{:width$} ", "NULL", width = w);
+            continue;
+        }
         match meta.get_column_type(i).expect("get column type") {
             DataType::Int16 => {
                 print!("{:width$} ", results.get_i16(fldname)?, width = w);
@@ -25,7 +32,7 @@ fn print_record(results: &mut EmbeddedResultSet, meta: &EmbeddedMetaData) -> Res
             DataType::Int32 => {
                 print!("{:width$} ", results.get_i32(fldname)?, width = w);
             }
-            DataType::Varchar => {
+            DataType::Varchar | DataType::Text => {
                 print!("{:width$} ", results.get_string(fldname)?, width = w);
             }
             DataType::Bool => {
@@ -34,6 +41,26 @@ fn print_record(results: &mut EmbeddedResultSet, meta: &EmbeddedMetaData) -> Res
             DataType::Date => {
                 print!("{:width$} ", results.get_date(fldname)?, width = w);
             }
+            DataType::Blob => {
+                let val = Constant::new_blob(results.get_blob(fldname)?);
+                print!("{:width$} ", val, width = w);
+            }
+            DataType::BigInt => {
+                print!("{:width$} ", results.get_i64(fldname)?, width = w);
+            }
+            DataType::Double => {
+                let val = Constant::new_f64(results.get_f64(fldname)?);
+                print!("{:width$} ", val, width = w);
+            }
+            DataType::Decimal => {
+                print!("{:width$} ", results.get_decimal(fldname)?, width = w);
+            }
+            DataType::Timestamp => {
+                print!("{:width$} ", results.get_timestamp(fldname)?, width = w);
+            }
+            DataType::Time => {
+                print!("{:width$} ", results.get_time(fldname)?, width = w);
+            }
         }
     }
     println!();
@@ -1,7 +1,10 @@
 use itertools::Itertools;
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
-use simpledb::repr::planrepr::{Operation, PlanRepr};
+use simpledb::{
+    materialize::recordcomparator::SortOrder,
+    repr::planrepr::{Operation, PlanRepr},
+};
 
 fn format_operation(op: Operation) -> String {
     match op {
@@ -9,23 +12,60 @@ fn format_operation(op: Operation) -> String {
             idxname: _,
             idxfldname,
             joinfld,
-        } => format!("INDEX JOIN SCAN BY {} = {}", idxfldname, joinfld),
+            join_type,
+        } => format!(
+            "INDEX {} SCAN BY {} = {}",
+            join_type.to_string().to_uppercase(),
+            idxfldname,
+            joinfld
+        ),
         Operation::IndexSelectScan {
             idxname: _,
             idxfldname,
             val,
         } => format!("INDEX SELECT SCAN BY {} = {}", idxfldname, val),
+        Operation::IndexRangeScan {
+            idxname: _,
+            idxfldname,
+            ranges,
+        } => format!(
+            "INDEX RANGE SCAN BY {} IN {}",
+            idxfldname,
+            ranges.iter().join(" or ")
+        ),
         Operation::GroupByScan {
             fields: _,
             aggfns: _,
         } => format!("GROUP BY",),
         Operation::Materialize => format!("MATERIALIZE"),
-        Operation::MergeJoinScan { fldname1, fldname2 } => {
-            format!("MERGE JOIN SCAN BY {} = {}", fldname1, fldname2)
-        }
-        Operation::SortScan { compflds } => format!("SORT SCAN BY ({})", compflds.iter().join(",")),
+        Operation::MergeJoinScan {
+            fldname1,
+            fldname2,
+            join_type,
+        } => format!(
+            "MERGE {} SCAN BY {} = {}",
+            join_type.to_string().to_uppercase(),
+            fldname1,
+            fldname2
+        ),
+        Operation::SortScan { compflds, orders } => format!(
+            "SORT SCAN BY ({})",
+            compflds
+                .iter()
+                .zip(orders.iter())
+                .map(|(fldname, order)| match order {
+                    SortOrder::Asc => fldname.to_string(),
+                    SortOrder::Desc => format!("{} DESC", fldname),
+                })
+                .join(",")
+        ),
         Operation::MultibufferProductScan => format!("MULTIBUFFER PRODUCT SCAN"),
         Operation::ProductScan => format!("PRODUCT SCAN"),
+        Operation::ProductJoinScan { join_type, pred } => format!(
+            "PRODUCT {} SCAN ON {}",
+            join_type.to_string().to_uppercase(),
+            pred
+        ),
         Operation::ProjectScan => format!("PROJECT SCAN"),
         Operation::SelectScan { pred: _ } => format!("SELECT SCAN"),
         Operation::TableScan { tblname: _ } => format!("TABLE SCAN"),
@@ -38,12 +78,18 @@ fn format_name(op: Operation) -> String {
             idxname,
             idxfldname: _,
             joinfld: _,
+            join_type: _,
         } => format!("{}", idxname),
         Operation::IndexSelectScan {
             idxname,
             idxfldname: _,
             val: _,
         } => format!("{}", idxname),
+        Operation::IndexRangeScan {
+            idxname,
+            idxfldname: _,
+            ranges: _,
+        } => format!("{}", idxname),
         Operation::GroupByScan {
             fields: _,
             aggfns: _,
@@ -52,10 +98,18 @@ fn format_name(op: Operation) -> String {
         Operation::MergeJoinScan {
             fldname1: _,
             fldname2: _,
+            join_type: _,
+        } => format!(""),
+        Operation::SortScan {
+            compflds: _,
+            orders: _,
         } => format!(""),
-        Operation::SortScan { compflds: _ } => format!(""),
         Operation::MultibufferProductScan => format!(""),
         Operation::ProductScan => format!(""),
+        Operation::ProductJoinScan {
+            join_type: _,
+            pred: _,
+        } => format!(""),
         Operation::ProjectScan => format!(""),
         Operation::SelectScan { pred: _ } => format!(""),
         Operation::TableScan { tblname } => format!("{}", tblname),
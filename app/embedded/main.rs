@@ -53,6 +53,15 @@ struct Opt {
 		possible_values = &config::QueryPlanner::variants(),
 		case_insensitive = true)]
     query_planner: config::QueryPlanner,
+
+    #[structopt(long,
+		default_value("Locking"),
+		possible_values = &config::ConcurrencyMgr::variants(),
+		case_insensitive = true)]
+    concurrency_mgr: config::ConcurrencyMgr,
+
+    #[structopt(long)]
+    no_autocommit: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +74,9 @@ struct Config {
     buffer_size: usize,
     buffer_manager: config::BufferMgr,
     query_planner: config::QueryPlanner,
+    concurrency_mgr: config::ConcurrencyMgr,
+
+    autocommit: bool,
 }
 
 impl Config {
@@ -78,6 +90,9 @@ impl Config {
             buffer_size: opt.buffer_size,
             buffer_manager: opt.buffer_manager,
             query_planner: opt.query_planner,
+            concurrency_mgr: opt.concurrency_mgr,
+
+            autocommit: !opt.no_autocommit,
         }
     }
 }
@@ -143,17 +158,24 @@ fn main() {
         num_of_buffers: cfg.buffer_size,
         buffer_manager: cfg.buffer_manager,
         query_planner: cfg.query_planner,
+        concurrency_mgr: cfg.concurrency_mgr,
     };
     info!("database config:");
     info!("      block size: {}", db_config.block_size);
     info!("   num of buffer: {}", db_config.num_of_buffers);
     info!("  buffer manager: {:?}", db_config.buffer_manager);
     info!("   query planner: {:?}", db_config.query_planner);
+    info!("     concurrency: {:?}", db_config.concurrency_mgr);
     let drvr = EmbeddedDriver::new(db_config);
     let mut conn = drvr.connect(&cfg.dbpath).unwrap_or_else(|_| {
         println!("couldn't connect database.");
         process::exit(1);
     });
+    info!("      autocommit: {}", cfg.autocommit);
+    conn.set_autocommit(cfg.autocommit).unwrap_or_else(|_| {
+        println!("couldn't set autocommit.");
+        process::exit(1);
+    });
 
     while let Ok(qry) = read_query() {
         exec(&mut conn, &qry.trim());
@@ -16,8 +16,8 @@ pub fn print_table_schema(
         schema.fields().len()
     );
 
-    println!(" #   name             type");
-    println!("--------------------------------------");
+    println!(" #   name             type             null");
+    println!("-------------------------------------------------");
     for (i, fldname) in schema.fields().iter().enumerate() {
         let fldtyp = match schema.field_type(fldname) {
             FieldType::SMALLINT => "smallint".to_string(),
@@ -25,8 +25,24 @@ pub fn print_table_schema(
             FieldType::VARCHAR => format!("varchar({})", schema.length(fldname)),
             FieldType::BOOL => "bool".to_string(),
             FieldType::DATE => "date".to_string(),
+            FieldType::TEXT => "text".to_string(),
+            FieldType::BLOB => "blob".to_string(),
+            FieldType::BIGINT => "bigint".to_string(),
+            FieldType::DOUBLE => "double".to_string(),
+            FieldType::DECIMAL => format!(
+                "decimal({}, {})",
+                schema.length(fldname),
+                schema.scale(fldname)
+            ),
+            FieldType::TIMESTAMP => "timestamp".to_string(),
+            FieldType::TIME => "time".to_string(),
         };
-        println!("{:>4} {:16} {:16}", i + 1, fldname, fldtyp);
+        let null = if schema.is_nullable(fldname) {
+            ""
+        } else {
+            "not null"
+        };
+        println!("{:>4} {:16} {:16} {}", i + 1, fldname, fldtyp, null);
     }
     println!();
 
@@ -59,6 +59,9 @@ struct Opt {
 
     #[structopt(short = "V", long = "version")]
     version: bool,
+
+    #[structopt(long)]
+    no_autocommit: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +69,7 @@ struct Config {
     addr: SocketAddr,
     dbname: String,
     version: bool,
+    autocommit: bool,
 }
 impl Config {
     pub fn new(opt: Opt) -> Self {
@@ -79,6 +83,7 @@ impl Config {
             addr,
             dbname: opt.dbname,
             version: opt.version,
+            autocommit: !opt.no_autocommit,
         }
     }
 }
@@ -134,6 +139,10 @@ async fn try_main<'a>(cfg: Config) -> Result<(), Box<dyn std::error::Error>> {
         println!("couldn't connect database.");
         process::exit(1);
     });
+    if conn.set_autocommit(cfg.autocommit).await.is_err() {
+        println!("couldn't set autocommit.");
+        process::exit(1);
+    }
 
     while let Ok(qry) = read_query(&cfg) {
         exec(&mut conn, &qry.trim()).await;
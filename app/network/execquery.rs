@@ -2,14 +2,17 @@ use anyhow::Result;
 use log::{info, warn};
 use std::{collections::HashMap, time::Instant};
 
-use simpledb::rdbc::{
-    network::{
-        metadata::NetworkResultSetMetaData, resultset, resultset::NetworkResultSet,
-        statement::NetworkStatement,
+use simpledb::{
+    query::constant::Constant,
+    rdbc::{
+        network::{
+            metadata::NetworkResultSetMetaData, resultset, resultset::NetworkResultSet,
+            statement::NetworkStatement,
+        },
+        resultsetadapter::ResultSetAdapter,
+        resultsetmetadataadapter::ResultSetMetaDataAdapter,
+        statementadapter::StatementAdapter,
     },
-    resultsetadapter::ResultSetAdapter,
-    resultsetmetadataadapter::ResultSetMetaDataAdapter,
-    statementadapter::StatementAdapter,
 };
 
 use crate::ClientError;
@@ -38,6 +41,27 @@ fn print_record(row: HashMap<&str, resultset::Value>, meta: &NetworkResultSetMet
             resultset::Value::Date(v) => {
                 print!("{:width$} ", v.clone(), width = w);
             }
+            resultset::Value::Blob(v) => {
+                print!("{:width$} ", Constant::new_blob(v.clone()), width = w);
+            }
+            resultset::Value::Null => {
+                print!("{:width$} ", "NULL", width = w);
+            }
+            resultset::Value::Int64(v) => {
+                print!("{:width$} ", v.clone(), width = w);
+            }
+            resultset::Value::Float64(v) => {
+                print!("{:width$} ", Constant::new_f64(*v), width = w);
+            }
+            resultset::Value::Decimal(v) => {
+                print!("{:width$} ", v.clone(), width = w);
+            }
+            resultset::Value::Timestamp(v) => {
+                print!("{:width$} ", v.clone(), width = w);
+            }
+            resultset::Value::Time(v) => {
+                print!("{:width$} ", v.clone(), width = w);
+            }
         }
     }
     println!();
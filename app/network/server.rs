@@ -4,12 +4,13 @@ pub use simpledb;
 use capnp_rpc::{rpc_twoparty_capnp, twoparty, RpcSystem};
 use env_logger::Env;
 use futures::{AsyncReadExt, FutureExt};
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::{
     collections::HashMap,
     error::Error,
     net::{SocketAddr, ToSocketAddrs},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use structopt::{clap, StructOpt};
 
@@ -18,7 +19,7 @@ use simpledb::{
     server::{
         config::{self, SimpleDBConfig},
         remote::RemoteDriverImpl,
-        simpledb::SimpleDB,
+        simpledb::{SimpleDB, CHECKPOINT_LOG_RECORDS},
     },
 };
 
@@ -50,6 +51,21 @@ struct Opt {
 		possible_values = &config::QueryPlanner::variants(),
 		case_insensitive = true)]
     query_planner: config::QueryPlanner,
+
+    #[structopt(long,
+		default_value("Locking"),
+		possible_values = &config::ConcurrencyMgr::variants(),
+		case_insensitive = true)]
+    concurrency_mgr: config::ConcurrencyMgr,
+
+    // seconds between checkpoints, 0 for none
+    #[structopt(long, default_value("60"))]
+    checkpoint_interval: u64,
+
+    // log records after which a checkpoint is taken at the next commit,
+    // CHECKPOINT_LOG_RECORDS by default
+    #[structopt(long)]
+    checkpoint_log_records: Option<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +76,9 @@ struct Config {
     buffer_size: usize,
     buffer_manager: config::BufferMgr,
     query_planner: config::QueryPlanner,
+    concurrency_mgr: config::ConcurrencyMgr,
+    checkpoint_interval: u64,
+    checkpoint_log_records: i32,
 }
 
 impl Config {
@@ -77,27 +96,42 @@ impl Config {
             buffer_size: opt.buffer_size,
             buffer_manager: opt.buffer_manager,
             query_planner: opt.query_planner,
+            concurrency_mgr: opt.concurrency_mgr,
+            checkpoint_interval: opt.checkpoint_interval,
+            checkpoint_log_records: opt.checkpoint_log_records.unwrap_or(CHECKPOINT_LOG_RECORDS),
         }
     }
 }
 
 pub struct ServerImpl {
     cfg: SimpleDBConfig,
+    checkpoint_log_records: i32,
     dbs: HashMap<String, Arc<Mutex<SimpleDB>>>,
 }
 impl ServerImpl {
-    pub fn new(cfg: SimpleDBConfig) -> Self {
+    pub fn new(cfg: SimpleDBConfig, checkpoint_log_records: i32) -> Self {
         Self {
             cfg,
+            checkpoint_log_records,
             dbs: HashMap::new(),
         }
     }
+    // a checkpoint of every open database, while their transactions go on
+    pub fn checkpoint(&self) {
+        for (dbname, db) in self.dbs.iter() {
+            match db.lock().unwrap().checkpoint() {
+                Ok(()) => info!("checkpoint: {}", dbname),
+                Err(e) => warn!("checkpoint failed: {}: {}", dbname, e),
+            }
+        }
+    }
 }
 impl simpledb::server::remote::Server for ServerImpl {
     fn get_database(&mut self, dbname: &str) -> Arc<Mutex<SimpleDB>> {
         if !self.dbs.contains_key(dbname) {
             let db_path = format!("{}/{}", DB_DIR, dbname);
-            let db = SimpleDB::build_from(self.cfg.clone())(&db_path).expect("new database");
+            let mut db = SimpleDB::build_from(self.cfg.clone())(&db_path).expect("new database");
+            db.set_checkpoint_log_records(self.checkpoint_log_records);
             self.dbs
                 .insert(dbname.to_string(), Arc::new(Mutex::new(db)));
         }
@@ -132,16 +166,38 @@ async fn try_main(cfg: Config) -> Result<(), Box<dyn Error>> {
         num_of_buffers: cfg.buffer_size,
         buffer_manager: cfg.buffer_manager,
         query_planner: cfg.query_planner,
+        concurrency_mgr: cfg.concurrency_mgr,
     };
     info!("database config:");
     info!("      block size: {}", db_config.block_size);
     info!("   num of buffer: {}", db_config.num_of_buffers);
     info!("  buffer manager: {:?}", db_config.buffer_manager);
     info!("   query planner: {:?}", db_config.query_planner);
-    let srv = ServerImpl::new(db_config);
+    info!("     concurrency: {:?}", db_config.concurrency_mgr);
+    info!(
+        "      checkpoint: every {}s, {} log records",
+        cfg.checkpoint_interval, cfg.checkpoint_log_records
+    );
+    let srv = Arc::new(Mutex::new(ServerImpl::new(
+        db_config,
+        cfg.checkpoint_log_records,
+    )));
+
+    if cfg.checkpoint_interval > 0 {
+        let srv = Arc::clone(&srv);
+        let period = Duration::from_secs(cfg.checkpoint_interval);
+        tokio::task::spawn_local(async move {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                srv.lock().unwrap().checkpoint();
+            }
+        });
+    }
 
     let listener = tokio::net::TcpListener::bind(&cfg.addr).await?;
-    let driver_impl = RemoteDriverImpl::new(Arc::new(Mutex::new(srv)));
+    let driver_impl = RemoteDriverImpl::new(srv);
     let driver_client: remote_driver::Client = capnp_rpc::new_client(driver_impl);
 
     loop {
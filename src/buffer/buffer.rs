@@ -125,6 +125,14 @@ impl Buffer {
     pub fn is_modified(&self) -> bool {
         self.txnum != -1
     }
+    // my own extends
+    // forget the block without writing it, because its file has been deleted or replaced.
+    pub fn discard(&mut self) {
+        self.contents.contents().fill(0);
+        self.blk = None;
+        self.txnum = -1;
+        self.lsn = -1;
+    }
     // extends by exercise 4.14
     pub fn pinned_at(&self) -> SystemTime {
         self.pinned_at
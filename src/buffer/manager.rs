@@ -45,6 +45,19 @@ pub trait BufferMgr {
     fn flush_all(&mut self, txnum: i32) -> Result<()>;
     fn unpin(&mut self, buff: Arc<Mutex<Buffer>>) -> Result<()>;
     fn pin(&mut self, blk: &BlockId) -> Result<Arc<Mutex<Buffer>>>;
+    // my own extends
+    // write the dirty blocks of every transaction, e.g. for a checkpoint.
+    fn flush_all_txs(&mut self) -> Result<()>;
+    // my own extends
+    // write the dirty blocks of a file, whichever transaction modified them.
+    fn flush_file(&mut self, filename: &str) -> Result<()>;
+    // my own extends
+    // write a dirty block, whichever transaction modified it last.
+    fn flush_block(&mut self, blk: &BlockId) -> Result<()>;
+    // my own extends
+    // drop the cached blocks of a deleted or replaced file,
+    // so that a file with the same name is read from the disk again.
+    fn discard_file(&mut self, filename: &str);
     // extends for statistics by exercise 4.18
     fn nums_total_pinned_unpinned(&self) -> (u32, u32);
     fn buffer_cache_hit_assigned(&self) -> (u32, u32);
@@ -166,6 +166,48 @@ impl BufferMgr for FifoUPBufferMgr {
 
         return Err(From::from(BufferMgrError::BufferAbort));
     }
+    // my own extends
+    fn flush_all_txs(&mut self) -> Result<()> {
+        for buff in self.bufferpool.iter() {
+            buff.lock().unwrap().flush()?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    fn flush_file(&mut self, filename: &str) -> Result<()> {
+        for buff in self.bufferpool.iter() {
+            let mut b = buff.lock().unwrap();
+            if b.block().is_some_and(|blk| blk.file_name() == filename) {
+                b.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+    // my own extends
+    fn flush_block(&mut self, blk: &BlockId) -> Result<()> {
+        for buff in self.bufferpool.iter() {
+            let mut b = buff.lock().unwrap();
+            if b.block() == Some(blk) {
+                b.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+    // my own extends
+    fn discard_file(&mut self, filename: &str) {
+        for buff in self.bufferpool.iter() {
+            let mut b = buff.lock().unwrap();
+            // a pinned buffer is still in use, so it is left alone
+            if !b.is_pinned() && b.block().is_some_and(|blk| blk.file_name() == filename) {
+                b.discard();
+            }
+        }
+        self.assigned_block_ids
+            .retain(|_, buff| buff.lock().unwrap().block().is_some());
+    }
     // extends by exercise 4.18
     fn nums_total_pinned_unpinned(&self) -> (u32, u32) {
         (self.num_of_total_pinned, self.num_of_total_unpinned)
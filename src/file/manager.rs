@@ -152,6 +152,33 @@ impl FileMgr {
         // ceiling
         Ok((meta.len() as i32 + self.blocksize - 1) / self.blocksize)
     }
+    // my own extends
+    pub fn delete_file(&mut self, filename: &str) -> Result<()> {
+        self.open_files.remove(filename);
+
+        let path = Path::new(&self.db_directory).join(filename);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn rename_file(&mut self, from: &str, to: &str) -> Result<()> {
+        self.open_files.remove(from);
+        self.open_files.remove(to);
+
+        let path = Path::new(&self.db_directory).join(from);
+        if path.exists() {
+            fs::rename(path, Path::new(&self.db_directory).join(to))?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn exists(&self, filename: &str) -> bool {
+        Path::new(&self.db_directory).join(filename).exists()
+    }
     pub fn is_new(&self) -> bool {
         self.is_new
     }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{Datelike, NaiveDate};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use core::fmt;
 use itertools::izip;
 use std::mem;
@@ -105,6 +105,56 @@ impl Page {
             Err(From::from(PageError::BufferSizeExceeded))
         }
     }
+    // my own extends
+    pub fn get_i64(&self, offset: usize) -> Result<i64> {
+        let i64_size = mem::size_of::<i64>();
+
+        if offset + i64_size - 1 < self.bb.len() {
+            let bytes = &self.bb[offset..offset + i64_size];
+            Ok(i64::from_be_bytes((*bytes).try_into()?))
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
+    // my own extends
+    pub fn set_i64(&mut self, offset: usize, n: i64) -> Result<usize> {
+        let bytes = n.to_be_bytes();
+
+        if offset + bytes.len() - 1 < self.bb.len() {
+            for (b, added) in izip!(&mut self.bb[offset..offset + bytes.len()], &bytes) {
+                *b = *added;
+            }
+
+            Ok(offset + bytes.len())
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
+    // my own extends
+    pub fn get_f64(&self, offset: usize) -> Result<f64> {
+        let f64_size = mem::size_of::<f64>();
+
+        if offset + f64_size - 1 < self.bb.len() {
+            let bytes = &self.bb[offset..offset + f64_size];
+            Ok(f64::from_be_bytes((*bytes).try_into()?))
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
+    // my own extends
+    pub fn set_f64(&mut self, offset: usize, n: f64) -> Result<usize> {
+        let bytes = n.to_be_bytes();
+
+        if offset + bytes.len() - 1 < self.bb.len() {
+            for (b, added) in izip!(&mut self.bb[offset..offset + bytes.len()], &bytes) {
+                *b = *added;
+            }
+
+            Ok(offset + bytes.len())
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
     // extends by exercise 3.17
     pub fn get_u32(&self, offset: usize) -> Result<u32> {
         let u32_size = mem::size_of::<u32>();
@@ -152,6 +202,25 @@ impl Page {
             Err(From::from(PageError::BufferSizeExceeded))
         }
     }
+    // my own extends
+    // bytes without the length in front of them
+    pub fn get_raw_bytes(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        if offset + len <= self.bb.len() {
+            Ok(&self.bb[offset..offset + len])
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
+    // my own extends
+    pub fn set_raw_bytes(&mut self, offset: usize, b: &[u8]) -> Result<usize> {
+        if offset + b.len() <= self.bb.len() {
+            self.bb[offset..offset + b.len()].copy_from_slice(b);
+
+            Ok(offset + b.len())
+        } else {
+            Err(From::from(PageError::BufferSizeExceeded))
+        }
+    }
     pub fn get_string(&self, offset: usize) -> Result<String> {
         let bytes = self.get_bytes(offset)?;
         let s = String::from_utf8(bytes.to_vec())?;
@@ -213,4 +282,38 @@ impl Page {
         let ymd = (((d.year() as u32) << 8) + d.month() << 8) + d.day();
         self.set_u32(offset, ymd)
     }
+    // my own extends
+    // internal representation of timestamp
+    // microseconds since 1970-01-01 00:00:00: i64
+    pub fn get_timestamp(&self, offset: usize) -> Result<NaiveDateTime> {
+        self.get_i64(offset).map(|micros| {
+            DateTime::from_timestamp_micros(micros)
+                .unwrap_or_default()
+                .naive_utc()
+        })
+    }
+    // my own extends
+    // internal representation of timestamp
+    // microseconds since 1970-01-01 00:00:00: i64
+    pub fn set_timestamp(&mut self, offset: usize, ts: NaiveDateTime) -> Result<usize> {
+        self.set_i64(offset, ts.and_utc().timestamp_micros())
+    }
+    // my own extends
+    // internal representation of time
+    // microseconds since midnight: i64
+    pub fn get_time(&self, offset: usize) -> Result<NaiveTime> {
+        self.get_i64(offset).map(|micros| {
+            let secs = micros.div_euclid(1_000_000) as u32;
+            let nanos = micros.rem_euclid(1_000_000) as u32 * 1_000;
+            NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).unwrap_or_default()
+        })
+    }
+    // my own extends
+    // internal representation of time
+    // microseconds since midnight: i64
+    pub fn set_time(&mut self, offset: usize, t: NaiveTime) -> Result<usize> {
+        let micros =
+            t.num_seconds_from_midnight() as i64 * 1_000_000 + t.nanosecond() as i64 / 1_000;
+        self.set_i64(offset, micros)
+    }
 }
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use core::fmt;
 
-use crate::{query::constant::Constant, record::rid::RID};
+use crate::{
+    query::constant::Constant,
+    record::{rid::RID, schema::FieldType},
+};
 
 pub mod btree;
 pub mod hash;
@@ -11,6 +14,7 @@ pub mod query;
 #[derive(Debug)]
 pub enum IndexError {
     NoTableScan,
+    RangeScanNotSupported,
 }
 
 impl std::error::Error for IndexError {}
@@ -20,6 +24,9 @@ impl fmt::Display for IndexError {
             IndexError::NoTableScan => {
                 write!(f, "no table scan")
             }
+            IndexError::RangeScanNotSupported => {
+                write!(f, "range scan not supported")
+            }
         }
     }
 }
@@ -31,6 +38,127 @@ pub trait Index {
     fn insert(&mut self, dataval: Constant, datarid: RID) -> Result<()>;
     fn delete(&mut self, dataval: Constant, datarid: RID) -> Result<()>;
     fn close(&mut self) -> Result<()>;
+    // my own extends
+    fn before_first_range(&mut self, range: SearchRange) -> Result<()>;
+}
+
+// my own extends
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum RangeBound {
+    Included(Constant),
+    Excluded(Constant),
+    Unbounded,
+}
+
+impl RangeBound {
+    pub fn value(&self) -> Option<&Constant> {
+        match self {
+            RangeBound::Included(val) | RangeBound::Excluded(val) => Some(val),
+            RangeBound::Unbounded => None,
+        }
+    }
+    fn as_field_type(&self, fldtype: FieldType) -> Result<Self> {
+        match self {
+            RangeBound::Included(val) => val.as_field_type(fldtype).map(RangeBound::Included),
+            RangeBound::Excluded(val) => val.as_field_type(fldtype).map(RangeBound::Excluded),
+            RangeBound::Unbounded => Ok(RangeBound::Unbounded),
+        }
+    }
+}
+
+// the range of search keys for an index range scan (e.g. 2020 <= GradYear < 2022)
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SearchRange {
+    low: RangeBound,
+    high: RangeBound,
+}
+
+impl fmt::Display for SearchRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.low {
+            RangeBound::Included(val) => write!(f, "[{}, ", val)?,
+            RangeBound::Excluded(val) => write!(f, "({}, ", val)?,
+            RangeBound::Unbounded => write!(f, "(, ")?,
+        }
+        match &self.high {
+            RangeBound::Included(val) => write!(f, "{}]", val),
+            RangeBound::Excluded(val) => write!(f, "{})", val),
+            RangeBound::Unbounded => write!(f, ")"),
+        }
+    }
+}
+
+impl SearchRange {
+    pub fn new(low: RangeBound, high: RangeBound) -> Self {
+        Self { low, high }
+    }
+    pub fn new_unbounded() -> Self {
+        Self::new(RangeBound::Unbounded, RangeBound::Unbounded)
+    }
+    pub fn low(&self) -> &RangeBound {
+        &self.low
+    }
+    pub fn high(&self) -> &RangeBound {
+        &self.high
+    }
+    pub fn is_below(&self, val: &Constant) -> bool {
+        match &self.low {
+            RangeBound::Included(low) => val < low,
+            RangeBound::Excluded(low) => val <= low,
+            RangeBound::Unbounded => false,
+        }
+    }
+    pub fn is_above(&self, val: &Constant) -> bool {
+        match &self.high {
+            RangeBound::Included(high) => val > high,
+            RangeBound::Excluded(high) => val >= high,
+            RangeBound::Unbounded => false,
+        }
+    }
+    // a range of just one value, [val, val]
+    pub fn is_point(&self) -> bool {
+        match (&self.low, &self.high) {
+            (RangeBound::Included(low), RangeBound::Included(high)) => low == high,
+            _ => false,
+        }
+    }
+    pub fn contains(&self, val: &Constant) -> bool {
+        !self.is_below(val) && !self.is_above(val)
+    }
+    // narrow the low bound if the given one is tighter
+    pub fn restrict_low(&mut self, bound: RangeBound) {
+        let tighter = match (&self.low, &bound) {
+            (_, RangeBound::Unbounded) => false,
+            (RangeBound::Unbounded, _) => true,
+            (cur, new) => {
+                let (curval, newval) = (cur.value().unwrap(), new.value().unwrap());
+                newval > curval || (newval == curval && matches!(new, RangeBound::Excluded(_)))
+            }
+        };
+        if tighter {
+            self.low = bound;
+        }
+    }
+    // narrow the high bound if the given one is tighter
+    pub fn restrict_high(&mut self, bound: RangeBound) {
+        let tighter = match (&self.high, &bound) {
+            (_, RangeBound::Unbounded) => false,
+            (RangeBound::Unbounded, _) => true,
+            (cur, new) => {
+                let (curval, newval) = (cur.value().unwrap(), new.value().unwrap());
+                newval < curval || (newval == curval && matches!(new, RangeBound::Excluded(_)))
+            }
+        };
+        if tighter {
+            self.high = bound;
+        }
+    }
+    pub fn as_field_type(&self, fldtype: FieldType) -> Result<Self> {
+        Ok(Self::new(
+            self.low.as_field_type(fldtype)?,
+            self.high.as_field_type(fldtype)?,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -43,10 +171,12 @@ mod tests {
         sync::{Arc, Mutex},
     };
 
+    use super::{Index, RangeBound, SearchRange};
     use crate::{
+        index::btree::index::BTreeIndex,
         plan::{plan::Plan, tableplan::TablePlan},
         query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
-        record::schema::Schema,
+        record::{layout::Layout, rid::RID, schema::Schema},
         server::simpledb::SimpleDB,
     };
 
@@ -262,4 +392,134 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn index_range_test() -> Result<()> {
+        if Path::new("_test/index_range").exists() {
+            fs::remove_dir_all("_test/index_range")?;
+        }
+
+        let db = SimpleDB::new_with("_test/index_range", 400, 8);
+        let tx = Arc::new(Mutex::new(db.new_tx()?));
+
+        let mut sch = Schema::new();
+        sch.add_i32_field("block");
+        sch.add_i32_field("id");
+        sch.add_i32_field("dataval");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+        let mut idx = BTreeIndex::new(Arc::clone(&tx), "idx_range", layout)?;
+
+        // many leaves, and an overflow chain for the value 25
+        let mut vals = vec![];
+        for i in 0..500 {
+            let val = if i % 3 == 0 { 25 } else { (i * 7) % 50 };
+            idx.insert(Constant::I32(val), RID::new(i, 0))?;
+            vals.push(val);
+        }
+
+        let ranges = vec![
+            (
+                RangeBound::Included(Constant::I32(10)),
+                RangeBound::Excluded(Constant::I32(30)),
+            ),
+            (
+                RangeBound::Excluded(Constant::I32(25)),
+                RangeBound::Unbounded,
+            ),
+            (
+                RangeBound::Unbounded,
+                RangeBound::Included(Constant::I32(25)),
+            ),
+            (
+                RangeBound::Included(Constant::I32(25)),
+                RangeBound::Included(Constant::I32(25)),
+            ),
+            (RangeBound::Unbounded, RangeBound::Unbounded),
+            (
+                RangeBound::Excluded(Constant::I32(49)),
+                RangeBound::Unbounded,
+            ),
+        ];
+        for (low, high) in ranges {
+            let range = SearchRange::new(low, high);
+            let expected = vals
+                .iter()
+                .filter(|v| range.contains(&Constant::I32(**v)))
+                .count();
+            idx.before_first_range(range.clone())?;
+            let mut rids = vec![];
+            while idx.next() {
+                rids.push(idx.get_data_rid()?.block_number());
+            }
+            idx.close()?;
+            println!("range {}: {} records", range, rids.len());
+            assert_eq!(rids.len(), expected);
+            assert!(rids
+                .iter()
+                .all(|i| range.contains(&Constant::I32(vals[*i as usize]))));
+        }
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn phantom_test() -> Result<()> {
+        if Path::new("_test/index_phantom").exists() {
+            fs::remove_dir_all("_test/index_phantom")?;
+        }
+
+        let db = SimpleDB::new_with("_test/index_phantom", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("block");
+        sch.add_i32_field("id");
+        sch.add_i32_field("dataval");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx = Arc::new(Mutex::new(db.new_tx()?));
+        let mut idx = BTreeIndex::new(Arc::clone(&tx), "idx_phantom", Arc::clone(&layout))?;
+        for (i, val) in [10, 20, 30, 40].iter().enumerate() {
+            idx.insert(Constant::I32(*val), RID::new(i as i32, 0))?;
+        }
+        idx.close()?;
+        tx.lock().unwrap().commit()?;
+
+        let range = SearchRange::new(
+            RangeBound::Included(Constant::I32(15)),
+            RangeBound::Included(Constant::I32(35)),
+        );
+        let scan = |idx: &mut BTreeIndex| -> Result<Vec<i32>> {
+            idx.before_first_range(range.clone())?;
+            let mut rids = vec![];
+            while idx.next() {
+                rids.push(idx.get_data_rid()?.block_number());
+            }
+            idx.close()?;
+            Ok(rids)
+        };
+
+        let tx1 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut idx1 = BTreeIndex::new(Arc::clone(&tx1), "idx_phantom", Arc::clone(&layout))?;
+        let first = scan(&mut idx1)?;
+        println!("Tx 1: range {} has {:?}", range, first);
+
+        // keys outside the range can be inserted, a key in the range cannot
+        let tx2 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut idx2 = BTreeIndex::new(Arc::clone(&tx2), "idx_phantom", Arc::clone(&layout))?;
+        idx2.insert(Constant::I32(5), RID::new(4, 0))?;
+        idx2.insert(Constant::I32(50), RID::new(5, 0))?;
+        println!("Tx 2: inserted 5 and 50");
+        let result = idx2.insert(Constant::I32(25), RID::new(6, 0));
+        println!("Tx 2: insert 25: {:?}", result);
+        assert!(result.is_err());
+        idx2.close()?;
+        tx2.lock().unwrap().rollback()?;
+
+        let second = scan(&mut idx1)?;
+        println!("Tx 1: range {} has {:?}", range, second);
+        assert_eq!(first, second);
+        tx1.lock().unwrap().commit()?;
+
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use core::fmt;
+use rust_decimal::Decimal;
 use std::{
     mem,
     sync::{Arc, Mutex},
@@ -16,6 +17,7 @@ use crate::{
 #[derive(Debug)]
 pub enum BTPageError {
     NoCurrentBlockError,
+    LargeObjectKey,
 }
 
 impl std::error::Error for BTPageError {}
@@ -25,6 +27,9 @@ impl fmt::Display for BTPageError {
             BTPageError::NoCurrentBlockError => {
                 write!(f, "no current block")
             }
+            BTPageError::LargeObjectKey => {
+                write!(f, "a TEXT or BLOB value cannot be an index key")
+            }
         }
     }
 }
@@ -60,7 +65,7 @@ impl BTPage {
     }
     pub fn close(&mut self) -> Result<()> {
         if let Some(currentblk) = self.currentblk.as_mut() {
-            self.tx.lock().unwrap().unpin(currentblk)?;
+            self.tx.lock().unwrap().unpin_index_page(currentblk)?;
             self.currentblk = None;
         }
 
@@ -104,6 +109,7 @@ impl BTPage {
             let blk = self.tx.lock().unwrap().append(&currentblk.file_name())?;
             self.tx.lock().unwrap().pin(&blk)?;
             self.format(&blk, flag)?;
+            self.tx.lock().unwrap().unpin(&blk)?;
             return Ok(blk);
         }
 
@@ -150,6 +156,22 @@ impl BTPage {
                         false,
                     )?;
                 }
+                FieldType::TEXT | FieldType::BLOB => {
+                    return Err(From::from(BTPageError::LargeObjectKey));
+                }
+                FieldType::BIGINT | FieldType::DECIMAL => {
+                    tx.set_i64(blk, (pos + offset) as i32, 0, false)?;
+                }
+                FieldType::DOUBLE => {
+                    tx.set_f64(blk, (pos + offset) as i32, 0.0, false)?;
+                }
+                FieldType::TIMESTAMP => {
+                    let ts = NaiveDateTime::default(); // NOTE: default 1970-01-01 00:00:00
+                    tx.set_timestamp(blk, (pos + offset) as i32, ts, false)?;
+                }
+                FieldType::TIME => {
+                    tx.set_time(blk, (pos + offset) as i32, NaiveTime::MIN, false)?;
+                }
             }
         }
 
@@ -233,6 +255,42 @@ impl BTPage {
 
         Err(From::from(BTPageError::NoCurrentBlockError))
     }
+    // my own extends
+    fn get_i64(&self, slot: i32, fldname: &str) -> Result<i64> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            return self.tx.lock().unwrap().get_i64(currentblk, pos);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn get_f64(&self, slot: i32, fldname: &str) -> Result<f64> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            return self.tx.lock().unwrap().get_f64(currentblk, pos);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn get_timestamp(&self, slot: i32, fldname: &str) -> Result<NaiveDateTime> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            return self.tx.lock().unwrap().get_timestamp(currentblk, pos);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn get_time(&self, slot: i32, fldname: &str) -> Result<NaiveTime> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            return self.tx.lock().unwrap().get_time(currentblk, pos);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
     fn get_val(&self, slot: i32, fldname: &str) -> Result<Constant> {
         let fldtype = self.layout.schema().field_type(fldname);
         match fldtype {
@@ -241,6 +299,16 @@ impl BTPage {
             FieldType::VARCHAR => Ok(Constant::new_string(self.get_string(slot, fldname)?)),
             FieldType::BOOL => Ok(Constant::new_bool(self.get_bool(slot, fldname)?)),
             FieldType::DATE => Ok(Constant::new_date(self.get_date(slot, fldname)?)),
+            FieldType::TEXT | FieldType::BLOB => Err(From::from(BTPageError::LargeObjectKey)),
+            FieldType::BIGINT => Ok(Constant::new_i64(self.get_i64(slot, fldname)?)),
+            FieldType::DOUBLE => Ok(Constant::new_f64(self.get_f64(slot, fldname)?)),
+            FieldType::DECIMAL => {
+                let scale = self.layout.schema().scale(fldname) as u32;
+                let unscaled = self.get_i64(slot, fldname)?;
+                Ok(Constant::new_decimal(Decimal::new(unscaled, scale)))
+            }
+            FieldType::TIMESTAMP => Ok(Constant::new_timestamp(self.get_timestamp(slot, fldname)?)),
+            FieldType::TIME => Ok(Constant::new_time(self.get_time(slot, fldname)?)),
         }
     }
     fn set_i16(&mut self, slot: i32, fldname: &str, val: i16) -> Result<()> {
@@ -288,6 +356,46 @@ impl BTPage {
 
         Err(From::from(BTPageError::NoCurrentBlockError))
     }
+    // my own extends
+    fn set_i64(&mut self, slot: i32, fldname: &str, val: i64) -> Result<()> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            let mut tx = self.tx.lock().unwrap();
+            return tx.set_i64(currentblk, pos, val, true);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn set_f64(&mut self, slot: i32, fldname: &str, val: f64) -> Result<()> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            let mut tx = self.tx.lock().unwrap();
+            return tx.set_f64(currentblk, pos, val, true);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn set_timestamp(&mut self, slot: i32, fldname: &str, val: NaiveDateTime) -> Result<()> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            let mut tx = self.tx.lock().unwrap();
+            return tx.set_timestamp(currentblk, pos, val, true);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
+    // my own extends
+    fn set_time(&mut self, slot: i32, fldname: &str, val: NaiveTime) -> Result<()> {
+        if let Some(currentblk) = self.currentblk.as_ref() {
+            let pos = self.fldpos(slot, fldname);
+            let mut tx = self.tx.lock().unwrap();
+            return tx.set_time(currentblk, pos, val, true);
+        }
+
+        Err(From::from(BTPageError::NoCurrentBlockError))
+    }
     fn set_val(&mut self, slot: i32, fldname: &str, val: Constant) -> Result<()> {
         let fldtype = self.layout.schema().field_type(fldname);
         match fldtype {
@@ -296,6 +404,16 @@ impl BTPage {
             FieldType::VARCHAR => self.set_string(slot, fldname, val.as_string()?),
             FieldType::BOOL => self.set_bool(slot, fldname, val.as_bool()?),
             FieldType::DATE => self.set_date(slot, fldname, val.as_date()?),
+            FieldType::TEXT | FieldType::BLOB => Err(From::from(BTPageError::LargeObjectKey)),
+            FieldType::BIGINT => self.set_i64(slot, fldname, val.as_i64()?),
+            FieldType::DOUBLE => self.set_f64(slot, fldname, val.as_f64()?),
+            FieldType::DECIMAL => {
+                let sch = self.layout.schema();
+                let unscaled = val.as_unscaled(sch.length(fldname), sch.scale(fldname))?;
+                self.set_i64(slot, fldname, unscaled)
+            }
+            FieldType::TIMESTAMP => self.set_timestamp(slot, fldname, val.as_timestamp()?),
+            FieldType::TIME => self.set_time(slot, fldname, val.as_time()?),
         }
     }
     fn set_num_recs(&mut self, n: i32) -> Result<()> {
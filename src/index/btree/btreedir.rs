@@ -33,8 +33,7 @@ impl BTreeDir {
     pub fn search(&mut self, searchkey: &Constant) -> Result<i32> {
         let mut childblk = self.find_child_block(searchkey)?;
         while self.contents.get_flag()? > 0 {
-            self.contents.close()?;
-            self.contents = BTPage::new(Arc::clone(&self.tx), childblk, Arc::clone(&self.layout))?;
+            self.move_to(childblk)?;
             childblk = self.find_child_block(searchkey)?;
         }
 
@@ -76,11 +75,47 @@ impl BTreeDir {
         Some(DirEntry::new(splitval, newblk.number()))
     }
     pub fn find_child_block(&self, searchkey: &Constant) -> Result<BlockId> {
+        let slot = self.find_child_slot(searchkey)?;
+        let blknum = self.contents.get_child_num(slot)?;
+        Ok(BlockId::new(&self.filename, blknum))
+    }
+    // my own extends
+    // returns the first key of the leaf following the leaf that holds the searchkey.
+    pub fn next_key(&mut self, searchkey: &Constant) -> Result<Option<Constant>> {
+        let mut nextkey = None;
+        loop {
+            let slot = self.find_child_slot(searchkey)?;
+            // the lower the level, the closer the next key
+            if slot + 1 < self.contents.get_num_recs()? {
+                nextkey = Some(self.contents.get_data_val(slot + 1)?);
+            }
+            if self.contents.get_flag()? == 0 {
+                break;
+            }
+            let childblk = BlockId::new(&self.filename, self.contents.get_child_num(slot)?);
+            self.move_to(childblk)?;
+        }
+
+        Ok(nextkey)
+    }
+    // my own extends
+    // the child is locked before the parent is released,
+    // so that no split of the child is missed on the way down.
+    fn move_to(&mut self, childblk: BlockId) -> Result<()> {
+        let child = BTPage::new(Arc::clone(&self.tx), childblk, Arc::clone(&self.layout))?;
+        child.get_flag()?;
+        self.contents.close()?;
+        self.contents = child;
+
+        Ok(())
+    }
+    fn find_child_slot(&self, searchkey: &Constant) -> Result<i32> {
         let mut slot = self.contents.find_slot_before(searchkey)?;
-        if self.contents.get_data_val(slot + 1)? == *searchkey {
+        if slot + 1 < self.contents.get_num_recs()?
+            && self.contents.get_data_val(slot + 1)? == *searchkey
+        {
             slot += 1;
         }
-        let blknum = self.contents.get_child_num(slot)?;
-        Ok(BlockId::new(&self.filename, blknum))
+        Ok(slot)
     }
 }
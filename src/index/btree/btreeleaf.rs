@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 use super::{btpage::BTPage, direntry::DirEntry};
 use crate::{
     file::block_id::BlockId,
+    index::SearchRange,
     query::constant::Constant,
     record::{layout::Layout, rid::RID},
     tx::transaction::Transaction,
@@ -34,6 +35,10 @@ pub struct BTreeLeaf {
     contents: BTPage,
     currentslot: i32,
     filename: String,
+    // my own extends
+    range: Option<SearchRange>,
+    // the last key returned in the range
+    lastkey: Option<Constant>,
 }
 
 impl BTreeLeaf {
@@ -54,12 +59,32 @@ impl BTreeLeaf {
             contents,
             currentslot,
             filename,
+            range: None,
+            lastkey: None,
         })
     }
+    // my own extends
+    // a leaf that iterates over the records in the range,
+    // starting from the position of the searchkey.
+    pub fn new_with_range(
+        tx: Arc<Mutex<Transaction>>,
+        blk: BlockId,
+        layout: Arc<Layout>,
+        searchkey: Constant,
+        range: SearchRange,
+    ) -> Result<Self> {
+        let mut leaf = Self::new(tx, blk, layout, searchkey)?;
+        leaf.range = Some(range);
+
+        Ok(leaf)
+    }
     pub fn close(&mut self) -> Result<()> {
         self.contents.close()
     }
     pub fn next(&mut self) -> bool {
+        if let Some(range) = self.range.clone() {
+            return self.next_in_range(&range);
+        }
         self.currentslot += 1;
         if self.currentslot >= self.contents.get_num_recs().unwrap() {
             self.try_overflow()
@@ -73,6 +98,11 @@ impl BTreeLeaf {
         self.contents.get_data_rid(self.currentslot)
     }
     pub fn delete(&mut self, datarid: RID) -> Result<()> {
+        let key = self.lock_key(&self.searchkey);
+        self.tx
+            .lock()
+            .unwrap()
+            .x_lock_key(&self.filename, Some(&key))?;
         while self.next() {
             if self.get_data_rid()? == datarid {
                 self.contents.delete(self.currentslot)?;
@@ -82,7 +112,25 @@ impl BTreeLeaf {
 
         Err(From::from(BTreeLeafError::RIDNotFound(datarid)))
     }
-    pub fn insert(&mut self, datarid: RID) -> Option<DirEntry> {
+    // my own extends
+    // next-key locking: the key after the new one is checked, so that no range scan
+    // holding it sees a phantom, and the new key is kept locked.
+    pub fn insert(&mut self, datarid: RID) -> Result<Option<DirEntry>> {
+        let nextslot = self.currentslot + 1;
+        let nextkey = if nextslot < self.contents.get_num_recs()? {
+            Some(self.lock_key(&self.contents.get_data_val(nextslot)?))
+        } else {
+            None
+        };
+        let key = self.lock_key(&self.searchkey);
+        let mut tx = self.tx.lock().unwrap();
+        tx.instant_x_lock_key(&self.filename, nextkey.as_ref())?;
+        tx.x_lock_key(&self.filename, Some(&key))?;
+        drop(tx);
+
+        Ok(self.insert_entry(datarid))
+    }
+    fn insert_entry(&mut self, datarid: RID) -> Option<DirEntry> {
         if self.contents.get_flag().unwrap() >= 0
             && self.contents.get_data_val(0).unwrap() > self.searchkey
         {
@@ -138,6 +186,79 @@ impl BTreeLeaf {
             return Some(DirEntry::new(splitkey, newblk.number()));
         }
     }
+    // my own extends
+    pub fn search_key(&self) -> &Constant {
+        &self.searchkey
+    }
+    // my own extends
+    pub fn last_key(&self) -> Option<&Constant> {
+        self.lastkey.as_ref()
+    }
+    // my own extends
+    // the search key is locked even if it is not in the index, so that it cannot be inserted.
+    pub fn lock_search_key(&self) -> Result<()> {
+        let key = self.lock_key(&self.searchkey);
+        self.tx
+            .lock()
+            .unwrap()
+            .s_lock_key(&self.filename, Some(&key))
+    }
+    // a key in the type of the index, under which it is locked
+    fn lock_key(&self, val: &Constant) -> Constant {
+        let fldtype = self.layout.schema().field_type("dataval");
+        val.as_field_type(fldtype).unwrap_or(val.clone())
+    }
+    fn s_lock_key(&self, val: Option<&Constant>) -> Result<()> {
+        let key = val.map(|val| self.lock_key(val));
+        self.tx
+            .lock()
+            .unwrap()
+            .s_lock_key(&self.filename, key.as_ref())
+    }
+    fn next_in_range(&mut self, range: &SearchRange) -> bool {
+        loop {
+            self.currentslot += 1;
+            let numrecs = self.contents.get_num_recs().unwrap();
+            if self.currentslot >= numrecs {
+                if !self.try_range_overflow() {
+                    // the gap up to the next leaf
+                    self.s_lock_key(None).unwrap();
+                    return false;
+                }
+                continue;
+            }
+            let val = self.contents.get_data_val(self.currentslot).unwrap();
+            if !range.is_below(&val) {
+                // the keys in the range, and the next key which closes it
+                self.s_lock_key(Some(&val)).unwrap();
+            }
+            if range.is_above(&val) {
+                // the rest of this page is above the range, but the overflow blocks
+                // hold the first key which may be in the range.
+                // the gap up to the next leaf is not locked: it is above the locked key.
+                if !self.try_range_overflow() {
+                    return false;
+                }
+                continue;
+            }
+            if range.contains(&val) {
+                self.lastkey = Some(val);
+                return true;
+            }
+        }
+    }
+    fn try_range_overflow(&mut self) -> bool {
+        let flag = self.contents.get_flag().unwrap();
+        if flag < 0 {
+            return false;
+        }
+        self.contents.close().unwrap();
+        let nextblk = BlockId::new(&self.filename, flag);
+        self.contents =
+            BTPage::new(Arc::clone(&self.tx), nextblk, Arc::clone(&self.layout)).unwrap();
+        self.currentslot = -1;
+        true
+    }
     fn try_overflow(&mut self) -> bool {
         let firstkey = self.contents.get_data_val(0).unwrap();
         let flag = self.contents.get_flag().unwrap();
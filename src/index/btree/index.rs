@@ -1,11 +1,12 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
 use std::sync::{Arc, Mutex};
 
 use super::{btreedir::BTreeDir, btreeleaf::BTreeLeaf};
 use crate::{
     file::block_id::BlockId,
-    index::{btree::btpage::BTPage, Index},
+    index::{btree::btpage::BTPage, Index, RangeBound, SearchRange},
     query::constant::Constant,
     record::{
         layout::Layout,
@@ -23,6 +24,8 @@ pub struct BTreeIndex {
     leaftbl: String,
     leaf: Option<BTreeLeaf>,
     rootblk: BlockId,
+    // my own extends
+    range: Option<SearchRange>,
 }
 
 impl BTreeIndex {
@@ -33,10 +36,13 @@ impl BTreeIndex {
     ) -> Result<Self> {
         // deal with the leaves
         let leaftbl = format!("{}leaf", idxname);
-        if tx.lock().unwrap().size(&leaftbl)? == 0 {
+        // the keys are locked instead of the end of the index files,
+        // which are locked only to create them.
+        if tx.lock().unwrap().peek_size(&leaftbl)? == 0 && tx.lock().unwrap().size(&leaftbl)? == 0 {
             let blk = tx.lock().unwrap().append(&leaftbl)?;
             let mut node = BTPage::new(Arc::clone(&tx), blk.clone(), Arc::clone(&leaf_layout))?;
             node.format(&blk, -1)?;
+            node.close()?;
         }
 
         // deal with the directory
@@ -46,20 +52,13 @@ impl BTreeIndex {
         let dirtbl = format!("{}dir", idxname);
         let dir_layout = Arc::new(Layout::new(Arc::new(dirsch.clone())));
         let rootblk = BlockId::new(&dirtbl, 0);
-        if tx.lock().unwrap().size(&dirtbl)? == 0 {
+        if tx.lock().unwrap().peek_size(&dirtbl)? == 0 && tx.lock().unwrap().size(&dirtbl)? == 0 {
             // create new root block
             tx.lock().unwrap().append(&dirtbl)?;
             let mut node = BTPage::new(Arc::clone(&tx), rootblk.clone(), Arc::clone(&dir_layout))?;
             node.format(&rootblk, 0)?;
             // insert initial directory entry
-            let fldtype = dirsch.field_type("dataval");
-            let minval = match fldtype {
-                FieldType::SMALLINT => Constant::new_i16(i16::MIN),
-                FieldType::INTEGER => Constant::new_i32(i32::MIN),
-                FieldType::VARCHAR => Constant::new_string("".to_string()),
-                FieldType::BOOL => Constant::new_bool(false),
-                FieldType::DATE => Constant::new_date(NaiveDate::from_ymd_opt(0, 1, 1).unwrap()), // NOTE: default 0000-01-01
-            };
+            let minval = Self::min_value(dirsch.field_type("dataval"));
             node.insert_dir(0, minval, 0)?;
             node.close()?;
         }
@@ -71,23 +70,81 @@ impl BTreeIndex {
             leaftbl,
             leaf: None,
             rootblk,
+            range: None,
         })
     }
     pub fn search_cost(numblocks: i32, rpb: i32) -> i32 {
         1 + ((numblocks as f32).ln() / (rpb as f32).ln()) as i32
     }
-}
+    // my own extends
+    pub fn delete_files(tx: Arc<Mutex<Transaction>>, idxname: &str) -> Result<()> {
+        let mut tx = tx.lock().unwrap();
+        tx.delete_file(&format!("{}leaf", idxname))?;
+        tx.delete_file(&format!("{}dir", idxname))
+    }
+    // my own extends
+    // moves the index files aside, so that the index is rebuilt from scratch when it is opened.
+    // they are removed on commit, or moved back on rollback.
+    pub fn move_files_aside(tx: Arc<Mutex<Transaction>>, idxname: &str) -> Result<()> {
+        let mut tx = tx.lock().unwrap();
+        for filename in [format!("{}leaf", idxname), format!("{}dir", idxname)] {
+            let mut n = 0;
+            while tx.file_exists(&format!("{}~{}", filename, n)) {
+                n += 1;
+            }
+            let oldfile = format!("{}~{}", filename, n);
+            tx.rename_file(&filename, &oldfile)?;
+            tx.delete_file(&oldfile)?;
+        }
 
-impl Index for BTreeIndex {
-    fn before_first(&mut self, searchkey: Constant) -> Result<()> {
+        Ok(())
+    }
+    // my own extends
+    fn min_value(fldtype: FieldType) -> Constant {
+        match fldtype {
+            FieldType::SMALLINT => Constant::new_i16(i16::MIN),
+            FieldType::INTEGER => Constant::new_i32(i32::MIN),
+            FieldType::VARCHAR => Constant::new_string("".to_string()),
+            FieldType::BOOL => Constant::new_bool(false),
+            FieldType::DATE => Constant::new_date(NaiveDate::from_ymd_opt(0, 1, 1).unwrap()), // NOTE: default 0000-01-01
+            FieldType::TEXT => Constant::new_string("".to_string()),
+            FieldType::BLOB => Constant::new_blob(vec![]),
+            FieldType::BIGINT => Constant::new_i64(i64::MIN),
+            FieldType::DOUBLE => Constant::new_f64(f64::NEG_INFINITY),
+            FieldType::DECIMAL => Constant::new_decimal(Decimal::MIN),
+            FieldType::TIMESTAMP => Constant::new_timestamp(NaiveDateTime::MIN),
+            FieldType::TIME => Constant::new_time(NaiveTime::MIN),
+        }
+    }
+    fn open_range_leaf(&mut self, searchkey: Constant, range: SearchRange) -> Result<()> {
+        let mut root = BTreeDir::new(
+            Arc::clone(&self.tx),
+            self.rootblk.clone(),
+            Arc::clone(&self.dir_layout),
+        )?;
+        let blknum = root.search(&searchkey)?;
+        let leafblk = BlockId::new(&self.leaftbl, blknum);
+        self.leaf = BTreeLeaf::new_with_range(
+            Arc::clone(&self.tx),
+            leafblk,
+            Arc::clone(&self.leaf_layout),
+            searchkey,
+            range,
+        )
+        .ok();
+        // the leaf is locked before its parent is released
+        root.close()
+    }
+    // my own extends
+    fn open_leaf(&mut self, searchkey: Constant) -> Result<()> {
         self.close()?;
+        self.range = None;
         let mut root = BTreeDir::new(
             Arc::clone(&self.tx),
             self.rootblk.clone(),
             Arc::clone(&self.dir_layout),
         )?;
         let blknum = root.search(&searchkey)?;
-        root.close()?;
         let leafblk = BlockId::new(&self.leaftbl, blknum);
         self.leaf = BTreeLeaf::new(
             Arc::clone(&self.tx),
@@ -96,18 +153,57 @@ impl Index for BTreeIndex {
             searchkey,
         )
         .ok();
+        // the leaf is locked before its parent is released
+        root.close()
+    }
+    fn next_in_range(&mut self, mut range: SearchRange) -> Result<bool> {
+        loop {
+            if self.leaf.as_mut().unwrap().next() {
+                return Ok(true);
+            }
+            // this leaf is exhausted, so move to the next leaf
+            let leaf = self.leaf.as_ref().unwrap();
+            let searchkey = leaf.search_key().clone();
+            // the keys already returned may have been moved to the next leaf by a split
+            if let Some(lastkey) = leaf.last_key() {
+                range.restrict_low(RangeBound::Excluded(lastkey.clone()));
+                self.range = Some(range.clone());
+            }
+            let mut root = BTreeDir::new(
+                Arc::clone(&self.tx),
+                self.rootblk.clone(),
+                Arc::clone(&self.dir_layout),
+            )?;
+            let nextkey = root.next_key(&searchkey)?;
+            root.close()?;
+            match nextkey {
+                Some(nextkey) if !range.is_above(&nextkey) => {
+                    self.close()?;
+                    self.open_range_leaf(nextkey, range.clone())?;
+                }
+                _ => return Ok(false),
+            }
+        }
+    }
+}
 
-        Ok(())
+impl Index for BTreeIndex {
+    fn before_first(&mut self, searchkey: Constant) -> Result<()> {
+        self.open_leaf(searchkey)?;
+        self.leaf.as_ref().unwrap().lock_search_key()
     }
     fn next(&mut self) -> bool {
+        if let Some(range) = self.range.clone() {
+            return self.next_in_range(range).unwrap();
+        }
         self.leaf.as_mut().unwrap().next()
     }
     fn get_data_rid(&mut self) -> Result<RID> {
         self.leaf.as_mut().unwrap().get_data_rid()
     }
     fn insert(&mut self, dataval: Constant, datarid: RID) -> Result<()> {
-        self.before_first(dataval)?;
-        let dirent = self.leaf.as_mut().unwrap().insert(datarid);
+        self.open_leaf(dataval)?;
+        let dirent = self.leaf.as_mut().unwrap().insert(datarid)?;
         self.leaf.as_mut().unwrap().close()?;
         match dirent {
             None => Ok(()),
@@ -126,7 +222,7 @@ impl Index for BTreeIndex {
         }
     }
     fn delete(&mut self, dataval: Constant, datarid: RID) -> Result<()> {
-        self.before_first(dataval)?;
+        self.open_leaf(dataval)?;
         self.leaf.as_mut().unwrap().delete(datarid)?;
         self.leaf.as_mut().unwrap().close()
     }
@@ -137,4 +233,13 @@ impl Index for BTreeIndex {
 
         Ok(())
     }
+    fn before_first_range(&mut self, range: SearchRange) -> Result<()> {
+        self.close()?;
+        let searchkey = match range.low().value() {
+            Some(low) => low.clone(),
+            None => Self::min_value(self.leaf_layout.schema().field_type("dataval")),
+        };
+        self.range = Some(range.clone());
+        self.open_range_leaf(searchkey, range)
+    }
 }
@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    index::{Index, IndexError},
+    index::{Index, IndexError, SearchRange},
     query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
     record::{layout::Layout, rid::RID, tablescan::TableScan},
     tx::transaction::Transaction,
@@ -102,4 +102,8 @@ impl Index for HashIndex {
 
         Ok(())
     }
+    fn before_first_range(&mut self, _range: SearchRange) -> Result<()> {
+        // NOTE: hashed values are not ordered
+        Err(From::from(IndexError::RangeScanNotSupported))
+    }
 }
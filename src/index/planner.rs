@@ -1,3 +1,4 @@
 pub mod indexjoinplan;
+pub mod indexrangeselectplan;
 pub mod indexselectplan;
 pub mod indexupdateplanner;
@@ -6,7 +6,10 @@ use crate::{
     index::query::indexjoinscan::IndexJoinScan,
     metadata::indexmanager::IndexInfo,
     plan::plan::Plan,
-    query::scan::Scan,
+    query::{
+        join::{join_schema, JoinType},
+        scan::Scan,
+    },
     record::schema::Schema,
     repr::planrepr::{Operation, PlanRepr},
 };
@@ -33,13 +36,24 @@ pub struct IndexJoinPlan {
     ii: IndexInfo,
     joinfield: String,
     sch: Arc<Schema>,
+    // my own extends
+    join_type: JoinType,
 }
 
 impl IndexJoinPlan {
     pub fn new(p1: Arc<dyn Plan>, p2: Arc<dyn Plan>, ii: IndexInfo, joinfield: &str) -> Self {
-        let mut sch = Schema::new();
-        sch.add_all(p1.schema());
-        sch.add_all(p2.schema());
+        Self::new_outer(p1, p2, ii, joinfield, JoinType::Inner)
+    }
+    // my own extends
+    // INNER or LEFT, because only the records of p2 found by the index are visited
+    pub fn new_outer(
+        p1: Arc<dyn Plan>,
+        p2: Arc<dyn Plan>,
+        ii: IndexInfo,
+        joinfield: &str,
+        join_type: JoinType,
+    ) -> Self {
+        let sch = join_schema(p1.schema(), p2.schema(), join_type);
 
         Self {
             p1,
@@ -47,6 +61,7 @@ impl IndexJoinPlan {
             ii,
             joinfield: joinfield.to_string(),
             sch: Arc::new(sch),
+            join_type,
         }
     }
 }
@@ -56,11 +71,12 @@ impl Plan for IndexJoinPlan {
         let s = self.p1.open()?;
         // throws an exception if p2 is not a table plan
         if let Ok(ts) = self.p2.open()?.lock().unwrap().as_table_scan() {
-            let scan = IndexJoinScan::new(
+            let scan = IndexJoinScan::new_outer(
                 s,
                 self.ii.open(),
                 &self.joinfield,
                 Arc::new(Mutex::new(ts.clone())),
+                self.join_type,
             )?;
             return Ok(Arc::new(Mutex::new(scan)));
         }
@@ -73,7 +89,12 @@ impl Plan for IndexJoinPlan {
             + self.records_output()
     }
     fn records_output(&self) -> i32 {
-        self.p1.records_output() * self.ii.records_output()
+        let output = self.p1.records_output() * self.ii.records_output();
+        // my own extends
+        if self.join_type.preserves_lhs() {
+            return output.max(self.p1.records_output());
+        }
+        output
     }
     fn distinct_values(&self, fldname: &str) -> i32 {
         if self.p1.schema().has_field(fldname) {
@@ -93,6 +114,7 @@ impl Plan for IndexJoinPlan {
             idxname: self.ii.index_name().to_string(),
             idxfldname: self.ii.field_name().to_string(),
             joinfld: self.joinfield.clone(),
+            join_type: self.join_type,
             r: self.blocks_accessed(),
             w: self.records_output(),
         })
@@ -106,6 +128,7 @@ pub struct IndexJoinPlanRepr {
     idxname: String,
     idxfldname: String,
     joinfld: String,
+    join_type: JoinType,
     r: i32,
     w: i32,
 }
@@ -116,6 +139,7 @@ impl PlanRepr for IndexJoinPlanRepr {
             idxname: self.idxname.clone(),
             idxfldname: self.idxfldname.clone(),
             joinfld: self.joinfld.clone(),
+            join_type: self.join_type,
         }
     }
     fn reads(&self) -> i32 {
@@ -0,0 +1,209 @@
+use anyhow::Result;
+use core::fmt;
+use std::{
+    cmp::min,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    index::{query::indexrangeselectscan::IndexRangeSelectScan, SearchRange},
+    metadata::indexmanager::IndexInfo,
+    plan::plan::Plan,
+    query::scan::Scan,
+    record::schema::Schema,
+    repr::planrepr::{Operation, PlanRepr},
+};
+
+#[derive(Debug)]
+pub enum IndexRangeSelectPlanError {
+    DowncastError,
+}
+impl std::error::Error for IndexRangeSelectPlanError {}
+impl fmt::Display for IndexRangeSelectPlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexRangeSelectPlanError::DowncastError => {
+                write!(f, "downcast error")
+            }
+        }
+    }
+}
+
+pub struct IndexRangeSelectPlan {
+    p: Arc<dyn Plan>,
+    ii: IndexInfo,
+    // my own extends
+    // disjoint ranges in the ascending order
+    ranges: Vec<SearchRange>,
+}
+
+impl IndexRangeSelectPlan {
+    pub fn new(p: Arc<dyn Plan>, ii: IndexInfo, range: SearchRange) -> Self {
+        Self::new_with(p, ii, vec![range])
+    }
+    // my own extends
+    pub fn new_with(p: Arc<dyn Plan>, ii: IndexInfo, ranges: Vec<SearchRange>) -> Self {
+        Self { p, ii, ranges }
+    }
+}
+
+impl Plan for IndexRangeSelectPlan {
+    fn open(&self) -> Result<Arc<Mutex<dyn Scan>>> {
+        // throws an exception if p is not a table plan.
+        if let Ok(ts) = self.p.open()?.lock().unwrap().as_table_scan() {
+            // NOTE: need to convert the bounds to the correct type.
+            let fldname = self.ii.field_name();
+            let fldtype = self.ii.table_schema().field_type(fldname);
+            let ranges = self
+                .ranges
+                .iter()
+                .map(|range| range.as_field_type(fldtype))
+                .collect::<Result<Vec<_>>>()?;
+
+            let scan = IndexRangeSelectScan::new_with(
+                Arc::new(Mutex::new(ts.clone())),
+                self.ii.open(),
+                ranges,
+            )?;
+            return Ok(Arc::new(Mutex::new(scan)));
+        }
+
+        Err(From::from(IndexRangeSelectPlanError::DowncastError))
+    }
+    fn blocks_accessed(&self) -> i32 {
+        let searches = self.ranges.len() as i32;
+        self.ii.blocks_accessed().saturating_mul(searches) + self.records_output()
+    }
+    fn records_output(&self) -> i32 {
+        // a single value (e.g. of an IN list) is selected as by an equality
+        let output = self
+            .ranges
+            .iter()
+            .map(|range| {
+                if range.is_point() {
+                    self.ii.records_output()
+                } else {
+                    self.ii.range_records_output()
+                }
+            })
+            .fold(0, i32::saturating_add);
+        min(output, self.p.records_output())
+    }
+    fn distinct_values(&self, fldname: &str) -> i32 {
+        // NOTE: one at least, because it is a divisor of the reduction factor.
+        min(self.p.distinct_values(fldname), self.records_output()).max(1)
+    }
+    fn schema(&self) -> Arc<Schema> {
+        self.p.schema()
+    }
+
+    fn repr(&self) -> Arc<dyn PlanRepr> {
+        Arc::new(IndexRangeSelectPlanRepr {
+            p: self.p.repr(),
+            idxname: self.ii.index_name().to_string(),
+            idxfldname: self.ii.field_name().to_string(),
+            ranges: self.ranges.clone(),
+            r: self.blocks_accessed(),
+            w: self.records_output(),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct IndexRangeSelectPlanRepr {
+    p: Arc<dyn PlanRepr>,
+    idxname: String,
+    idxfldname: String,
+    ranges: Vec<SearchRange>,
+    r: i32,
+    w: i32,
+}
+
+impl PlanRepr for IndexRangeSelectPlanRepr {
+    fn operation(&self) -> Operation {
+        Operation::IndexRangeScan {
+            idxname: self.idxname.clone(),
+            idxfldname: self.idxfldname.clone(),
+            ranges: self.ranges.clone(),
+        }
+    }
+    fn reads(&self) -> i32 {
+        self.r
+    }
+    fn writes(&self) -> i32 {
+        self.w
+    }
+    fn sub_plan_reprs(&self) -> Vec<Arc<dyn PlanRepr>> {
+        vec![Arc::clone(&self.p)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::{
+        index::RangeBound, metadata::manager::MetadataMgr, plan::tableplan::TablePlan,
+        query::constant::Constant, query::tests, server::simpledb::SimpleDB,
+    };
+
+    #[test]
+    fn unit_test() -> Result<()> {
+        if Path::new("_test/indexrangeselectplan").exists() {
+            fs::remove_dir_all("_test/indexrangeselectplan")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/indexrangeselectplan", 400, 8);
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        let mut mdm = MetadataMgr::new(true, Arc::clone(&tx))?;
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        tests::init_sampledb(&mut mdm, Arc::clone(&tx))?;
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        let mdm = Arc::new(Mutex::new(mdm));
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        let srcplan = Arc::new(TablePlan::new(
+            "STUDENT",
+            Arc::clone(&tx),
+            Arc::clone(&mdm),
+        )?);
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        let iimap = mdm
+            .lock()
+            .unwrap()
+            .get_index_info("STUDENT", Arc::clone(&tx))?;
+        let ii = iimap.get("GradYear").unwrap().clone();
+        let p = Arc::clone(&srcplan);
+        // 2020 < GradYear <= 2022
+        let range = SearchRange::new(
+            RangeBound::Excluded(Constant::I32(2020)),
+            RangeBound::Included(Constant::I32(2022)),
+        );
+        let plan = IndexRangeSelectPlan::new(p, ii, range);
+
+        let scan = plan.open()?;
+        scan.lock().unwrap().before_first()?;
+        let mut iter = scan.lock().unwrap();
+        let mut rows = 0;
+        while iter.next() {
+            let name = iter.get_string("SName")?;
+            let year = iter.get_i32("GradYear")?;
+            println!("{:<10}{:>8}", name, year);
+            assert!(year > 2020 && year <= 2022);
+            rows += 1;
+        }
+        iter.close()?;
+        assert_eq!(rows, 5);
+        tx.lock().unwrap().commit()?;
+        assert_eq!(tx.lock().unwrap().available_buffs(), 8);
+
+        Ok(())
+    }
+}
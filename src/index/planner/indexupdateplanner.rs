@@ -6,19 +6,29 @@ use std::sync::{Arc, Mutex};
 use crate::{
     metadata::manager::MetadataMgr,
     parser::{
-        createindexdata::CreateIndexData, createtabledata::CreateTableData,
-        createviewdata::CreateViewData, deletedata::DeleteData, insertdata::InsertData,
+        altertabledata::{AlterAction, AlterTableData},
+        createindexdata::CreateIndexData,
+        createtabledata::CreateTableData,
+        createviewdata::CreateViewData,
+        deletedata::DeleteData,
+        dropdata::DropData,
+        insertdata::InsertData,
         modifydata::ModifyData,
     },
     plan::{
         plan::Plan, selectplan::SelectPlan, tableplan::TablePlan, updateplanner::UpdatePlanner,
     },
+    query::constant::Constant,
     tx::transaction::Transaction,
 };
 
 #[derive(Debug)]
 pub enum IndexUpdatePlannerError {
     DowncastError,
+    NotNullViolation(String),
+    TableNotFound(String),
+    ViewNotFound(String),
+    IndexNotFound(String),
 }
 
 impl std::error::Error for IndexUpdatePlannerError {}
@@ -28,6 +38,18 @@ impl fmt::Display for IndexUpdatePlannerError {
             IndexUpdatePlannerError::DowncastError => {
                 write!(f, "downcast error")
             }
+            IndexUpdatePlannerError::NotNullViolation(fldname) => {
+                write!(f, "null value in not null field: {}", fldname)
+            }
+            IndexUpdatePlannerError::TableNotFound(tblname) => {
+                write!(f, "table not found: {}", tblname)
+            }
+            IndexUpdatePlannerError::ViewNotFound(viewname) => {
+                write!(f, "view not found: {}", viewname)
+            }
+            IndexUpdatePlannerError::IndexNotFound(idxname) => {
+                write!(f, "index not found: {}", idxname)
+            }
         }
     }
 }
@@ -47,6 +69,16 @@ impl UpdatePlanner for IndexUpdatePlanner {
     fn execute_insert(&self, data: InsertData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
         let tblname = data.table_name();
         let p = TablePlan::new(tblname, Arc::clone(&tx), Arc::clone(&self.mdm))?;
+        // my own extends
+        // check the NOT NULL constraints before the record is inserted
+        let sch = p.schema();
+        for fldname in sch.fields() {
+            if !sch.is_nullable(fldname) && data.val(fldname).is_none_or(|v| v.is_null()) {
+                return Err(From::from(IndexUpdatePlannerError::NotNullViolation(
+                    fldname.to_string(),
+                )));
+            }
+        }
         // first, insert the record
         if let Ok(s) = p.open()?.lock().unwrap().to_update_scan() {
             s.insert()?;
@@ -60,6 +92,10 @@ impl UpdatePlanner for IndexUpdatePlanner {
                 debug!("Modify field {} to val {:?}", fldname, &val);
                 // NOTE: UpdateScan can convert val to the correct type.
                 s.set_val(fldname, val.clone())?;
+                // NOTE: NULL is not stored in the index.
+                if val.is_null() {
+                    continue;
+                }
                 if let Some(ii) = indexes.get(fldname) {
                     // NOTE: convert the type here, because Index doesn't convert val.
                     let fldtype = ii.table_schema().field_type(fldname);
@@ -70,6 +106,13 @@ impl UpdatePlanner for IndexUpdatePlanner {
                     idx.lock().unwrap().close()?;
                 }
             }
+            // my own extends
+            // the omitted fields are NULL
+            for fldname in p.schema().fields() {
+                if !data.fields().contains(fldname) {
+                    s.set_val(fldname, Constant::Null)?;
+                }
+            }
             s.close()?;
 
             return Ok(1);
@@ -80,6 +123,7 @@ impl UpdatePlanner for IndexUpdatePlanner {
     fn execute_delete(&self, data: DeleteData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
         let tblname = data.table_name();
         let tp = TablePlan::new(tblname, Arc::clone(&tx), Arc::clone(&self.mdm))?;
+        data.pred().check_types(&tp.schema())?;
         let p = SelectPlan::new(Arc::new(tp), data.pred().clone());
         let mut md = self.mdm.lock().unwrap();
         let indexes = md.get_index_info(tblname, Arc::clone(&tx))?;
@@ -92,6 +136,9 @@ impl UpdatePlanner for IndexUpdatePlanner {
                 for fldname in indexes.keys() {
                     // NOTE: UpdateScan can convert val to the correct type.
                     let val = s.get_val(fldname)?;
+                    if val.is_null() {
+                        continue;
+                    }
                     if let Some(ii) = indexes.get(fldname) {
                         // NOTE: convert the type here, because Index doesn't convert val.
                         let fldtype = ii.table_schema().field_type(fldname);
@@ -117,6 +164,12 @@ impl UpdatePlanner for IndexUpdatePlanner {
         let tblname = data.table_name();
         let fldname = data.target_field();
         let tp = TablePlan::new(tblname, Arc::clone(&tx), Arc::clone(&self.mdm))?;
+        // my own extends
+        // type checking of the new value and the predicate against the table
+        let newexpr = data.new_value().resolve(&tp.schema())?;
+        newexpr.check_assignable(&tp.schema(), fldname)?;
+        data.pred().check_types(&tp.schema())?;
+        let fldtype = tp.schema().field_type(fldname);
         let p = SelectPlan::new(Arc::new(tp), data.pred().clone());
         let mut md = self.mdm.lock().unwrap();
         let indexes = md.get_index_info(tblname, Arc::clone(&tx))?;
@@ -128,20 +181,22 @@ impl UpdatePlanner for IndexUpdatePlanner {
             while s.next() {
                 // first, update the record
                 let scan = s.to_scan()?;
-                let newval = data.new_value().evaluate(scan)?;
+                // NOTE: the value has the type of the expression, not of the field,
+                // and neither set_val nor Index converts it.
+                let newval = newexpr.evaluate(scan)?.as_field_type(fldtype)?;
                 let oldval = s.get_val(fldname)?;
-                // NOTE: UpdateScan can convert val to the correct type.
                 s.set_val(data.target_field(), newval.clone())?;
                 // then update the appropriate index, if it exists
                 if let Some(idx) = idx.as_ref() {
-                    // NOTE: convert the type here, because Index doesn't convert val.
-                    let fldtype = ii.unwrap().table_schema().field_type(fldname);
                     let oldval = oldval.as_field_type(fldtype)?;
-                    let newval = newval.as_field_type(fldtype)?;
 
                     let rid = s.get_rid()?;
-                    idx.lock().unwrap().delete(oldval, rid)?;
-                    idx.lock().unwrap().insert(newval, rid)?;
+                    if !oldval.is_null() {
+                        idx.lock().unwrap().delete(oldval, rid)?;
+                    }
+                    if !newval.is_null() {
+                        idx.lock().unwrap().insert(newval, rid)?;
+                    }
                 }
                 count += 1;
             }
@@ -161,7 +216,12 @@ impl UpdatePlanner for IndexUpdatePlanner {
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<i32> {
         let md = self.mdm.lock().unwrap();
-        md.create_table(data.table_name(), Arc::new(data.new_schema().clone()), tx)?;
+        md.create_table_with(
+            data.table_name(),
+            Arc::new(data.new_schema().clone()),
+            data.format(),
+            tx,
+        )?;
         Ok(0)
     }
     fn execute_create_view(
@@ -182,4 +242,52 @@ impl UpdatePlanner for IndexUpdatePlanner {
         md.create_index(data.index_name(), data.table_name(), data.field_name(), tx)?;
         Ok(0)
     }
+    fn execute_drop_table(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let mut md = self.mdm.lock().unwrap();
+        if !md.drop_table(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(IndexUpdatePlannerError::TableNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_drop_view(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let md = self.mdm.lock().unwrap();
+        if !md.drop_view(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(IndexUpdatePlannerError::ViewNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_drop_index(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let md = self.mdm.lock().unwrap();
+        if !md.drop_index(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(IndexUpdatePlannerError::IndexNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_alter_table(
+        &self,
+        data: AlterTableData,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<i32> {
+        let mut md = self.mdm.lock().unwrap();
+        let tblname = data.table_name();
+        let found = match data.action() {
+            AlterAction::AddField(fldname, fi) => md.add_field(tblname, fldname, *fi, tx)?,
+            AlterAction::DropField(fldname) => md.drop_field(tblname, fldname, tx)?,
+            AlterAction::RenameField(oldname, newname) => {
+                md.rename_field(tblname, oldname, newname, tx)?
+            }
+        };
+        if !found {
+            return Err(From::from(IndexUpdatePlannerError::TableNotFound(
+                tblname.to_string(),
+            )));
+        }
+        Ok(0)
+    }
 }
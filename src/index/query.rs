@@ -1,2 +1,3 @@
 pub mod indexjoinscan;
+pub mod indexrangeselectscan;
 pub mod indexselectscan;
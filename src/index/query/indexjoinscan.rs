@@ -6,13 +6,14 @@ use std::sync::{Arc, Mutex};
 use crate::{
     index::Index,
     materialize::sortscan::SortScan,
-    query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
+    query::{constant::Constant, join::JoinType, scan::Scan, updatescan::UpdateScan},
     record::tablescan::TableScan,
 };
 
 #[derive(Debug)]
 pub enum IndexJoinScanError {
     DowncastError,
+    UnsupportedJoin(JoinType),
 }
 
 impl std::error::Error for IndexJoinScanError {}
@@ -22,6 +23,9 @@ impl fmt::Display for IndexJoinScanError {
             IndexJoinScanError::DowncastError => {
                 write!(f, "downcast error")
             }
+            IndexJoinScanError::UnsupportedJoin(join_type) => {
+                write!(f, "unsupported by index join: {}", join_type)
+            }
         }
     }
 }
@@ -31,6 +35,11 @@ pub struct IndexJoinScan {
     idx: Arc<Mutex<dyn Index>>,
     joinfield: String,
     rhs: Arc<Mutex<TableScan>>,
+    // my own extends
+    // INNER or LEFT, where the fields of rhs are NULL for the records of lhs matching nothing
+    join_type: JoinType,
+    matched: bool,
+    padded: bool,
 }
 
 impl IndexJoinScan {
@@ -40,11 +49,27 @@ impl IndexJoinScan {
         joinfld: &str,
         rhs: Arc<Mutex<TableScan>>,
     ) -> Result<Self> {
+        Self::new_outer(lhs, idx, joinfld, rhs, JoinType::Inner)
+    }
+    // my own extends
+    pub fn new_outer(
+        lhs: Arc<Mutex<dyn Scan>>,
+        idx: Arc<Mutex<dyn Index>>,
+        joinfld: &str,
+        rhs: Arc<Mutex<TableScan>>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        if join_type.preserves_rhs() {
+            return Err(From::from(IndexJoinScanError::UnsupportedJoin(join_type)));
+        }
         let mut scan = Self {
             lhs,
             idx,
             joinfield: joinfld.to_string(),
             rhs,
+            join_type,
+            matched: false,
+            padded: false,
         };
         scan.before_first()?;
 
@@ -58,17 +83,33 @@ impl IndexJoinScan {
 
 impl Scan for IndexJoinScan {
     fn before_first(&mut self) -> Result<()> {
+        self.matched = false;
+        self.padded = false;
         self.lhs.lock().unwrap().before_first()?;
         self.lhs.lock().unwrap().next();
         self.reset_index()
     }
     fn next(&mut self) -> bool {
         loop {
-            if self.idx.lock().unwrap().next() {
-                let rid = self.idx.lock().unwrap().get_data_rid().unwrap();
-                self.rhs.lock().unwrap().move_to_rid(rid).unwrap();
-                return true;
+            if !self.padded {
+                while self.idx.lock().unwrap().next() {
+                    let rid = self.idx.lock().unwrap().get_data_rid().unwrap();
+                    let mut rhs = self.rhs.lock().unwrap();
+                    rhs.move_to_rid(rid).unwrap();
+                    // in MVCC mode, the index also points to records the snapshot does not see
+                    if rhs.is_visible() {
+                        self.matched = true;
+                        return true;
+                    }
+                }
+                // my own extends
+                if self.join_type.preserves_lhs() && !self.matched {
+                    self.padded = true;
+                    return true;
+                }
             }
+            self.matched = false;
+            self.padded = false;
             if !self.lhs.lock().unwrap().next() {
                 return false;
             }
@@ -76,6 +117,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Constant::Null.as_i16();
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_i16(fldname)
         } else {
@@ -83,6 +127,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_i32(&mut self, fldname: &str) -> Result<i32> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Constant::Null.as_i32();
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_i32(fldname)
         } else {
@@ -90,6 +137,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_string(&mut self, fldname: &str) -> Result<String> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Constant::Null.as_string().map(|s| s.to_string());
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_string(fldname)
         } else {
@@ -97,6 +147,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_bool(&mut self, fldname: &str) -> Result<bool> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Constant::Null.as_bool();
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_bool(fldname)
         } else {
@@ -104,6 +157,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Constant::Null.as_date();
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_date(fldname)
         } else {
@@ -111,6 +167,9 @@ impl Scan for IndexJoinScan {
         }
     }
     fn get_val(&mut self, fldname: &str) -> Result<Constant> {
+        if self.padded && self.rhs.lock().unwrap().has_field(fldname) {
+            return Ok(Constant::Null);
+        }
         if self.rhs.lock().unwrap().has_field(fldname) {
             self.rhs.lock().unwrap().get_val(fldname)
         } else {
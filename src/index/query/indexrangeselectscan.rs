@@ -0,0 +1,129 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use core::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    index::{Index, SearchRange},
+    materialize::sortscan::SortScan,
+    query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
+    record::tablescan::TableScan,
+};
+
+#[derive(Debug)]
+pub enum IndexRangeSelectScanError {
+    DowncastError,
+}
+
+impl std::error::Error for IndexRangeSelectScanError {}
+impl fmt::Display for IndexRangeSelectScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexRangeSelectScanError::DowncastError => {
+                write!(f, "downcast error")
+            }
+        }
+    }
+}
+
+pub struct IndexRangeSelectScan {
+    ts: Arc<Mutex<TableScan>>,
+    idx: Arc<Mutex<dyn Index>>,
+    // disjoint ranges, which are scanned one after another (e.g. the values of an IN list)
+    ranges: Vec<SearchRange>,
+    current: usize,
+}
+
+impl IndexRangeSelectScan {
+    pub fn new(
+        ts: Arc<Mutex<TableScan>>,
+        idx: Arc<Mutex<dyn Index>>,
+        range: SearchRange,
+    ) -> Result<Self> {
+        Self::new_with(ts, idx, vec![range])
+    }
+    pub fn new_with(
+        ts: Arc<Mutex<TableScan>>,
+        idx: Arc<Mutex<dyn Index>>,
+        ranges: Vec<SearchRange>,
+    ) -> Result<Self> {
+        let mut scan = Self {
+            ts,
+            idx,
+            ranges,
+            current: 0,
+        };
+        scan.before_first()?;
+
+        Ok(scan)
+    }
+}
+
+impl Scan for IndexRangeSelectScan {
+    fn before_first(&mut self) -> Result<()> {
+        self.current = 0;
+        match self.ranges.first() {
+            Some(range) => self.idx.lock().unwrap().before_first_range(range.clone()),
+            None => Ok(()),
+        }
+    }
+    fn next(&mut self) -> bool {
+        while self.current < self.ranges.len() {
+            while self.idx.lock().unwrap().next() {
+                let rid = self.idx.lock().unwrap().get_data_rid().unwrap();
+                let mut ts = self.ts.lock().unwrap();
+                ts.move_to_rid(rid).unwrap();
+                // in MVCC mode, the index also points to records the snapshot does not see
+                if ts.is_visible() {
+                    return true;
+                }
+            }
+            // go on to the next range
+            self.current += 1;
+            if let Some(range) = self.ranges.get(self.current) {
+                self.idx
+                    .lock()
+                    .unwrap()
+                    .before_first_range(range.clone())
+                    .unwrap();
+            }
+        }
+
+        false
+    }
+    fn get_i16(&mut self, fldname: &str) -> Result<i16> {
+        self.ts.lock().unwrap().get_i16(fldname)
+    }
+    fn get_i32(&mut self, fldname: &str) -> Result<i32> {
+        self.ts.lock().unwrap().get_i32(fldname)
+    }
+    fn get_string(&mut self, fldname: &str) -> Result<String> {
+        self.ts.lock().unwrap().get_string(fldname)
+    }
+    fn get_bool(&mut self, fldname: &str) -> Result<bool> {
+        self.ts.lock().unwrap().get_bool(fldname)
+    }
+    fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
+        self.ts.lock().unwrap().get_date(fldname)
+    }
+    fn get_val(&mut self, fldname: &str) -> Result<Constant> {
+        self.ts.lock().unwrap().get_val(fldname)
+    }
+    fn has_field(&self, fldname: &str) -> bool {
+        self.ts.lock().unwrap().has_field(fldname)
+    }
+    fn close(&mut self) -> Result<()> {
+        self.idx.lock().unwrap().close()?;
+        self.ts.lock().unwrap().close()
+    }
+
+    fn to_update_scan(&mut self) -> Result<&mut dyn UpdateScan> {
+        Err(From::from(IndexRangeSelectScanError::DowncastError))
+    }
+    fn as_table_scan(&mut self) -> Result<&mut TableScan> {
+        Err(From::from(IndexRangeSelectScanError::DowncastError))
+    }
+    fn as_sort_scan(&mut self) -> Result<&mut SortScan> {
+        Err(From::from(IndexRangeSelectScanError::DowncastError))
+    }
+}
@@ -50,13 +50,17 @@ impl Scan for IndexSelectScan {
         self.idx.lock().unwrap().before_first(self.val.clone())
     }
     fn next(&mut self) -> bool {
-        let ok = self.idx.lock().unwrap().next();
-        if ok {
+        while self.idx.lock().unwrap().next() {
             let rid = self.idx.lock().unwrap().get_data_rid().unwrap();
-            self.ts.lock().unwrap().move_to_rid(rid).unwrap();
+            let mut ts = self.ts.lock().unwrap();
+            ts.move_to_rid(rid).unwrap();
+            // in MVCC mode, the index also points to records the snapshot does not see
+            if ts.is_visible() {
+                return true;
+            }
         }
 
-        ok
+        false
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
         self.ts.lock().unwrap().get_i16(fldname)
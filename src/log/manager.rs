@@ -57,6 +57,10 @@ impl LogMgr {
 
         Ok(())
     }
+    // my own extends
+    pub fn latest_lsn(&self) -> i32 {
+        self.latest_lsn
+    }
     pub fn iterator(&mut self) -> Result<LogIterator> {
         self.flush_to_fm()?;
         let iter = LogIterator::new(Arc::clone(&self.fm), self.currentblk.clone())?;
@@ -1,12 +1,112 @@
+use anyhow::Result;
+use core::fmt;
 use std::sync::{Arc, Mutex};
 
-use crate::query::{constant::Constant, scan::Scan};
+use crate::{
+    query::{
+        constant::{Constant, ConstantError},
+        expression::ArithOp,
+        scan::Scan,
+    },
+    record::schema::{FieldInfo, Schema, SchemaError},
+};
 
+use self::{avgfn::AvgFn, countfn::CountFn, maxfn::MaxFn, minfn::MinFn, sumfn::SumFn};
+
+pub mod avgfn;
+pub mod countfn;
 pub mod maxfn;
+pub mod minfn;
+pub mod sumfn;
 
 pub trait AggregationFn {
     fn process_first(&self, scan: Arc<Mutex<dyn Scan>>);
     fn process_next(&self, scan: Arc<Mutex<dyn Scan>>);
     fn field_name(&self) -> String;
-    fn value(&self) -> Constant;
+    fn value(&self) -> Result<Constant>;
+    // my own extends
+    fn field_info(&self, sch: Arc<Schema>) -> FieldInfo;
+    // my own extends
+    // the value of no records, e.g. count(*) of an empty table without group by
+    fn process_empty(&self);
+}
+
+// my own extends
+// aggregate function in sql (e.g. count(*), max(gradyear))
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Aggregation {
+    // None means count(*)
+    Count(Option<String>),
+    Sum(String),
+    Min(String),
+    Max(String),
+    Avg(String),
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aggregation::Count(None) => write!(f, "count(*)"),
+            Aggregation::Count(Some(fldname)) => write!(f, "count({})", fldname),
+            Aggregation::Sum(fldname) => write!(f, "sum({})", fldname),
+            Aggregation::Min(fldname) => write!(f, "min({})", fldname),
+            Aggregation::Max(fldname) => write!(f, "max({})", fldname),
+            Aggregation::Avg(fldname) => write!(f, "avg({})", fldname),
+        }
+    }
+}
+
+impl Aggregation {
+    pub fn field_name(&self) -> String {
+        self.function().field_name()
+    }
+    pub fn function(&self) -> Arc<dyn AggregationFn> {
+        match self {
+            Aggregation::Count(None) => Arc::new(CountFn::new_all()),
+            Aggregation::Count(Some(fldname)) => Arc::new(CountFn::new(fldname)),
+            Aggregation::Sum(fldname) => Arc::new(SumFn::new(fldname)),
+            Aggregation::Min(fldname) => Arc::new(MinFn::new(fldname)),
+            Aggregation::Max(fldname) => Arc::new(MaxFn::new(fldname)),
+            Aggregation::Avg(fldname) => Arc::new(AvgFn::new(fldname)),
+        }
+    }
+    // the aggregation of the field which the schema resolves the name to
+    pub fn resolve(&self, sch: &Schema) -> Result<Self, SchemaError> {
+        Ok(match self {
+            Aggregation::Count(None) => Aggregation::Count(None),
+            Aggregation::Count(Some(fldname)) => Aggregation::Count(Some(sch.resolve(fldname)?)),
+            Aggregation::Sum(fldname) => Aggregation::Sum(sch.resolve(fldname)?),
+            Aggregation::Min(fldname) => Aggregation::Min(sch.resolve(fldname)?),
+            Aggregation::Max(fldname) => Aggregation::Max(sch.resolve(fldname)?),
+            Aggregation::Avg(fldname) => Aggregation::Avg(sch.resolve(fldname)?),
+        })
+    }
+}
+
+// my own extends
+// the sum of the numbers so far and the next one. integers are added up in an i64.
+// a value which is not a number is ignored.
+// fails with the addition (e.g. "9223372036854775807 + 1") if it overflows the sum.
+fn add_number(sum: Option<Constant>, val: Constant) -> Result<Option<Constant>, String> {
+    let val = match val {
+        Constant::I16(_) | Constant::I32(_) => Constant::new_i64(val.as_i64().unwrap()),
+        Constant::I64(_) | Constant::Decimal(_) | Constant::F64(_) => val,
+        _ => return Ok(sum),
+    };
+    let Some(sum) = sum else {
+        return Ok(Some(val));
+    };
+    ArithOp::Add
+        .apply(&sum, &val)
+        .map(Some)
+        .map_err(|_| format!("{} {} {}", sum, ArithOp::Add, val))
+}
+
+// my own extends
+// the error of the addition which overflowed the sum, see add_number.
+fn out_of_range(overflow: &Option<String>) -> Result<()> {
+    match overflow {
+        Some(expr) => Err(From::from(ConstantError::OutOfRange(expr.clone()))),
+        None => Ok(()),
+    }
 }
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use super::{add_number, out_of_range, AggregationFn};
+use crate::{
+    query::{
+        constant::Constant,
+        expression::{ArithOp, DIV_SCALE_INCREMENT, MAX_DECIMAL_PRECISION},
+        scan::Scan,
+    },
+    record::schema::{FieldInfo, FieldType, Schema},
+};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AvgFn {
+    fldname: String,
+    sum: RefCell<Option<Constant>>,
+    count: RefCell<i64>,
+    // the addition which overflowed the sum, see add_number
+    overflow: RefCell<Option<String>>,
+}
+
+impl AvgFn {
+    pub fn new(fldname: &str) -> Self {
+        Self {
+            fldname: fldname.to_string(),
+            sum: RefCell::new(None),
+            count: RefCell::new(0),
+            overflow: RefCell::new(None),
+        }
+    }
+    fn get_val(&self, scan: Arc<Mutex<dyn Scan>>) -> Option<Constant> {
+        let val = scan.lock().unwrap().get_val(&self.fldname).ok();
+        val.filter(|v| !v.is_null())
+    }
+}
+
+impl AggregationFn for AvgFn {
+    fn process_first(&self, scan: Arc<Mutex<dyn Scan>>) {
+        *self.sum.borrow_mut() = None;
+        *self.count.borrow_mut() = 0;
+        *self.overflow.borrow_mut() = None;
+        self.process_next(scan);
+    }
+    fn process_next(&self, scan: Arc<Mutex<dyn Scan>>) {
+        // NULL is ignored
+        if let Some(val) = self.get_val(scan) {
+            if self.overflow.borrow().is_some() {
+                return;
+            }
+            let sum = self.sum.borrow_mut().take();
+            match add_number(sum, val) {
+                Ok(sum) => *self.sum.borrow_mut() = sum,
+                Err(expr) => *self.overflow.borrow_mut() = Some(expr),
+            }
+            *self.count.borrow_mut() += 1;
+        }
+    }
+    fn field_name(&self) -> String {
+        format!("avgof{}", self.fldname)
+    }
+    fn value(&self) -> Result<Constant> {
+        out_of_range(&self.overflow.borrow())?;
+        let count = Constant::new_i64(*self.count.borrow());
+        Ok(match (*self.sum.borrow()).as_ref() {
+            Some(Constant::F64(fval)) => ArithOp::Div.apply(&Constant::new_f64(*fval), &count),
+            // a DECIMAL is divided with the fraction, not truncated like an integer
+            Some(sum) => sum
+                .as_decimal()
+                .and_then(|dval| ArithOp::Div.apply(&Constant::new_decimal(dval), &count)),
+            None => return Ok(Constant::Null),
+        }
+        .unwrap_or(Constant::Null))
+    }
+    fn process_empty(&self) {
+        *self.sum.borrow_mut() = None;
+        *self.count.borrow_mut() = 0;
+        *self.overflow.borrow_mut() = None;
+    }
+    // the average of integers or DECIMALs is a DECIMAL with more digits of the fraction
+    fn field_info(&self, sch: Arc<Schema>) -> FieldInfo {
+        match sch.field_type(&self.fldname) {
+            FieldType::DOUBLE => FieldInfo::new(FieldType::DOUBLE, 0),
+            fld_type => {
+                let scale = match fld_type {
+                    FieldType::DECIMAL => sch.scale(&self.fldname),
+                    _ => 0,
+                };
+                let mut fi = FieldInfo::new(FieldType::DECIMAL, MAX_DECIMAL_PRECISION);
+                fi.scale = (scale + DIV_SCALE_INCREMENT as usize).min(MAX_DECIMAL_PRECISION);
+                fi
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use super::AggregationFn;
+use crate::{
+    query::{constant::Constant, scan::Scan},
+    record::schema::{FieldInfo, FieldType, Schema},
+};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CountFn {
+    // None means COUNT(*)
+    fldname: Option<String>,
+    count: RefCell<i32>,
+}
+
+impl CountFn {
+    pub fn new(fldname: &str) -> Self {
+        Self {
+            fldname: Some(fldname.to_string()),
+            count: RefCell::new(0),
+        }
+    }
+    pub fn new_all() -> Self {
+        Self {
+            fldname: None,
+            count: RefCell::new(0),
+        }
+    }
+    fn counts(&self, scan: Arc<Mutex<dyn Scan>>) -> bool {
+        match self.fldname.as_ref() {
+            // NULL is not counted
+            Some(fldname) => scan
+                .lock()
+                .unwrap()
+                .get_val(fldname)
+                .is_ok_and(|v| !v.is_null()),
+            None => true,
+        }
+    }
+}
+
+impl AggregationFn for CountFn {
+    fn process_first(&self, scan: Arc<Mutex<dyn Scan>>) {
+        *self.count.borrow_mut() = if self.counts(scan) { 1 } else { 0 };
+    }
+    fn process_next(&self, scan: Arc<Mutex<dyn Scan>>) {
+        if self.counts(scan) {
+            *self.count.borrow_mut() += 1;
+        }
+    }
+    fn field_name(&self) -> String {
+        match self.fldname.as_ref() {
+            Some(fldname) => format!("countof{}", fldname),
+            None => "countofall".to_string(),
+        }
+    }
+    fn value(&self) -> Result<Constant> {
+        Ok(Constant::I32(*self.count.borrow()))
+    }
+    fn process_empty(&self) {
+        *self.count.borrow_mut() = 0;
+    }
+    fn field_info(&self, _sch: Arc<Schema>) -> FieldInfo {
+        FieldInfo::new(FieldType::INTEGER, 0)
+    }
+}
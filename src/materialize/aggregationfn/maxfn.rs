@@ -1,10 +1,14 @@
+use anyhow::Result;
 use std::{
     cell::RefCell,
     sync::{Arc, Mutex},
 };
 
 use super::AggregationFn;
-use crate::query::{constant::Constant, scan::Scan};
+use crate::{
+    query::{constant::Constant, scan::Scan},
+    record::schema::{FieldInfo, Schema},
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct MaxFn {
@@ -19,22 +23,45 @@ impl MaxFn {
             val: RefCell::new(None),
         }
     }
+    fn get_val(&self, scan: Arc<Mutex<dyn Scan>>) -> Option<Constant> {
+        let val = scan.lock().unwrap().get_val(&self.fldname).ok();
+        val.filter(|v| !v.is_null())
+    }
 }
 
 impl AggregationFn for MaxFn {
     fn process_first(&self, scan: Arc<Mutex<dyn Scan>>) {
-        *self.val.borrow_mut() = scan.lock().unwrap().get_val(&self.fldname).ok()
+        *self.val.borrow_mut() = self.get_val(scan);
     }
     fn process_next(&self, scan: Arc<Mutex<dyn Scan>>) {
-        let newval = scan.lock().unwrap().get_val(&self.fldname).ok();
-        if newval > *self.val.borrow() {
-            *self.val.borrow_mut() = newval;
+        // NULL is ignored
+        if let Some(newval) = self.get_val(scan) {
+            let replace = match self.val.borrow().as_ref() {
+                Some(val) => newval > *val,
+                None => true,
+            };
+            if replace {
+                *self.val.borrow_mut() = Some(newval);
+            }
         }
     }
     fn field_name(&self) -> String {
         format!("maxof{}", self.fldname)
     }
-    fn value(&self) -> Constant {
-        (*self.val.borrow()).as_ref().unwrap().clone()
+    fn value(&self) -> Result<Constant> {
+        // NOTE: no value until the first record is processed (e.g. explain plan),
+        //       or all values are NULL.
+        Ok((*self.val.borrow())
+            .as_ref()
+            .cloned()
+            .unwrap_or(Constant::Null))
+    }
+    fn process_empty(&self) {
+        *self.val.borrow_mut() = None;
+    }
+    fn field_info(&self, sch: Arc<Schema>) -> FieldInfo {
+        let mut fi = FieldInfo::new(sch.field_type(&self.fldname), sch.length(&self.fldname));
+        fi.scale = sch.scale(&self.fldname);
+        fi
     }
 }
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use super::AggregationFn;
+use crate::{
+    query::{constant::Constant, scan::Scan},
+    record::schema::{FieldInfo, Schema},
+};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct MinFn {
+    fldname: String,
+    val: RefCell<Option<Constant>>,
+}
+
+impl MinFn {
+    pub fn new(fldname: &str) -> Self {
+        Self {
+            fldname: fldname.to_string(),
+            val: RefCell::new(None),
+        }
+    }
+    fn get_val(&self, scan: Arc<Mutex<dyn Scan>>) -> Option<Constant> {
+        let val = scan.lock().unwrap().get_val(&self.fldname).ok();
+        val.filter(|v| !v.is_null())
+    }
+}
+
+impl AggregationFn for MinFn {
+    fn process_first(&self, scan: Arc<Mutex<dyn Scan>>) {
+        *self.val.borrow_mut() = self.get_val(scan);
+    }
+    fn process_next(&self, scan: Arc<Mutex<dyn Scan>>) {
+        // NULL is ignored
+        if let Some(newval) = self.get_val(scan) {
+            let replace = match self.val.borrow().as_ref() {
+                Some(val) => newval < *val,
+                None => true,
+            };
+            if replace {
+                *self.val.borrow_mut() = Some(newval);
+            }
+        }
+    }
+    fn field_name(&self) -> String {
+        format!("minof{}", self.fldname)
+    }
+    fn value(&self) -> Result<Constant> {
+        // NOTE: no value until the first record is processed (e.g. explain plan),
+        //       or all values are NULL.
+        Ok((*self.val.borrow())
+            .as_ref()
+            .cloned()
+            .unwrap_or(Constant::Null))
+    }
+    fn process_empty(&self) {
+        *self.val.borrow_mut() = None;
+    }
+    fn field_info(&self, sch: Arc<Schema>) -> FieldInfo {
+        let mut fi = FieldInfo::new(sch.field_type(&self.fldname), sch.length(&self.fldname));
+        fi.scale = sch.scale(&self.fldname);
+        fi
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use super::{add_number, out_of_range, AggregationFn};
+use crate::{
+    query::{constant::Constant, expression::MAX_DECIMAL_PRECISION, scan::Scan},
+    record::schema::{FieldInfo, FieldType, Schema},
+};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SumFn {
+    fldname: String,
+    // None until a value which is not NULL is processed
+    sum: RefCell<Option<Constant>>,
+    // the addition which overflowed the sum, see add_number
+    overflow: RefCell<Option<String>>,
+}
+
+impl SumFn {
+    pub fn new(fldname: &str) -> Self {
+        Self {
+            fldname: fldname.to_string(),
+            sum: RefCell::new(None),
+            overflow: RefCell::new(None),
+        }
+    }
+    fn get_val(&self, scan: Arc<Mutex<dyn Scan>>) -> Option<Constant> {
+        let val = scan.lock().unwrap().get_val(&self.fldname).ok();
+        val.filter(|v| !v.is_null())
+    }
+}
+
+impl AggregationFn for SumFn {
+    fn process_first(&self, scan: Arc<Mutex<dyn Scan>>) {
+        *self.sum.borrow_mut() = None;
+        *self.overflow.borrow_mut() = None;
+        self.process_next(scan);
+    }
+    fn process_next(&self, scan: Arc<Mutex<dyn Scan>>) {
+        // NULL is ignored
+        if let Some(val) = self.get_val(scan) {
+            if self.overflow.borrow().is_some() {
+                return;
+            }
+            let sum = self.sum.borrow_mut().take();
+            match add_number(sum, val) {
+                Ok(sum) => *self.sum.borrow_mut() = sum,
+                Err(expr) => *self.overflow.borrow_mut() = Some(expr),
+            }
+        }
+    }
+    fn field_name(&self) -> String {
+        format!("sumof{}", self.fldname)
+    }
+    fn value(&self) -> Result<Constant> {
+        out_of_range(&self.overflow.borrow())?;
+        Ok((*self.sum.borrow())
+            .as_ref()
+            .cloned()
+            .unwrap_or(Constant::Null))
+    }
+    fn process_empty(&self) {
+        *self.sum.borrow_mut() = None;
+        *self.overflow.borrow_mut() = None;
+    }
+    // the sum of integers is a BIGINT, and the sum of DECIMALs keeps their scale
+    fn field_info(&self, sch: Arc<Schema>) -> FieldInfo {
+        match sch.field_type(&self.fldname) {
+            FieldType::DECIMAL => {
+                let mut fi = FieldInfo::new(FieldType::DECIMAL, MAX_DECIMAL_PRECISION);
+                fi.scale = sch.scale(&self.fldname);
+                fi
+            }
+            FieldType::DOUBLE => FieldInfo::new(FieldType::DOUBLE, 0),
+            _ => FieldInfo::new(FieldType::BIGINT, 0),
+        }
+    }
+}
@@ -38,7 +38,9 @@ impl GroupByPlan {
             sch.add(fldname, plan.schema());
         }
         for aggfn in aggfns.iter() {
-            sch.add_i32_field(&aggfn.field_name());
+            let info = aggfn.field_info(plan.schema());
+            sch.add_field(&aggfn.field_name(), info.fld_type, info.length);
+            sch.set_scale(&aggfn.field_name(), info.scale);
         }
 
         Self {
@@ -90,7 +92,7 @@ impl Plan for GroupByPlan {
             aggfns: self
                 .aggfns()
                 .iter()
-                .map(|f| (f.field_name(), f.value()))
+                .map(|f| (f.field_name(), f.value().unwrap_or(Constant::Null)))
                 .collect(),
             r: self.blocks_accessed(),
             w: self.records_output(),
@@ -36,6 +36,9 @@ pub struct GroupByScan {
     aggfns: Vec<Arc<dyn AggregationFn>>,
     groupval: Option<GroupValue>,
     moregroups: bool,
+    // my own extends
+    // without group by fields, no records are still one group
+    emptygroup: bool,
 }
 
 impl GroupByScan {
@@ -50,6 +53,7 @@ impl GroupByScan {
             aggfns,
             groupval: None,
             moregroups: false,
+            emptygroup: false,
         };
         scan.before_first().unwrap();
 
@@ -61,10 +65,19 @@ impl Scan for GroupByScan {
     fn before_first(&mut self) -> Result<()> {
         self.s.lock().unwrap().before_first()?;
         self.moregroups = self.s.lock().unwrap().next();
+        self.emptygroup = !self.moregroups && self.groupfields.is_empty();
 
         Ok(())
     }
     fn next(&mut self) -> bool {
+        if self.emptygroup {
+            self.emptygroup = false;
+            for aggfn in self.aggfns.iter() {
+                aggfn.process_empty();
+            }
+            self.groupval = Some(GroupValue::new(Arc::clone(&self.s), vec![]));
+            return true;
+        }
         if !self.moregroups {
             return false;
         }
@@ -120,8 +133,7 @@ impl Scan for GroupByScan {
         }
         for aggfn in self.aggfns.iter() {
             if aggfn.field_name() == fldname {
-                let val = aggfn.value();
-                return Ok(val);
+                return aggfn.value();
             }
         }
 
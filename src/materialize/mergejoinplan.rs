@@ -9,7 +9,10 @@ use super::mergejoinscan::MergeJoinScan;
 use crate::{
     materialize::sortplan::SortPlan,
     plan::plan::Plan,
-    query::scan::Scan,
+    query::{
+        join::{join_schema, JoinType},
+        scan::Scan,
+    },
     record::schema::Schema,
     repr::planrepr::{Operation, PlanRepr},
     tx::transaction::Transaction,
@@ -38,6 +41,8 @@ pub struct MergeJoinPlan {
     fldname1: String,
     fldname2: String,
     sch: Arc<Schema>,
+    // my own extends
+    join_type: JoinType,
 }
 
 impl MergeJoinPlan {
@@ -50,9 +55,27 @@ impl MergeJoinPlan {
         fldname1: &str,
         fldname2: &str,
     ) -> Self {
-        let mut sch = Schema::new();
-        sch.add_all(p1.schema());
-        sch.add_all(p2.schema());
+        Self::new_outer(
+            next_table_num,
+            tx,
+            p1,
+            p2,
+            fldname1,
+            fldname2,
+            JoinType::Inner,
+        )
+    }
+    // my own extends
+    pub fn new_outer(
+        next_table_num: Arc<Mutex<i32>>,
+        tx: Arc<Mutex<Transaction>>,
+        p1: Arc<dyn Plan>,
+        p2: Arc<dyn Plan>,
+        fldname1: &str,
+        fldname2: &str,
+        join_type: JoinType,
+    ) -> Self {
+        let sch = join_schema(p1.schema(), p2.schema(), join_type);
 
         let sortlist1 = vec![fldname1.to_string()];
         let plan1 = SortPlan::new(Arc::clone(&next_table_num), p1, sortlist1, Arc::clone(&tx));
@@ -65,6 +88,7 @@ impl MergeJoinPlan {
             fldname1: fldname1.to_string(),
             fldname2: fldname2.to_string(),
             sch: Arc::new(sch),
+            join_type,
         }
     }
 }
@@ -74,7 +98,8 @@ impl Plan for MergeJoinPlan {
         let s1 = self.p1.open()?;
         if let Ok(s2) = self.p2.open() {
             let s2 = Arc::new(Mutex::new(s2.lock().unwrap().as_sort_scan()?.to_owned()));
-            let scan = MergeJoinScan::new(s1, s2, &self.fldname1, &self.fldname2);
+            let scan =
+                MergeJoinScan::new_outer(s1, s2, &self.fldname1, &self.fldname2, self.join_type);
 
             return Ok(Arc::new(Mutex::new(scan)));
         }
@@ -90,7 +115,16 @@ impl Plan for MergeJoinPlan {
             self.p2.distinct_values(&self.fldname2),
         );
 
-        (self.p1.records_output() * self.p2.records_output()) / maxvals
+        let output = (self.p1.records_output() * self.p2.records_output()) / maxvals;
+        // my own extends
+        let mut preserved = 0;
+        if self.join_type.preserves_lhs() {
+            preserved += self.p1.records_output();
+        }
+        if self.join_type.preserves_rhs() {
+            preserved += self.p2.records_output();
+        }
+        output.max(preserved)
     }
     fn distinct_values(&self, fldname: &str) -> i32 {
         if self.p1.schema().has_field(fldname) {
@@ -109,6 +143,7 @@ impl Plan for MergeJoinPlan {
             p2: self.p2.repr(),
             fldname1: self.fldname1.clone(),
             fldname2: self.fldname2.clone(),
+            join_type: self.join_type,
             r: self.blocks_accessed(),
             w: self.records_output(),
         })
@@ -121,6 +156,7 @@ pub struct MergeJoinPlanRepr {
     p2: Arc<dyn PlanRepr>,
     fldname1: String,
     fldname2: String,
+    join_type: JoinType,
     r: i32,
     w: i32,
 }
@@ -130,6 +166,7 @@ impl PlanRepr for MergeJoinPlanRepr {
         Operation::MergeJoinScan {
             fldname1: self.fldname1.clone(),
             fldname2: self.fldname2.clone(),
+            join_type: self.join_type,
         }
     }
     fn reads(&self) -> i32 {
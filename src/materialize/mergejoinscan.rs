@@ -4,7 +4,11 @@ use core::fmt;
 use std::sync::{Arc, Mutex};
 
 use super::sortscan::SortScan;
-use crate::query::{constant::Constant, scan::Scan};
+use crate::query::{
+    constant::Constant,
+    join::{JoinSide, JoinType},
+    scan::Scan,
+};
 
 #[derive(Debug)]
 pub enum MergeJoinScanError {
@@ -29,6 +33,13 @@ pub struct MergeJoinScan {
     fldname1: String,
     fldname2: String,
     joinval: Option<Constant>,
+    // my own extends
+    // the outer joins pad the records matching nothing with NULL
+    join_type: JoinType,
+    started: bool,
+    hasmore1: bool,
+    hasmore2: bool,
+    padded: Option<JoinSide>,
 }
 
 impl MergeJoinScan {
@@ -37,6 +48,16 @@ impl MergeJoinScan {
         s2: Arc<Mutex<SortScan>>,
         fldname1: &str,
         fldname2: &str,
+    ) -> Self {
+        Self::new_outer(s1, s2, fldname1, fldname2, JoinType::Inner)
+    }
+    // my own extends
+    pub fn new_outer(
+        s1: Arc<Mutex<dyn Scan>>,
+        s2: Arc<Mutex<SortScan>>,
+        fldname1: &str,
+        fldname2: &str,
+        join_type: JoinType,
     ) -> Self {
         let mut scan = Self {
             s1,
@@ -44,21 +65,109 @@ impl MergeJoinScan {
             fldname1: fldname1.to_string(),
             fldname2: fldname2.to_string(),
             joinval: None,
+            join_type,
+            started: false,
+            hasmore1: false,
+            hasmore2: false,
+            padded: None,
         };
         scan.before_first().unwrap();
 
         scan
     }
+    // my own extends
+    // the same merge as the inner join, except that the records which are skipped
+    // because they match nothing are returned with the other side padded.
+    fn next_outer(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            self.hasmore1 = self.s1.lock().unwrap().next();
+            self.hasmore2 = self.s2.lock().unwrap().next();
+        } else {
+            match self.padded {
+                None => {
+                    self.hasmore2 = self.s2.lock().unwrap().next();
+                    if self.hasmore2
+                        && self.s2.lock().unwrap().get_val(&self.fldname2).ok() == self.joinval
+                    {
+                        return true;
+                    }
+                    self.hasmore1 = self.s1.lock().unwrap().next();
+                    if self.hasmore1
+                        && self.s1.lock().unwrap().get_val(&self.fldname1).ok() == self.joinval
+                    {
+                        self.s2.lock().unwrap().restore_position();
+                        self.hasmore2 = true;
+                        return true;
+                    }
+                    self.joinval = None;
+                }
+                Some(JoinSide::Rhs) => self.hasmore1 = self.s1.lock().unwrap().next(),
+                Some(JoinSide::Lhs) => self.hasmore2 = self.s2.lock().unwrap().next(),
+            }
+        }
+
+        loop {
+            match (self.hasmore1, self.hasmore2) {
+                (true, true) => {
+                    let v1 = self.s1.lock().unwrap().get_val(&self.fldname1).unwrap();
+                    let v2 = self.s2.lock().unwrap().get_val(&self.fldname2).unwrap();
+                    if v1.is_null() || (!v2.is_null() && v1 < v2) {
+                        if self.join_type.preserves_lhs() {
+                            self.padded = Some(JoinSide::Rhs);
+                            return true;
+                        }
+                        self.hasmore1 = self.s1.lock().unwrap().next();
+                    } else if v2.is_null() || v1 > v2 {
+                        if self.join_type.preserves_rhs() {
+                            self.padded = Some(JoinSide::Lhs);
+                            return true;
+                        }
+                        self.hasmore2 = self.s2.lock().unwrap().next();
+                    } else {
+                        self.s2.lock().unwrap().save_position();
+                        self.joinval = Some(v2);
+                        self.padded = None;
+                        return true;
+                    }
+                }
+                (true, false) if self.join_type.preserves_lhs() => {
+                    self.padded = Some(JoinSide::Rhs);
+                    return true;
+                }
+                (false, true) if self.join_type.preserves_rhs() => {
+                    self.padded = Some(JoinSide::Lhs);
+                    return true;
+                }
+                _ => return false,
+            }
+        }
+    }
+    fn is_padded(&self, fldname: &str) -> bool {
+        match self.padded {
+            Some(JoinSide::Lhs) => self.s1.lock().unwrap().has_field(fldname),
+            Some(JoinSide::Rhs) => self.s2.lock().unwrap().has_field(fldname),
+            None => false,
+        }
+    }
 }
 
 impl Scan for MergeJoinScan {
     fn before_first(&mut self) -> Result<()> {
+        self.joinval = None;
+        self.started = false;
+        self.padded = None;
         self.s1.lock().unwrap().before_first()?;
         self.s2.lock().unwrap().before_first()?;
 
         Ok(())
     }
     fn next(&mut self) -> bool {
+        // my own extends
+        if self.join_type != JoinType::Inner {
+            return self.next_outer();
+        }
+
         let mut hasmore2 = self.s2.lock().unwrap().next();
         if hasmore2 && self.s2.lock().unwrap().get_val(&self.fldname2).ok() == self.joinval {
             return true;
@@ -73,7 +182,13 @@ impl Scan for MergeJoinScan {
         while hasmore1 && hasmore2 {
             let v1 = self.s1.lock().unwrap().get_val(&self.fldname1).unwrap();
             let v2 = self.s2.lock().unwrap().get_val(&self.fldname2).unwrap();
-            if v1 < v2 {
+            // my own extends
+            // NULL never joins (NULL sorts first)
+            if v1.is_null() {
+                hasmore1 = self.s1.lock().unwrap().next();
+            } else if v2.is_null() {
+                hasmore2 = self.s2.lock().unwrap().next();
+            } else if v1 < v2 {
                 hasmore1 = self.s1.lock().unwrap().next();
             } else if v1 > v2 {
                 hasmore2 = self.s2.lock().unwrap().next();
@@ -87,6 +202,9 @@ impl Scan for MergeJoinScan {
         false
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_i16();
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_i16(fldname)
         } else {
@@ -94,6 +212,9 @@ impl Scan for MergeJoinScan {
         }
     }
     fn get_i32(&mut self, fldname: &str) -> Result<i32> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_i32();
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_i32(fldname)
         } else {
@@ -101,6 +222,9 @@ impl Scan for MergeJoinScan {
         }
     }
     fn get_string(&mut self, fldname: &str) -> Result<String> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_string().map(|s| s.to_string());
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_string(fldname)
         } else {
@@ -108,6 +232,9 @@ impl Scan for MergeJoinScan {
         }
     }
     fn get_bool(&mut self, fldname: &str) -> Result<bool> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_bool();
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_bool(fldname)
         } else {
@@ -115,6 +242,9 @@ impl Scan for MergeJoinScan {
         }
     }
     fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_date();
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_date(fldname)
         } else {
@@ -122,6 +252,9 @@ impl Scan for MergeJoinScan {
         }
     }
     fn get_val(&mut self, fldname: &str) -> Result<Constant> {
+        if self.is_padded(fldname) {
+            return Ok(Constant::Null);
+        }
         if self.s1.lock().unwrap().has_field(fldname) {
             self.s1.lock().unwrap().get_val(fldname)
         } else {
@@ -1,3 +1,4 @@
+use core::fmt;
 use std::{
     cmp::Ordering,
     sync::{Arc, Mutex},
@@ -5,20 +6,44 @@ use std::{
 
 use crate::query::scan::Scan;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "asc"),
+            SortOrder::Desc => write!(f, "desc"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RecordComparator {
     fields: Vec<String>,
+    orders: Vec<SortOrder>,
 }
 
 impl RecordComparator {
     pub fn new(fields: Vec<String>) -> Self {
-        Self { fields }
+        let orders = vec![SortOrder::Asc; fields.len()];
+        Self { fields, orders }
+    }
+    pub fn new_with_orders(fields: Vec<String>, orders: Vec<SortOrder>) -> Self {
+        Self { fields, orders }
     }
     pub fn compare(&self, s1: Arc<Mutex<dyn Scan>>, s2: Arc<Mutex<dyn Scan>>) -> Ordering {
-        for fldname in self.fields.iter() {
-            let val1 = s1.lock().unwrap().get_val(fldname).unwrap();
-            let val2 = s2.lock().unwrap().get_val(fldname).unwrap();
-            let result = val1.cmp(&val2);
+        for (fldname, order) in self.fields.iter().zip(self.orders.iter()) {
+            // the planner checks the sort fields, and a missing value is sorted first
+            let val1 = s1.lock().unwrap().get_val(fldname).ok();
+            let val2 = s2.lock().unwrap().get_val(fldname).ok();
+            let result = match order {
+                SortOrder::Asc => val1.cmp(&val2),
+                SortOrder::Desc => val2.cmp(&val1),
+            };
             if result.is_ne() {
                 return result;
             }
@@ -30,4 +55,7 @@ impl RecordComparator {
     pub fn fields(&self) -> Vec<String> {
         self.fields.clone()
     }
+    pub fn orders(&self) -> Vec<SortOrder> {
+        self.orders.clone()
+    }
 }
@@ -2,7 +2,9 @@ use anyhow::Result;
 use std::sync::{Arc, Mutex};
 
 use super::{
-    materializeplan::MaterializePlan, recordcomparator::RecordComparator, sortscan::SortScan,
+    materializeplan::MaterializePlan,
+    recordcomparator::{RecordComparator, SortOrder},
+    sortscan::SortScan,
     temptable::TempTable,
 };
 use crate::{
@@ -30,9 +32,19 @@ impl SortPlan {
         p: Arc<dyn Plan>,
         sortfields: Vec<String>,
         tx: Arc<Mutex<Transaction>>,
+    ) -> Self {
+        let orders = vec![SortOrder::Asc; sortfields.len()];
+        Self::new_with_orders(next_table_num, p, sortfields, orders, tx)
+    }
+    pub fn new_with_orders(
+        next_table_num: Arc<Mutex<i32>>,
+        p: Arc<dyn Plan>,
+        sortfields: Vec<String>,
+        orders: Vec<SortOrder>,
+        tx: Arc<Mutex<Transaction>>,
     ) -> Self {
         let sch = p.schema();
-        let comp = RecordComparator::new(sortfields);
+        let comp = RecordComparator::new_with_orders(sortfields, orders);
 
         Self {
             next_table_num,
@@ -46,6 +58,12 @@ impl SortPlan {
         let mut temps = vec![];
         src.lock().unwrap().before_first().unwrap();
         if !src.lock().unwrap().next() {
+            // NOTE: SortScan expects at least one run, so an empty source yields one empty run.
+            temps.push(TempTable::new(
+                Arc::clone(&self.next_table_num),
+                Arc::clone(&self.tx),
+                Arc::clone(&self.sch),
+            ));
             return temps;
         }
         let mut currenttemp = TempTable::new(
@@ -171,6 +189,7 @@ impl Plan for SortPlan {
         Arc::new(SortPlanRepr {
             p: self.p.repr(),
             compflds: self.comp.fields(),
+            orders: self.comp.orders(),
             r: self.blocks_accessed(),
             w: self.records_output(),
         })
@@ -181,6 +200,7 @@ impl Plan for SortPlan {
 pub struct SortPlanRepr {
     p: Arc<dyn PlanRepr>,
     compflds: Vec<String>,
+    orders: Vec<SortOrder>,
     r: i32,
     w: i32,
 }
@@ -189,6 +209,7 @@ impl PlanRepr for SortPlanRepr {
     fn operation(&self) -> Operation {
         Operation::SortScan {
             compflds: self.compflds.clone(),
+            orders: self.orders.clone(),
         }
     }
     fn reads(&self) -> i32 {
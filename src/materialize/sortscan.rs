@@ -90,6 +90,7 @@ impl SortScan {
 
 impl Scan for SortScan {
     fn before_first(&mut self) -> Result<()> {
+        self.currentscan = ScanEither::NoScan;
         self.s1.lock().unwrap().before_first()?;
         self.hasmore1 = self.s1.lock().unwrap().next();
         if let Some(s2) = self.s2.as_ref() {
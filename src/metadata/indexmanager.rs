@@ -11,7 +11,7 @@ use super::{
 };
 use crate::{
     index::{btree::index::BTreeIndex, Index},
-    query::{scan::Scan, updatescan::UpdateScan},
+    query::{scan::Scan, term::RANGE_REDUCTION_FACTOR, updatescan::UpdateScan},
     record::{layout::Layout, schema::FieldType, schema::Schema, tablescan::TableScan},
     tx::transaction::Transaction,
 };
@@ -90,6 +90,83 @@ impl IndexMgr {
 
         Ok(result)
     }
+    // my own extends
+    // returns false if the index does not exist
+    pub fn drop_index(&self, idxname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        let mut found = false;
+        let mut ts = TableScan::new(Arc::clone(&tx), "idxcat", Arc::clone(&self.layout))?;
+        while ts.next() {
+            if ts.get_string("indexname")? == idxname {
+                ts.delete()?;
+                found = true;
+            }
+        }
+        ts.close()?;
+
+        if found {
+            BTreeIndex::delete_files(tx, idxname)?;
+        }
+
+        Ok(found)
+    }
+    // my own extends
+    pub fn rename_field(
+        &self,
+        tblname: &str,
+        oldname: &str,
+        newname: &str,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<()> {
+        let mut ts = TableScan::new(tx, "idxcat", Arc::clone(&self.layout))?;
+        while ts.next() {
+            if ts.get_string("tablename")? == tblname && ts.get_string("fieldname")? == oldname {
+                ts.set_string("fieldname", newname.to_string())?;
+            }
+        }
+        ts.close()
+    }
+    // my own extends
+    // a rewritten table has new record ids, so each of its indexes is built again.
+    pub fn rebuild_indexes(&mut self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        self.statmgr.remove_stat_info(tblname);
+        let layout = self.tblmgr.get_layout(tblname, Arc::clone(&tx))?;
+        for (fldname, ii) in self.get_index_info(tblname, Arc::clone(&tx))? {
+            BTreeIndex::move_files_aside(Arc::clone(&tx), ii.index_name())?;
+            let idx = ii.open();
+            let mut ts = TableScan::new(Arc::clone(&tx), tblname, Arc::clone(&layout))?;
+            while ts.next() {
+                let val = ts.get_val(&fldname)?;
+                // nulls are never indexed
+                if !val.is_null() {
+                    idx.lock().unwrap().insert(val, ts.get_rid()?)?;
+                }
+            }
+            ts.close()?;
+            idx.lock().unwrap().close()?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn drop_table_indexes(&mut self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        let mut idxnames = vec![];
+        let mut ts = TableScan::new(Arc::clone(&tx), "idxcat", Arc::clone(&self.layout))?;
+        while ts.next() {
+            if ts.get_string("tablename")? == tblname {
+                idxnames.push(ts.get_string("indexname")?);
+                ts.delete()?;
+            }
+        }
+        ts.close()?;
+
+        for idxname in idxnames {
+            BTreeIndex::delete_files(Arc::clone(&tx), &idxname)?;
+        }
+        // this is a copy of the stat manager held by MetadataMgr, so it must be cleared too.
+        self.statmgr.remove_stat_info(tblname);
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -154,6 +231,11 @@ impl IndexInfo {
     pub fn records_output(&self) -> i32 {
         self.si.records_output() / self.si.distinct_values(&self.fldname)
     }
+    // my own extends
+    pub fn range_records_output(&self) -> i32 {
+        let distinct_values = self.si.distinct_values(&self.fldname);
+        self.si.records_output() / distinct_values.clamp(1, RANGE_REDUCTION_FACTOR)
+    }
     pub fn distinct_values(&self, fname: &str) -> i32 {
         if self.fldname == fname {
             return 1;
@@ -182,6 +264,30 @@ impl IndexInfo {
             FieldType::DATE => {
                 sch.add_date_field("dataval");
             }
+            // refused by MetadataMgr::create_index, and by BTPage
+            FieldType::TEXT => {
+                sch.add_text_field("dataval");
+            }
+            FieldType::BLOB => {
+                sch.add_blob_field("dataval");
+            }
+            FieldType::BIGINT => {
+                sch.add_i64_field("dataval");
+            }
+            FieldType::DOUBLE => {
+                sch.add_f64_field("dataval");
+            }
+            FieldType::DECIMAL => {
+                let precision = self.tbl_schema.length(&self.fldname);
+                let scale = self.tbl_schema.scale(&self.fldname);
+                sch.add_decimal_field("dataval", precision, scale);
+            }
+            FieldType::TIMESTAMP => {
+                sch.add_timestamp_field("dataval");
+            }
+            FieldType::TIME => {
+                sch.add_time_field("dataval");
+            }
         }
 
         Arc::new(Layout::new(Arc::new(sch)))
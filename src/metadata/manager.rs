@@ -1,4 +1,5 @@
 use anyhow::Result;
+use core::fmt;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
@@ -11,10 +12,62 @@ use super::{
     viewmanager::ViewMgr,
 };
 use crate::{
-    record::{layout::Layout, schema::Schema},
+    query::scan::Scan,
+    record::{
+        layout::{Layout, RecordFormat},
+        schema::{FieldInfo, Schema},
+        tablescan::TableScan,
+    },
     tx::transaction::Transaction,
 };
 
+#[derive(Debug)]
+pub enum MetadataMgrError {
+    CatalogTable(String),
+    FieldNotFound(String),
+    DuplicateField(String),
+    IndexedField(String),
+    LastField(String),
+    NotNullOnNonEmptyTable(String),
+    LargeObjectIndex(String),
+}
+
+impl std::error::Error for MetadataMgrError {}
+impl fmt::Display for MetadataMgrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetadataMgrError::CatalogTable(tblname) => {
+                write!(f, "catalog table cannot be changed: {}", tblname)
+            }
+            MetadataMgrError::FieldNotFound(fldname) => {
+                write!(f, "field not found: {}", fldname)
+            }
+            MetadataMgrError::DuplicateField(fldname) => {
+                write!(f, "field already exists: {}", fldname)
+            }
+            MetadataMgrError::IndexedField(fldname) => {
+                write!(f, "field has an index, drop it first: {}", fldname)
+            }
+            MetadataMgrError::LastField(fldname) => {
+                write!(f, "cannot drop the only field of a table: {}", fldname)
+            }
+            MetadataMgrError::NotNullOnNonEmptyTable(fldname) => {
+                write!(
+                    f,
+                    "cannot add a not null field to a table with records: {}",
+                    fldname
+                )
+            }
+            MetadataMgrError::LargeObjectIndex(fldname) => {
+                write!(f, "a TEXT or BLOB field cannot be indexed: {}", fldname)
+            }
+        }
+    }
+}
+
+// my own extends
+const CATALOG_TABLES: [&str; 4] = ["tblcat", "fldcat", "viewcat", "idxcat"];
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MetadataMgr {
     tblmgr: TableMgr,
@@ -45,9 +98,188 @@ impl MetadataMgr {
     ) -> Result<()> {
         self.tblmgr.create_table(tblname, sch, tx)
     }
+    // my own extends
+    pub fn create_table_with(
+        &self,
+        tblname: &str,
+        sch: Arc<Schema>,
+        format: RecordFormat,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<()> {
+        self.tblmgr.create_table_with(tblname, sch, format, tx)
+    }
     pub fn get_layout(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<Arc<Layout>> {
         self.tblmgr.get_layout(tblname, tx)
     }
+    // my own extends
+    // drops the table along with its indexes. returns false if the table does not exist
+    pub fn drop_table(&mut self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        if !self.user_table_exists(tblname, Arc::clone(&tx))? {
+            return Ok(false);
+        }
+
+        self.idxmgr.drop_table_indexes(tblname, Arc::clone(&tx))?;
+        self.tblmgr.drop_table(tblname, tx)?;
+        self.statmgr.remove_stat_info(tblname);
+
+        Ok(true)
+    }
+    // my own extends
+    // the following alter the table by rewriting it. each returns false if the table does not exist
+    pub fn add_field(
+        &mut self,
+        tblname: &str,
+        fldname: &str,
+        fi: FieldInfo,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<bool> {
+        if !self.user_table_exists(tblname, Arc::clone(&tx))? {
+            return Ok(false);
+        }
+        let layout = self.tblmgr.get_layout(tblname, Arc::clone(&tx))?;
+        let old_sch = layout.schema();
+        if old_sch.has_field(fldname) {
+            return Err(From::from(MetadataMgrError::DuplicateField(
+                fldname.to_string(),
+            )));
+        }
+        // the existing records would have no value for the field
+        if !fi.nullable {
+            let mut ts = TableScan::new(Arc::clone(&tx), tblname, layout)?;
+            let is_empty = !ts.next();
+            ts.close()?;
+            if !is_empty {
+                return Err(From::from(MetadataMgrError::NotNullOnNonEmptyTable(
+                    fldname.to_string(),
+                )));
+            }
+        }
+
+        let (mut sch, fldmap) = Self::copy_schema(&old_sch, |f| Some(f.to_string()));
+        sch.add_field(fldname, fi.fld_type, fi.length);
+        sch.set_scale(fldname, fi.scale);
+        if !fi.nullable {
+            sch.set_not_null(fldname);
+        }
+        self.rewrite_table(tblname, sch, fldmap, tx)?;
+
+        Ok(true)
+    }
+    // my own extends
+    pub fn drop_field(
+        &mut self,
+        tblname: &str,
+        fldname: &str,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<bool> {
+        if !self.user_table_exists(tblname, Arc::clone(&tx))? {
+            return Ok(false);
+        }
+        let old_sch = self.tblmgr.get_layout(tblname, Arc::clone(&tx))?.schema();
+        if !old_sch.has_field(fldname) {
+            return Err(From::from(MetadataMgrError::FieldNotFound(
+                fldname.to_string(),
+            )));
+        }
+        if old_sch.fields().len() == 1 {
+            return Err(From::from(MetadataMgrError::LastField(fldname.to_string())));
+        }
+        // an index cannot outlive its field
+        let indexes = self.idxmgr.get_index_info(tblname, Arc::clone(&tx))?;
+        if indexes.contains_key(fldname) {
+            return Err(From::from(MetadataMgrError::IndexedField(
+                fldname.to_string(),
+            )));
+        }
+
+        let (sch, fldmap) = Self::copy_schema(&old_sch, |f| (f != fldname).then(|| f.to_string()));
+        self.rewrite_table(tblname, sch, fldmap, tx)?;
+
+        Ok(true)
+    }
+    // my own extends
+    pub fn rename_field(
+        &mut self,
+        tblname: &str,
+        oldname: &str,
+        newname: &str,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<bool> {
+        if !self.user_table_exists(tblname, Arc::clone(&tx))? {
+            return Ok(false);
+        }
+        let old_sch = self.tblmgr.get_layout(tblname, Arc::clone(&tx))?.schema();
+        if !old_sch.has_field(oldname) {
+            return Err(From::from(MetadataMgrError::FieldNotFound(
+                oldname.to_string(),
+            )));
+        }
+        if old_sch.has_field(newname) {
+            return Err(From::from(MetadataMgrError::DuplicateField(
+                newname.to_string(),
+            )));
+        }
+
+        let (sch, fldmap) = Self::copy_schema(&old_sch, |f| {
+            if f == oldname {
+                Some(newname.to_string())
+            } else {
+                Some(f.to_string())
+            }
+        });
+        self.idxmgr
+            .rename_field(tblname, oldname, newname, Arc::clone(&tx))?;
+        self.rewrite_table(tblname, sch, fldmap, tx)?;
+
+        Ok(true)
+    }
+    // the catalog tables can be neither dropped nor altered
+    fn user_table_exists(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        if CATALOG_TABLES.contains(&tblname) {
+            return Err(From::from(MetadataMgrError::CatalogTable(
+                tblname.to_string(),
+            )));
+        }
+
+        self.tblmgr.has_table(tblname, tx)
+    }
+    // builds a schema keeping the order of the old fields.
+    // newname maps an old field to its new name, or to None if it is dropped.
+    fn copy_schema(
+        old_sch: &Schema,
+        newname: impl Fn(&str) -> Option<String>,
+    ) -> (Schema, HashMap<String, String>) {
+        let mut sch = Schema::new();
+        let mut fldmap = HashMap::new();
+        for fldname in old_sch.fields() {
+            if let Some(newfld) = newname(fldname) {
+                sch.add_field(
+                    &newfld,
+                    old_sch.field_type(fldname),
+                    old_sch.length(fldname),
+                );
+                sch.set_scale(&newfld, old_sch.scale(fldname));
+                if !old_sch.is_nullable(fldname) {
+                    sch.set_not_null(&newfld);
+                }
+                fldmap.insert(newfld, fldname.to_string());
+            }
+        }
+
+        (sch, fldmap)
+    }
+    fn rewrite_table(
+        &mut self,
+        tblname: &str,
+        sch: Schema,
+        fldmap: HashMap<String, String>,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<()> {
+        self.tblmgr
+            .alter_table(tblname, Arc::new(sch), &fldmap, Arc::clone(&tx))?;
+        self.statmgr.remove_stat_info(tblname);
+        self.idxmgr.rebuild_indexes(tblname, tx)
+    }
     pub fn create_view(
         &self,
         viewname: &str,
@@ -59,6 +291,10 @@ impl MetadataMgr {
     pub fn get_view_def(&self, viewname: &str, tx: Arc<Mutex<Transaction>>) -> Result<String> {
         self.viewmgr.get_view_def(viewname, tx)
     }
+    // my own extends
+    pub fn drop_view(&self, viewname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        self.viewmgr.drop_view(viewname, tx)
+    }
     pub fn create_index(
         &self,
         idxname: &str,
@@ -66,8 +302,20 @@ impl MetadataMgr {
         fldname: &str,
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<()> {
+        // my own extends
+        // a large object does not fit in an index record
+        let sch = self.tblmgr.get_layout(tblname, Arc::clone(&tx))?.schema();
+        if sch.has_field(fldname) && sch.field_type(fldname).is_large_object() {
+            return Err(From::from(MetadataMgrError::LargeObjectIndex(
+                fldname.to_string(),
+            )));
+        }
         self.idxmgr.create_index(idxname, tblname, fldname, tx)
     }
+    // my own extends
+    pub fn drop_index(&self, idxname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        self.idxmgr.drop_index(idxname, tx)
+    }
     pub fn get_index_info(
         &mut self,
         tblname: &str,
@@ -128,6 +376,15 @@ mod tests {
                 }
                 FieldType::BOOL => "bool".to_string(),
                 FieldType::DATE => "date".to_string(),
+                FieldType::TEXT => "text".to_string(),
+                FieldType::BLOB => "blob".to_string(),
+                FieldType::BIGINT => "bigint".to_string(),
+                FieldType::DOUBLE => "double".to_string(),
+                FieldType::DECIMAL => {
+                    format!("decimal({}, {})", sch2.length(fldname), sch2.scale(fldname))
+                }
+                FieldType::TIMESTAMP => "timestamp".to_string(),
+                FieldType::TIME => "time".to_string(),
             };
             println!("{}: {}", fldname, fld_type);
         }
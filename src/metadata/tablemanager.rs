@@ -1,4 +1,5 @@
 use anyhow::Result;
+use core::fmt;
 use num_traits::FromPrimitive;
 use std::{
     collections::HashMap,
@@ -7,14 +8,47 @@ use std::{
 };
 
 use crate::{
-    query::{scan::Scan, updatescan::UpdateScan},
-    record::{layout::Layout, schema::Schema, tablescan::TableScan},
+    file::block_id::BlockId,
+    query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
+    record::{
+        layout::{Layout, RecordFormat},
+        overflow::overflow_file,
+        schema::Schema,
+        tablescan::TableScan,
+    },
     tx::transaction::Transaction,
 };
 
 // table or field name
 pub const MAX_NAME: usize = 16;
 
+// my own extends
+// the version of the layout of tblcat and fldcat, kept in its own file.
+// version 1 added tblcat.format, fldcat.nullable and fldcat.scale.
+// a database without the file was created with the original layout.
+const CATALOG_VERSION: i32 = 1;
+const CATALOG_VERSION_FILE: &str = "catalog.ver";
+
+#[derive(Debug)]
+pub enum TableMgrError {
+    IncompatibleCatalog(i32),
+}
+
+impl std::error::Error for TableMgrError {}
+impl fmt::Display for TableMgrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableMgrError::IncompatibleCatalog(version) => {
+                write!(
+                    f,
+                    "the database was created with catalog version {}, but version {} is required; recreate the database",
+                    version, CATALOG_VERSION
+                )
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TableMgr {
     tcat_layout: Arc<Layout>,
@@ -26,6 +60,7 @@ impl TableMgr {
         let mut tcat_schema = Schema::new();
         tcat_schema.add_string_field("tblname", MAX_NAME);
         tcat_schema.add_i32_field("slotsize");
+        tcat_schema.add_i32_field("format");
         let tcat_layout = Arc::new(Layout::new(Arc::new(tcat_schema)));
         let mut fcat_schema = Schema::new();
         fcat_schema.add_string_field("tblname", MAX_NAME);
@@ -33,6 +68,8 @@ impl TableMgr {
         fcat_schema.add_i32_field("type");
         fcat_schema.add_i32_field("length");
         fcat_schema.add_i32_field("offset");
+        fcat_schema.add_bool_field("nullable");
+        fcat_schema.add_i32_field("scale");
         let fcat_layout = Arc::new(Layout::new(Arc::new(fcat_schema)));
         let mgr = Self {
             tcat_layout,
@@ -42,6 +79,16 @@ impl TableMgr {
         if is_new {
             mgr.create_table("tblcat", mgr.tcat_layout.schema(), Arc::clone(&tx))?;
             mgr.create_table("fldcat", mgr.fcat_layout.schema(), Arc::clone(&tx))?;
+            let mut tx = tx.lock().unwrap();
+            let blk = tx.append(CATALOG_VERSION_FILE)?;
+            tx.pin(&blk)?;
+            tx.set_i32(&blk, 0, CATALOG_VERSION, true)?;
+            tx.unpin(&blk)?;
+        } else {
+            let version = catalog_version(&mut tx.lock().unwrap())?;
+            if version != CATALOG_VERSION {
+                return Err(From::from(TableMgrError::IncompatibleCatalog(version)));
+            }
         }
 
         Ok(mgr)
@@ -52,12 +99,23 @@ impl TableMgr {
         sch: Arc<Schema>,
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<()> {
-        let layout = Layout::new(sch);
+        self.create_table_with(tblname, sch, RecordFormat::Fixed, tx)
+    }
+    // my own extends
+    pub fn create_table_with(
+        &self,
+        tblname: &str,
+        sch: Arc<Schema>,
+        format: RecordFormat,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<()> {
+        let layout = Layout::new_with_format(sch, format);
         // insert one record into tblcat
         let mut tcat = TableScan::new(Arc::clone(&tx), "tblcat", Arc::clone(&self.tcat_layout))?;
         tcat.insert()?;
         tcat.set_string("tblname", tblname.to_string())?;
         tcat.set_i32("slotsize", layout.slot_size() as i32)?;
+        tcat.set_i32("format", format as i32)?;
         tcat.close()?;
         // insert a record into fldcat for each field
         let mut fcat = TableScan::new(tx, "fldcat", Arc::clone(&self.fcat_layout))?;
@@ -68,6 +126,8 @@ impl TableMgr {
             fcat.set_i32("type", layout.schema().field_type(fldname) as i32)?;
             fcat.set_i32("length", layout.schema().length(fldname) as i32)?;
             fcat.set_i32("offset", layout.offset(fldname) as i32)?;
+            fcat.set_bool("nullable", layout.schema().is_nullable(fldname))?;
+            fcat.set_i32("scale", layout.schema().scale(fldname) as i32)?;
         }
         fcat.close()?;
 
@@ -75,10 +135,12 @@ impl TableMgr {
     }
     pub fn get_layout(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<Arc<Layout>> {
         let mut size = -1;
+        let mut format = RecordFormat::Fixed;
         let mut tcat = TableScan::new(Arc::clone(&tx), "tblcat", Arc::clone(&self.tcat_layout))?;
         while tcat.next() {
             if tcat.get_string("tblname")? == tblname {
                 size = tcat.get_i32("slotsize")?;
+                format = FromPrimitive::from_i32(tcat.get_i32("format")?).unwrap_or_default();
                 break;
             }
         }
@@ -95,13 +157,148 @@ impl TableMgr {
                 let offset = fcat.get_i32("offset")? as usize;
                 offsets.insert(fldname.clone(), offset);
                 sch.add_field(&fldname, fldtype, fldlen);
+                sch.set_scale(&fldname, fcat.get_i32("scale")? as usize);
+                if !fcat.get_bool("nullable")? {
+                    sch.set_not_null(&fldname);
+                }
             }
         }
         fcat.close()?;
 
-        let layout = Arc::new(Layout::new_with(Arc::new(sch), offsets, size as usize));
+        let layout = Arc::new(Layout::new_with(
+            Arc::new(sch),
+            offsets,
+            size as usize,
+            format,
+        ));
         Ok(layout)
     }
+    // my own extends
+    pub fn has_table(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        let mut found = false;
+        let mut tcat = TableScan::new(tx, "tblcat", Arc::clone(&self.tcat_layout))?;
+        while tcat.next() {
+            if tcat.get_string("tblname")? == tblname {
+                found = true;
+                break;
+            }
+        }
+        tcat.close()?;
+
+        Ok(found)
+    }
+    // my own extends
+    // returns false if the table does not exist
+    pub fn drop_table(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        if !self.remove_catalog(tblname, Arc::clone(&tx))? {
+            return Ok(false);
+        }
+
+        // if you change the name, you must change TableScan, too.
+        let tblfile = format!("{}.tbl", tblname);
+        tx.lock().unwrap().delete_file(&tblfile)?;
+        tx.lock().unwrap().delete_file(&overflow_file(&tblfile))?;
+
+        Ok(true)
+    }
+    // my own extends
+    // rewrites every record of the table under the layout of the new schema.
+    // fldmap maps a field of the new schema to the old field its values come from;
+    // the fields not in it are set to null.
+    pub fn alter_table(
+        &self,
+        tblname: &str,
+        sch: Arc<Schema>,
+        fldmap: &HashMap<String, String>,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<()> {
+        let old_layout = self.get_layout(tblname, Arc::clone(&tx))?;
+        // '~' never appears in an identifier, so the name cannot clash with a table.
+        // a table altered twice in a transaction still has its first copy pending deletion.
+        let mut n = 0;
+        while tx
+            .lock()
+            .unwrap()
+            .file_exists(&format!("{}~{}.tbl", tblname, n))
+        {
+            n += 1;
+        }
+        let oldtbl = format!("{}~{}", tblname, n);
+        let (tblfile, oldfile) = (format!("{}.tbl", tblname), format!("{}.tbl", oldtbl));
+        tx.lock().unwrap().rename_file(&tblfile, &oldfile)?;
+        // the large objects go along with the records
+        if tx.lock().unwrap().file_exists(&overflow_file(&tblfile)) {
+            tx.lock()
+                .unwrap()
+                .rename_file(&overflow_file(&tblfile), &overflow_file(&oldfile))?;
+        }
+
+        self.remove_catalog(tblname, Arc::clone(&tx))?;
+        self.create_table_with(
+            tblname,
+            Arc::clone(&sch),
+            old_layout.format(),
+            Arc::clone(&tx),
+        )?;
+        let new_layout = self.get_layout(tblname, Arc::clone(&tx))?;
+
+        let mut src = TableScan::new(Arc::clone(&tx), &oldtbl, old_layout)?;
+        let mut dest = TableScan::new(Arc::clone(&tx), tblname, new_layout)?;
+        while src.next() {
+            dest.insert()?;
+            for fldname in sch.fields() {
+                let val = match fldmap.get(fldname) {
+                    Some(oldname) => src.get_val(oldname)?,
+                    None => Constant::Null,
+                };
+                dest.set_val(fldname, val)?;
+            }
+        }
+        src.close()?;
+        dest.close()?;
+
+        tx.lock().unwrap().delete_file(&oldfile)?;
+        tx.lock().unwrap().delete_file(&overflow_file(&oldfile))
+    }
+    // my own extends
+    // removes the rows of tblcat and fldcat. returns false if the table does not exist
+    fn remove_catalog(&self, tblname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        let mut found = false;
+        let mut tcat = TableScan::new(Arc::clone(&tx), "tblcat", Arc::clone(&self.tcat_layout))?;
+        while tcat.next() {
+            if tcat.get_string("tblname")? == tblname {
+                tcat.delete()?;
+                found = true;
+            }
+        }
+        tcat.close()?;
+        if !found {
+            return Ok(false);
+        }
+
+        let mut fcat = TableScan::new(Arc::clone(&tx), "fldcat", Arc::clone(&self.fcat_layout))?;
+        while fcat.next() {
+            if fcat.get_string("tblname")? == tblname {
+                fcat.delete()?;
+            }
+        }
+        fcat.close()?;
+
+        Ok(true)
+    }
+}
+
+// my own extends
+fn catalog_version(tx: &mut Transaction) -> Result<i32> {
+    if !tx.file_exists(CATALOG_VERSION_FILE) || tx.size(CATALOG_VERSION_FILE)? == 0 {
+        return Ok(0);
+    }
+    let blk = BlockId::new(CATALOG_VERSION_FILE, 0);
+    tx.pin(&blk)?;
+    let version = tx.get_i32(&blk, 0)?;
+    tx.unpin(&blk)?;
+
+    Ok(version)
 }
 
 #[cfg(test)]
@@ -143,6 +340,15 @@ mod tests {
                 }
                 FieldType::BOOL => "bool".to_string(),
                 FieldType::DATE => "date".to_string(),
+                FieldType::TEXT => "text".to_string(),
+                FieldType::BLOB => "blob".to_string(),
+                FieldType::BIGINT => "bigint".to_string(),
+                FieldType::DOUBLE => "double".to_string(),
+                FieldType::DECIMAL => {
+                    format!("decimal({}, {})", sch2.length(fldname), sch2.scale(fldname))
+                }
+                FieldType::TIMESTAMP => "timestamp".to_string(),
+                FieldType::TIME => "time".to_string(),
             };
             println!("{}: {}", fldname, fld_type);
         }
@@ -190,4 +396,26 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn catalog_version_test() -> Result<()> {
+        if Path::new("_test/catalogvertest").exists() {
+            fs::remove_dir_all("_test/catalogvertest")?;
+        }
+
+        SimpleDB::new("_test/catalogvertest")?;
+        // reopening a database of the current layout
+        SimpleDB::new("_test/catalogvertest")?;
+
+        // a database created before the catalog version was kept
+        fs::remove_file("_test/catalogvertest/catalog.ver")?;
+        let err = SimpleDB::new("_test/catalogvertest").err().unwrap();
+        println!("{}", err);
+        match err.downcast_ref::<TableMgrError>() {
+            Some(TableMgrError::IncompatibleCatalog(0)) => {}
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        Ok(())
+    }
 }
@@ -53,6 +53,23 @@ impl ViewMgr {
 
         Ok(result)
     }
+    // my own extends
+    // returns false if the view does not exist
+    pub fn drop_view(&self, vname: &str, tx: Arc<Mutex<Transaction>>) -> Result<bool> {
+        let mut found = false;
+
+        let layout = self.tbl_mgr.get_layout("viewcat", Arc::clone(&tx))?;
+        let mut ts = TableScan::new(tx, "viewcat", layout)?;
+        while ts.next() {
+            if ts.get_string("viewname")? == vname {
+                ts.delete()?;
+                found = true;
+            }
+        }
+        ts.close()?;
+
+        Ok(found)
+    }
 }
 
 #[cfg(test)]
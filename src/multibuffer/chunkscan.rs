@@ -126,9 +126,47 @@ impl Scan for ChunkScan {
         match self.layout.schema().field_type(fldname) {
             FieldType::SMALLINT => Ok(Constant::new_i16(self.get_i16(fldname)?)),
             FieldType::INTEGER => Ok(Constant::new_i32(self.get_i32(fldname)?)),
-            FieldType::VARCHAR => Ok(Constant::new_string(self.get_string(fldname)?)),
+            FieldType::VARCHAR | FieldType::TEXT => {
+                Ok(Constant::new_string(self.get_string(fldname)?))
+            }
             FieldType::BOOL => Ok(Constant::new_bool(self.get_bool(fldname)?)),
             FieldType::DATE => Ok(Constant::new_date(self.get_date(fldname)?)),
+            FieldType::BLOB => Ok(Constant::new_blob(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_blob(self.currentslot, fldname)?,
+            )),
+            FieldType::BIGINT => Ok(Constant::new_i64(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_i64(self.currentslot, fldname)?,
+            )),
+            FieldType::DOUBLE => Ok(Constant::new_f64(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_f64(self.currentslot, fldname)?,
+            )),
+            FieldType::DECIMAL => Ok(Constant::new_decimal(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_decimal(self.currentslot, fldname)?,
+            )),
+            FieldType::TIMESTAMP => Ok(Constant::new_timestamp(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_timestamp(self.currentslot, fldname)?,
+            )),
+            FieldType::TIME => Ok(Constant::new_time(
+                self.rp
+                    .as_mut()
+                    .unwrap()
+                    .get_time(self.currentslot, fldname)?,
+            )),
         }
     }
     fn has_field(&self, fldname: &str) -> bool {
@@ -1,13 +1,31 @@
 use anyhow::Result;
 use combine::Parser;
 use core::fmt;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
 use super::tableplanner::TablePlanner;
 use crate::{
+    materialize::{groupbyplan::GroupByPlan, sortplan::SortPlan},
     metadata::manager::MetadataMgr,
-    parser::{parser::query, querydata::QueryData},
-    plan::{plan::Plan, planner::Planner, projectplan::ProjectPlan, queryplanner::QueryPlanner},
+    multibuffer::multibufferproductplan::MultibufferProductPlan,
+    parser::{
+        parser::query,
+        querydata::{JoinedTable, QueryData},
+    },
+    plan::{
+        plan::Plan,
+        planner::Planner,
+        productplan::ProductPlan,
+        projectplan::ProjectPlan,
+        queryplanner::{check_aggregations, check_sort_fields, qualify_tables, QueryPlanner},
+        selectplan::SelectPlan,
+        tableplan::TablePlan,
+    },
+    query::join::join_schema,
+    record::schema::Schema,
     tx::transaction::Transaction,
 };
 
@@ -110,37 +128,148 @@ impl HeuristicQueryPlanner {
     }
 
     // view support
-    fn create_tableplanners_rec(
+    // my own extends
+    // returns the plans of the views and the items with outer joins, which are not reordered.
+    // a view is planned by itself, because the names of its tables are not visible from the query.
+    fn create_tableplanners(
         &mut self,
         data: &QueryData,
+        qualified: &HashSet<String>,
+        views: &HashMap<String, Arc<dyn Plan>>,
         tx: Arc<Mutex<Transaction>>,
-    ) -> Result<()> {
-        for tblname in data.tables().iter() {
-            let mut viewdef = self
-                .mdm
-                .lock()
-                .unwrap()
-                .get_view_def(tblname, Arc::clone(&tx))?;
-            if !viewdef.is_empty() {
-                let mut parser = query();
-                // NOTE: query parser expect terminater.
-                viewdef = format!("{};", viewdef);
-                let (viewdata, _) = parser.parse(viewdef.as_str())?;
-                self.create_tableplanners_rec(&viewdata, Arc::clone(&tx))?;
+    ) -> Result<Vec<Arc<dyn Plan>>> {
+        let mut plans = vec![];
+        for name in data.tables().iter() {
+            if let Some(p) = views.get(name) {
+                plans.push(Arc::clone(p));
                 continue;
             }
 
-            let tp = TablePlanner::new(
+            let tp = TablePlanner::new_qualified(
                 Arc::clone(&self.next_table_num),
-                tblname,
+                data.table_of(name),
+                name,
+                qualified,
                 data.pred().clone(),
                 Arc::clone(&tx),
                 Arc::clone(&self.mdm),
             );
             self.tableplanners.push(tp)
         }
+        for jt in data.joined_tables().iter() {
+            plans.push(self.create_joined_plan(data, jt, qualified, views, Arc::clone(&tx))?);
+        }
+
+        Ok(plans)
+    }
+    fn get_view_data(
+        &self,
+        tblname: &str,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<Option<QueryData>> {
+        let mut viewdef = self.mdm.lock().unwrap().get_view_def(tblname, tx)?;
+        if viewdef.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = query();
+        // NOTE: query parser expect terminater.
+        viewdef = format!("{};", viewdef);
+        let (viewdata, _) = parser.parse(viewdef.as_str())?;
+
+        Ok(Some(viewdata))
+    }
+    // my own extends
+    fn create_view_plan(
+        &self,
+        viewdata: QueryData,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<Arc<dyn Plan>> {
+        let mut planner =
+            HeuristicQueryPlanner::new(Arc::clone(&self.next_table_num), Arc::clone(&self.mdm));
+        planner.create_plan(viewdata, tx)
+    }
+    // my own extends
+    // the tables are joined in the order written, because an outer join can't be reordered
+    fn create_joined_plan(
+        &self,
+        data: &QueryData,
+        jt: &JoinedTable,
+        qualified: &HashSet<String>,
+        views: &HashMap<String, Arc<dyn Plan>>,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<Arc<dyn Plan>> {
+        let name = jt.table_name();
+        let mut p: Arc<dyn Plan> = match views.get(name) {
+            Some(p) => Arc::clone(p),
+            None => Arc::new(TablePlan::new_qualified(
+                data.table_of(name),
+                name,
+                qualified,
+                Arc::clone(&tx),
+                Arc::clone(&self.mdm),
+            )?),
+        };
+        for join in jt.joins() {
+            let name = join.table_name();
+            if let Some(rhs) = views.get(name) {
+                let sch = join_schema(p.schema(), rhs.schema(), join.join_type());
+                join.pred().check_types(&sch)?;
+                p = Arc::new(ProductPlan::new_outer(
+                    p,
+                    Arc::clone(rhs),
+                    join.pred().clone(),
+                    join.join_type(),
+                ));
+                continue;
+            }
+
+            let tp = TablePlanner::new_qualified(
+                Arc::clone(&self.next_table_num),
+                data.table_of(name),
+                name,
+                qualified,
+                join.pred().clone(),
+                Arc::clone(&tx),
+                Arc::clone(&self.mdm),
+            );
+            p = tp.make_outer_join_plan(p, join.join_type())?;
+        }
 
-        Ok(())
+        Ok(p)
+    }
+    // the views of the query are planned into views, and the fields which more than one
+    // table (or view) has are qualified by its alias (or name), see queryplanner::qualify_tables
+    fn qualify_tables(
+        &self,
+        data: &QueryData,
+        views: &mut HashMap<String, Arc<dyn Plan>>,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<(HashSet<String>, Schema)> {
+        let mut schemas = vec![];
+        for name in data.range_names() {
+            let tblname = data.table_of(name);
+            let sch = match self.get_view_data(tblname, Arc::clone(&tx))? {
+                Some(viewdata) => {
+                    let p = self.create_view_plan(viewdata, Arc::clone(&tx))?;
+                    let sch = p.schema();
+                    views.insert(name.to_string(), p);
+                    sch
+                }
+                None => {
+                    let mdm = self.mdm.lock().unwrap();
+                    mdm.get_layout(tblname, Arc::clone(&tx))?.schema()
+                }
+            };
+            schemas.push((name.to_string(), sch));
+        }
+        let (qualified, scope) = qualify_tables(&schemas)?;
+        for (name, p) in views.iter_mut() {
+            if p.schema().fields().iter().any(|f| qualified.contains(f)) {
+                *p = Arc::new(ProjectPlan::new_qualified(Arc::clone(p), name, &qualified));
+            }
+        }
+
+        Ok((qualified, scope))
     }
 }
 
@@ -150,11 +279,24 @@ impl QueryPlanner for HeuristicQueryPlanner {
         data: QueryData,
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<Arc<dyn Plan>> {
+        // my own extends
+        // Step 0, Qualify the fields which more than one table (or view) has by its alias
+        // (or name), and resolve the field names of the query
+        let mut views = HashMap::new();
+        let (qualified, scope) = self.qualify_tables(&data, &mut views, Arc::clone(&tx))?;
+        let data = data.resolve(&scope)?;
+
         // Step 1, Create a TablePlanner object for each mentioned table
-        self.create_tableplanners_rec(&data, Arc::clone(&tx))?;
+        let mut joinplans =
+            self.create_tableplanners(&data, &qualified, &views, Arc::clone(&tx))?;
+        let has_joins = !joinplans.is_empty();
 
         // Step 2, Choose the lowest-size plan to begin the join order
-        let mut currentplan = self.get_lowest_select_plan()?;
+        let mut currentplan = if self.tableplanners.is_empty() && has_joins {
+            joinplans.remove(0)
+        } else {
+            self.get_lowest_select_plan()?
+        };
 
         // Step 3, Repeatedly add a plan to the join order
         while !self.tableplanners.is_empty() {
@@ -166,7 +308,56 @@ impl QueryPlanner for HeuristicQueryPlanner {
             }
         }
 
-        // Step 4, Project on the field names and return
+        // my own extends
+        // Step 3b, Add the views and the outer joins, and the predicate is selected after them
+        // because it must not be pushed down to the padded side.
+        for p in joinplans {
+            currentplan = Arc::new(MultibufferProductPlan::new(
+                Arc::clone(&self.next_table_num),
+                Arc::clone(&tx),
+                currentplan,
+                p,
+            ));
+        }
+
+        data.pred().check_types(&currentplan.schema())?;
+        if has_joins && !data.pred().is_empty() {
+            currentplan = Arc::new(SelectPlan::new(currentplan, data.pred().clone()));
+        }
+
+        // Step 4, Group by the group by fields and compute the aggregations
+        if !data.group_fields().is_empty() || !data.aggregations().is_empty() {
+            check_aggregations(&currentplan.schema(), data.aggregations())?;
+            let aggfns = data.aggregations().iter().map(|a| a.function()).collect();
+            currentplan = Arc::new(GroupByPlan::new(
+                Arc::clone(&self.next_table_num),
+                Arc::clone(&tx),
+                currentplan,
+                data.group_fields().clone(),
+                aggfns,
+            ));
+        }
+
+        // Step 5, Compute the expressions of the select list, which can be sorted on
+        if !data.expressions().is_empty() {
+            currentplan = Arc::new(ProjectPlan::new_extended(currentplan, data.expressions())?);
+        }
+
+        // Step 6, Sort on the order by fields
+        if !data.order_fields().is_empty() {
+            let (sortfields, orders): (Vec<String>, _) =
+                data.order_fields().iter().cloned().unzip();
+            check_sort_fields(&currentplan.schema(), &sortfields)?;
+            currentplan = Arc::new(SortPlan::new_with_orders(
+                Arc::clone(&self.next_table_num),
+                currentplan,
+                sortfields,
+                orders,
+                Arc::clone(&tx),
+            ));
+        }
+
+        // Step 7, Project on the field names and return
         let plan = ProjectPlan::new(currentplan, data.fields().clone());
         Ok(Arc::new(plan))
     }
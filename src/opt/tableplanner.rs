@@ -1,15 +1,26 @@
+use anyhow::Result;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
 use crate::{
-    index::planner::{indexjoinplan::IndexJoinPlan, indexselectplan::IndexSelectPlan},
+    index::{
+        planner::{
+            indexjoinplan::IndexJoinPlan, indexrangeselectplan::IndexRangeSelectPlan,
+            indexselectplan::IndexSelectPlan,
+        },
+        RangeBound, SearchRange,
+    },
+    materialize::mergejoinplan::MergeJoinPlan,
     metadata::{indexmanager::IndexInfo, manager::MetadataMgr},
     multibuffer::multibufferproductplan::MultibufferProductPlan,
-    plan::{plan::Plan, selectplan::SelectPlan, tableplan::TablePlan},
-    query::predicate::Predicate,
-    record::schema::Schema,
+    plan::{plan::Plan, productplan::ProductPlan, selectplan::SelectPlan, tableplan::TablePlan},
+    query::{
+        join::{join_schema, JoinType},
+        predicate::Predicate,
+    },
+    record::schema::{qualified_name, Schema},
     tx::transaction::Transaction,
 };
 
@@ -33,10 +44,50 @@ impl TablePlanner {
         tx: Arc<Mutex<Transaction>>,
         mdm: Arc<Mutex<MetadataMgr>>,
     ) -> Self {
-        let myplan = Arc::new(TablePlan::new(tblname, Arc::clone(&tx), Arc::clone(&mdm)).unwrap());
+        let qualified = HashSet::new();
+        Self::new_qualified(
+            next_table_num,
+            tblname,
+            tblname,
+            &qualified,
+            mypred,
+            tx,
+            mdm,
+        )
+    }
+    // my own extends
+    // the table referred to by the qualifier (its alias or name) in a query,
+    // whose fields in qualified are renamed to qualifier.fldname as the indexes on them.
+    pub fn new_qualified(
+        next_table_num: Arc<Mutex<i32>>,
+        tblname: &str,
+        qualifier: &str,
+        qualified: &HashSet<String>,
+        mypred: Predicate,
+        tx: Arc<Mutex<Transaction>>,
+        mdm: Arc<Mutex<MetadataMgr>>,
+    ) -> Self {
+        let myplan = Arc::new(
+            TablePlan::new_qualified(
+                tblname,
+                qualifier,
+                qualified,
+                Arc::clone(&tx),
+                Arc::clone(&mdm),
+            )
+            .unwrap(),
+        );
         let myschema = myplan.schema();
         let mut mdm = mdm.lock().unwrap();
-        let indexes = mdm.get_index_info(tblname, Arc::clone(&tx)).unwrap();
+        let indexes = mdm
+            .get_index_info(tblname, Arc::clone(&tx))
+            .unwrap()
+            .into_iter()
+            .map(|(fldname, ii)| match qualified.contains(&fldname) {
+                true => (qualified_name(qualifier, &fldname), ii),
+                false => (fldname, ii),
+            })
+            .collect();
 
         Self {
             next_table_num,
@@ -70,6 +121,52 @@ impl TablePlanner {
 
         p
     }
+    // my own extends
+    // JOIN ... ON, where mypred is the condition of ON. the condition decides which records
+    // match, so an index join or a merge join is used only if it is just an equality of fields.
+    pub fn make_outer_join_plan(
+        &self,
+        current: Arc<dyn Plan>,
+        join_type: JoinType,
+    ) -> Result<Arc<dyn Plan>> {
+        let currsch = current.schema();
+        let sch = join_schema(Arc::clone(&currsch), Arc::clone(&self.myschema), join_type);
+        self.mypred.check_types(&sch)?;
+
+        let myplan = Arc::clone(&self.myplan);
+        if self.mypred.terms().len() == 1 && self.mypred.disjunctions().is_empty() {
+            for fldname in self.myschema.fields() {
+                if let Some(outerfield) = self.mypred.equates_with_field(fldname) {
+                    if !currsch.has_field(outerfield) {
+                        continue;
+                    }
+                    // the index finds the matching records of this table only
+                    if !join_type.preserves_rhs() {
+                        if let Some(ii) = self.indexes.get(fldname) {
+                            let ii = ii.clone();
+                            let plan = IndexJoinPlan::new_outer(
+                                current, myplan, ii, outerfield, join_type,
+                            );
+                            return Ok(Arc::new(plan));
+                        }
+                    }
+                    let plan = MergeJoinPlan::new_outer(
+                        Arc::clone(&self.next_table_num),
+                        Arc::clone(&self.tx),
+                        current,
+                        myplan,
+                        outerfield,
+                        fldname,
+                        join_type,
+                    );
+                    return Ok(Arc::new(plan));
+                }
+            }
+        }
+
+        let plan = ProductPlan::new_outer(current, myplan, self.mypred.clone(), join_type);
+        Ok(Arc::new(plan))
+    }
     pub fn make_product_plan(&self, current: Arc<dyn Plan>) -> Option<Arc<dyn Plan>> {
         let myplan = Arc::clone(&self.myplan);
         if let Some(p) = self.add_select_pred(myplan) {
@@ -92,6 +189,34 @@ impl TablePlanner {
                 return Some(Arc::new(plan));
             }
         }
+        // my own extends
+        // an IN list is scanned as the ranges of its values
+        for fldname in self.indexes.keys() {
+            if let Some(vals) = self.mypred.equates_with_constants(fldname) {
+                let ranges = vals
+                    .into_iter()
+                    .map(|val| {
+                        SearchRange::new(
+                            RangeBound::Included(val.clone()),
+                            RangeBound::Included(val),
+                        )
+                    })
+                    .collect();
+                let ii = self.indexes.get(fldname).unwrap();
+                let myplan = Arc::clone(&self.myplan);
+                let plan = IndexRangeSelectPlan::new_with(myplan, ii.clone(), ranges);
+                return Some(Arc::new(plan));
+            }
+        }
+        // no equality selection, so try a range scan on an inequality selection (or a prefix LIKE)
+        for fldname in self.indexes.keys() {
+            if let Some(range) = self.mypred.range_with_constant(fldname) {
+                let ii = self.indexes.get(fldname).unwrap();
+                let myplan = Arc::clone(&self.myplan);
+                let plan = IndexRangeSelectPlan::new(myplan, ii.clone(), range);
+                return Some(Arc::new(plan));
+            }
+        }
 
         None
     }
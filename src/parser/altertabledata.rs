@@ -0,0 +1,27 @@
+use crate::record::schema::FieldInfo;
+
+// my own extends
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AlterAction {
+    AddField(String, FieldInfo),
+    DropField(String),
+    RenameField(String, String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AlterTableData {
+    tblname: String,
+    action: AlterAction,
+}
+
+impl AlterTableData {
+    pub fn new(tblname: String, action: AlterAction) -> Self {
+        Self { tblname, action }
+    }
+    pub fn table_name(&self) -> &str {
+        &self.tblname
+    }
+    pub fn action(&self) -> &AlterAction {
+        &self.action
+    }
+}
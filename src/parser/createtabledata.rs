@@ -1,14 +1,24 @@
-use crate::record::schema::Schema;
+use crate::record::{layout::RecordFormat, schema::Schema};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CreateTableData {
     tblname: String,
     sch: Schema,
+    // my own extends
+    format: RecordFormat,
 }
 
 impl CreateTableData {
     pub fn new(tblname: String, sch: Schema) -> Self {
-        Self { tblname, sch }
+        Self::new_with(tblname, sch, RecordFormat::Fixed)
+    }
+    // my own extends
+    pub fn new_with(tblname: String, sch: Schema, format: RecordFormat) -> Self {
+        Self {
+            tblname,
+            sch,
+            format,
+        }
     }
     pub fn table_name(&self) -> &str {
         &self.tblname
@@ -16,4 +26,8 @@ impl CreateTableData {
     pub fn new_schema(&self) -> &Schema {
         &self.sch
     }
+    // my own extends
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
 }
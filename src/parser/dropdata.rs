@@ -0,0 +1,18 @@
+// my own extends
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct DropData {
+    name: String,
+    if_exists: bool,
+}
+
+impl DropData {
+    pub fn new(name: String, if_exists: bool) -> Self {
+        Self { name, if_exists }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn if_exists(&self) -> bool {
+        self.if_exists
+    }
+}
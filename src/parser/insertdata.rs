@@ -24,4 +24,9 @@ impl InsertData {
     pub fn vals(&self) -> &Vec<Constant> {
         &self.vals
     }
+    // my own extends
+    pub fn val(&self, fldname: &str) -> Option<&Constant> {
+        let pos = self.flds.iter().position(|f| f == fldname)?;
+        self.vals.get(pos)
+    }
 }
@@ -1,20 +1,47 @@
 use combine::{
     any, attempt,
-    error::ParseError,
-    parser::char::{alpha_num, char, digit, letter, spaces, string, string_cmp},
-    stream::Stream,
-    {between, chainl1, many, many1, optional, satisfy, sep_by, sep_by1, Parser},
+    error::{ParseError, StreamError},
+    parser,
+    parser::char::{alpha_num, char, digit, hex_digit, letter, spaces, string, string_cmp},
+    parser::combinator::recognize,
+    parser::repeat::{skip_many, skip_many1},
+    stream::{Stream, StreamErrorFor},
+    {between, chainl1, many, many1, not_followed_by, optional, satisfy, sep_by, sep_by1, Parser},
 };
-use std::usize;
+use rust_decimal::Decimal;
+use std::{str::FromStr, usize};
 
 use super::{
-    createindexdata::CreateIndexData, createtabledata::CreateTableData,
-    createviewdata::CreateViewData, ddl::DDL, deletedata::DeleteData, dml::DML,
-    insertdata::InsertData, modifydata::ModifyData, querydata::QueryData, sql::SQL,
+    altertabledata::{AlterAction, AlterTableData},
+    createindexdata::CreateIndexData,
+    createtabledata::CreateTableData,
+    createviewdata::CreateViewData,
+    ddl::DDL,
+    deletedata::DeleteData,
+    dml::DML,
+    dropdata::DropData,
+    insertdata::InsertData,
+    modifydata::ModifyData,
+    querydata::{JoinData, JoinedTable, QueryData},
+    sql::SQL,
+    tcl::TCL,
 };
 use crate::{
-    query::{constant::Constant, expression::Expression, predicate::Predicate, term::Term},
-    record::schema::{FieldInfo, FieldType, Schema},
+    materialize::{aggregationfn::Aggregation, recordcomparator::SortOrder},
+    query::{
+        constant::Constant,
+        expression::{ArithOp, Expression},
+        join::JoinType,
+        likepattern::LikePattern,
+        predicate::Predicate,
+        scalarfn::Function,
+        term::{Operator, Term},
+    },
+    record::{
+        layout::RecordFormat,
+        schema::{qualified_name, FieldInfo, FieldType, Schema},
+    },
+    tx::concurrency::isolation::IsolationLevel,
 };
 
 /// primitive parser
@@ -30,6 +57,23 @@ where
         .skip(spaces().silent())
 }
 
+// my own extends
+// a keyword that must not be followed by an identifier character,
+// so that OR does not match the prefix of ORDER.
+fn reserved_word<Input>(s: &'static str) -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(
+        string_cmp(s, |x, y| x.eq_ignore_ascii_case(&y))
+            .skip(not_followed_by(alpha_num().or(char('_')))),
+    )
+    .map(|x| x.to_string())
+    // lexeme
+    .skip(spaces().silent())
+}
+
 fn kw_select<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
@@ -70,6 +114,118 @@ where
         .skip(spaces().silent())
 }
 
+fn kw_or<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("OR")
+}
+
+fn kw_not<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("NOT")
+}
+
+fn kw_is<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("IS")
+}
+
+fn kw_null<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("NULL")
+}
+
+fn kw_between<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("BETWEEN")
+}
+
+fn kw_like<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("LIKE")
+}
+
+fn kw_escape<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("ESCAPE")
+}
+
+fn kw_in<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("IN")
+}
+
+fn kw_join<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("JOIN")
+}
+
+fn kw_inner<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("INNER")
+}
+
+fn kw_left<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("LEFT")
+}
+
+fn kw_right<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("RIGHT")
+}
+
+fn kw_full<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("FULL")
+}
+
+fn kw_outer<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("OUTER")
+}
+
 fn kw_insert<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
@@ -200,356 +356,1285 @@ where
         .skip(spaces().silent())
 }
 
-fn kw_view<Input>() -> impl Parser<Input, Output = String>
+fn kw_text<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    keyword("VIEW")
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("TEXT")
 }
 
-fn kw_as<Input>() -> impl Parser<Input, Output = String>
+fn kw_blob<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    keyword("AS")
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("BLOB")
 }
 
-fn kw_index<Input>() -> impl Parser<Input, Output = String>
+fn kw_bigint<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    keyword("INDEX")
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("BIGINT")
 }
 
-fn kw_on<Input>() -> impl Parser<Input, Output = String>
+fn kw_double<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    keyword("ON")
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("DOUBLE").or(reserved_word("REAL"))
 }
 
-fn delim_parenl<Input>() -> impl Parser<Input, Output = char>
+fn kw_decimal<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    char('(')
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("DECIMAL")
 }
 
-fn delim_parenr<Input>() -> impl Parser<Input, Output = char>
+fn kw_timestamp<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    char(')')
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("TIMESTAMP")
 }
 
-fn delim_comma<Input>() -> impl Parser<Input, Output = char>
+fn kw_time<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    char(',')
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("TIME")
 }
 
-fn binop_eq<Input>() -> impl Parser<Input, Output = char>
+fn kw_cast<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    char('=')
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("CAST")
 }
 
-fn terminate<Input>() -> impl Parser<Input, Output = char>
+fn kw_view<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    char(';')
+    keyword("VIEW")
         // lexeme
         .skip(spaces().silent())
 }
 
-/// token
+fn kw_as<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    // NOTE: AS must not match the prefix of ASC.
+    reserved_word("AS")
+}
 
-fn id_tok<Input>() -> impl Parser<Input, Output = String>
+fn kw_index<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    letter()
-        .and(many(alpha_num().or(char('_'))))
-        .map(|(x, mut xs): (char, Vec<char>)| {
-            xs.insert(0, x);
-            xs.into_iter().collect()
-        })
+    keyword("INDEX")
         // lexeme
         .skip(spaces().silent())
 }
 
-fn i32_tok<Input>() -> impl Parser<Input, Output = i32>
+fn kw_on<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    optional(char('-').or(char('+')))
-        .and(many1(digit()).map(|s: String| s.parse::<i32>()))
-        .map(|(s, v)| {
-            if let Some(sign) = s {
-                if sign == '-' {
-                    return v.unwrap_or_default() * -1;
-                }
-            }
-            v.unwrap_or_default()
-        })
+    keyword("ON")
         // lexeme
         .skip(spaces().silent())
 }
 
-fn str_tok<Input>() -> impl Parser<Input, Output = String>
+fn kw_drop<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let almost = satisfy(|c| c != '\'' && c != '\\');
-    let escaped = attempt(char('\\').with(any()));
-    let quote_quote = attempt(char('\'').skip(char('\'')));
-    let internal_string = almost.or(escaped).or(quote_quote);
-
-    between(
-        char('\''),
-        char('\''),
-        many(internal_string).map(|v: Vec<char>| v.into_iter().collect::<String>()),
-    )
-    // lexeme
-    .skip(spaces().silent())
+    reserved_word("DROP")
 }
 
-fn bool_tok<Input>() -> impl Parser<Input, Output = bool>
+fn kw_if<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    attempt(string("false"))
-        .map(|_| false)
-        .or(string("true").map(|_| true))
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("IF")
 }
 
-/// Methods for parsing predicates and their components
-
-fn field<Input>() -> impl Parser<Input, Output = String>
+fn kw_exists<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    id_tok()
+    reserved_word("EXISTS")
 }
 
-fn constant<Input>() -> impl Parser<Input, Output = Constant>
+fn kw_alter<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    attempt(str_tok())
-        .map(|sval| Constant::new_string(sval))
-        .or(attempt(i32_tok()).map(|ival| Constant::new_i32(ival))) // pick it up as the largest signed integer
-        .or(bool_tok().map(|bval| Constant::new_bool(bval)))
-        // lexeme
-        .skip(spaces().silent())
+    reserved_word("ALTER")
 }
 
-fn expression<Input>() -> impl Parser<Input, Output = Expression>
+fn kw_add<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    // try constant first, because field can get bool value too.
-    attempt(constant())
-        .map(|c| Expression::Val(c))
-        .or(field().map(|fldname| Expression::new_fldname(fldname)))
+    reserved_word("ADD")
 }
 
-fn term<Input>() -> impl Parser<Input, Output = Term>
+fn kw_rename<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    expression()
-        .skip(binop_eq())
-        .and(expression())
-        .map(|(lhs, rhs)| Term::new(lhs, rhs))
+    reserved_word("RENAME")
 }
 
-fn predicate<Input>() -> impl Parser<Input, Output = Predicate>
+fn kw_column<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let pred1 = term().map(|t| Predicate::new(t));
-    let conjoin = kw_and().map(|_| {
-        |mut l: Predicate, mut r: Predicate| {
-            l.conjoin_with(&mut r);
-            l
-        }
-    });
-
-    chainl1(pred1, conjoin)
+    reserved_word("COLUMN")
 }
 
-/// Methods for parsing queries
-
-pub fn query<Input>() -> impl Parser<Input, Output = QueryData>
+fn kw_to<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let fields = kw_select().with(select_list());
-    let tables = kw_from().with(table_list());
-    let where_clause = kw_where().with(predicate());
-
-    fields
-        .and(tables)
-        .and(optional(where_clause))
-        .skip(terminate())
-        .map(|((fs, ts), op)| {
-            let pred = op.unwrap_or(Predicate::new_empty());
-            QueryData::new(fs, ts, pred)
-        })
+    reserved_word("TO")
 }
 
-fn select_list<Input>() -> impl Parser<Input, Output = Vec<String>>
+fn kw_begin<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let fld1 = field().map(|f| vec![f]);
-    let sep = delim_comma().map(|_| {
-        |mut x: Vec<String>, mut y: Vec<String>| {
-            x.append(&mut y);
-            x
-        }
-    });
-
-    chainl1(fld1, sep)
+    reserved_word("BEGIN")
 }
 
-fn table_list<Input>() -> impl Parser<Input, Output = Vec<String>>
+fn kw_commit<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let id_tok1 = id_tok().map(|f| vec![f]);
-    let sep = delim_comma().map(|_| {
-        |mut x: Vec<String>, mut y: Vec<String>| {
-            x.append(&mut y);
-            x
-        }
-    });
-
-    chainl1(id_tok1, sep)
+    reserved_word("COMMIT")
 }
 
-/// Methods for parsing the various update commands
-
-pub fn update_cmd<Input>() -> impl Parser<Input, Output = SQL>
+fn kw_rollback<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    attempt(insert().map(|i| SQL::DML(DML::Insert(i))))
-        .or(attempt(delete().map(|d| SQL::DML(DML::Delete(d)))))
-        .or(attempt(modify().map(|m| SQL::DML(DML::Modify(m)))))
-        .or(ddl().map(|ddl| SQL::DDL(ddl)))
+    reserved_word("ROLLBACK")
 }
 
-fn ddl<Input>() -> impl Parser<Input, Output = DDL>
+fn kw_savepoint<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    attempt(create_table().map(|t| DDL::Table(t)))
-        .or(attempt(create_view().map(|v| DDL::View(v))))
-        .or(create_index().map(|i| DDL::Index(i)))
+    reserved_word("SAVEPOINT")
 }
 
-/// Method for parsing delete commands
-
-pub fn delete<Input>() -> impl Parser<Input, Output = DeleteData>
+fn kw_transaction<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let prelude = kw_delete().and(kw_from());
-    let where_clause = kw_where().with(predicate());
-
-    prelude
-        .with(id_tok())
-        .and(optional(where_clause))
-        .skip(terminate())
-        .map(|(tblname, opred)| {
-            let pred = opred.unwrap_or(Predicate::new_empty());
-            DeleteData::new(tblname, pred)
-        })
+    reserved_word("TRANSACTION")
 }
 
-/// Methods for parsing insert commands
-
-pub fn insert<Input>() -> impl Parser<Input, Output = InsertData>
+fn kw_isolation<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    let prelude = kw_insert().and(kw_into());
-    let fields = between(delim_parenl(), delim_parenr(), field_list());
-    let vals = kw_values().with(between(delim_parenl(), delim_parenr(), const_list()));
-
-    prelude
-        .with(id_tok())
-        .and(fields)
-        .and(vals)
-        .skip(terminate())
-        .map(|((t, fs), vs)| InsertData::new(t, fs, vs))
+    reserved_word("ISOLATION")
 }
 
-fn field_list<Input>() -> impl Parser<Input, Output = Vec<String>>
+fn kw_level<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    sep_by1(field(), delim_comma())
+    reserved_word("LEVEL")
 }
 
-fn const_list<Input>() -> impl Parser<Input, Output = Vec<Constant>>
+fn kw_read<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    sep_by1(constant(), delim_comma())
+    reserved_word("READ")
 }
 
-/// Method for parsing modify commands
+fn kw_uncommitted<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("UNCOMMITTED")
+}
+
+fn kw_committed<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("COMMITTED")
+}
+
+fn kw_repeatable<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("REPEATABLE")
+}
+
+fn kw_serializable<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("SERIALIZABLE")
+}
+
+fn kw_format<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("FORMAT")
+}
+
+fn kw_fixed<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("FIXED")
+}
+
+fn kw_slotted<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    reserved_word("SLOTTED")
+}
+
+fn kw_order<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("ORDER")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_by<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("BY")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_asc<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("ASC")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_desc<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("DESC")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_group<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("GROUP")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_count<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("COUNT")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_sum<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("SUM")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_min<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("MIN")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_max<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("MAX")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn kw_avg<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    keyword("AVG")
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn delim_parenl<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char('(')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn delim_parenr<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char(')')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn delim_comma<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char(',')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn delim_asterisk<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char('*')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn binop_eq<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char('=')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn binop<Input>() -> impl Parser<Input, Output = Operator>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(string("<=").map(|_| Operator::Le))
+        .or(attempt(string("<>").map(|_| Operator::Ne)))
+        .or(attempt(string(">=").map(|_| Operator::Ge)))
+        .or(attempt(string("!=").map(|_| Operator::Ne)))
+        .or(char('<').map(|_| Operator::Lt))
+        .or(char('>').map(|_| Operator::Gt))
+        .or(char('=').map(|_| Operator::Eq))
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn terminate<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    char(';')
+        // lexeme
+        .skip(spaces().silent())
+}
+
+/// token
+
+fn id_tok<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    letter()
+        .and(many(alpha_num().or(char('_'))))
+        .map(|(x, mut xs): (char, Vec<char>)| {
+            xs.insert(0, x);
+            xs.into_iter().collect()
+        })
+        // lexeme
+        .skip(spaces().silent())
+}
+
+fn i32_tok<Input>() -> impl Parser<Input, Output = i32>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    optional(char('-').or(char('+')))
+        .and(many1(digit()).map(|s: String| s.parse::<i32>()))
+        .map(|(s, v)| {
+            if let Some(sign) = s {
+                if sign == '-' {
+                    return v.unwrap_or_default() * -1;
+                }
+            }
+            v.unwrap_or_default()
+        })
+        // lexeme
+        .skip(spaces().silent())
+}
+
+// my own extends
+// an integer is the smaller of INTEGER and BIGINT that holds it.
+// a number with a decimal point is a DECIMAL, and one with an exponent is a DOUBLE.
+fn number_tok<Input>() -> impl Parser<Input, Output = Constant>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let sign = || optional(char('-').or(char('+')));
+    let fraction = char('.').with(skip_many(digit()));
+    let exponent = attempt(
+        char('e')
+            .or(char('E'))
+            .with(sign())
+            .with(skip_many1(digit())),
+    );
+
+    recognize((
+        sign(),
+        skip_many1(digit()),
+        optional(fraction),
+        optional(exponent),
+    ))
+    .map(|s: String| {
+        let approx = || Constant::new_f64(s.parse().unwrap_or(f64::NAN));
+        if s.contains(['e', 'E']) {
+            return approx();
+        }
+        if !s.contains('.') {
+            if let Ok(ival) = s.parse::<i32>() {
+                return Constant::new_i32(ival);
+            }
+            if let Ok(ival) = s.parse::<i64>() {
+                return Constant::new_i64(ival);
+            }
+        }
+        // too many digits for a DECIMAL, too
+        Decimal::from_str(&s)
+            .map(Constant::new_decimal)
+            .unwrap_or_else(|_| approx())
+    })
+    // lexeme
+    .skip(spaces().silent())
+}
+
+fn str_tok<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let almost = satisfy(|c| c != '\'' && c != '\\');
+    let escaped = attempt(char('\\').with(any()));
+    let quote_quote = attempt(char('\'').skip(char('\'')));
+    let internal_string = almost.or(escaped).or(quote_quote);
+
+    between(
+        char('\''),
+        char('\''),
+        many(internal_string).map(|v: Vec<char>| v.into_iter().collect::<String>()),
+    )
+    // lexeme
+    .skip(spaces().silent())
+}
+
+// my own extends
+// a BLOB literal, two hexadecimal digits a byte: X'0A1B'
+fn blob_tok<Input>() -> impl Parser<Input, Output = Vec<u8>>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let byte = hex_digit()
+        .and(hex_digit())
+        .map(|(h, l): (char, char)| (h.to_digit(16).unwrap() * 16 + l.to_digit(16).unwrap()) as u8);
+
+    char('X')
+        .or(char('x'))
+        .with(between(char('\''), char('\''), many(byte)))
+        // lexeme
+        .skip(spaces().silent())
+}
+
+// my own extends
+// a typed literal of a date or a time: DATE '2024-01-31', TIMESTAMP '2024-01-31 12:34:56.789',
+// TIME '12:34:56'. a plain string is converted when it is compared with a field or stored.
+fn datetime_tok<Input>() -> impl Parser<Input, Output = Constant>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let invalid =
+        || StreamErrorFor::<Input>::message_static_message("invalid date or time literal");
+    let timestamp = kw_timestamp().with(str_tok()).and_then(move |s| {
+        Constant::new_string(s)
+            .as_timestamp()
+            .map(Constant::new_timestamp)
+            .map_err(|_| invalid())
+    });
+    let time = kw_time().with(str_tok()).and_then(move |s| {
+        Constant::new_string(s)
+            .as_time()
+            .map(Constant::new_time)
+            .map_err(|_| invalid())
+    });
+    let date = reserved_word("DATE").with(str_tok()).and_then(move |s| {
+        Constant::new_string(s)
+            .as_date()
+            .map(Constant::new_date)
+            .map_err(|_| invalid())
+    });
+
+    attempt(timestamp).or(attempt(time)).or(date)
+}
+
+fn bool_tok<Input>() -> impl Parser<Input, Output = bool>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(string("false"))
+        .map(|_| false)
+        .or(string("true").map(|_| true))
+        // lexeme
+        .skip(spaces().silent())
+}
+
+/// Methods for parsing predicates and their components
+
+fn field<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    // my own extends
+    // a field qualified by the alias (or the name) of its table, e.g. e1.name
+    id_tok()
+        .and(optional(attempt(char('.').with(id_tok()))))
+        .map(|(x, oy)| match oy {
+            Some(y) => qualified_name(&x, &y),
+            None => x,
+        })
+}
+
+fn constant<Input>() -> impl Parser<Input, Output = Constant>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(str_tok())
+        .map(|sval| Constant::new_string(sval))
+        .or(attempt(blob_tok()).map(Constant::new_blob))
+        .or(attempt(datetime_tok()))
+        .or(attempt(number_tok()))
+        .or(attempt(bool_tok()).map(|bval| Constant::new_bool(bval)))
+        .or(kw_null().map(|_| Constant::Null))
+        // lexeme
+        .skip(spaces().silent())
+}
+
+parser! {
+    // my own extends
+    // the operators from the lowest precedence: ||, + and -, * / and %, unary -
+    fn expression[Input]()(Input) -> Expression
+    where [Input: Stream<Token = char>]
+    {
+        let concat = attempt(string("||"))
+            .map(|_| ArithOp::Concat)
+            // lexeme
+            .skip(spaces().silent());
+
+        chainl1(additive(), arith_op(concat))
+    }
+}
+
+fn additive<Input>() -> impl Parser<Input, Output = Expression>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let op = char('+')
+        .map(|_| ArithOp::Add)
+        .or(char('-').map(|_| ArithOp::Sub))
+        // lexeme
+        .skip(spaces().silent());
+
+    chainl1(multiplicative(), arith_op(op))
+}
+
+fn multiplicative<Input>() -> impl Parser<Input, Output = Expression>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let op = char('*')
+        .map(|_| ArithOp::Mul)
+        .or(char('/').map(|_| ArithOp::Div))
+        .or(char('%').map(|_| ArithOp::Mod))
+        // lexeme
+        .skip(spaces().silent());
+
+    chainl1(unary(), arith_op(op))
+}
+
+fn arith_op<Input, P>(
+    op: P,
+) -> impl Parser<Input, Output = impl FnOnce(Expression, Expression) -> Expression>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input, Output = ArithOp>,
+{
+    op.map(|op| move |l: Expression, r: Expression| Expression::new_binary(l, op, r))
+}
+
+parser! {
+    fn unary[Input]()(Input) -> Expression
+    where [Input: Stream<Token = char>]
+    {
+        // a negative number is a constant
+        let neg = char('-')
+            .skip(spaces().silent())
+            .with(unary())
+            .map(Expression::new_neg);
+        let parens = between(delim_parenl(), delim_parenr(), expression());
+        let cast = attempt(kw_cast().skip(delim_parenl()))
+            .with(expression())
+            .skip(kw_as())
+            .and(type_def())
+            .skip(delim_parenr())
+            .map(|(expr, fi)| {
+                let func = Function::Cast {
+                    fld_type: fi.fld_type,
+                    length: fi.length,
+                    scale: fi.scale,
+                };
+                Expression::new_call(func, vec![expr])
+            });
+        let call = attempt(id_tok().skip(delim_parenl()))
+            .and_then(|name| {
+                Function::from_name(&name).ok_or_else(|| {
+                    StreamErrorFor::<Input>::message_static_message("unknown function")
+                })
+            })
+            .and(sep_by(expression(), delim_comma()))
+            .skip(delim_parenr())
+            .map(|(func, args)| Expression::new_call(func, args));
+
+        // try constant first, because field can get bool value too.
+        attempt(constant())
+            .map(|c| Expression::Val(c))
+            .or(neg)
+            .or(parens)
+            .or(cast)
+            .or(call)
+            .or(field().map(|fldname| Expression::new_fldname(fldname)))
+    }
+}
+
+fn term<Input>() -> impl Parser<Input, Output = Term>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    expression()
+        .and(binop())
+        .and(expression())
+        .map(|((lhs, op), rhs)| Term::new_with(lhs, op, rhs))
+}
+
+// my own extends
+// x BETWEEN a AND b is x >= a and x <= b
+fn between_range<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(expression().skip(kw_between()))
+        .and(expression())
+        .skip(kw_and())
+        .and(expression())
+        .map(|((expr, low), high)| {
+            let mut pred = Predicate::new(Term::new_with(expr.clone(), Operator::Ge, low));
+            pred.conjoin_with(&mut Predicate::new(Term::new_with(
+                expr,
+                Operator::Le,
+                high,
+            )));
+            pred
+        })
+}
+
+// my own extends
+// x IS NULL, x IS NOT NULL
+fn null_test<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(expression().skip(kw_is()))
+        .and(optional(kw_not()))
+        .skip(kw_null())
+        .map(|(expr, not)| {
+            let op = if not.is_some() {
+                Operator::IsNotNull
+            } else {
+                Operator::IsNull
+            };
+            Predicate::new(Term::new_unary(expr, op))
+        })
+}
+
+// my own extends
+// x [NOT] LIKE pattern [ESCAPE 'c']
+// the pattern with ESCAPE must be a string, which is rewritten with the default escape character.
+fn like_test<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(expression().and(optional(kw_not())).skip(kw_like()))
+        .and(expression())
+        .and(optional(kw_escape().with(str_tok())))
+        .and_then(|(((expr, not), pattern), escape)| {
+            let pattern = match (pattern, escape) {
+                (pattern, None) => pattern,
+                (Expression::Val(Constant::String(pattern)), Some(escape)) => {
+                    let mut chars = escape.chars();
+                    let escape = match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => {
+                            return Err(StreamErrorFor::<Input>::message_static_message(
+                                "escape must be a character",
+                            ))
+                        }
+                    };
+                    let pattern = LikePattern::new_with(&pattern, escape).map_err(|_| {
+                        StreamErrorFor::<Input>::message_static_message(
+                            "pattern ends with the escape character",
+                        )
+                    })?;
+                    Expression::new_val(Constant::String(pattern.to_string()))
+                }
+                _ => {
+                    return Err(StreamErrorFor::<Input>::message_static_message(
+                        "pattern with escape must be a string",
+                    ))
+                }
+            };
+            let op = if not.is_some() {
+                Operator::NotLike
+            } else {
+                Operator::Like
+            };
+            Ok(Predicate::new(Term::new_with(expr, op, pattern)))
+        })
+}
+
+// my own extends
+// x [NOT] IN (a, b, ...) is x = a or x = b or ...
+fn in_list<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(expression().and(optional(kw_not())).skip(kw_in()))
+        .and(between(
+            delim_parenl(),
+            delim_parenr(),
+            sep_by1(expression(), delim_comma()),
+        ))
+        .map(|((expr, not), vals): (_, Vec<Expression>)| {
+            let mut preds = vals
+                .into_iter()
+                .map(|val| Predicate::new(Term::new(expr.clone(), val)))
+                .collect::<Vec<_>>();
+            let pred = if preds.len() == 1 {
+                preds.remove(0)
+            } else {
+                Predicate::new_disjunction(preds)
+            };
+            if not.is_some() {
+                pred.negate()
+            } else {
+                pred
+            }
+        })
+}
+
+parser! {
+    fn predicate[Input]()(Input) -> Predicate
+    where [Input: Stream<Token = char>]
+    {
+        disjunction()
+    }
+}
+
+fn disjunction<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let disjoin =
+        kw_or().map(|_| |l: Predicate, r: Predicate| Predicate::new_disjunction(vec![l, r]));
+
+    chainl1(conjunction(), disjoin)
+}
+
+fn conjunction<Input>() -> impl Parser<Input, Output = Predicate>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let conjoin = kw_and().map(|_| {
+        |mut l: Predicate, mut r: Predicate| {
+            l.conjoin_with(&mut r);
+            l
+        }
+    });
+
+    chainl1(factor(), conjoin)
+}
+
+parser! {
+    fn factor[Input]()(Input) -> Predicate
+    where [Input: Stream<Token = char>]
+    {
+        let negation = kw_not().with(factor()).map(|p| p.negate());
+        let parens = between(delim_parenl(), delim_parenr(), predicate());
+
+        negation
+            .or(attempt(parens))
+            .or(between_range())
+            .or(null_test())
+            .or(like_test())
+            .or(in_list())
+            .or(term().map(|t| Predicate::new(t)))
+    }
+}
+
+/// Methods for parsing queries
+
+pub fn query<Input>() -> impl Parser<Input, Output = QueryData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let fields = kw_select().with(select_list());
+    let tables = kw_from().with(table_list());
+    let where_clause = kw_where().with(predicate());
+    let group_by_clause = attempt(kw_group().and(kw_by())).with(field_list());
+    let order_by_clause = kw_order().and(kw_by()).with(order_list());
+
+    fields
+        .and(tables)
+        .and(optional(where_clause))
+        .and(optional(group_by_clause))
+        .and(optional(order_by_clause))
+        .skip(terminate())
+        .map(|(((((fs, mut aggs, exprs), items), op), og), oo)| {
+            let mut pred = op.unwrap_or(Predicate::new_empty());
+            // the inner joins are the product of the tables with their conditions in where
+            let mut ts = vec![];
+            let mut joined_tables = vec![];
+            let mut aliases = vec![];
+            for (tblname, joins, mut item_aliases) in items {
+                aliases.append(&mut item_aliases);
+                if joins.iter().all(|join| join.join_type() == JoinType::Inner) {
+                    ts.push(tblname);
+                    for join in joins {
+                        ts.push(join.table_name().to_string());
+                        pred.conjoin_with(&mut join.pred().clone());
+                    }
+                } else {
+                    joined_tables.push(JoinedTable::new(tblname, joins));
+                }
+            }
+            let groupfields = og.unwrap_or_default();
+            let mut orderfields = vec![];
+            for (fldname, order, oagg) in oo.unwrap_or_default() {
+                // an aggregation in order by is computed even if it is not selected
+                if let Some(agg) = oagg {
+                    if !aggs.contains(&agg) {
+                        aggs.push(agg);
+                    }
+                }
+                orderfields.push((fldname, order));
+            }
+            let mut data = QueryData::new_with(fs, ts, pred, groupfields, aggs, orderfields, exprs);
+            for jt in joined_tables {
+                data.add_joined_table(jt);
+            }
+            for (alias, tblname) in aliases {
+                data.add_alias(alias, tblname);
+            }
+            data
+        })
+}
+
+fn select_list<Input>(
+) -> impl Parser<Input, Output = (Vec<String>, Vec<Aggregation>, Vec<(String, Expression)>)>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    sep_by1(select_column(), delim_comma()).map(|items: Vec<(String, SelectColumn)>| {
+        let mut fields = vec![];
+        let mut aggs = vec![];
+        let mut exprs = vec![];
+        for (fldname, col) in items {
+            match col {
+                SelectColumn::Field => {}
+                SelectColumn::Aggregation(agg) => {
+                    if !aggs.contains(&agg) {
+                        aggs.push(agg);
+                    }
+                }
+                SelectColumn::Expression(expr) => exprs.push((fldname.clone(), expr)),
+            }
+            fields.push(fldname);
+        }
+        (fields, aggs, exprs)
+    })
+}
+
+// my own extends
+enum SelectColumn {
+    Field,
+    Aggregation(Aggregation),
+    Expression(Expression),
+}
+
+// a computed column without an alias is named by the text of its expression
+fn select_column<Input>() -> impl Parser<Input, Output = (String, SelectColumn)>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let alias = kw_as().with(id_tok());
+
+    attempt(aggregation())
+        .map(|agg| (agg.field_name(), SelectColumn::Aggregation(agg)))
+        .or(expression()
+            .and(optional(alias))
+            .map(|(expr, oalias)| match (expr, oalias) {
+                (Expression::Fldname(fldname), None) => (fldname, SelectColumn::Field),
+                (expr, Some(alias)) => (alias, SelectColumn::Expression(expr)),
+                (expr, None) => (expr.to_string(), SelectColumn::Expression(expr)),
+            }))
+}
+
+fn select_item<Input>() -> impl Parser<Input, Output = (String, Option<Aggregation>)>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(aggregation())
+        .map(|agg| (agg.field_name(), Some(agg)))
+        .or(field().map(|fldname| (fldname, None)))
+}
+
+fn aggregation<Input>() -> impl Parser<Input, Output = Aggregation>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let count_arg = delim_asterisk().map(|_| None).or(field().map(Some));
+    let count = kw_count().with(between(delim_parenl(), delim_parenr(), count_arg));
+    let sum = kw_sum().with(between(delim_parenl(), delim_parenr(), field()));
+    let min = kw_min().with(between(delim_parenl(), delim_parenr(), field()));
+    let max = kw_max().with(between(delim_parenl(), delim_parenr(), field()));
+    let avg = kw_avg().with(between(delim_parenl(), delim_parenr(), field()));
+
+    attempt(count.map(Aggregation::Count))
+        .or(attempt(sum.map(Aggregation::Sum)))
+        .or(attempt(min.map(Aggregation::Min)))
+        .or(attempt(max.map(Aggregation::Max)))
+        .or(avg.map(Aggregation::Avg))
+}
+
+// my own extends
+// an item of the from clause: the name of its first table, its JOIN clauses
+// and the aliases of its tables with the table names
+type FromItem = (String, Vec<JoinData>, Vec<(String, String)>);
+// a JOIN clause and the alias of its table with the table name
+type JoinClause = (JoinData, Option<(String, String)>);
+
+fn table_list<Input>() -> impl Parser<Input, Output = Vec<FromItem>>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    sep_by1(from_item(), delim_comma())
+}
+
+// table followed by JOIN clauses, where a table with an alias is named by the alias
+fn from_item<Input>() -> impl Parser<Input, Output = FromItem>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    table_ref()
+        .and(many(join_clause()))
+        .map(|((tblname, oalias), items): (_, Vec<JoinClause>)| {
+            let mut aliases = vec![];
+            let name = match oalias {
+                Some(alias) => {
+                    aliases.push((alias.clone(), tblname));
+                    alias
+                }
+                None => tblname,
+            };
+            let mut joins = vec![];
+            for (join, oalias) in items {
+                aliases.extend(oalias);
+                joins.push(join);
+            }
+            (name, joins, aliases)
+        })
+}
+
+// tblname [[AS] alias]
+fn table_ref<Input>() -> impl Parser<Input, Output = (String, Option<String>)>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    // the keywords which may follow a table in the from clause
+    const FOLLOWERS: [&str; 10] = [
+        "WHERE", "GROUP", "ORDER", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "OUTER", "ON",
+    ];
+    let alias = attempt(id_tok().and_then(|name: String| {
+        if FOLLOWERS.iter().any(|kw| kw.eq_ignore_ascii_case(&name)) {
+            Err(StreamErrorFor::<Input>::message_static_message(
+                "reserved word",
+            ))
+        } else {
+            Ok(name)
+        }
+    }));
+
+    id_tok().and(optional(kw_as().with(id_tok()).or(alias)))
+}
+
+// [INNER | LEFT [OUTER] | RIGHT [OUTER] | FULL [OUTER]] JOIN table ON predicate,
+// and the alias of the table
+fn join_clause<Input>() -> impl Parser<Input, Output = JoinClause>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let join_type = kw_inner()
+        .map(|_| JoinType::Inner)
+        .or(kw_left().skip(optional(kw_outer())).map(|_| JoinType::Left))
+        .or(kw_right()
+            .skip(optional(kw_outer()))
+            .map(|_| JoinType::Right))
+        .or(kw_full().skip(optional(kw_outer())).map(|_| JoinType::Full));
+
+    optional(join_type)
+        .skip(kw_join())
+        .and(table_ref())
+        .skip(kw_on())
+        .and(predicate())
+        .map(|((join_type, (tblname, oalias)), pred)| {
+            let join_type = join_type.unwrap_or(JoinType::Inner);
+            match oalias {
+                Some(alias) => (
+                    JoinData::new(join_type, alias.clone(), pred),
+                    Some((alias, tblname)),
+                ),
+                None => (JoinData::new(join_type, tblname, pred), None),
+            }
+        })
+}
+
+fn order_list<Input>() -> impl Parser<Input, Output = Vec<(String, SortOrder, Option<Aggregation>)>>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    sep_by1(order_field(), delim_comma())
+}
+
+fn order_field<Input>() -> impl Parser<Input, Output = (String, SortOrder, Option<Aggregation>)>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let asc = attempt(kw_asc()).map(|_| SortOrder::Asc);
+    let desc = attempt(kw_desc()).map(|_| SortOrder::Desc);
+
+    select_item()
+        .and(optional(asc.or(desc)))
+        .map(|((fldname, oagg), oo)| (fldname, oo.unwrap_or(SortOrder::Asc), oagg))
+}
+
+/// Methods for parsing the various update commands
+
+pub fn update_cmd<Input>() -> impl Parser<Input, Output = SQL>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(insert().map(|i| SQL::DML(DML::Insert(i))))
+        .or(attempt(delete().map(|d| SQL::DML(DML::Delete(d)))))
+        .or(attempt(modify().map(|m| SQL::DML(DML::Modify(m)))))
+        .or(attempt(tcl().map(SQL::TCL)))
+        .or(ddl().map(|ddl| SQL::DDL(ddl)))
+}
+
+fn ddl<Input>() -> impl Parser<Input, Output = DDL>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(create_table().map(|t| DDL::Table(t)))
+        .or(attempt(create_view().map(|v| DDL::View(v))))
+        .or(attempt(create_index().map(|i| DDL::Index(i))))
+        .or(attempt(drop_table().map(DDL::DropTable)))
+        .or(attempt(drop_view().map(DDL::DropView)))
+        .or(attempt(drop_index().map(DDL::DropIndex)))
+        .or(alter_table().map(DDL::Alter))
+}
+
+/// Method for parsing delete commands
+
+pub fn delete<Input>() -> impl Parser<Input, Output = DeleteData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_delete().and(kw_from());
+    let where_clause = kw_where().with(predicate());
+
+    prelude
+        .with(id_tok())
+        .and(optional(where_clause))
+        .skip(terminate())
+        .map(|(tblname, opred)| {
+            let pred = opred.unwrap_or(Predicate::new_empty());
+            DeleteData::new(tblname, pred)
+        })
+}
+
+/// Methods for parsing insert commands
+
+pub fn insert<Input>() -> impl Parser<Input, Output = InsertData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_insert().and(kw_into());
+    let fields = between(delim_parenl(), delim_parenr(), field_list());
+    let vals = kw_values().with(between(delim_parenl(), delim_parenr(), const_list()));
+
+    prelude
+        .with(id_tok())
+        .and(fields)
+        .and(vals)
+        .skip(terminate())
+        .map(|((t, fs), vs)| InsertData::new(t, fs, vs))
+}
+
+fn field_list<Input>() -> impl Parser<Input, Output = Vec<String>>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    sep_by1(field(), delim_comma())
+}
+
+fn const_list<Input>() -> impl Parser<Input, Output = Vec<Constant>>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    sep_by1(constant(), delim_comma())
+}
+
+/// Method for parsing modify commands
 
 pub fn modify<Input>() -> impl Parser<Input, Output = ModifyData>
 where
@@ -579,17 +1664,28 @@ where
 {
     let prelude = kw_create().and(kw_table());
     let field_defs = between(delim_parenl(), delim_parenr(), field_defs());
+    // my own extends
+    let format = kw_format().with(
+        kw_fixed()
+            .map(|_| RecordFormat::Fixed)
+            .or(kw_slotted().map(|_| RecordFormat::Slotted)),
+    );
 
     prelude
         .with(id_tok())
         .and(field_defs)
+        .and(optional(format))
         .skip(terminate())
-        .map(|(tblname, fdefs)| {
+        .map(|((tblname, fdefs), format)| {
             let mut sch = Schema::new();
             for (fldname, fi) in fdefs.iter() {
-                sch.add_field(fldname, fi.fld_type, fi.length)
+                sch.add_field(fldname, fi.fld_type, fi.length);
+                sch.set_scale(fldname, fi.scale);
+                if !fi.nullable {
+                    sch.set_not_null(fldname);
+                }
             }
-            CreateTableData::new(tblname, sch)
+            CreateTableData::new_with(tblname, sch, format.unwrap_or_default())
         })
 }
 
@@ -606,7 +1702,18 @@ where
     Input: Stream<Token = char>,
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    id_tok().and(type_def())
+    let not_null = attempt(kw_not().and(kw_null()));
+
+    id_tok()
+        .and(type_def())
+        .and(optional(not_null))
+        .map(|((fldname, fi), nn)| {
+            let fi = FieldInfo {
+                nullable: nn.is_none(),
+                ..fi
+            };
+            (fldname, fi)
+        })
 }
 
 fn type_def<Input>() -> impl Parser<Input, Output = FieldInfo>
@@ -621,12 +1728,46 @@ where
         .map(|n| FieldInfo::new(FieldType::VARCHAR, n as usize));
     let bool_def = kw_bool().map(|_| FieldInfo::new(FieldType::BOOL, 0));
     let date_def = kw_date().map(|_| FieldInfo::new(FieldType::DATE, 0));
+    let text_def = kw_text().map(|_| FieldInfo::new(FieldType::TEXT, 0));
+    let blob_def = kw_blob().map(|_| FieldInfo::new(FieldType::BLOB, 0));
+    let bigint_def = kw_bigint().map(|_| FieldInfo::new(FieldType::BIGINT, 0));
+    let double_def = kw_double().map(|_| FieldInfo::new(FieldType::DOUBLE, 0));
+    let timestamp_def = kw_timestamp().map(|_| FieldInfo::new(FieldType::TIMESTAMP, 0));
+    let time_def = kw_time().map(|_| FieldInfo::new(FieldType::TIME, 0));
+    // the precision is at most 18 digits, which an i64 holds
+    let decimal_def = kw_decimal()
+        .with(optional(between(
+            delim_parenl(),
+            delim_parenr(),
+            i32_tok().and(optional(delim_comma().with(i32_tok()))),
+        )))
+        .and_then(|ps| {
+            let (precision, scale) = match ps {
+                Some((p, s)) => (p, s.unwrap_or(0)),
+                None => (18, 0),
+            };
+            if !(1..=18).contains(&precision) || !(0..=precision).contains(&scale) {
+                return Err(StreamErrorFor::<Input>::message_static_message(
+                    "invalid precision or scale of DECIMAL",
+                ));
+            }
+            let mut fi = FieldInfo::new(FieldType::DECIMAL, precision as usize);
+            fi.scale = scale as usize;
+            Ok(fi)
+        });
 
     attempt(int32_def)
         .or(attempt(int16_def))
         .or(attempt(varchar_def))
         .or(attempt(bool_def))
-        .or(date_def)
+        .or(attempt(date_def))
+        .or(attempt(text_def))
+        .or(attempt(blob_def))
+        .or(attempt(bigint_def))
+        .or(attempt(double_def))
+        .or(attempt(decimal_def))
+        .or(attempt(timestamp_def))
+        .or(time_def)
 }
 
 /// Method for parsing create view commands
@@ -656,15 +1797,151 @@ where
     prelude
         .with(id_tok())
         .and(kw_on().with(id_tok()))
-        .and(between(delim_parenl(), delim_parenr(), field()))
+        .and(between(delim_parenl(), delim_parenr(), id_tok()))
         .skip(terminate())
         .map(|((idxname, tblname), fldname)| CreateIndexData::new(idxname, tblname, fldname))
 }
 
+/// Methods for parsing drop commands
+
+pub fn drop_table<Input>() -> impl Parser<Input, Output = DropData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_drop().and(kw_table());
+
+    prelude
+        .with(if_exists())
+        .and(id_tok())
+        .skip(terminate())
+        .map(|(ie, tblname)| DropData::new(tblname, ie))
+}
+
+pub fn drop_view<Input>() -> impl Parser<Input, Output = DropData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_drop().and(kw_view());
+
+    prelude
+        .with(if_exists())
+        .and(id_tok())
+        .skip(terminate())
+        .map(|(ie, viewname)| DropData::new(viewname, ie))
+}
+
+pub fn drop_index<Input>() -> impl Parser<Input, Output = DropData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_drop().and(kw_index());
+
+    prelude
+        .with(if_exists())
+        .and(id_tok())
+        .skip(terminate())
+        .map(|(ie, idxname)| DropData::new(idxname, ie))
+}
+
+fn if_exists<Input>() -> impl Parser<Input, Output = bool>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    optional(attempt(kw_if().and(kw_exists()))).map(|o| o.is_some())
+}
+
+/// Method for parsing alter table commands
+
+pub fn alter_table<Input>() -> impl Parser<Input, Output = AlterTableData>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_alter().and(kw_table());
+    // the COLUMN keyword is optional
+    let column = || optional(attempt(kw_column()));
+    let add = kw_add()
+        .with(column())
+        .with(field_def())
+        .map(|(fldname, fi)| AlterAction::AddField(fldname, fi));
+    // the names of the fields of the table, which cannot be qualified
+    let drop = kw_drop()
+        .with(column())
+        .with(id_tok())
+        .map(AlterAction::DropField);
+    let rename = kw_rename()
+        .with(column())
+        .with(id_tok())
+        .and(kw_to().with(id_tok()))
+        .map(|(oldname, newname)| AlterAction::RenameField(oldname, newname));
+
+    prelude
+        .with(id_tok())
+        .and(attempt(add).or(attempt(drop)).or(rename))
+        .skip(terminate())
+        .map(|(tblname, action)| AlterTableData::new(tblname, action))
+}
+
+/// Method for parsing transaction control commands
+
+pub fn tcl<Input>() -> impl Parser<Input, Output = TCL>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    // the TRANSACTION keyword is optional
+    let transaction = || optional(attempt(kw_transaction()));
+    let begin = kw_begin().with(transaction()).map(|_| TCL::Begin);
+    let commit = kw_commit().with(transaction()).map(|_| TCL::Commit);
+    let rollback_to = kw_rollback()
+        .with(transaction())
+        .with(kw_to())
+        .with(optional(attempt(kw_savepoint())))
+        .with(id_tok())
+        .map(TCL::RollbackToSavepoint);
+    let rollback = kw_rollback().with(transaction()).map(|_| TCL::Rollback);
+    let savepoint = kw_savepoint().with(id_tok()).map(TCL::Savepoint);
+
+    attempt(begin.skip(terminate()))
+        .or(attempt(commit.skip(terminate())))
+        .or(attempt(rollback_to.skip(terminate())))
+        .or(attempt(rollback.skip(terminate())))
+        .or(attempt(savepoint.skip(terminate())))
+        .or(set_isolation_level().map(TCL::SetIsolationLevel))
+}
+
+pub fn set_isolation_level<Input>() -> impl Parser<Input, Output = IsolationLevel>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let prelude = kw_set()
+        .and(kw_transaction())
+        .and(kw_isolation())
+        .and(kw_level());
+    let read = kw_read().with(
+        kw_uncommitted()
+            .map(|_| IsolationLevel::ReadUncommitted)
+            .or(kw_committed().map(|_| IsolationLevel::ReadCommitted)),
+    );
+    let level = read
+        .or(kw_repeatable()
+            .and(kw_read())
+            .map(|_| IsolationLevel::RepeatableRead))
+        .or(kw_serializable().map(|_| IsolationLevel::Serializable));
+
+    prelude.with(level).skip(terminate())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use chrono::{NaiveDate, NaiveTime};
     use combine::error::StringStreamError;
 
     #[test]
@@ -733,6 +2010,43 @@ mod tests {
         );
         assert_eq!(parser.parse("true"), Ok((Constant::Bool(true), "")));
         assert_eq!(parser.parse("false"), Ok((Constant::Bool(false), "")));
+        assert_eq!(parser.parse("NULL"), Ok((Constant::Null, "")));
+        assert_eq!(parser.parse("null"), Ok((Constant::Null, "")));
+        assert!(parser.parse("nullable").is_err());
+        assert_eq!(
+            parser.parse("X'00ff7A'"),
+            Ok((Constant::Blob(vec![0x00, 0xff, 0x7a]), ""))
+        );
+        assert_eq!(parser.parse("x''"), Ok((Constant::Blob(vec![]), "")));
+        assert_eq!(
+            parser.parse("3000000000"),
+            Ok((Constant::I64(3_000_000_000), ""))
+        );
+        assert_eq!(
+            parser.parse("-12.50"),
+            Ok((Constant::Decimal(Decimal::new(-1250, 2)), ""))
+        );
+        assert_eq!(parser.parse("1.5e3"), Ok((Constant::F64(1500.0), "")));
+        let day = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        assert_eq!(
+            parser.parse("TIMESTAMP '2024-01-31 12:34:56.789'"),
+            Ok((
+                Constant::Timestamp(day.and_hms_milli_opt(12, 34, 56, 789).unwrap()),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("time '12:34:56'"),
+            Ok((
+                Constant::Time(NaiveTime::from_hms_opt(12, 34, 56).unwrap()),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("DATE '2024-01-31'"),
+            Ok((Constant::Date(day), ""))
+        );
+        assert!(parser.parse("TIME '25:00:00'").is_err());
     }
 
     #[test]
@@ -769,6 +2083,94 @@ mod tests {
             parser.parse("fldname"),
             Ok((Expression::Fldname("fldname".to_string()), ""))
         );
+
+        let fld = |f: &str| Expression::new_fldname(f.to_string());
+        let val = |v: i32| Expression::new_val(Constant::I32(v));
+        assert_eq!(
+            parser.parse("price * qty + 1"),
+            Ok((
+                Expression::new_binary(
+                    Expression::new_binary(fld("price"), ArithOp::Mul, fld("qty")),
+                    ArithOp::Add,
+                    val(1)
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("a - -1 - b"),
+            Ok((
+                Expression::new_binary(
+                    Expression::new_binary(fld("a"), ArithOp::Sub, val(-1)),
+                    ArithOp::Sub,
+                    fld("b")
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("-(a + 1) % 3"),
+            Ok((
+                Expression::new_binary(
+                    Expression::new_neg(Expression::new_binary(fld("a"), ArithOp::Add, val(1))),
+                    ArithOp::Mod,
+                    val(3)
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("'no.' || id + 1"),
+            Ok((
+                Expression::new_binary(
+                    Expression::new_val(Constant::String("no.".to_string())),
+                    ArithOp::Concat,
+                    Expression::new_binary(fld("id"), ArithOp::Add, val(1))
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("UPPER(name) || substr(code, 1, 2)"),
+            Ok((
+                Expression::new_binary(
+                    Expression::new_call(Function::Upper, vec![fld("name")]),
+                    ArithOp::Concat,
+                    Expression::new_call(Function::Substr, vec![fld("code"), val(1), val(2)])
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("CAST(price * 2 AS decimal(10, 2))"),
+            Ok((
+                Expression::new_call(
+                    Function::Cast {
+                        fld_type: FieldType::DECIMAL,
+                        length: 10,
+                        scale: 2
+                    },
+                    vec![Expression::new_binary(fld("price"), ArithOp::Mul, val(2))]
+                ),
+                ""
+            ))
+        );
+        assert!(parser.parse("unknown_fn(a)").is_err());
+        // the text of an expression is parsed to the same expression
+        for text in [
+            "a * (b + c) / 2",
+            "a - (b - c)",
+            "-(-1) * -a",
+            "(a || 'x') || b",
+            "coalesce(a, -abs(b), 0) + length(trim(c))",
+            "date_add(d, 1 + n)",
+            "cast(a as varchar(5)) || cast(b as decimal(4, 1))",
+        ] {
+            let (expr, _) = parser.parse(text).unwrap();
+            println!("{} => {}", text, expr);
+            let printed = expr.to_string();
+            assert_eq!(expression().parse(printed.as_str()), Ok((expr, "")));
+        }
     }
 
     #[test]
@@ -975,6 +2377,39 @@ mod tests {
                 ""
             ))
         );
+        assert_eq!(
+            parser.parse("age <= 42"),
+            Ok((
+                Term::new_with(
+                    Expression::Fldname("age".to_string()),
+                    Operator::Le,
+                    Expression::Val(Constant::I32(42))
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("age<>42"),
+            Ok((
+                Term::new_with(
+                    Expression::Fldname("age".to_string()),
+                    Operator::Ne,
+                    Expression::Val(Constant::I32(42))
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("age > -1"),
+            Ok((
+                Term::new_with(
+                    Expression::Fldname("age".to_string()),
+                    Operator::Gt,
+                    Expression::Val(Constant::I32(-1))
+                ),
+                ""
+            ))
+        );
     }
 
     #[test]
@@ -1053,25 +2488,164 @@ mod tests {
             ))
         );
         assert_eq!(
-            parser.parse("'julio' = 'willy'"),
-            Ok((
-                Predicate::new(Term::new(
-                    Expression::Val(Constant::String("julio".to_string())),
-                    Expression::Val(Constant::String("willy".to_string()))
-                )),
-                ""
-            ))
+            parser.parse("'julio' = 'willy'"),
+            Ok((
+                Predicate::new(Term::new(
+                    Expression::Val(Constant::String("julio".to_string())),
+                    Expression::Val(Constant::String("willy".to_string()))
+                )),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("DId = MajorId"),
+            Ok((
+                Predicate::new(Term::new(
+                    Expression::Fldname("DId".to_string()),
+                    Expression::Fldname("MajorId".to_string())
+                )),
+                ""
+            ))
+        );
+
+        let age_lt = Term::new_with(
+            Expression::Fldname("age".to_string()),
+            Operator::Lt,
+            Expression::Val(Constant::I32(18)),
+        );
+        let name_eq = Term::new(
+            Expression::Fldname("name".to_string()),
+            Expression::Val(Constant::String("joe".to_string())),
+        );
+        let order_ge = Term::new_with(
+            Expression::Fldname("order_id".to_string()),
+            Operator::Ge,
+            Expression::Val(Constant::I32(3)),
+        );
+        let expected = Predicate::new_disjunction(vec![
+            Predicate::new(age_lt.clone()),
+            Predicate::new(name_eq.clone()),
+            Predicate::new(order_ge.clone()),
+        ]);
+        assert_eq!(
+            parser.parse("age < 18 or name = 'joe' OR order_id >= 3"),
+            Ok((expected.clone(), ""))
+        );
+        assert_eq!(
+            predicate().parse(expected.to_string().as_str()),
+            Ok((expected.clone(), ""))
+        );
+        let mut expected = Predicate::new(name_eq.clone());
+        expected.conjoin_with(&mut Predicate::new_disjunction(vec![
+            Predicate::new(age_lt.clone()),
+            Predicate::new(order_ge.clone()),
+        ]));
+        assert_eq!(
+            parser.parse("name = 'joe' and (age < 18 or order_id >= 3)"),
+            Ok((expected.clone(), ""))
+        );
+        assert_eq!(
+            predicate().parse(expected.to_string().as_str()),
+            Ok((expected.clone(), ""))
+        );
+        // not (age < 18 and name = 'joe') = age >= 18 or name <> 'joe'
+        assert_eq!(
+            parser.parse("NOT (age < 18 AND name = 'joe')"),
+            Ok((
+                Predicate::new_disjunction(vec![
+                    Predicate::new(age_lt.negate()),
+                    Predicate::new(name_eq.negate()),
+                ]),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("not not age < 18"),
+            Ok((Predicate::new(age_lt.clone()), ""))
+        );
+        // "or" must not be taken from "order"
+        assert_eq!(
+            parser.parse("age < 18 order by age"),
+            Ok((Predicate::new(age_lt.clone()), "order by age"))
+        );
+        // age between 18 and 30 = age >= 18 and age <= 30
+        let mut expected = Predicate::new(Term::new_with(
+            Expression::Fldname("age".to_string()),
+            Operator::Ge,
+            Expression::Val(Constant::I32(18)),
+        ));
+        expected.conjoin_with(&mut Predicate::new(Term::new_with(
+            Expression::Fldname("age".to_string()),
+            Operator::Le,
+            Expression::Val(Constant::I32(30)),
+        )));
+        expected.conjoin_with(&mut Predicate::new(name_eq.clone()));
+        assert_eq!(
+            parser.parse("age BETWEEN 18 AND 30 and name = 'joe'"),
+            Ok((expected, ""))
+        );
+        // age is null, age is not null
+        let age_is_null = Term::new_unary(Expression::Fldname("age".to_string()), Operator::IsNull);
+        let mut expected = Predicate::new(age_is_null.clone());
+        expected.conjoin_with(&mut Predicate::new(name_eq.clone()));
+        assert_eq!(
+            parser.parse("age IS NULL and name = 'joe'"),
+            Ok((expected.clone(), ""))
+        );
+        assert_eq!(
+            predicate().parse(expected.to_string().as_str()),
+            Ok((expected.clone(), ""))
+        );
+        assert_eq!(
+            parser.parse("age is not null"),
+            Ok((Predicate::new(age_is_null.negate()), ""))
+        );
+        assert_eq!(
+            parser.parse("not age is null"),
+            Ok((Predicate::new(age_is_null.negate()), ""))
+        );
+        // name like 'jo%', name not like ...
+        let name = Expression::Fldname("name".to_string());
+        let pattern = |p: &str| Expression::Val(Constant::String(p.to_string()));
+        let name_like = Term::new_with(name.clone(), Operator::Like, pattern("jo%"));
+        assert_eq!(
+            parser.parse("name LIKE 'jo%'"),
+            Ok((Predicate::new(name_like.clone()), ""))
+        );
+        assert_eq!(
+            parser.parse("name not like 'jo%'"),
+            Ok((Predicate::new(name_like.negate()), ""))
         );
         assert_eq!(
-            parser.parse("DId = MajorId"),
+            predicate().parse(name_like.to_string().as_str()),
+            Ok((Predicate::new(name_like.clone()), ""))
+        );
+        // the pattern is rewritten with the default escape character
+        assert_eq!(
+            parser.parse("name like '100#%#_%' escape '#'"),
             Ok((
-                Predicate::new(Term::new(
-                    Expression::Fldname("DId".to_string()),
-                    Expression::Fldname("MajorId".to_string())
+                Predicate::new(Term::new_with(
+                    name.clone(),
+                    Operator::Like,
+                    pattern("100\\%\\_%")
                 )),
                 ""
             ))
         );
+        assert!(parser.parse("name like 'ab#' escape '#'").is_err());
+        assert!(parser.parse("name like 'ab' escape '##'").is_err());
+        // age in (18, 20) = age = 18 or age = 20
+        let age = Expression::Fldname("age".to_string());
+        let age_eq =
+            |v: i32| Predicate::new(Term::new(age.clone(), Expression::Val(Constant::I32(v))));
+        let expected = Predicate::new_disjunction(vec![age_eq(18), age_eq(20)]);
+        assert_eq!(parser.parse("age IN (18, 20)"), Ok((expected.clone(), "")));
+        assert_eq!(
+            parser.parse("age not in (18, 20)"),
+            Ok((expected.negate(), ""))
+        );
+        assert_eq!(parser.parse("age in (18)"), Ok((age_eq(18), "")));
+        assert!(parser.parse("age in ()").is_err());
     }
 
     #[test]
@@ -1153,6 +2727,297 @@ mod tests {
                 ""
             ))
         );
+        assert_eq!(
+            parser.parse("SELECT name, age FROM student ORDER BY age DESC, name;"),
+            Ok((
+                QueryData::new_with(
+                    vec!["name".to_string(), "age".to_string()],
+                    vec!["student".to_string()],
+                    Predicate::new_empty(),
+                    vec![],
+                    vec![],
+                    vec![
+                        ("age".to_string(), SortOrder::Desc),
+                        ("name".to_string(), SortOrder::Asc)
+                    ],
+                    vec![],
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("SELECT name FROM student WHERE age = 20 order by name asc;"),
+            Ok((
+                QueryData::new_with(
+                    vec!["name".to_string()],
+                    vec!["student".to_string()],
+                    Predicate::new(Term::new(
+                        Expression::Fldname("age".to_string()),
+                        Expression::Val(Constant::I32(20))
+                    )),
+                    vec![],
+                    vec![],
+                    vec![("name".to_string(), SortOrder::Asc)],
+                    vec![],
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse(
+                "SELECT MajorId, count(*), max(GradYear) FROM student \
+                  GROUP BY MajorId ORDER BY count(*) DESC, avg(GradYear);"
+            ),
+            Ok((
+                QueryData::new_with(
+                    vec![
+                        "MajorId".to_string(),
+                        "countofall".to_string(),
+                        "maxofGradYear".to_string()
+                    ],
+                    vec!["student".to_string()],
+                    Predicate::new_empty(),
+                    vec!["MajorId".to_string()],
+                    vec![
+                        Aggregation::Count(None),
+                        Aggregation::Max("GradYear".to_string()),
+                        Aggregation::Avg("GradYear".to_string())
+                    ],
+                    vec![
+                        ("countofall".to_string(), SortOrder::Desc),
+                        ("avgofGradYear".to_string(), SortOrder::Asc)
+                    ],
+                    vec![],
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("SELECT count(SId), sum(GradYear), min(SName) FROM student;"),
+            Ok((
+                QueryData::new_with(
+                    vec![
+                        "countofSId".to_string(),
+                        "sumofGradYear".to_string(),
+                        "minofSName".to_string()
+                    ],
+                    vec!["student".to_string()],
+                    Predicate::new_empty(),
+                    vec![],
+                    vec![
+                        Aggregation::Count(Some("SId".to_string())),
+                        Aggregation::Sum("GradYear".to_string()),
+                        Aggregation::Min("SName".to_string())
+                    ],
+                    vec![],
+                    vec![],
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser
+                .parse("SELECT name, price * qty AS total, name || '!' FROM item ORDER BY total;"),
+            Ok((
+                QueryData::new_with(
+                    vec![
+                        "name".to_string(),
+                        "total".to_string(),
+                        "name || '!'".to_string()
+                    ],
+                    vec!["item".to_string()],
+                    Predicate::new_empty(),
+                    vec![],
+                    vec![],
+                    vec![("total".to_string(), SortOrder::Asc)],
+                    vec![
+                        (
+                            "total".to_string(),
+                            Expression::new_binary(
+                                Expression::Fldname("price".to_string()),
+                                ArithOp::Mul,
+                                Expression::Fldname("qty".to_string())
+                            )
+                        ),
+                        (
+                            "name || '!'".to_string(),
+                            Expression::new_binary(
+                                Expression::Fldname("name".to_string()),
+                                ArithOp::Concat,
+                                Expression::Val(Constant::String("!".to_string()))
+                            )
+                        )
+                    ],
+                ),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn join_test() {
+        let mut parser = query();
+        let eq = |lhs: &str, rhs: &str| {
+            Predicate::new(Term::new(
+                Expression::Fldname(lhs.to_string()),
+                Expression::Fldname(rhs.to_string()),
+            ))
+        };
+
+        // the inner joins are the same as the tables and the predicate in where
+        let mut expected = Predicate::new(Term::new(
+            Expression::Fldname("GradYear".to_string()),
+            Expression::Val(Constant::I32(2020)),
+        ));
+        expected.conjoin_with(&mut eq("MajorId", "DId"));
+        assert_eq!(
+            parser.parse(
+                "SELECT SName FROM STUDENT JOIN DEPT ON MajorId = DId WHERE GradYear = 2020;"
+            ),
+            Ok((
+                QueryData::new(
+                    vec!["SName".to_string()],
+                    vec!["STUDENT".to_string(), "DEPT".to_string()],
+                    expected,
+                ),
+                ""
+            ))
+        );
+
+        let mut expected = QueryData::new(
+            vec!["SName".to_string(), "DName".to_string()],
+            vec!["COURSE".to_string()],
+            Predicate::new_empty(),
+        );
+        expected.add_joined_table(JoinedTable::new(
+            "STUDENT".to_string(),
+            vec![JoinData::new(
+                JoinType::Left,
+                "DEPT".to_string(),
+                eq("MajorId", "DId"),
+            )],
+        ));
+        let (data, _) = parser
+            .parse(
+                "SELECT SName, DName FROM STUDENT LEFT OUTER JOIN DEPT ON MajorId = DId, COURSE;",
+            )
+            .unwrap();
+        assert_eq!(data, expected);
+        // the text of a view is parsed again
+        println!("{}", data);
+        assert_eq!(
+            data.to_string(),
+            "select SName, DName from COURSE, STUDENT left join DEPT on MajorId=DId"
+        );
+        let text = format!("{};", data);
+        assert_eq!(query().parse(text.as_str()), Ok((expected, "")));
+
+        let mut expected =
+            QueryData::new(vec!["SName".to_string()], vec![], Predicate::new_empty());
+        expected.add_joined_table(JoinedTable::new(
+            "STUDENT".to_string(),
+            vec![
+                JoinData::new(
+                    JoinType::Inner,
+                    "ENROLL".to_string(),
+                    eq("SId", "StudentId"),
+                ),
+                JoinData::new(
+                    JoinType::Full,
+                    "SECTION".to_string(),
+                    eq("SectionId", "SectId"),
+                ),
+                JoinData::new(JoinType::Right, "COURSE".to_string(), eq("CourseId", "CId")),
+            ],
+        ));
+        assert_eq!(
+            parser.parse(
+                "SELECT SName FROM STUDENT INNER JOIN ENROLL ON SId = StudentId \
+                   FULL JOIN SECTION ON SectionId = SectId \
+                   right outer join COURSE on CourseId = CId;"
+            ),
+            Ok((expected, ""))
+        );
+
+        assert!(parser
+            .parse("SELECT SName FROM STUDENT LEFT DEPT ON MajorId = DId;")
+            .is_err());
+        assert!(parser
+            .parse("SELECT SName FROM STUDENT JOIN DEPT;")
+            .is_err());
+        assert!(parser
+            .parse("SELECT SName FROM STUDENT OUTER JOIN DEPT ON MajorId = DId;")
+            .is_err());
+    }
+
+    #[test]
+    fn alias_test() {
+        let mut parser = query();
+        let eq = |lhs: &str, rhs: &str| {
+            Predicate::new(Term::new(
+                Expression::Fldname(lhs.to_string()),
+                Expression::Fldname(rhs.to_string()),
+            ))
+        };
+
+        let mut expected = QueryData::new(
+            vec!["e1.Name".to_string(), "e2.Name".to_string()],
+            vec!["e1".to_string(), "e2".to_string()],
+            eq("e1.MgrId", "e2.Id"),
+        );
+        expected.add_alias("e1".to_string(), "EMP".to_string());
+        expected.add_alias("e2".to_string(), "EMP".to_string());
+        let (data, _) = parser
+            .parse("SELECT e1.Name, e2.Name FROM EMP e1, EMP AS e2 WHERE e1.MgrId = e2.Id;")
+            .unwrap();
+        assert_eq!(data, expected);
+        println!("{}", data);
+        assert_eq!(
+            data.to_string(),
+            "select e1.Name, e2.Name from EMP e1, EMP e2 where e1.MgrId=e2.Id"
+        );
+        assert_eq!(data.range_names(), vec!["e1", "e2"]);
+        assert_eq!(data.table_of("e2"), "EMP");
+        let text = format!("{};", data);
+        assert_eq!(query().parse(text.as_str()), Ok((expected, "")));
+
+        let (data, _) = parser
+            .parse("SELECT SName FROM STUDENT s ORDER BY s.SName;")
+            .unwrap();
+        assert_eq!(data.tables(), &vec!["s".to_string()]);
+        assert_eq!(
+            data.order_fields(),
+            &vec![("s.SName".to_string(), SortOrder::Asc)]
+        );
+
+        // the keywords after a table are not its alias
+        let mut expected = QueryData::new(
+            vec!["SName".to_string()],
+            vec!["STUDENT".to_string()],
+            Predicate::new_empty(),
+        );
+        expected.add_joined_table(JoinedTable::new(
+            "s".to_string(),
+            vec![JoinData::new(
+                JoinType::Left,
+                "d".to_string(),
+                eq("s.MajorId", "d.DId"),
+            )],
+        ));
+        expected.add_alias("s".to_string(), "STUDENT".to_string());
+        expected.add_alias("d".to_string(), "DEPT".to_string());
+        let (data, _) = parser
+            .parse("SELECT SName FROM STUDENT, STUDENT s LEFT JOIN DEPT d ON s.MajorId = d.DId;")
+            .unwrap();
+        assert_eq!(data, expected);
+        assert_eq!(data.range_names(), vec!["STUDENT", "s", "d"]);
+        assert_eq!(
+            data.to_string(),
+            "select SName from STUDENT, STUDENT s left join DEPT d on s.MajorId=d.DId"
+        );
+
+        assert!(parser.parse("SELECT e.Name FROM EMP e e2;").is_err());
+        assert!(parser.parse("SELECT e. FROM EMP e;").is_err());
     }
 
     #[test]
@@ -1274,6 +3139,59 @@ mod tests {
         assert_eq!(parser.parse(
 	    "CREATE TABLE STUDENT (SId integer, SName varchar(10), GradYear integer, MajorId integer);"
 	), Ok((CreateTableData::new("STUDENT".to_string(), expected), "")));
+
+        let mut expected = Schema::new();
+        expected.add_i32_field("SId");
+        expected.set_not_null("SId");
+        expected.add_string_field("SName", 10);
+        assert_eq!(
+            parser.parse("CREATE TABLE STUDENT (SId integer NOT NULL, SName varchar(10));"),
+            Ok((CreateTableData::new("STUDENT".to_string(), expected), ""))
+        );
+
+        let mut expected = Schema::new();
+        expected.add_i32_field("SId");
+        expected.add_string_field("SName", 10);
+        assert_eq!(
+            parser.parse("CREATE TABLE STUDENT (SId integer, SName varchar(10)) FORMAT SLOTTED;"),
+            Ok((
+                CreateTableData::new_with("STUDENT".to_string(), expected, RecordFormat::Slotted),
+                ""
+            ))
+        );
+
+        let mut expected = Schema::new();
+        expected.add_i32_field("DId");
+        expected.add_text_field("Body");
+        expected.add_blob_field("Image");
+        assert_eq!(
+            parser.parse("CREATE TABLE DOC (DId integer, Body text, Image blob);"),
+            Ok((CreateTableData::new("DOC".to_string(), expected), ""))
+        );
+
+        let mut expected = Schema::new();
+        expected.add_i64_field("PId");
+        expected.add_f64_field("Weight");
+        expected.add_f64_field("Height");
+        expected.add_decimal_field("Price", 10, 2);
+        expected.add_decimal_field("Stock", 18, 0);
+        assert_eq!(
+            parser.parse(
+                "CREATE TABLE PRODUCT (PId bigint, Weight double, Height real, Price decimal(10, 2), Stock decimal);"
+            ),
+            Ok((CreateTableData::new("PRODUCT".to_string(), expected), ""))
+        );
+        assert!(parser
+            .parse("CREATE TABLE PRODUCT (Price decimal(20, 2));")
+            .is_err());
+
+        let mut expected = Schema::new();
+        expected.add_timestamp_field("CreatedAt");
+        expected.add_time_field("Opens");
+        assert_eq!(
+            parser.parse("CREATE TABLE AUDIT (CreatedAt timestamp, Opens time);"),
+            Ok((CreateTableData::new("AUDIT".to_string(), expected), ""))
+        );
     }
 
     #[test]
@@ -1313,6 +3231,176 @@ mod tests {
                 ""
             ))
         );
+        assert!(parser
+            .parse("CREATE INDEX idx_grad_year ON STUDENT (STUDENT.GradYear);")
+            .is_err());
+    }
+    #[test]
+    fn drop_test() {
+        let mut parser = drop_table();
+        assert_eq!(
+            parser.parse("DROP TABLE STUDENT;"),
+            Ok((DropData::new("STUDENT".to_string(), false), ""))
+        );
+        assert_eq!(
+            parser.parse("drop table if exists STUDENT;"),
+            Ok((DropData::new("STUDENT".to_string(), true), ""))
+        );
+        // not the IF keyword
+        assert_eq!(
+            parser.parse("DROP TABLE iffy;"),
+            Ok((DropData::new("iffy".to_string(), false), ""))
+        );
+        assert_eq!(
+            parser.parse("DROP TABLE;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+        let mut parser = drop_view();
+        assert_eq!(
+            parser.parse("DROP VIEW name_dep;"),
+            Ok((DropData::new("name_dep".to_string(), false), ""))
+        );
+        let mut parser = drop_index();
+        assert_eq!(
+            parser.parse("DROP INDEX IF EXISTS idx_grad_year;"),
+            Ok((DropData::new("idx_grad_year".to_string(), true), ""))
+        );
+    }
+    #[test]
+    fn alter_table_test() {
+        let mut parser = alter_table();
+        assert_eq!(
+            parser.parse("ALTER TABLE STUDENT ADD COLUMN Email varchar(20);"),
+            Ok((
+                AlterTableData::new(
+                    "STUDENT".to_string(),
+                    AlterAction::AddField(
+                        "Email".to_string(),
+                        FieldInfo::new(FieldType::VARCHAR, 20)
+                    )
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("alter table STUDENT add Active bool not null;"),
+            Ok((
+                AlterTableData::new(
+                    "STUDENT".to_string(),
+                    AlterAction::AddField(
+                        "Active".to_string(),
+                        FieldInfo::new_with(FieldType::BOOL, 0, false)
+                    )
+                ),
+                ""
+            ))
+        );
+        // a field may be named like the optional keyword
+        assert_eq!(
+            parser.parse("ALTER TABLE STUDENT DROP columns;"),
+            Ok((
+                AlterTableData::new(
+                    "STUDENT".to_string(),
+                    AlterAction::DropField("columns".to_string())
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("ALTER TABLE STUDENT DROP COLUMN GradYear;"),
+            Ok((
+                AlterTableData::new(
+                    "STUDENT".to_string(),
+                    AlterAction::DropField("GradYear".to_string())
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("ALTER TABLE STUDENT RENAME COLUMN SName TO Name;"),
+            Ok((
+                AlterTableData::new(
+                    "STUDENT".to_string(),
+                    AlterAction::RenameField("SName".to_string(), "Name".to_string())
+                ),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("ALTER TABLE STUDENT RENAME SName;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+        for sql in [
+            "ALTER TABLE STUDENT DROP COLUMN s.GradYear;",
+            "ALTER TABLE STUDENT RENAME COLUMN STUDENT.SName TO Name;",
+            "ALTER TABLE STUDENT RENAME COLUMN SName TO s.Name;",
+        ] {
+            assert!(parser.parse(sql).is_err());
+        }
+    }
+    #[test]
+    fn tcl_test() {
+        let mut parser = tcl();
+        assert_eq!(parser.parse("BEGIN;"), Ok((TCL::Begin, "")));
+        assert_eq!(parser.parse("begin transaction;"), Ok((TCL::Begin, "")));
+        assert_eq!(parser.parse("COMMIT;"), Ok((TCL::Commit, "")));
+        assert_eq!(parser.parse("ROLLBACK;"), Ok((TCL::Rollback, "")));
+        assert_eq!(
+            parser.parse("SAVEPOINT before_alter;"),
+            Ok((TCL::Savepoint("before_alter".to_string()), ""))
+        );
+        assert_eq!(
+            parser.parse("ROLLBACK TO SAVEPOINT before_alter;"),
+            Ok((TCL::RollbackToSavepoint("before_alter".to_string()), ""))
+        );
+        assert_eq!(
+            parser.parse("rollback to sp1;"),
+            Ok((TCL::RollbackToSavepoint("sp1".to_string()), ""))
+        );
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;"),
+            Ok((TCL::SetIsolationLevel(IsolationLevel::Serializable), ""))
+        );
+        assert_eq!(
+            parser.parse("SAVEPOINT;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+        assert_eq!(
+            parser.parse("COMMITTED;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+        assert_eq!(
+            parser.parse("ROLLBACK TO;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+    }
+    #[test]
+    fn set_isolation_level_test() {
+        let mut parser = set_isolation_level();
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL READ UNCOMMITTED;"),
+            Ok((IsolationLevel::ReadUncommitted, ""))
+        );
+        assert_eq!(
+            parser.parse("set transaction isolation level read committed;"),
+            Ok((IsolationLevel::ReadCommitted, ""))
+        );
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ;"),
+            Ok((IsolationLevel::RepeatableRead, ""))
+        );
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;"),
+            Ok((IsolationLevel::Serializable, ""))
+        );
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL READ;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
+        assert_eq!(
+            parser.parse("SET TRANSACTION ISOLATION LEVEL SERIALIZABLEX;"),
+            Err(StringStreamError::UnexpectedParse)
+        );
     }
     #[test]
     fn update_cmd_test() {
@@ -1321,6 +3409,13 @@ mod tests {
             parser.parse("select name, age from student where majorid = 20"),
             Err(StringStreamError::UnexpectedParse),
         );
+        assert_eq!(
+            parser.parse("set transaction isolation level repeatable read;"),
+            Ok((
+                SQL::TCL(TCL::SetIsolationLevel(IsolationLevel::RepeatableRead)),
+                ""
+            ))
+        );
         assert_eq!(
             parser.parse("insert into student (name, age) values ('Calvin', 9);"),
             Ok((
@@ -1400,5 +3495,26 @@ mod tests {
                 ""
             ))
         );
+        assert_eq!(
+            parser.parse("drop table student;"),
+            Ok((
+                SQL::DDL(DDL::DropTable(DropData::new("student".to_string(), false))),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("drop view if exists name_dep;"),
+            Ok((
+                SQL::DDL(DDL::DropView(DropData::new("name_dep".to_string(), true))),
+                ""
+            ))
+        );
+        assert_eq!(
+            parser.parse("drop index idx_age;"),
+            Ok((
+                SQL::DDL(DDL::DropIndex(DropData::new("idx_age".to_string(), false))),
+                ""
+            ))
+        );
     }
 }
@@ -1,12 +1,78 @@
+use anyhow::Result;
 use core::fmt;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::query::predicate::Predicate;
+use crate::{
+    materialize::{aggregationfn::Aggregation, recordcomparator::SortOrder},
+    query::{expression::Expression, join::JoinType, predicate::Predicate},
+    record::schema::Schema,
+};
+
+// my own extends
+// JOIN tblname ON pred, where tblname is the alias if the table has one
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct JoinData {
+    join_type: JoinType,
+    tblname: String,
+    pred: Predicate,
+}
+
+impl JoinData {
+    pub fn new(join_type: JoinType, tblname: String, pred: Predicate) -> Self {
+        Self {
+            join_type,
+            tblname,
+            pred,
+        }
+    }
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+    pub fn table_name(&self) -> &str {
+        &self.tblname
+    }
+    pub fn pred(&self) -> &Predicate {
+        &self.pred
+    }
+}
+
+// my own extends
+// an item of the from clause with outer joins, which are joined from left to right.
+// (the inner joins alone are the same as the tables and the predicate in where)
+// tblname is the alias if the table has one.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct JoinedTable {
+    tblname: String,
+    joins: Vec<JoinData>,
+}
+
+impl JoinedTable {
+    pub fn new(tblname: String, joins: Vec<JoinData>) -> Self {
+        Self { tblname, joins }
+    }
+    pub fn table_name(&self) -> &str {
+        &self.tblname
+    }
+    pub fn joins(&self) -> &Vec<JoinData> {
+        &self.joins
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct QueryData {
     fields: Vec<String>,
     tables: Vec<String>,
     pred: Predicate,
+    groupfields: Vec<String>,
+    aggregations: Vec<Aggregation>,
+    orderfields: Vec<(String, SortOrder)>,
+    // my own extends
+    // the computed fields of the select list and their expressions
+    exprs: Vec<(String, Expression)>,
+    // the items of the from clause with outer joins
+    joined_tables: Vec<JoinedTable>,
+    // the tables of the from clause by their aliases, which are in place of their names
+    aliases: BTreeMap<String, String>,
 }
 
 impl fmt::Display for QueryData {
@@ -15,20 +81,61 @@ impl fmt::Display for QueryData {
         result.push("select");
         let mut fs = vec![];
         for fldname in self.fields.iter() {
-            fs.push(fldname.as_str());
+            match self
+                .aggregations
+                .iter()
+                .find(|agg| agg.field_name() == *fldname)
+            {
+                Some(agg) => fs.push(agg.to_string()),
+                None => match self.exprs.iter().find(|(name, _)| name == fldname) {
+                    // an expression without an alias is named by its text
+                    Some((name, expr)) if *name == expr.to_string() => fs.push(name.to_string()),
+                    Some((name, expr)) => fs.push(format!("{} as {}", expr, name)),
+                    None => fs.push(fldname.to_string()),
+                },
+            }
         }
         let fs_str = fs.join(", ");
         result.push(fs_str.as_str());
         result.push("from");
         let mut ts = vec![];
         for tblname in self.tables.iter() {
-            ts.push(tblname.as_str())
+            ts.push(self.table_ref(tblname))
+        }
+        for jt in self.joined_tables.iter() {
+            let mut t = self.table_ref(jt.table_name());
+            for join in jt.joins() {
+                t = format!(
+                    "{} {} {} on {}",
+                    t,
+                    join.join_type(),
+                    self.table_ref(join.table_name()),
+                    join.pred()
+                );
+            }
+            ts.push(t)
         }
         let ts_str = ts.join(", ");
         result.push(ts_str.as_str());
-        result.push("where");
         let pred_str = self.pred.to_string();
-        result.push(&pred_str.as_str());
+        if !pred_str.is_empty() {
+            result.push("where");
+            result.push(pred_str.as_str());
+        }
+        let gs_str = self.groupfields.join(", ");
+        if !self.groupfields.is_empty() {
+            result.push("group by");
+            result.push(gs_str.as_str());
+        }
+        let mut os = vec![];
+        for (fldname, order) in self.orderfields.iter() {
+            os.push(format!("{} {}", fldname, order));
+        }
+        let os_str = os.join(", ");
+        if !os.is_empty() {
+            result.push("order by");
+            result.push(os_str.as_str());
+        }
 
         // MEMO: I don't append a terminater, because we'll be able to use this
         // as sub-query at a future.
@@ -39,12 +146,36 @@ impl fmt::Display for QueryData {
 
 impl QueryData {
     pub fn new(fields: Vec<String>, tables: Vec<String>, pred: Predicate) -> Self {
+        Self::new_with(fields, tables, pred, vec![], vec![], vec![], vec![])
+    }
+    pub fn new_with(
+        fields: Vec<String>,
+        tables: Vec<String>,
+        pred: Predicate,
+        groupfields: Vec<String>,
+        aggregations: Vec<Aggregation>,
+        orderfields: Vec<(String, SortOrder)>,
+        exprs: Vec<(String, Expression)>,
+    ) -> Self {
         Self {
             fields,
             tables,
             pred,
+            groupfields,
+            aggregations,
+            orderfields,
+            exprs,
+            joined_tables: vec![],
+            aliases: BTreeMap::new(),
         }
     }
+    // my own extends
+    pub fn add_joined_table(&mut self, jt: JoinedTable) {
+        self.joined_tables.push(jt);
+    }
+    pub fn add_alias(&mut self, alias: String, tblname: String) {
+        self.aliases.insert(alias, tblname);
+    }
     pub fn fields(&self) -> &Vec<String> {
         &self.fields
     }
@@ -54,4 +185,107 @@ impl QueryData {
     pub fn pred(&self) -> &Predicate {
         &self.pred
     }
+    pub fn group_fields(&self) -> &Vec<String> {
+        &self.groupfields
+    }
+    pub fn aggregations(&self) -> &Vec<Aggregation> {
+        &self.aggregations
+    }
+    pub fn order_fields(&self) -> &Vec<(String, SortOrder)> {
+        &self.orderfields
+    }
+    pub fn expressions(&self) -> &Vec<(String, Expression)> {
+        &self.exprs
+    }
+    pub fn joined_tables(&self) -> &Vec<JoinedTable> {
+        &self.joined_tables
+    }
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+    // the table of a name in the from clause, which is either an alias or a table name
+    pub fn table_of<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases
+            .get(name)
+            .map_or(name, |tblname| tblname.as_str())
+    }
+    // the names of all the tables in the from clause (the aliases of those which have one),
+    // the outer joined ones included
+    pub fn range_names(&self) -> Vec<&str> {
+        let mut names = self
+            .tables
+            .iter()
+            .map(|tblname| tblname.as_str())
+            .collect::<Vec<&str>>();
+        for jt in self.joined_tables.iter() {
+            names.push(jt.table_name());
+            names.extend(jt.joins().iter().map(|join| join.table_name()));
+        }
+        names
+    }
+    // the query whose field names are those of the schema of its tables,
+    // where a qualified name (alias.fldname) or a name of two tables is resolved by the schema.
+    // the names of the aggregations and the computed fields are kept.
+    pub fn resolve(&self, sch: &Schema) -> Result<QueryData> {
+        let mut data = self.clone();
+        let mut names = HashMap::new();
+        data.aggregations = vec![];
+        for agg in self.aggregations.iter() {
+            let resolved = agg.resolve(sch)?;
+            names.insert(agg.field_name(), resolved.field_name());
+            data.aggregations.push(resolved);
+        }
+        let resolve_name = |fldname: &String| -> Result<String> {
+            if let Some(name) = names.get(fldname) {
+                return Ok(name.to_string());
+            }
+            if self.exprs.iter().any(|(name, _)| name == fldname) {
+                return Ok(fldname.to_string());
+            }
+            Ok(sch.resolve(fldname)?)
+        };
+        data.fields = self
+            .fields
+            .iter()
+            .map(resolve_name)
+            .collect::<Result<_>>()?;
+        data.pred = self.pred.resolve(sch)?;
+        data.groupfields = self
+            .groupfields
+            .iter()
+            .map(|fldname| Ok(sch.resolve(fldname)?))
+            .collect::<Result<_>>()?;
+        data.orderfields = self
+            .orderfields
+            .iter()
+            .map(|(fldname, order)| Ok((resolve_name(fldname)?, *order)))
+            .collect::<Result<_>>()?;
+        data.exprs = self
+            .exprs
+            .iter()
+            .map(|(name, expr)| Ok((name.to_string(), expr.resolve(sch)?)))
+            .collect::<Result<_>>()?;
+        data.joined_tables = vec![];
+        for jt in self.joined_tables.iter() {
+            let mut joins = vec![];
+            for join in jt.joins() {
+                let pred = join.pred().resolve(sch)?;
+                joins.push(JoinData::new(
+                    join.join_type(),
+                    join.table_name().to_string(),
+                    pred,
+                ));
+            }
+            data.add_joined_table(JoinedTable::new(jt.table_name().to_string(), joins));
+        }
+
+        Ok(data)
+    }
+    // tblname alias, or tblname
+    fn table_ref(&self, name: &str) -> String {
+        match self.aliases.get(name) {
+            Some(tblname) => format!("{} {}", tblname, name),
+            None => name.to_string(),
+        }
+    }
 }
@@ -1,7 +1,9 @@
-use super::{ddl::DDL, dml::DML};
+use super::{ddl::DDL, dml::DML, tcl::TCL};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SQL {
     DDL(DDL),
     DML(DML),
+    // my own extends
+    TCL(TCL),
 }
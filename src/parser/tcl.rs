@@ -0,0 +1,13 @@
+use crate::tx::concurrency::isolation::IsolationLevel;
+
+// my own extends
+// transaction control statements
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TCL {
+    Begin,
+    Commit,
+    Rollback,
+    Savepoint(String),
+    RollbackToSavepoint(String),
+    SetIsolationLevel(IsolationLevel),
+}
@@ -1,20 +1,31 @@
 use anyhow::Result;
 use combine::Parser;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
-use super::{plan::Plan, queryplanner::QueryPlanner};
+use super::{
+    plan::Plan,
+    queryplanner::{check_aggregations, check_sort_fields, qualify_tables, QueryPlanner},
+};
 use crate::{
+    materialize::{groupbyplan::GroupByPlan, sortplan::SortPlan},
     metadata::manager::MetadataMgr,
     parser::{parser::query, querydata::QueryData},
     plan::{
         productplan::ProductPlan, projectplan::ProjectPlan, selectplan::SelectPlan,
         tableplan::TablePlan,
     },
+    query::join::join_schema,
     tx::transaction::Transaction,
 };
 
 #[derive(Debug, Clone)]
 pub struct BasicQueryPlanner {
+    // static member (shared by all Materializeplan and Temptable)
+    next_table_num: Arc<Mutex<i32>>,
+
     mdm: Arc<Mutex<MetadataMgr>>,
 }
 
@@ -24,28 +35,52 @@ impl QueryPlanner for BasicQueryPlanner {
         data: QueryData,
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<Arc<dyn Plan>> {
+        // my own extends
+        // Step 0: Qualify the fields which more than one table (or view) has by its alias
+        // (or name), and resolve the field names of the query
+        let mut sources = vec![];
+        for name in data.range_names() {
+            let p = self.create_table_plan(data.table_of(name), Arc::clone(&tx))?;
+            sources.push((name.to_string(), p));
+        }
+        let schemas = sources
+            .iter()
+            .map(|(name, p)| (name.to_string(), p.schema()))
+            .collect::<Vec<_>>();
+        let (qualified, scope) = qualify_tables(&schemas)?;
+        let data = data.resolve(&scope)?;
+        let mut tableplans = HashMap::new();
+        for (name, p) in sources {
+            let sch = p.schema();
+            let p: Arc<dyn Plan> = if sch.fields().iter().any(|f| qualified.contains(f)) {
+                Arc::new(ProjectPlan::new_qualified(p, &name, &qualified))
+            } else {
+                p
+            };
+            tableplans.insert(name, p);
+        }
+
         // Step 1: Create a plan for each mentioned table or view
         let mut plans: Vec<Arc<dyn Plan>> = vec![];
         for tblname in data.tables() {
-            let mut viewdef = self
-                .mdm
-                .lock()
-                .unwrap()
-                .get_view_def(tblname, Arc::clone(&tx))?;
-            if !viewdef.is_empty() {
-                // Recursively plan the view.
-                let mut parser = query();
-                // NOTE: query parser expect terminater.
-                viewdef = format!("{};", viewdef);
-                let (viewdata, _) = parser.parse(viewdef.as_str())?;
-                plans.push(self.create_plan(viewdata, Arc::clone(&tx))?);
-            } else {
-                plans.push(Arc::new(TablePlan::new(
-                    tblname,
-                    Arc::clone(&tx),
-                    self.mdm.clone(),
-                )?))
+            plans.push(Arc::clone(&tableplans[tblname]));
+        }
+        // my own extends
+        // the tables of JOIN ... ON are joined from left to right by the nested loops
+        for jt in data.joined_tables() {
+            let mut p = Arc::clone(&tableplans[jt.table_name()]);
+            for join in jt.joins() {
+                let rhs = Arc::clone(&tableplans[join.table_name()]);
+                let sch = join_schema(p.schema(), rhs.schema(), join.join_type());
+                join.pred().check_types(&sch)?;
+                p = Arc::new(ProductPlan::new_outer(
+                    p,
+                    rhs,
+                    join.pred().clone(),
+                    join.join_type(),
+                ));
             }
+            plans.push(p);
         }
         // Step 2: Create the product of all table plans
         let mut p = plans.remove(0);
@@ -53,15 +88,72 @@ impl QueryPlanner for BasicQueryPlanner {
             p = Arc::new(ProductPlan::new(Arc::clone(&p), nextplan));
         }
         // Step 3: Add a selection plan for the predicate
+        data.pred().check_types(&p.schema())?;
         p = Arc::new(SelectPlan::new(Arc::clone(&p), data.pred().clone()));
 
-        // Step 4: Project on the field names
+        // Step 4: Group by the group by fields and compute the aggregations
+        if !data.group_fields().is_empty() || !data.aggregations().is_empty() {
+            check_aggregations(&p.schema(), data.aggregations())?;
+            let aggfns = data.aggregations().iter().map(|a| a.function()).collect();
+            p = Arc::new(GroupByPlan::new(
+                Arc::clone(&self.next_table_num),
+                Arc::clone(&tx),
+                p,
+                data.group_fields().clone(),
+                aggfns,
+            ));
+        }
+
+        // Step 5: Compute the expressions of the select list, which can be sorted on
+        if !data.expressions().is_empty() {
+            p = Arc::new(ProjectPlan::new_extended(p, data.expressions())?);
+        }
+
+        // Step 6: Sort on the order by fields
+        if !data.order_fields().is_empty() {
+            let (sortfields, orders): (Vec<String>, _) =
+                data.order_fields().iter().cloned().unzip();
+            check_sort_fields(&p.schema(), &sortfields)?;
+            p = Arc::new(SortPlan::new_with_orders(
+                Arc::clone(&self.next_table_num),
+                p,
+                sortfields,
+                orders,
+                Arc::clone(&tx),
+            ));
+        }
+
+        // Step 7: Project on the field names
         Ok(Arc::new(ProjectPlan::new(p, data.fields().clone())))
     }
 }
 
 impl BasicQueryPlanner {
-    pub fn new(mdm: Arc<Mutex<MetadataMgr>>) -> Self {
-        Self { mdm }
+    pub fn new(next_table_num: Arc<Mutex<i32>>, mdm: Arc<Mutex<MetadataMgr>>) -> Self {
+        Self {
+            next_table_num,
+            mdm,
+        }
+    }
+    fn create_table_plan(
+        &mut self,
+        tblname: &str,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<Arc<dyn Plan>> {
+        let mut viewdef = self
+            .mdm
+            .lock()
+            .unwrap()
+            .get_view_def(tblname, Arc::clone(&tx))?;
+        if !viewdef.is_empty() {
+            // Recursively plan the view.
+            let mut parser = query();
+            // NOTE: query parser expect terminater.
+            viewdef = format!("{};", viewdef);
+            let (viewdata, _) = parser.parse(viewdef.as_str())?;
+            return self.create_plan(viewdata, tx);
+        }
+
+        Ok(Arc::new(TablePlan::new(tblname, tx, self.mdm.clone())?))
     }
 }
@@ -6,11 +6,17 @@ use super::{selectplan::SelectPlan, tableplan::TablePlan, updateplanner::UpdateP
 use crate::{
     metadata::manager::MetadataMgr,
     parser::{
-        createindexdata::CreateIndexData, createtabledata::CreateTableData,
-        createviewdata::CreateViewData, deletedata::DeleteData, insertdata::InsertData,
+        altertabledata::{AlterAction, AlterTableData},
+        createindexdata::CreateIndexData,
+        createtabledata::CreateTableData,
+        createviewdata::CreateViewData,
+        deletedata::DeleteData,
+        dropdata::DropData,
+        insertdata::InsertData,
         modifydata::ModifyData,
     },
     plan::plan::Plan,
+    query::constant::Constant,
     tx::transaction::Transaction,
 };
 
@@ -19,6 +25,10 @@ pub enum BasicUpdatePlannerError {
     DeleteAbort,
     InsertAbort,
     ModifyAbort,
+    NotNullViolation(String),
+    TableNotFound(String),
+    ViewNotFound(String),
+    IndexNotFound(String),
 }
 
 impl std::error::Error for BasicUpdatePlannerError {}
@@ -34,6 +44,18 @@ impl fmt::Display for BasicUpdatePlannerError {
             BasicUpdatePlannerError::ModifyAbort => {
                 write!(f, "modify abort")
             }
+            BasicUpdatePlannerError::NotNullViolation(fldname) => {
+                write!(f, "null value in not null field: {}", fldname)
+            }
+            BasicUpdatePlannerError::TableNotFound(tblname) => {
+                write!(f, "table not found: {}", tblname)
+            }
+            BasicUpdatePlannerError::ViewNotFound(viewname) => {
+                write!(f, "view not found: {}", viewname)
+            }
+            BasicUpdatePlannerError::IndexNotFound(idxname) => {
+                write!(f, "index not found: {}", idxname)
+            }
         }
     }
 }
@@ -50,6 +72,7 @@ impl UpdatePlanner for BasicUpdatePlanner {
             tx,
             Arc::clone(&self.mdm),
         )?);
+        data.pred().check_types(&p1.schema())?;
         let p2 = SelectPlan::new(p1, data.pred().clone());
         if let Ok(s) = p2.open() {
             if let Ok(us) = s.lock().unwrap().to_update_scan() {
@@ -70,12 +93,21 @@ impl UpdatePlanner for BasicUpdatePlanner {
             tx,
             Arc::clone(&self.mdm),
         )?);
+        // my own extends
+        // type checking of the new value and the predicate against the table
+        let newval = data.new_value().resolve(&p1.schema())?;
+        newval.check_assignable(&p1.schema(), data.target_field())?;
+        data.pred().check_types(&p1.schema())?;
+        let fldtype = p1.schema().field_type(data.target_field());
         let p2 = SelectPlan::new(p1, data.pred().clone());
         if let Ok(s) = p2.open() {
             if let Ok(us) = s.lock().unwrap().to_update_scan() {
                 let mut count = 0;
                 while us.next() {
-                    let val = data.new_value().evaluate(us.to_scan()?)?;
+                    let val = newval.evaluate(us.to_scan()?)?;
+                    // my own extends
+                    // the value has the type of the expression, not of the field.
+                    let val = val.as_field_type(fldtype)?;
                     us.set_val(data.target_field(), val)?;
                     count += 1;
                 }
@@ -91,6 +123,16 @@ impl UpdatePlanner for BasicUpdatePlanner {
             tx,
             Arc::clone(&self.mdm),
         )?);
+        // my own extends
+        // check the NOT NULL constraints before the record is inserted
+        let sch = p.schema();
+        for fldname in sch.fields() {
+            if !sch.is_nullable(fldname) && data.val(fldname).is_none_or(|v| v.is_null()) {
+                return Err(From::from(BasicUpdatePlannerError::NotNullViolation(
+                    fldname.to_string(),
+                )));
+            }
+        }
         if let Ok(s) = p.open() {
             if let Ok(us) = s.lock().unwrap().to_update_scan() {
                 us.insert()?;
@@ -100,6 +142,13 @@ impl UpdatePlanner for BasicUpdatePlanner {
                         us.set_val(fldname, val.clone())?;
                     }
                 }
+                // my own extends
+                // the omitted fields are NULL
+                for fldname in p.schema().fields() {
+                    if !data.fields().contains(fldname) {
+                        us.set_val(fldname, Constant::Null)?;
+                    }
+                }
                 us.close()?;
                 return Ok(1);
             }
@@ -112,7 +161,12 @@ impl UpdatePlanner for BasicUpdatePlanner {
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<i32> {
         let mdm = self.mdm.lock().unwrap();
-        mdm.create_table(data.table_name(), Arc::new(data.new_schema().clone()), tx)?;
+        mdm.create_table_with(
+            data.table_name(),
+            Arc::new(data.new_schema().clone()),
+            data.format(),
+            tx,
+        )?;
         Ok(0)
     }
     fn execute_create_view(
@@ -133,6 +187,54 @@ impl UpdatePlanner for BasicUpdatePlanner {
         mdm.create_index(data.index_name(), data.table_name(), data.field_name(), tx)?;
         Ok(0)
     }
+    fn execute_drop_table(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let mut mdm = self.mdm.lock().unwrap();
+        if !mdm.drop_table(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(BasicUpdatePlannerError::TableNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_drop_view(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let mdm = self.mdm.lock().unwrap();
+        if !mdm.drop_view(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(BasicUpdatePlannerError::ViewNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_drop_index(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
+        let mdm = self.mdm.lock().unwrap();
+        if !mdm.drop_index(data.name(), tx)? && !data.if_exists() {
+            return Err(From::from(BasicUpdatePlannerError::IndexNotFound(
+                data.name().to_string(),
+            )));
+        }
+        Ok(0)
+    }
+    fn execute_alter_table(
+        &self,
+        data: AlterTableData,
+        tx: Arc<Mutex<Transaction>>,
+    ) -> Result<i32> {
+        let mut mdm = self.mdm.lock().unwrap();
+        let tblname = data.table_name();
+        let found = match data.action() {
+            AlterAction::AddField(fldname, fi) => mdm.add_field(tblname, fldname, *fi, tx)?,
+            AlterAction::DropField(fldname) => mdm.drop_field(tblname, fldname, tx)?,
+            AlterAction::RenameField(oldname, newname) => {
+                mdm.rename_field(tblname, oldname, newname, tx)?
+            }
+        };
+        if !found {
+            return Err(From::from(BasicUpdatePlannerError::TableNotFound(
+                tblname.to_string(),
+            )));
+        }
+        Ok(0)
+    }
 }
 
 impl BasicUpdatePlanner {
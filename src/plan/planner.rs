@@ -6,14 +6,15 @@ use std::sync::{Arc, Mutex};
 
 use super::{plan::Plan, queryplanner::QueryPlanner, updateplanner::UpdatePlanner};
 use crate::{
-    parser::parser::{query, update_cmd},
-    parser::{ddl::DDL, dml::DML, sql::SQL},
+    parser::parser::{query, tcl, update_cmd},
+    parser::{ddl::DDL, dml::DML, querydata::QueryData, sql::SQL, tcl::TCL},
     tx::transaction::Transaction,
 };
 
 #[derive(Debug)]
 pub enum PlannerError {
     InvalidExecuteCommand,
+    NotGroupedField(String),
 }
 
 impl std::error::Error for PlannerError {}
@@ -23,6 +24,13 @@ impl fmt::Display for PlannerError {
             PlannerError::InvalidExecuteCommand => {
                 write!(f, "invalid execute command")
             }
+            PlannerError::NotGroupedField(fldname) => {
+                write!(
+                    f,
+                    "field must appear in group by or be aggregated: {}",
+                    fldname
+                )
+            }
         }
     }
 }
@@ -51,8 +59,52 @@ impl Planner {
             warn!("parser cant parse full text: {}", rest);
         }
         // TODO: code to verify the query should be here...
+        self.verify_query(&data)?;
         self.qplanner.lock().unwrap().create_plan(data, tx)
     }
+    // my own extends
+    fn verify_query(&self, data: &QueryData) -> Result<()> {
+        if data.group_fields().is_empty() && data.aggregations().is_empty() {
+            return Ok(());
+        }
+        let aggfields = data
+            .aggregations()
+            .iter()
+            .map(|a| a.field_name())
+            .collect::<Vec<String>>();
+        for fldname in data
+            .fields()
+            .iter()
+            .chain(data.order_fields().iter().map(|(f, _)| f))
+        {
+            // a computed field is grouped if the fields of its expression are
+            let fldnames = match data.expressions().iter().find(|(name, _)| name == fldname) {
+                Some((_, expr)) => expr.field_names(),
+                None => vec![fldname.as_str()],
+            };
+            for fldname in fldnames {
+                if data.group_fields().iter().any(|f| f == fldname)
+                    || aggfields.iter().any(|f| f == fldname)
+                {
+                    continue;
+                }
+                return Err(From::from(PlannerError::NotGroupedField(
+                    fldname.to_string(),
+                )));
+            }
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // a transaction control command, which the connection runs instead of the planner.
+    pub fn transaction_control(&self, cmd: &str) -> Option<TCL> {
+        let mut parser = tcl();
+        match parser.parse(cmd) {
+            Ok((tcl, rest)) if rest.trim().is_empty() => Some(tcl),
+            _ => None,
+        }
+    }
     pub fn execute_update(&mut self, cmd: &str, tx: Arc<Mutex<Transaction>>) -> Result<i32> {
         let mut parser = update_cmd();
         let (data, rest) = parser.parse(cmd)?;
@@ -88,6 +140,39 @@ impl Planner {
                     let p = self.uplanner.lock().unwrap();
                     return p.execute_create_index(cidata, tx);
                 }
+                DDL::DropTable(ddata) => {
+                    let p = self.uplanner.lock().unwrap();
+                    return p.execute_drop_table(ddata, tx);
+                }
+                DDL::DropView(ddata) => {
+                    let p = self.uplanner.lock().unwrap();
+                    return p.execute_drop_view(ddata, tx);
+                }
+                DDL::DropIndex(ddata) => {
+                    let p = self.uplanner.lock().unwrap();
+                    return p.execute_drop_index(ddata, tx);
+                }
+                DDL::Alter(adata) => {
+                    let p = self.uplanner.lock().unwrap();
+                    return p.execute_alter_table(adata, tx);
+                }
+            },
+            // my own extends
+            SQL::TCL(tcl) => match tcl {
+                TCL::SetIsolationLevel(level) => {
+                    tx.lock().unwrap().set_isolation_level(level);
+                    return Ok(0);
+                }
+                TCL::Savepoint(name) => {
+                    tx.lock().unwrap().savepoint(&name)?;
+                    return Ok(0);
+                }
+                TCL::RollbackToSavepoint(name) => {
+                    tx.lock().unwrap().rollback_to_savepoint(&name)?;
+                    return Ok(0);
+                }
+                // the transaction itself is started and ended by the connection
+                _ => return Err(From::from(PlannerError::InvalidExecuteCommand)),
             },
         }
     }
@@ -96,10 +181,110 @@ impl Planner {
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
     use std::sync::{Arc, Mutex};
-    use std::{fs, path::Path};
+    use std::{fs, path::Path, str::FromStr};
 
-    use crate::server::simpledb::SimpleDB;
+    use super::Planner;
+    use crate::{
+        index::{RangeBound, SearchRange},
+        query::{constant::Constant, join::JoinType},
+        repr::planrepr::Operation,
+        server::{
+            config::{self, SimpleDBConfig},
+            simpledb::SimpleDB,
+        },
+        tx::transaction::Transaction,
+    };
+
+    // a new database in the directory, removing the one of the last run
+    fn new_db(dir: &str) -> Result<SimpleDB> {
+        if Path::new(dir).exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        SimpleDB::new(dir)
+    }
+
+    fn build_db(
+        dir: &str,
+        query_planner: config::QueryPlanner,
+        concurrency_mgr: config::ConcurrencyMgr,
+    ) -> Result<SimpleDB> {
+        if Path::new(dir).exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        let cfg = SimpleDBConfig {
+            block_size: 400,
+            num_of_buffers: 16,
+            buffer_manager: config::BufferMgr::LRU,
+            query_planner,
+            concurrency_mgr,
+        };
+        SimpleDB::build_from(cfg)(dir)
+    }
+
+    // the values of the fields of the records of the query, and the operations of its plan
+    fn select(
+        planner: &mut Planner,
+        tx: &Arc<Mutex<Transaction>>,
+        query: &str,
+        fields: &[&str],
+    ) -> Result<(Vec<Vec<Constant>>, Vec<Operation>)> {
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(tx))?;
+        let mut reprs = vec![plan.repr()];
+        let mut ops = vec![];
+        while let Some(repr) = reprs.pop() {
+            ops.push(repr.operation());
+            reprs.append(&mut repr.sub_plan_reprs());
+        }
+        let scan = plan.open()?;
+        let mut rows = vec![];
+        let mut iter = scan.lock().unwrap();
+        while iter.next() {
+            let mut row = vec![];
+            for fldname in fields {
+                row.push(iter.get_val(fldname)?);
+            }
+            println!("{:?}", row);
+            rows.push(row);
+        }
+        iter.close()?;
+        Ok((rows, ops))
+    }
+
+    fn select_rows(
+        planner: &mut Planner,
+        tx: &Arc<Mutex<Transaction>>,
+        query: &str,
+        fields: &[&str],
+    ) -> Result<Vec<Vec<Constant>>> {
+        Ok(select(planner, tx, query, fields)?.0)
+    }
+
+    // the sorted pairs of the two fields (NULL as None), and the operations of the plan
+    type Rows = Vec<(Option<String>, Option<String>)>;
+    fn select_pairs(
+        planner: &mut Planner,
+        tx: &Arc<Mutex<Transaction>>,
+        query: &str,
+        fld1: &str,
+        fld2: &str,
+    ) -> Result<(Rows, Vec<Operation>)> {
+        let (rows, ops) = select(planner, tx, query, &[fld1, fld2])?;
+        let text = |val: &Constant| match val {
+            Constant::Null => None,
+            Constant::String(s) => Some(s.clone()),
+            val => Some(val.to_string()),
+        };
+        let mut pairs: Rows = rows
+            .iter()
+            .map(|row| (text(&row[0]), text(&row[1])))
+            .collect();
+        pairs.sort();
+        Ok((pairs, ops))
+    }
 
     #[test]
     fn unit_test() -> Result<()> {
@@ -182,6 +367,109 @@ mod tests {
         iter.close()?;
         println!("Rows = {}", rows);
 
+        // SELECT Table ORDER BY
+        let query = "SELECT SName, GradYear FROM STUDENT ORDER BY GradYear DESC, SName;";
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+        let scan = plan.open()?;
+        let mut names = vec![];
+        let mut iter = scan.lock().unwrap();
+        println!("SName     GradYear");
+        println!("------------------");
+        while iter.next() {
+            let name = iter.get_string("SName")?;
+            let year = iter.get_i32("GradYear")?;
+            println!("{:<10}{:>8}", name, year);
+            names.push(name);
+        }
+        iter.close()?;
+        println!("Rows = {}", names.len());
+        assert_eq!(
+            names,
+            vec!["max", "sue", "art", "joe", "lee", "amy", "bob", "kim", "pat"]
+        );
+
+        // SELECT Table GROUP BY
+        let query = "SELECT MajorId, COUNT(*), MAX(GradYear), MIN(SName), AVG(GradYear), \
+                       SUM(GradYear) FROM STUDENT GROUP BY MajorId ORDER BY COUNT(*) DESC;";
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+        let scan = plan.open()?;
+        let mut groups = vec![];
+        let mut avgs = vec![];
+        let mut iter = scan.lock().unwrap();
+        println!(" MajorId   Count MaxYear MinName   SumYear   AvgYear");
+        println!("----------------------------------------------------");
+        while iter.next() {
+            let major_id = iter.get_i32("MajorId")?;
+            let count = iter.get_i32("countofall")?;
+            let max_year = iter.get_i32("maxofGradYear")?;
+            let min_name = iter.get_string("minofSName")?;
+            let sum_year = iter.get_val("sumofGradYear")?.as_i64()?;
+            let avg_year = iter.get_val("avgofGradYear")?;
+            println!(
+                "{:>8}{:>8}{:>8} {:<10}{:>7}{:>10}",
+                major_id, count, max_year, min_name, sum_year, avg_year
+            );
+            groups.push((major_id, count, max_year, min_name, sum_year));
+            avgs.push(avg_year.to_string());
+        }
+        iter.close()?;
+        println!("Rows = {}", groups.len());
+        assert_eq!(
+            groups,
+            vec![
+                (20, 4, 2022, "amy".to_string(), 8081),
+                (10, 3, 2022, "joe".to_string(), 6064),
+                (30, 2, 2021, "art".to_string(), 4041),
+            ]
+        );
+        // the average is not truncated to an integer
+        assert_eq!(avgs, vec!["2020.2500", "2021.3333", "2020.5000"]);
+        let query = "SELECT SName, COUNT(*) FROM STUDENT GROUP BY MajorId;";
+        println!("Query: {}", query);
+        assert!(planner.create_query_plan(query, Arc::clone(&tx)).is_err());
+
+        // SELECT Table WHERE range, OR and NOT
+        let query = "SELECT SName FROM STUDENT \
+                      WHERE NOT GradYear <= 2020 AND (MajorId = 10 OR SName = 'sue') \
+                      ORDER BY SName;";
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+        let scan = plan.open()?;
+        let mut names = vec![];
+        let mut iter = scan.lock().unwrap();
+        println!("SName");
+        println!("----------");
+        while iter.next() {
+            let name = iter.get_string("SName")?;
+            println!("{:<10}", name);
+            names.push(name);
+        }
+        iter.close()?;
+        println!("Rows = {}", names.len());
+        assert_eq!(names, vec!["joe", "lee", "max", "sue"]);
+
+        // SELECT Table WHERE BETWEEN
+        let query = "SELECT SName, GradYear FROM STUDENT \
+                      WHERE GradYear BETWEEN 2019 AND 2020 ORDER BY SName;";
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+        let scan = plan.open()?;
+        let mut names = vec![];
+        let mut iter = scan.lock().unwrap();
+        println!("SName     GradYear");
+        println!("--------------------");
+        while iter.next() {
+            let name = iter.get_string("SName")?;
+            let year = iter.get_i32("GradYear")?;
+            println!("{:<10}{:>8}", name, year);
+            names.push(name);
+        }
+        iter.close()?;
+        println!("Rows = {}", names.len());
+        assert_eq!(names, vec!["amy", "bob", "kim", "pat"]);
+
         // SELECT View
 
         let query = "SELECT SName, DName FROM name_dep WHERE GradYear = 2020;";
@@ -272,6 +560,1194 @@ mod tests {
         tx.lock().unwrap().commit()?;
         // tx.lock().unwrap().rollback()?;
 
+        Ok(())
+    }
+    #[test]
+    fn null_test() -> Result<()> {
+        if Path::new("_test/planner_null").exists() {
+            fs::remove_dir_all("_test/planner_null")?;
+        }
+
+        let simpledb = SimpleDB::new("_test/planner_null")?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        let sqls = vec![
+            "CREATE TABLE STUDENT (SId integer NOT NULL, SName varchar(10), GradYear integer, MajorId integer);",
+            "CREATE INDEX idx_grad_year ON STUDENT (GradYear);",
+            "INSERT INTO STUDENT (SId, SName, GradYear, MajorId) VALUES (1, 'joe', 2021, 10);",
+            "INSERT INTO STUDENT (SId, SName, GradYear, MajorId) VALUES (2, 'amy', NULL, 20);",
+            "INSERT INTO STUDENT (SId, SName, MajorId) VALUES (3, 'max', 10);",
+            "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (4, 'sue', 2022);",
+            "INSERT INTO STUDENT (SId, GradYear, MajorId) VALUES (5, 2020, 20);",
+            "UPDATE STUDENT SET MajorId = NULL WHERE SId = 1;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+        // NOT NULL constraint
+        let sql = "INSERT INTO STUDENT (SName) VALUES ('bob');";
+        println!("Execute: {}", sql);
+        assert!(planner.execute_update(sql, Arc::clone(&tx)).is_err());
+
+        let mut select = |query: &str| -> Result<Vec<i32>> {
+            println!("Query: {}", query);
+            let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+            let scan = plan.open()?;
+            let mut ids = vec![];
+            let mut iter = scan.lock().unwrap();
+            while iter.next() {
+                let id = iter.get_i32("SId")?;
+                println!(
+                    "{:>4} {:>10} {:>6} {:>4}",
+                    id,
+                    iter.get_val("SName")?,
+                    iter.get_val("GradYear")?,
+                    iter.get_val("MajorId")?
+                );
+                ids.push(id);
+            }
+            iter.close()?;
+            Ok(ids)
+        };
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE GradYear IS NULL ORDER BY SId;")?, vec![2, 3]);
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE GradYear IS NOT NULL ORDER BY SId;")?, vec![1, 4, 5]);
+        // comparisons with NULL are unknown, and so is its negation
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE MajorId = 10 ORDER BY SId;")?, vec![3]);
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE NOT MajorId = 10 ORDER BY SId;")?, vec![2, 5]);
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE GradYear > 2020 ORDER BY SId;")?, vec![1, 4]);
+        assert_eq!(
+            select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE MajorId = NULL;")?,
+            vec![]
+        );
+        // unknown or true = true
+        assert_eq!(select("SELECT SId, SName, GradYear, MajorId FROM STUDENT WHERE MajorId = 20 OR SName = 'joe' ORDER BY SId;")?, vec![1, 2, 5]);
+
+        // aggregates ignore NULL
+        let query = "SELECT MajorId, COUNT(*), COUNT(GradYear), MAX(GradYear), MIN(GradYear) FROM STUDENT GROUP BY MajorId;";
+        println!("Query: {}", query);
+        let plan = planner.create_query_plan(query, Arc::clone(&tx))?;
+        let scan = plan.open()?;
+        let mut rows = vec![];
+        let mut iter = scan.lock().unwrap();
+        while iter.next() {
+            let row = (
+                iter.get_val("MajorId")?,
+                iter.get_val("countofall")?,
+                iter.get_val("countofGradYear")?,
+                iter.get_val("maxofGradYear")?,
+                iter.get_val("minofGradYear")?,
+            );
+            println!("{:?}", row);
+            rows.push(row);
+        }
+        iter.close()?;
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    Constant::Null,
+                    Constant::I32(2),
+                    Constant::I32(2),
+                    Constant::I32(2022),
+                    Constant::I32(2021)
+                ),
+                (
+                    Constant::I32(10),
+                    Constant::I32(1),
+                    Constant::I32(0),
+                    Constant::Null,
+                    Constant::Null
+                ),
+                (
+                    Constant::I32(20),
+                    Constant::I32(2),
+                    Constant::I32(1),
+                    Constant::I32(2020),
+                    Constant::I32(2020)
+                ),
+            ]
+        );
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_aggregate_test() -> Result<()> {
+        for query_planner in [config::QueryPlanner::Basic, config::QueryPlanner::Heuristic] {
+            let dir = format!("_test/planner_empty_aggregate_{}", query_planner).to_lowercase();
+            let simpledb = build_db(&dir, query_planner, config::ConcurrencyMgr::Locking)?;
+
+            let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+            let mut planner = simpledb.planner()?;
+            let sql =
+                "CREATE TABLE STUDENT (SId integer NOT NULL, SName varchar(10), GradYear integer);";
+            planner.execute_update(sql, Arc::clone(&tx))?;
+
+            // without group by, no records are one row
+            let query = "SELECT COUNT(*), COUNT(GradYear), SUM(GradYear), AVG(GradYear), \
+                           MAX(SName), MIN(SName) FROM STUDENT;";
+            let fields = [
+                "countofall",
+                "countofGradYear",
+                "sumofGradYear",
+                "avgofGradYear",
+                "maxofSName",
+                "minofSName",
+            ];
+            let rows = select_rows(&mut planner, &tx, query, &fields)?;
+            assert_eq!(
+                rows,
+                vec![vec![
+                    Constant::I32(0),
+                    Constant::I32(0),
+                    Constant::Null,
+                    Constant::Null,
+                    Constant::Null,
+                    Constant::Null,
+                ]]
+            );
+            let sql = "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (1, 'joe', 2021);";
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            let query = "SELECT COUNT(*) FROM STUDENT WHERE GradYear = 2020;";
+            let rows = select_rows(&mut planner, &tx, query, &["countofall"])?;
+            assert_eq!(rows, vec![vec![Constant::I32(0)]]);
+
+            // with group by, no records are no groups
+            let query =
+                "SELECT GradYear, COUNT(*) FROM STUDENT WHERE GradYear = 2020 GROUP BY GradYear;";
+            let rows = select_rows(&mut planner, &tx, query, &["GradYear", "countofall"])?;
+            assert!(rows.is_empty());
+
+            tx.lock().unwrap().commit()?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sum_test() -> Result<()> {
+        let simpledb = new_db("_test/planner_sum")?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+        for sql in [
+            "CREATE TABLE NUMS (Id integer NOT NULL, Name varchar(10), Big bigint, Grp integer);",
+            "INSERT INTO NUMS (Id, Name, Big, Grp) VALUES (1, 'max', 9223372036854775807, 10);",
+            "INSERT INTO NUMS (Id, Name, Big, Grp) VALUES (2, 'one', 1, 10);",
+            "INSERT INTO NUMS (Id, Name, Big, Grp) VALUES (3, 'two', 2, 20);",
+        ] {
+            planner.execute_update(sql, Arc::clone(&tx))?;
+        }
+
+        // the sum overflows a BIGINT
+        let query = "SELECT SUM(Big), AVG(Big) FROM NUMS;";
+        for fldname in ["sumofBig", "avgofBig"] {
+            let result = select_rows(&mut planner, &tx, query, &[fldname]);
+            println!("{} => {:?}", fldname, result.as_ref().err());
+            assert!(result.is_err());
+        }
+        let query = "SELECT Grp, SUM(Big) FROM NUMS WHERE Id > 1 GROUP BY Grp;";
+        let rows = select_rows(&mut planner, &tx, query, &["Grp", "sumofBig"])?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![Constant::I32(10), Constant::I64(1)],
+                vec![Constant::I32(20), Constant::I64(2)],
+            ]
+        );
+
+        // only numbers are added up
+        for query in [
+            "SELECT SUM(Name) FROM NUMS;",
+            "SELECT Grp, AVG(Name) FROM NUMS GROUP BY Grp;",
+        ] {
+            let result = planner.create_query_plan(query, Arc::clone(&tx));
+            println!("Query: {} => {:?}", query, result.as_ref().err());
+            assert!(result.is_err());
+        }
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_order_by_test() -> Result<()> {
+        for query_planner in [config::QueryPlanner::Basic, config::QueryPlanner::Heuristic] {
+            let dir = format!("_test/planner_unknown_order_by_{}", query_planner).to_lowercase();
+            let simpledb = build_db(&dir, query_planner, config::ConcurrencyMgr::Locking)?;
+
+            let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+            let mut planner = simpledb.planner()?;
+            for sql in [
+                "CREATE TABLE STUDENT (SId integer NOT NULL, SName varchar(10), MajorId integer);",
+                "INSERT INTO STUDENT (SId, SName, MajorId) VALUES (1, 'joe', 10);",
+                "INSERT INTO STUDENT (SId, SName, MajorId) VALUES (2, 'amy', 20);",
+            ] {
+                planner.execute_update(sql, Arc::clone(&tx))?;
+            }
+
+            for query in [
+                "SELECT SId FROM STUDENT ORDER BY GradYear;",
+                "SELECT SId FROM STUDENT ORDER BY SName, s.SId;",
+                "SELECT MajorId, COUNT(*) FROM STUDENT GROUP BY MajorId ORDER BY countofSId;",
+            ] {
+                let result = planner.create_query_plan(query, Arc::clone(&tx));
+                println!("Query: {} => {:?}", query, result.as_ref().err());
+                assert!(result.is_err());
+            }
+            let query = "SELECT SId FROM STUDENT ORDER BY SName;";
+            let rows = select_rows(&mut planner, &tx, query, &["SId"])?;
+            assert_eq!(rows, vec![vec![Constant::I32(2)], vec![Constant::I32(1)]]);
+
+            tx.lock().unwrap().commit()?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn expression_test() -> Result<()> {
+        let simpledb = new_db("_test/planner_expression")?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        let sqls = vec![
+            "CREATE TABLE ITEM (Id integer NOT NULL, Name varchar(10), Price decimal(8, 2), Qty smallint);",
+            "INSERT INTO ITEM (Id, Name, Price, Qty) VALUES (1, 'pen', 1.50, 4);",
+            "INSERT INTO ITEM (Id, Name, Price, Qty) VALUES (2, 'book', 12.00, 1);",
+            "INSERT INTO ITEM (Id, Name, Price, Qty) VALUES (3, 'eraser', 0.80, 10);",
+            "INSERT INTO ITEM (Id, Name, Price) VALUES (4, 'ruler', 2.25);",
+            "UPDATE ITEM SET Qty = Qty * 2 + 1 WHERE Id = 2;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+
+        let dec = |s: &str| Constant::Decimal(Decimal::from_str(s).unwrap());
+
+        // a computed column can be sorted on by its alias
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Name, Price * Qty AS Total FROM ITEM WHERE Qty + 1 > 2 ORDER BY Total DESC;",
+            &["Name", "Total"],
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![Constant::String("book".to_string()), dec("36.00")],
+                vec![Constant::String("eraser".to_string()), dec("8.00")],
+                vec![Constant::String("pen".to_string()), dec("6.00")],
+            ]
+        );
+        // a column without an alias is named by its expression. NULL is propagated.
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Id, 'no.' || Id AS Label, -Qty, Qty / 0 AS Z FROM ITEM WHERE Id % 2 = 0 ORDER BY Id;",
+            &["Id", "Label", "-Qty", "Z"],
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    Constant::I32(2),
+                    Constant::String("no.2".to_string()),
+                    Constant::I16(-3),
+                    Constant::Null
+                ],
+                vec![
+                    Constant::I32(4),
+                    Constant::String("no.4".to_string()),
+                    Constant::Null,
+                    Constant::Null
+                ],
+            ]
+        );
+        // an expression of the group by fields
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Qty, Qty * 10 AS Q10, COUNT(*) FROM ITEM WHERE Qty IS NOT NULL GROUP BY Qty ORDER BY Q10;",
+            &["Qty", "Q10", "countofall"],
+        )?;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][1], Constant::I16(30));
+
+        // type errors
+        for query in [
+            "SELECT Name + 1 AS X FROM ITEM;",
+            "SELECT Id FROM ITEM WHERE -Name = 1;",
+            "SELECT Id FROM ITEM WHERE Nme || 'x' = 'x';",
+            "SELECT Price * 2 AS P FROM ITEM GROUP BY Qty;",
+        ] {
+            let result = planner.create_query_plan(query, Arc::clone(&tx));
+            println!("Query: {} => {:?}", query, result.as_ref().err());
+            assert!(result.is_err());
+        }
+        let sql = "UPDATE ITEM SET Qty = Name * 2;";
+        assert!(planner.execute_update(sql, Arc::clone(&tx)).is_err());
+        // the type of the new value is checked even if no record is updated
+        for sql in [
+            "UPDATE ITEM SET Qty = Name WHERE Id = 0;",
+            "UPDATE ITEM SET Name = Qty + 1 WHERE Id = 0;",
+            "UPDATE ITEM SET Qty = Price * 2 WHERE Id = 0;",
+        ] {
+            let result = planner.execute_update(sql, Arc::clone(&tx));
+            println!("Execute: {} => {:?}", sql, result.as_ref().err());
+            assert!(result.is_err());
+        }
+        let sql = "UPDATE ITEM SET Price = Qty * 2 WHERE Id = 0;";
+        assert_eq!(planner.execute_update(sql, Arc::clone(&tx))?, 0);
+        // a value out of the range of the field
+        let sql = "UPDATE ITEM SET Qty = Qty * 10000 WHERE Id = 3;";
+        assert!(planner.execute_update(sql, Arc::clone(&tx)).is_err());
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn function_test() -> Result<()> {
+        let simpledb = new_db("_test/planner_function")?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        let sqls = vec![
+            "CREATE TABLE MEMBER (Id integer NOT NULL, Name varchar(10), Nick varchar(10), Joined date, Score smallint);",
+            "INSERT INTO MEMBER (Id, Name, Nick, Joined, Score) VALUES (1, ' alice ', 'al', '2023-01-31', -5);",
+            "INSERT INTO MEMBER (Id, Name, Joined, Score) VALUES (2, 'Bob', '2024-02-28', 7);",
+            "INSERT INTO MEMBER (Id, Name) VALUES (3, 'carol');",
+            "UPDATE MEMBER SET Name = UPPER(TRIM(Name)) WHERE LENGTH(Name) > 5;",
+            "UPDATE MEMBER SET Score = ABS(Score) WHERE Score < 0;",
+            "UPDATE MEMBER SET Joined = DATE_ADD(Joined, 1) WHERE YEAR(Joined) = 2024;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+
+        let s = |s: &str| Constant::String(s.to_string());
+        let date = |s: &str| Constant::Date(NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap());
+
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Id, Name, COALESCE(Nick, LOWER(SUBSTR(Name, 1, 2))) AS N, Score, Joined, MONTH(Joined) AS M FROM MEMBER ORDER BY Id;",
+            &["Id", "Name", "N", "Score", "Joined", "M"],
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    Constant::I32(1),
+                    s("ALICE"),
+                    s("al"),
+                    Constant::I16(5),
+                    date("2023-01-31"),
+                    Constant::I32(1)
+                ],
+                vec![
+                    Constant::I32(2),
+                    s("Bob"),
+                    s("bo"),
+                    Constant::I16(7),
+                    date("2024-02-29"),
+                    Constant::I32(2)
+                ],
+                vec![
+                    Constant::I32(3),
+                    s("carol"),
+                    s("ca"),
+                    Constant::Null,
+                    Constant::Null,
+                    Constant::Null
+                ],
+            ]
+        );
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Id, CAST(Id AS varchar(3)) || '-' || CAST(Score * 10 AS decimal(5, 1)) AS Code FROM MEMBER WHERE YEAR(CAST(Joined AS timestamp)) < 2024;",
+            &["Id", "Code"],
+        )?;
+        assert_eq!(rows, vec![vec![Constant::I32(1), s("1-50.0")]]);
+        // COALESCE is of the widest type of its arguments, whichever of them is not NULL
+        let rows = select_rows(
+            &mut planner,
+            &tx,
+            "SELECT Id, COALESCE(Score, 1.5) AS S, COALESCE(Joined, CAST(Joined AS timestamp)) AS J FROM MEMBER;",
+            &["S", "J"],
+        )?;
+        let timestamp = |s: &str| {
+            Constant::Timestamp(
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            )
+        };
+        assert_eq!(
+            rows,
+            vec![
+                vec![Constant::Decimal(Decimal::from(5)), timestamp("2023-01-31")],
+                vec![Constant::Decimal(Decimal::from(7)), timestamp("2024-02-29")],
+                vec![Constant::Decimal(Decimal::from_str("1.5")?), Constant::Null],
+            ]
+        );
+        // the values compare equal across the types, so the types are checked, too
+        assert!(rows.iter().all(|row| matches!(row[0], Constant::Decimal(_))
+            && matches!(row[1], Constant::Timestamp(_) | Constant::Null)));
+
+        // type errors
+        for query in [
+            "SELECT UPPER(Id) AS X FROM MEMBER;",
+            "SELECT Id FROM MEMBER WHERE SUBSTR(Name, 'a') = 'b';",
+            "SELECT Id FROM MEMBER WHERE YEAR(Name) = 2024;",
+            "SELECT COALESCE(Name, Score) AS X FROM MEMBER;",
+            "SELECT NOSUCH(Name) AS X FROM MEMBER;",
+        ] {
+            let result = planner.create_query_plan(query, Arc::clone(&tx));
+            println!("Query: {} => {:?}", query, result.as_ref().err());
+            assert!(result.is_err());
+        }
+        // the value is converted to the type of the field, if it can be
+        let sql = "UPDATE MEMBER SET Score = LENGTH(Name);";
+        assert!(planner.execute_update(sql, Arc::clone(&tx)).is_ok());
+        let sql = "UPDATE MEMBER SET Score = Name;";
+        assert!(planner.execute_update(sql, Arc::clone(&tx)).is_err());
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn like_in_test() -> Result<()> {
+        let simpledb = new_db("_test/planner_like_in")?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        let sqls = vec![
+            "CREATE TABLE PERSON (Id integer NOT NULL, Name varchar(10), Dept integer);",
+            "CREATE INDEX idx_name ON PERSON (Name);",
+            "CREATE INDEX idx_dept ON PERSON (Dept);",
+            "INSERT INTO PERSON (Id, Name, Dept) VALUES (1, 'alice', 10);",
+            "INSERT INTO PERSON (Id, Name, Dept) VALUES (2, 'albert', 20);",
+            "INSERT INTO PERSON (Id, Name, Dept) VALUES (3, 'bob', 30);",
+            "INSERT INTO PERSON (Id, Name, Dept) VALUES (4, 'al_x', 10);",
+            "INSERT INTO PERSON (Id, Name, Dept) VALUES (5, 'ALAN', 40);",
+            "INSERT INTO PERSON (Id, Name) VALUES (6, 'carol');",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+
+        // the ids of the records, and the ranges of the index scan if used
+        let mut select_ids = |query: &str| -> Result<(Vec<i32>, Option<Vec<SearchRange>>)> {
+            let (rows, ops) = select(&mut planner, &tx, query, &["Id"])?;
+            let mut ids = rows
+                .iter()
+                .map(|row| row[0].as_i32())
+                .collect::<Result<Vec<_>>>()?;
+            ids.sort();
+            let ranges = ops.into_iter().find_map(|op| match op {
+                Operation::IndexRangeScan { ranges, .. } => Some(ranges),
+                _ => None,
+            });
+            println!("{:?} by {:?}", ids, ranges);
+            Ok((ids, ranges))
+        };
+        let s = |s: &str| Constant::String(s.to_string());
+        let point = |v: i32| {
+            SearchRange::new(
+                RangeBound::Included(Constant::I32(v)),
+                RangeBound::Included(Constant::I32(v)),
+            )
+        };
+
+        // a prefix LIKE is a range of the index
+        let (ids, ranges) = select_ids("SELECT Id FROM PERSON WHERE Name LIKE 'al%';")?;
+        assert_eq!(ids, vec![1, 2, 4]);
+        assert_eq!(
+            ranges,
+            Some(vec![SearchRange::new(
+                RangeBound::Included(s("al")),
+                RangeBound::Excluded(s("am"))
+            )])
+        );
+        let (ids, _) = select_ids("SELECT Id FROM PERSON WHERE Name LIKE 'al!_%' ESCAPE '!';")?;
+        assert_eq!(ids, vec![4]);
+        let (ids, ranges) = select_ids("SELECT Id FROM PERSON WHERE Name LIKE '_o%';")?;
+        assert_eq!(ids, vec![3]);
+        assert_eq!(ranges, None);
+        // NULL is neither like nor not like the pattern
+        let (ids, _) = select_ids("SELECT Id FROM PERSON WHERE Name NOT LIKE '%o%';")?;
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+
+        // an IN list is the ranges of its values
+        let (ids, ranges) = select_ids("SELECT Id FROM PERSON WHERE Dept IN (30, 10, 30);")?;
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert_eq!(ranges, Some(vec![point(10), point(30)]));
+        let (ids, _) = select_ids("SELECT Id FROM PERSON WHERE Dept NOT IN (10, 20);")?;
+        assert_eq!(ids, vec![3, 5]);
+        let (ids, _) = select_ids(
+            "SELECT Id FROM PERSON WHERE Name IN ('bob', 'alice') AND Dept IN (10, 20);",
+        )?;
+        assert_eq!(ids, vec![1]);
+        let (ids, _) = select_ids("SELECT Id FROM PERSON WHERE Dept + 1 IN (21, 41);")?;
+        assert_eq!(ids, vec![2, 5]);
+
+        // type errors
+        for query in [
+            "SELECT Id FROM PERSON WHERE Dept LIKE '1%';",
+            "SELECT Id FROM PERSON WHERE Name LIKE 1;",
+        ] {
+            let result = planner.create_query_plan(query, Arc::clone(&tx));
+            println!("Query: {} => {:?}", query, result.as_ref().err());
+            assert!(result.is_err());
+        }
+        let sql = "DELETE FROM PERSON WHERE Name LIKE 'al%' AND Dept IN (10, 40);";
+        assert_eq!(planner.execute_update(sql, Arc::clone(&tx))?, 2);
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_test() -> Result<()> {
+        for query_planner in [config::QueryPlanner::Basic, config::QueryPlanner::Heuristic] {
+            let dir = format!("_test/planner_join_{}", query_planner).to_lowercase();
+            let simpledb = build_db(&dir, query_planner, config::ConcurrencyMgr::Locking)?;
+            join_test_with(&simpledb)?;
+        }
+
+        Ok(())
+    }
+
+    fn join_test_with(simpledb: &SimpleDB) -> Result<()> {
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        let sqls = vec![
+            "CREATE TABLE EMP (Id integer NOT NULL, Name varchar(10), DeptId integer);",
+            "CREATE TABLE DEPT (DId integer NOT NULL, DName varchar(10));",
+            "CREATE INDEX idx_did ON DEPT (DId);",
+            "CREATE TABLE PROJ (PId integer NOT NULL, PDept integer);",
+            "INSERT INTO EMP (Id, Name, DeptId) VALUES (1, 'alice', 10);",
+            "INSERT INTO EMP (Id, Name, DeptId) VALUES (2, 'bob', 20);",
+            "INSERT INTO EMP (Id, Name) VALUES (3, 'carol');",
+            "INSERT INTO EMP (Id, Name, DeptId) VALUES (4, 'dave', 40);",
+            "INSERT INTO DEPT (DId, DName) VALUES (10, 'sales');",
+            "INSERT INTO DEPT (DId, DName) VALUES (20, 'dev');",
+            "INSERT INTO DEPT (DId, DName) VALUES (30, 'ops');",
+            "INSERT INTO PROJ (PId, PDept) VALUES (100, 20);",
+            "INSERT INTO PROJ (PId, PDept) VALUES (200, 30);",
+            "CREATE VIEW empdept AS SELECT Name, DName FROM EMP LEFT JOIN DEPT ON DeptId = DId;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+
+        let row = |a: Option<&str>, b: Option<&str>| (a.map(String::from), b.map(String::from));
+        let alice = row(Some("alice"), Some("sales"));
+        let bob = row(Some("bob"), Some("dev"));
+        let carol = row(Some("carol"), None);
+        let dave = row(Some("dave"), None);
+        let ops = row(None, Some("ops"));
+
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP INNER JOIN DEPT ON DeptId = DId;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(rows, vec![alice.clone(), bob.clone()]);
+        let (rows, plan_ops) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP LEFT OUTER JOIN DEPT ON DeptId = DId;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(
+            rows,
+            vec![alice.clone(), bob.clone(), carol.clone(), dave.clone()]
+        );
+        println!("{:?}", plan_ops);
+        assert!(plan_ops.iter().any(|op| matches!(
+            op,
+            Operation::IndexJoinScan {
+                join_type: JoinType::Left,
+                ..
+            } | Operation::ProductJoinScan {
+                join_type: JoinType::Left,
+                ..
+            }
+        )));
+        let (rows, plan_ops) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP RIGHT JOIN DEPT ON DeptId = DId;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(rows, vec![ops.clone(), alice.clone(), bob.clone()]);
+        assert!(plan_ops.iter().any(|op| matches!(
+            op,
+            Operation::MergeJoinScan {
+                join_type: JoinType::Right,
+                ..
+            } | Operation::ProductJoinScan {
+                join_type: JoinType::Right,
+                ..
+            }
+        )));
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP FULL JOIN DEPT ON DeptId = DId;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(
+            rows,
+            vec![ops, alice.clone(), bob.clone(), carol.clone(), dave.clone()]
+        );
+        // the condition of ON decides the matching records, unlike the one of WHERE
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP LEFT JOIN DEPT ON DeptId = DId AND DName <> 'dev';",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                alice.clone(),
+                row(Some("bob"), None),
+                carol.clone(),
+                dave.clone()
+            ]
+        );
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM EMP LEFT JOIN DEPT ON DeptId = DId WHERE DName IS NULL;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(rows, vec![carol.clone(), dave.clone()]);
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, PId FROM EMP LEFT JOIN DEPT ON DeptId = DId \
+               LEFT JOIN PROJ ON DId = PDept WHERE Id < 4;",
+            "Name",
+            "PId",
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                row(Some("alice"), None),
+                row(Some("bob"), Some("100")),
+                row(Some("carol"), None)
+            ]
+        );
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT Name, DName FROM empdept;",
+            "Name",
+            "DName",
+        )?;
+        assert_eq!(rows, vec![alice, bob, carol, dave]);
+
+        // ON must refer to the joined tables
+        let query = "SELECT Name FROM EMP LEFT JOIN DEPT ON DeptId = PDept;";
+        let result = planner.create_query_plan(query, Arc::clone(&tx));
+        println!("Query: {} => {:?}", query, result.as_ref().err());
+        assert!(result.is_err());
+
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn alias_test() -> Result<()> {
+        for query_planner in [config::QueryPlanner::Basic, config::QueryPlanner::Heuristic] {
+            for concurrency_mgr in [
+                config::ConcurrencyMgr::Locking,
+                config::ConcurrencyMgr::MVCC,
+            ] {
+                let dir = format!("_test/planner_alias_{}_{}", query_planner, concurrency_mgr)
+                    .to_lowercase();
+                let simpledb = build_db(&dir, query_planner.clone(), concurrency_mgr)?;
+                alias_test_with(&simpledb)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn alias_test_with(simpledb: &SimpleDB) -> Result<()> {
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut planner = simpledb.planner()?;
+
+        // EMP and DEPT share the names of their fields
+        let sqls = vec![
+            "CREATE TABLE EMP (Id integer NOT NULL, Name varchar(10), MgrId integer, DeptId integer);",
+            "CREATE INDEX idx_emp_id ON EMP (Id);",
+            "CREATE TABLE DEPT (Id integer NOT NULL, Name varchar(10));",
+            "INSERT INTO EMP (Id, Name, DeptId) VALUES (1, 'alice', 10);",
+            "INSERT INTO EMP (Id, Name, MgrId, DeptId) VALUES (2, 'bob', 1, 20);",
+            "INSERT INTO EMP (Id, Name, MgrId, DeptId) VALUES (3, 'carol', 1, 10);",
+            "INSERT INTO EMP (Id, Name, MgrId, DeptId) VALUES (4, 'dave', 2, 20);",
+            "INSERT INTO DEPT (Id, Name) VALUES (10, 'sales');",
+            "INSERT INTO DEPT (Id, Name) VALUES (20, 'dev');",
+            "CREATE VIEW boss AS SELECT Id, Name FROM EMP WHERE MgrId IS NULL;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+
+        let row = |a: Option<&str>, b: Option<&str>| (a.map(String::from), b.map(String::from));
+
+        // a field of two tables is named by the alias of its table
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT e.Name, d.Name FROM EMP e, DEPT AS d WHERE e.DeptId = d.Id AND e.Id > 2;",
+            "e.Name",
+            "d.Name",
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                row(Some("carol"), Some("sales")),
+                row(Some("dave"), Some("dev"))
+            ]
+        );
+        // a field of one table keeps its name, and the table name qualifies it too
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT EMP.Name, DeptId FROM EMP WHERE EMP.Id = 2;",
+            "Name",
+            "DeptId",
+        )?;
+        assert_eq!(rows, vec![row(Some("bob"), Some("20"))]);
+        // self join
+        let (rows, plan_ops) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT e.Name, m.Name FROM EMP e, EMP m WHERE e.MgrId = m.Id ORDER BY e.Name;",
+            "e.Name",
+            "m.Name",
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                row(Some("bob"), Some("alice")),
+                row(Some("carol"), Some("alice")),
+                row(Some("dave"), Some("bob"))
+            ]
+        );
+        println!("{:?}", plan_ops);
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT e.Name, m.Name FROM EMP e LEFT JOIN EMP m ON e.MgrId = m.Id WHERE e.Id < 3;",
+            "e.Name",
+            "m.Name",
+        )?;
+        assert_eq!(
+            rows,
+            vec![row(Some("alice"), None), row(Some("bob"), Some("alice"))]
+        );
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT d.Name, count(e.Id) FROM EMP e, DEPT d WHERE e.DeptId = d.Id GROUP BY d.Name;",
+            "d.Name",
+            "countofe.Id",
+        )?;
+        assert_eq!(
+            rows,
+            vec![row(Some("dev"), Some("2")), row(Some("sales"), Some("2"))]
+        );
+        // a view with an alias
+        let (rows, _) = select_pairs(
+            &mut planner,
+            &tx,
+            "SELECT e.Name, b.Name FROM EMP e, boss b WHERE e.MgrId = b.Id;",
+            "e.Name",
+            "b.Name",
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                row(Some("bob"), Some("alice")),
+                row(Some("carol"), Some("alice"))
+            ]
+        );
+
+        for query in [
+            // Name is of both tables
+            "SELECT Name FROM EMP e, DEPT d WHERE e.DeptId = d.Id;",
+            "SELECT e.Name FROM EMP e, EMP m WHERE MgrId = m.Id;",
+            // the table is named by its alias
+            "SELECT EMP.Name FROM EMP e;",
+            "SELECT x.Name FROM EMP e;",
+            "SELECT Name FROM EMP, EMP;",
+            "SELECT e.Name FROM EMP e, DEPT e;",
+        ] {
+            let result = planner.create_query_plan(query, Arc::clone(&tx));
+            println!("Query: {} => {:?}", query, result.as_ref().err());
+            assert!(result.is_err());
+        }
+
+        tx.lock().unwrap().commit()?;
+
+        // a snapshot reads a record changed after it from the saved version,
+        // whose values are found by the qualified names, too
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        if tx.lock().unwrap().is_mvcc() {
+            let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+            let sql = "UPDATE EMP SET Name = 'alicia' WHERE Id = 1;";
+            planner.execute_update(sql, Arc::clone(&tx2))?;
+            tx2.lock().unwrap().commit()?;
+            let (rows, _) = select_pairs(
+                &mut planner,
+                &tx,
+                "SELECT e.Name, m.Name FROM EMP e, EMP m WHERE e.MgrId = m.Id AND e.Id = 2;",
+                "e.Name",
+                "m.Name",
+            )?;
+            assert_eq!(rows, vec![row(Some("bob"), Some("alice"))]);
+        }
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn drop_test() -> Result<()> {
+        if Path::new("_test/planner_drop").exists() {
+            fs::remove_dir_all("_test/planner_drop")?;
+        }
+
+        let simpledb = SimpleDB::new("_test/planner_drop")?;
+        let mut planner = simpledb.planner()?;
+
+        let count =
+            |planner: &mut Planner, query: &str, tx: Arc<Mutex<Transaction>>| -> Result<i32> {
+                println!("Query: {}", query);
+                let plan = planner.create_query_plan(query, tx)?;
+                let scan = plan.open()?;
+                let mut n = 0;
+                let mut iter = scan.lock().unwrap();
+                while iter.next() {
+                    n += 1;
+                }
+                iter.close()?;
+                println!("{} records", n);
+                Ok(n)
+            };
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let sqls = vec![
+            "CREATE TABLE STUDENT (SId integer, SName varchar(10), GradYear integer);",
+            "CREATE INDEX idx_grad_year ON STUDENT (GradYear);",
+            "CREATE VIEW grad2021 AS SELECT SName FROM STUDENT WHERE GradYear = 2021;",
+            "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (1, 'joe', 2021);",
+            "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (2, 'amy', 2020);",
+            "CREATE TABLE DEPT (DId integer, DName varchar(10));",
+            "INSERT INTO DEPT (DId, DName) VALUES (10, 'compsci');",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+        tx.lock().unwrap().commit()?;
+
+        // a rolled back drop leaves the table as it was
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        planner.execute_update("DROP TABLE DEPT;", Arc::clone(&tx))?;
+        tx.lock().unwrap().rollback()?;
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        assert_eq!(
+            count(&mut planner, "SELECT DId FROM DEPT;", Arc::clone(&tx))?,
+            1
+        );
+        tx.lock().unwrap().commit()?;
+
+        // so does a rolled back drop of a table recreated under the same name
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        for sql in [
+            "DROP TABLE DEPT;",
+            "CREATE TABLE DEPT (DId integer, DName varchar(10));",
+            "INSERT INTO DEPT (DId, DName) VALUES (20, 'math');",
+            "INSERT INTO DEPT (DId, DName) VALUES (30, 'drama');",
+        ] {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+        tx.lock().unwrap().rollback()?;
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        assert_eq!(
+            count(
+                &mut planner,
+                "SELECT DId FROM DEPT WHERE DId = 10;",
+                Arc::clone(&tx)
+            )?,
+            1
+        );
+        assert_eq!(
+            count(&mut planner, "SELECT DId FROM DEPT;", Arc::clone(&tx))?,
+            1
+        );
+        tx.lock().unwrap().commit()?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let sqls = vec![
+            "DROP VIEW grad2021;",
+            "DROP INDEX idx_grad_year;",
+            "DROP TABLE STUDENT;",
+            "DROP TABLE IF EXISTS STUDENT;",
+            "DROP INDEX IF EXISTS idx_grad_year;",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+        for sql in [
+            "DROP TABLE STUDENT;",
+            "DROP VIEW grad2021;",
+            "DROP INDEX idx_grad_year;",
+            "DROP TABLE tblcat;",
+        ] {
+            let result = planner.execute_update(sql, Arc::clone(&tx));
+            println!("Execute: {} ... {:?}", sql, result);
+            assert!(result.is_err());
+        }
+        // the files are removed on commit
+        assert!(Path::new("_test/planner_drop/STUDENT.tbl").exists());
+        tx.lock().unwrap().commit()?;
+        assert!(!Path::new("_test/planner_drop/STUDENT.tbl").exists());
+        assert!(!Path::new("_test/planner_drop/idx_grad_yearleaf").exists());
+        assert!(!Path::new("_test/planner_drop/idx_grad_yeardir").exists());
+
+        // the name can be reused, even within the dropping transaction
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let sqls = vec![
+            "CREATE TABLE STUDENT (SId integer, MajorId integer);",
+            "INSERT INTO STUDENT (SId, MajorId) VALUES (3, 10);",
+            "DROP TABLE DEPT;",
+            "CREATE TABLE DEPT (DId integer);",
+        ];
+        for sql in sqls {
+            print!("Execute: {} ... ", sql);
+            planner.execute_update(sql, Arc::clone(&tx))?;
+            println!("Done");
+        }
+        assert_eq!(
+            count(
+                &mut planner,
+                "SELECT SId, MajorId FROM STUDENT;",
+                Arc::clone(&tx)
+            )?,
+            1
+        );
+        assert_eq!(
+            count(&mut planner, "SELECT DId FROM DEPT;", Arc::clone(&tx))?,
+            0
+        );
+        tx.lock().unwrap().commit()?;
+        assert!(Path::new("_test/planner_drop/DEPT.tbl").exists());
+        // the old file moved aside for the new table is removed on commit
+        assert!(!Path::new("_test/planner_drop/DEPT.tbl~0").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn alter_test() -> Result<()> {
+        if Path::new("_test/planner_alter").exists() {
+            fs::remove_dir_all("_test/planner_alter")?;
+        }
+
+        let simpledb = SimpleDB::new("_test/planner_alter")?;
+        let mut planner = simpledb.planner()?;
+
+        let select = |planner: &mut Planner,
+                      query: &str,
+                      fields: &[&str],
+                      tx: Arc<Mutex<Transaction>>|
+         -> Result<Vec<Vec<Constant>>> {
+            println!("Query: {}", query);
+            let plan = planner.create_query_plan(query, tx)?;
+            let scan = plan.open()?;
+            let mut rows = vec![];
+            let mut iter = scan.lock().unwrap();
+            while iter.next() {
+                let mut row = vec![];
+                for fldname in fields {
+                    row.push(iter.get_val(fldname)?);
+                }
+                println!("{:?}", row);
+                rows.push(row);
+            }
+            iter.close()?;
+            Ok(rows)
+        };
+        let execute = |planner: &mut Planner, sqls: &[&str], tx: Arc<Mutex<Transaction>>| {
+            for sql in sqls {
+                print!("Execute: {} ... ", sql);
+                planner.execute_update(sql, Arc::clone(&tx))?;
+                println!("Done");
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        execute(
+            &mut planner,
+            &[
+                "CREATE TABLE STUDENT (SId integer NOT NULL, SName varchar(10), GradYear integer);",
+                "CREATE INDEX idx_grad_year ON STUDENT (GradYear);",
+                "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (1, 'joe', 2021);",
+                "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (2, 'amy', 2020);",
+                "INSERT INTO STUDENT (SId, SName, GradYear) VALUES (3, 'max', 2021);",
+            ],
+            Arc::clone(&tx),
+        )?;
+        tx.lock().unwrap().commit()?;
+
+        // a rolled back alter leaves the table and its index as they were
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        execute(
+            &mut planner,
+            &[
+                "ALTER TABLE STUDENT DROP COLUMN SName;",
+                "ALTER TABLE STUDENT ADD COLUMN MajorId integer;",
+            ],
+            Arc::clone(&tx),
+        )?;
+        tx.lock().unwrap().rollback()?;
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let rows = select(
+            &mut planner,
+            "SELECT SId, SName FROM STUDENT WHERE GradYear = 2021 ORDER BY SId;",
+            &["SId", "SName"],
+            Arc::clone(&tx),
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![Constant::I32(1), Constant::String("joe".to_string())],
+                vec![Constant::I32(3), Constant::String("max".to_string())],
+            ]
+        );
+        tx.lock().unwrap().commit()?;
+        assert!(!Path::new("_test/planner_alter/STUDENT~0.tbl").exists());
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        execute(
+            &mut planner,
+            &[
+                "ALTER TABLE STUDENT ADD COLUMN MajorId integer;",
+                "UPDATE STUDENT SET MajorId = 10 WHERE SId = 1;",
+                "ALTER TABLE STUDENT RENAME COLUMN SName TO Name;",
+                "ALTER TABLE STUDENT RENAME COLUMN GradYear TO Grad;",
+            ],
+            Arc::clone(&tx),
+        )?;
+        for sql in [
+            "ALTER TABLE STUDENT DROP COLUMN Grad;",
+            "ALTER TABLE STUDENT DROP COLUMN SName;",
+            "ALTER TABLE STUDENT RENAME COLUMN Name TO SId;",
+            "ALTER TABLE STUDENT ADD COLUMN MajorId integer;",
+            "ALTER TABLE STUDENT ADD COLUMN Active bool NOT NULL;",
+            "ALTER TABLE DEPT ADD COLUMN DName varchar(10);",
+            "ALTER TABLE tblcat DROP COLUMN slotsize;",
+        ] {
+            let result = planner.execute_update(sql, Arc::clone(&tx));
+            println!("Execute: {} ... {:?}", sql, result);
+            assert!(result.is_err());
+        }
+        // the index follows the renamed field
+        let rows = select(
+            &mut planner,
+            "SELECT SId, Name, Grad, MajorId FROM STUDENT WHERE Grad = 2021 ORDER BY SId;",
+            &["SId", "Name", "Grad", "MajorId"],
+            Arc::clone(&tx),
+        )?;
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    Constant::I32(1),
+                    Constant::String("joe".to_string()),
+                    Constant::I32(2021),
+                    Constant::I32(10)
+                ],
+                vec![
+                    Constant::I32(3),
+                    Constant::String("max".to_string()),
+                    Constant::I32(2021),
+                    Constant::Null
+                ],
+            ]
+        );
+        execute(
+            &mut planner,
+            &[
+                "DROP INDEX idx_grad_year;",
+                "ALTER TABLE STUDENT DROP COLUMN Grad;",
+            ],
+            Arc::clone(&tx),
+        )?;
+        tx.lock().unwrap().commit()?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let rows = select(
+            &mut planner,
+            "SELECT SId, Name, MajorId FROM STUDENT ORDER BY SId;",
+            &["SId", "Name", "MajorId"],
+            Arc::clone(&tx),
+        )?;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][1], Constant::String("amy".to_string()));
+        tx.lock().unwrap().commit()?;
+
         Ok(())
     }
 }
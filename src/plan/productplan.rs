@@ -3,7 +3,12 @@ use std::sync::{Arc, Mutex};
 
 use super::plan::Plan;
 use crate::{
-    query::{productscan::ProductScan, scan::Scan},
+    query::{
+        join::{join_schema, JoinType},
+        predicate::Predicate,
+        productscan::ProductScan,
+        scan::Scan,
+    },
     record::schema::Schema,
     repr::planrepr::{Operation, PlanRepr},
 };
@@ -13,6 +18,9 @@ pub struct ProductPlan {
     p1: Arc<dyn Plan>,
     p2: Arc<dyn Plan>,
     schema: Arc<Schema>,
+    // my own extends
+    // JOIN ... ON, which is joined by the nested loops
+    join: Option<(JoinType, Predicate)>,
 }
 
 impl Plan for ProductPlan {
@@ -20,13 +28,33 @@ impl Plan for ProductPlan {
         let s1 = self.p1.open()?;
         let s2 = self.p2.open()?;
 
+        // my own extends
+        if let Some((join_type, pred)) = self.join.as_ref() {
+            let scan = ProductScan::new_outer(s1, s2, pred.clone(), *join_type);
+            return Ok(Arc::new(Mutex::new(scan)));
+        }
+
         Ok(Arc::new(Mutex::new(ProductScan::new(s1, s2))))
     }
     fn blocks_accessed(&self) -> i32 {
         self.p1.blocks_accessed() + (self.p1.records_output() * self.p2.blocks_accessed())
     }
     fn records_output(&self) -> i32 {
-        self.p1.records_output() * self.p2.records_output()
+        let product = self.p1.records_output() * self.p2.records_output();
+        // my own extends
+        // the records satisfying the predicate, and the preserved records matching nothing
+        if let Some((join_type, pred)) = self.join.as_ref() {
+            let p = Arc::new(ProductPlan::new(Arc::clone(&self.p1), Arc::clone(&self.p2)));
+            let mut preserved = 0;
+            if join_type.preserves_lhs() {
+                preserved += self.p1.records_output();
+            }
+            if join_type.preserves_rhs() {
+                preserved += self.p2.records_output();
+            }
+            return (product / pred.reduction_factor(p).max(1)).max(preserved);
+        }
+        product
     }
     fn distinct_values(&self, fldname: &str) -> i32 {
         if self.p1.schema().has_field(fldname) {
@@ -43,6 +71,7 @@ impl Plan for ProductPlan {
         Arc::new(ProductPlanRepr {
             p1: self.p1.repr(),
             p2: self.p2.repr(),
+            join: self.join.clone(),
             r: self.blocks_accessed(),
             w: self.records_output(),
         })
@@ -53,13 +82,20 @@ impl Plan for ProductPlan {
 pub struct ProductPlanRepr {
     p1: Arc<dyn PlanRepr>,
     p2: Arc<dyn PlanRepr>,
+    join: Option<(JoinType, Predicate)>,
     r: i32,
     w: i32,
 }
 
 impl PlanRepr for ProductPlanRepr {
     fn operation(&self) -> Operation {
-        Operation::ProductScan
+        match self.join.as_ref() {
+            Some((join_type, pred)) => Operation::ProductJoinScan {
+                join_type: *join_type,
+                pred: pred.clone(),
+            },
+            None => Operation::ProductScan,
+        }
     }
     fn reads(&self) -> i32 {
         self.r
@@ -82,6 +118,23 @@ impl ProductPlan {
             p1,
             p2,
             schema: Arc::new(schema),
+            join: None,
+        }
+    }
+    // my own extends
+    pub fn new_outer(
+        p1: Arc<dyn Plan>,
+        p2: Arc<dyn Plan>,
+        pred: Predicate,
+        join_type: JoinType,
+    ) -> Self {
+        let schema = join_schema(p1.schema(), p2.schema(), join_type);
+
+        Self {
+            p1,
+            p2,
+            schema: Arc::new(schema),
+            join: Some((join_type, pred)),
         }
     }
 }
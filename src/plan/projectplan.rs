@@ -1,9 +1,12 @@
 use anyhow::Result;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 use super::plan::Plan;
 use crate::{
-    query::{projectscan::ProjectScan, scan::Scan},
+    query::{expression::Expression, projectscan::ProjectScan, scan::Scan},
     record::schema::Schema,
     repr::planrepr::{Operation, PlanRepr},
 };
@@ -12,14 +15,17 @@ use crate::{
 pub struct ProjectPlan {
     p: Arc<dyn Plan>,
     schema: Arc<Schema>,
+    // my own extends
+    exprs: Vec<(String, Expression)>,
 }
 
 impl Plan for ProjectPlan {
     fn open(&self) -> Result<Arc<Mutex<dyn Scan>>> {
         let s = self.p.open()?;
-        Ok(Arc::new(Mutex::new(ProjectScan::new(
+        Ok(Arc::new(Mutex::new(ProjectScan::new_with(
             s,
             self.schema.fields().clone(),
+            self.exprs.clone(),
         ))))
     }
     fn blocks_accessed(&self) -> i32 {
@@ -29,7 +35,16 @@ impl Plan for ProjectPlan {
         self.p.records_output()
     }
     fn distinct_values(&self, fldname: &str) -> i32 {
-        self.p.distinct_values(fldname)
+        match self.exprs.iter().find(|(name, _)| name == fldname) {
+            // at most as many as the values of the field which varies most
+            Some((_, expr)) => expr
+                .field_names()
+                .iter()
+                .map(|f| self.p.distinct_values(f))
+                .max()
+                .unwrap_or(1),
+            None => self.p.distinct_values(fldname),
+        }
     }
     fn schema(&self) -> Arc<Schema> {
         Arc::clone(&self.schema)
@@ -76,6 +91,68 @@ impl ProjectPlan {
         Self {
             p,
             schema: Arc::new(schema),
+            exprs: vec![],
+        }
+    }
+    // my own extends
+    // the fields of the list are passed through, or computed by their expressions.
+    // the expressions are type checked against the schema of the underlying plan.
+    pub fn new_with(
+        p: Arc<dyn Plan>,
+        fieldlist: Vec<String>,
+        exprs: Vec<(String, Expression)>,
+    ) -> Result<Self> {
+        let srcsch = p.schema();
+        let mut schema = Schema::new();
+        for fldname in fieldlist {
+            match exprs.iter().find(|(name, _)| *name == fldname) {
+                Some((_, expr)) => {
+                    let fi = expr.field_info(&srcsch)?;
+                    schema.add_field(&fldname, fi.fld_type, fi.length);
+                    schema.set_scale(&fldname, fi.scale);
+                    if !fi.nullable {
+                        schema.set_not_null(&fldname);
+                    }
+                }
+                None => schema.add(&fldname, Arc::clone(&srcsch)),
+            }
+        }
+
+        Ok(Self {
+            p,
+            schema: Arc::new(schema),
+            exprs,
+        })
+    }
+    // the fields of the plan and the computed fields, which replace the fields of the same names
+    pub fn new_extended(p: Arc<dyn Plan>, exprs: &[(String, Expression)]) -> Result<Self> {
+        let mut fieldlist = p.schema().fields().clone();
+        fieldlist.retain(|fldname| !exprs.iter().any(|(name, _)| name == fldname));
+        fieldlist.extend(exprs.iter().map(|(name, _)| name.clone()));
+        Self::new_with(p, fieldlist, exprs.to_vec())
+    }
+    // the fields of a view referred to by the qualifier (its alias or name) in a query,
+    // where the fields in qualified are renamed to qualifier.fldname.
+    pub fn new_qualified(p: Arc<dyn Plan>, qualifier: &str, qualified: &HashSet<String>) -> Self {
+        let srcsch = p.schema();
+        let schema = srcsch.qualify(qualifier, qualified);
+        let exprs = srcsch
+            .fields()
+            .iter()
+            .zip(schema.fields())
+            .filter(|(fldname, name)| fldname != name)
+            .map(|(fldname, name)| {
+                (
+                    name.to_string(),
+                    Expression::new_fldname(fldname.to_string()),
+                )
+            })
+            .collect();
+
+        Self {
+            p,
+            schema: Arc::new(schema),
+            exprs,
         }
     }
 }
@@ -1,8 +1,40 @@
 use anyhow::Result;
-use std::sync::{Arc, Mutex};
+use core::fmt;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 use super::plan::Plan;
-use crate::{parser::querydata::QueryData, tx::transaction::Transaction};
+use crate::{
+    materialize::aggregationfn::Aggregation, parser::querydata::QueryData, record::schema::Schema,
+    tx::transaction::Transaction,
+};
+
+// my own extends
+#[derive(Debug)]
+pub enum QueryPlannerError {
+    DuplicateTable(String),
+    UnknownSortField(String),
+    NotNumericAggregation(String),
+}
+
+impl std::error::Error for QueryPlannerError {}
+impl fmt::Display for QueryPlannerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryPlannerError::DuplicateTable(tblname) => {
+                write!(f, "table name specified more than once: {}", tblname)
+            }
+            QueryPlannerError::UnknownSortField(fldname) => {
+                write!(f, "order by field not found: {}", fldname)
+            }
+            QueryPlannerError::NotNumericAggregation(agg) => {
+                write!(f, "aggregation of a field which is not a number: {}", agg)
+            }
+        }
+    }
+}
 
 pub trait QueryPlanner {
     fn create_plan(
@@ -11,3 +43,61 @@ pub trait QueryPlanner {
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<Arc<dyn Plan>>;
 }
+
+// my own extends
+// the tables (or views) of the from clause by their names (or aliases) and their schemas.
+// returns the fields which more than one of them has, which are qualified by the name
+// of the table to tell them apart, and the schema of all the tables to resolve the query.
+pub fn qualify_tables(tables: &[(String, Arc<Schema>)]) -> Result<(HashSet<String>, Schema)> {
+    let mut names = HashSet::new();
+    let mut fields = HashSet::new();
+    let mut qualified = HashSet::new();
+    for (name, sch) in tables.iter() {
+        if !names.insert(name) {
+            return Err(From::from(QueryPlannerError::DuplicateTable(
+                name.to_string(),
+            )));
+        }
+        for fldname in sch.fields() {
+            if !fields.insert(fldname) {
+                qualified.insert(fldname.to_string());
+            }
+        }
+    }
+    let mut scope = Schema::new();
+    for (name, sch) in tables.iter() {
+        scope.add_all(Arc::new(sch.qualify(name, &qualified)));
+    }
+
+    Ok((qualified, scope))
+}
+
+// my own extends
+// the fields to sort on must be in the schema of the plan to sort.
+pub fn check_sort_fields(sch: &Schema, sortfields: &[String]) -> Result<()> {
+    for fldname in sortfields {
+        if !sch.has_field(fldname) {
+            return Err(From::from(QueryPlannerError::UnknownSortField(
+                fldname.to_string(),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// my own extends
+// the fields of SUM and AVG must be numbers.
+pub fn check_aggregations(sch: &Schema, aggregations: &[Aggregation]) -> Result<()> {
+    for agg in aggregations {
+        if let Aggregation::Sum(fldname) | Aggregation::Avg(fldname) = agg {
+            if sch.has_field(fldname) && sch.field_type(fldname).numeric_rank().is_none() {
+                return Err(From::from(QueryPlannerError::NotNumericAggregation(
+                    agg.to_string(),
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
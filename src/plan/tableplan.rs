@@ -1,5 +1,8 @@
 use anyhow::Result;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 use super::plan::Plan;
 use crate::{
@@ -91,6 +94,21 @@ impl TablePlan {
             si,
         })
     }
+    // my own extends
+    // the table referred to by the qualifier (its alias or name) in a query,
+    // whose fields in qualified are renamed to qualifier.fldname.
+    pub fn new_qualified(
+        tblname: &str,
+        qualifier: &str,
+        qualified: &HashSet<String>,
+        tx: Arc<Mutex<Transaction>>,
+        md: Arc<Mutex<MetadataMgr>>,
+    ) -> Result<Self> {
+        let mut plan = Self::new(tblname, tx, md)?;
+        plan.layout = Arc::new(plan.layout.qualify(qualifier, qualified));
+
+        Ok(plan)
+    }
 }
 
 #[cfg(test)]
@@ -3,9 +3,9 @@ use std::sync::{Arc, Mutex};
 
 use crate::{
     parser::{
-        createindexdata::CreateIndexData, createtabledata::CreateTableData,
-        createviewdata::CreateViewData, deletedata::DeleteData, insertdata::InsertData,
-        modifydata::ModifyData,
+        altertabledata::AlterTableData, createindexdata::CreateIndexData,
+        createtabledata::CreateTableData, createviewdata::CreateViewData, deletedata::DeleteData,
+        dropdata::DropData, insertdata::InsertData, modifydata::ModifyData,
     },
     tx::transaction::Transaction,
 };
@@ -26,4 +26,10 @@ pub trait UpdatePlanner {
         data: CreateIndexData,
         tx: Arc<Mutex<Transaction>>,
     ) -> Result<i32>;
+    // my own extends
+    fn execute_drop_table(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32>;
+    fn execute_drop_view(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32>;
+    fn execute_drop_index(&self, data: DropData, tx: Arc<Mutex<Transaction>>) -> Result<i32>;
+    fn execute_alter_table(&self, data: AlterTableData, tx: Arc<Mutex<Transaction>>)
+        -> Result<i32>;
 }
@@ -1,8 +1,11 @@
 pub mod constant;
 pub mod expression;
+pub mod join;
+pub mod likepattern;
 pub mod predicate;
 pub mod productscan;
 pub mod projectscan;
+pub mod scalarfn;
 pub mod scan;
 pub mod selectscan;
 pub mod term;
@@ -1,13 +1,19 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use core::fmt;
 use log::debug;
+use rust_decimal::{prelude::ToPrimitive, Decimal, RoundingStrategy};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
 
 use crate::record::schema::FieldType;
 
 #[derive(Debug)]
 pub enum ConstantError {
     TypeError,
+    OutOfRange(String),
 }
 
 impl std::error::Error for ConstantError {}
@@ -17,34 +23,55 @@ impl fmt::Display for ConstantError {
             Self::TypeError => {
                 write!(f, "type error")
             }
+            Self::OutOfRange(val) => {
+                write!(f, "value out of range: {}", val)
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, Hash)]
+#[derive(Debug, Clone)]
 pub enum Constant {
     I16(i16),
     I32(i32),
     String(String),
     Bool(bool),
     Date(NaiveDate),
+    // my own extends
+    // SQL NULL, which can be stored in any nullable field
+    Null,
+    // my own extends
+    // the value of a BLOB field. a TEXT field has a String.
+    Blob(Vec<u8>),
+    // my own extends
+    I64(i64),
+    F64(f64),
+    Decimal(Decimal),
+    // my own extends
+    Timestamp(NaiveDateTime),
+    Time(NaiveTime),
 }
+
+// my own extends
+// the text of a timestamp or a time, which is compared with a string.
+// the fraction of a second is only written when it is not zero.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+pub const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
 impl PartialEq for Constant {
     fn eq(&self, other: &Self) -> bool {
         match self {
-            Constant::I16(l) => match other {
-                Constant::I16(r) => *l == *r,
-                Constant::I32(r) => *l as i32 == *r,
-                _ => false,
-            },
-            Constant::I32(l) => match other {
-                Constant::I16(r) => *l == *r as i32,
-                Constant::I32(r) => *l == *r,
-                _ => false,
-            },
+            Constant::I16(_)
+            | Constant::I32(_)
+            | Constant::I64(_)
+            | Constant::F64(_)
+            | Constant::Decimal(_) => self.numeric_cmp(other) == Some(Ordering::Equal),
             Constant::String(l) => match other {
                 Constant::String(r) => *l == *r,
                 Constant::Date(r) => *l == *r.format("%Y-%m-%d").to_string(),
+                Constant::Timestamp(_) | Constant::Time(_) => {
+                    Some(l) == other.text_value().as_ref()
+                }
                 _ => false,
             },
             Constant::Bool(l) => match other {
@@ -56,11 +83,89 @@ impl PartialEq for Constant {
                 Constant::Date(r) => *l == *r,
                 _ => false,
             },
+            // NOTE: NULL equals NULL only for grouping and sorting.
+            //       the comparison in a predicate is unknown (see Operator::apply).
+            Constant::Null => matches!(other, Constant::Null),
+            Constant::Blob(l) => match other {
+                Constant::Blob(r) => *l == *r,
+                _ => false,
+            },
+            Constant::Timestamp(l) => match other {
+                Constant::String(r) => self.text_value().as_ref() == Some(r),
+                Constant::Timestamp(r) => *l == *r,
+                _ => false,
+            },
+            Constant::Time(l) => match other {
+                Constant::String(r) => self.text_value().as_ref() == Some(r),
+                Constant::Time(r) => *l == *r,
+                _ => false,
+            },
         }
     }
 }
 impl Eq for Constant {}
 
+// my own extends
+// consistent with PartialEq: equal numbers of any types, or a date or a time and its text, hash alike.
+impl Hash for Constant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_order().hash(state);
+        match self {
+            Constant::I16(_)
+            | Constant::I32(_)
+            | Constant::I64(_)
+            | Constant::F64(_)
+            | Constant::Decimal(_) => {
+                let fval = self.float_value().unwrap_or(f64::NAN);
+                if fval.is_nan() {
+                    f64::NAN.to_bits().hash(state)
+                } else {
+                    // -0.0 == 0.0
+                    (fval + 0.0).to_bits().hash(state)
+                }
+            }
+            Constant::String(sval) => sval.hash(state),
+            Constant::Date(dval) => dval.format("%Y-%m-%d").to_string().hash(state),
+            Constant::Bool(bval) => bval.hash(state),
+            Constant::Null => {}
+            Constant::Blob(bval) => bval.hash(state),
+            Constant::Timestamp(_) | Constant::Time(_) => {
+                self.text_value().unwrap_or_default().hash(state)
+            }
+        }
+    }
+}
+
+// my own extends
+// ordering consistent with PartialEq (e.g. I16(1) < I32(2) < F64(2.5))
+impl PartialOrd for Constant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Constant {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let Some(ord) = self.numeric_cmp(other) {
+            return ord;
+        }
+        match (self, other) {
+            (Constant::String(l), Constant::String(r)) => l.cmp(r),
+            (Constant::String(l), Constant::Date(r)) => l.cmp(&r.format("%Y-%m-%d").to_string()),
+            (Constant::Bool(l), Constant::Bool(r)) => l.cmp(r),
+            (Constant::Date(l), Constant::String(r)) => l.format("%Y-%m-%d").to_string().cmp(r),
+            (Constant::Date(l), Constant::Date(r)) => l.cmp(r),
+            (Constant::Blob(l), Constant::Blob(r)) => l.cmp(r),
+            (Constant::Timestamp(l), Constant::Timestamp(r)) => l.cmp(r),
+            (Constant::Time(l), Constant::Time(r)) => l.cmp(r),
+            // a string, a date, a timestamp or a time of another type is compared by its text
+            (l, r) if l.type_order() == 1 && r.type_order() == 1 => {
+                l.text_value().cmp(&r.text_value())
+            }
+            _ => self.type_order().cmp(&other.type_order()),
+        }
+    }
+}
+
 impl fmt::Display for Constant {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -69,6 +174,19 @@ impl fmt::Display for Constant {
             Constant::String(sval) => write!(f, "'{}'", sval),
             Constant::Bool(bval) => write!(f, "{}", bval),
             Constant::Date(dval) => write!(f, "{}", dval.format("%Y-%m-%d")),
+            Constant::Null => write!(f, "NULL"),
+            Constant::Blob(bval) => {
+                write!(f, "X'")?;
+                for b in bval {
+                    write!(f, "{:02X}", b)?;
+                }
+                write!(f, "'")
+            }
+            Constant::I64(ival) => write!(f, "{}", ival),
+            Constant::F64(fval) => write!(f, "{:?}", fval),
+            Constant::Decimal(dval) => write!(f, "{}", dval),
+            Constant::Timestamp(tsval) => write!(f, "{}", tsval.format(TIMESTAMP_FORMAT)),
+            Constant::Time(tval) => write!(f, "{}", tval.format(TIME_FORMAT)),
         }
     }
 }
@@ -89,6 +207,33 @@ impl Constant {
     pub fn new_date(dval: NaiveDate) -> Self {
         Constant::Date(dval)
     }
+    // my own extends
+    pub fn new_blob(bval: Vec<u8>) -> Self {
+        Constant::Blob(bval)
+    }
+    // my own extends
+    pub fn new_i64(ival: i64) -> Self {
+        Constant::I64(ival)
+    }
+    // my own extends
+    pub fn new_f64(fval: f64) -> Self {
+        Constant::F64(fval)
+    }
+    // my own extends
+    pub fn new_decimal(dval: Decimal) -> Self {
+        Constant::Decimal(dval)
+    }
+    // my own extends
+    pub fn new_timestamp(tsval: NaiveDateTime) -> Self {
+        Constant::Timestamp(tsval)
+    }
+    // my own extends
+    pub fn new_time(tval: NaiveTime) -> Self {
+        Constant::Time(tval)
+    }
+    pub fn is_null(&self) -> bool {
+        matches!(self, Constant::Null)
+    }
     pub fn as_i16(&self) -> Result<i16> {
         match self {
             Constant::I16(ival) => Ok(*ival),
@@ -96,6 +241,10 @@ impl Constant {
                 debug!("try to convert from i32 to i16: {}", *ival);
                 i16::try_from(*ival).map_err(|_| From::from(ConstantError::TypeError))
             }
+            Constant::I64(ival) => {
+                debug!("try to convert from i64 to i16: {}", *ival);
+                i16::try_from(*ival).map_err(|_| From::from(ConstantError::TypeError))
+            }
             _ => Err(From::from(ConstantError::TypeError)),
         }
     }
@@ -106,9 +255,64 @@ impl Constant {
                 Ok(*ival as i32)
             }
             Constant::I32(ival) => Ok(*ival),
+            Constant::I64(ival) => {
+                debug!("try to convert from i64 to i32: {}", *ival);
+                i32::try_from(*ival).map_err(|_| From::from(ConstantError::TypeError))
+            }
             _ => Err(From::from(ConstantError::TypeError)),
         }
     }
+    // my own extends
+    // a DECIMAL without a fraction is an integer, too.
+    pub fn as_i64(&self) -> Result<i64> {
+        match self {
+            Constant::I16(ival) => Ok(*ival as i64),
+            Constant::I32(ival) => Ok(*ival as i64),
+            Constant::I64(ival) => Ok(*ival),
+            Constant::Decimal(dval) if dval.fract().is_zero() => {
+                debug!("try to convert from decimal to i64: {}", dval);
+                dval.to_i64()
+                    .ok_or_else(|| From::from(ConstantError::OutOfRange(dval.to_string())))
+            }
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+    // my own extends
+    pub fn as_f64(&self) -> Result<f64> {
+        self.float_value()
+            .ok_or_else(|| From::from(ConstantError::TypeError))
+    }
+    // my own extends
+    pub fn as_decimal(&self) -> Result<Decimal> {
+        match self {
+            Constant::F64(fval) => {
+                debug!("try to convert from f64 to decimal: {}", fval);
+                Decimal::try_from(*fval)
+                    .map_err(|_| From::from(ConstantError::OutOfRange(fval.to_string())))
+            }
+            _ => self
+                .exact_value()
+                .ok_or_else(|| From::from(ConstantError::TypeError)),
+        }
+    }
+    // my own extends
+    // the value as an integer in units of the scale of a DECIMAL(precision, scale) field.
+    // it is rounded half away from zero, and fails if it has more digits than the precision.
+    pub fn as_unscaled(&self, precision: usize, scale: usize) -> Result<i64> {
+        let dval = self
+            .as_decimal()?
+            .round_dp_with_strategy(scale as u32, RoundingStrategy::MidpointAwayFromZero);
+        let limit = 10u128.pow(precision as u32);
+        let mut unscaled = dval.mantissa();
+        for _ in dval.scale()..scale as u32 {
+            unscaled = unscaled.saturating_mul(10);
+        }
+        if unscaled.unsigned_abs() >= limit {
+            return Err(From::from(ConstantError::OutOfRange(dval.to_string())));
+        }
+
+        Ok(unscaled as i64)
+    }
     pub fn as_string(&self) -> Result<&str> {
         match self {
             Constant::String(sval) => Ok(sval),
@@ -132,14 +336,129 @@ impl Constant {
             _ => Err(From::from(ConstantError::TypeError)),
         }
     }
+    // my own extends
+    // a date is the midnight of the day.
+    pub fn as_timestamp(&self) -> Result<NaiveDateTime> {
+        match self {
+            Constant::String(sval) => {
+                debug!("try to convert from string to timestamp: {}", sval);
+                NaiveDateTime::parse_from_str(sval, TIMESTAMP_FORMAT)
+                    .or_else(|_| NaiveDateTime::parse_from_str(sval, "%Y-%m-%dT%H:%M:%S%.f"))
+                    .map_err(|_| From::from(ConstantError::TypeError))
+            }
+            Constant::Date(dval) => Ok(dval.and_time(NaiveTime::MIN)),
+            Constant::Timestamp(tsval) => Ok(*tsval),
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+    // my own extends
+    pub fn as_time(&self) -> Result<NaiveTime> {
+        match self {
+            Constant::String(sval) => {
+                debug!("try to convert from string to time: {}", sval);
+                NaiveTime::parse_from_str(sval, TIME_FORMAT)
+                    .map_err(|_| From::from(ConstantError::TypeError))
+            }
+            Constant::Timestamp(tsval) => Ok(tsval.time()),
+            Constant::Time(tval) => Ok(*tval),
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+    // my own extends
+    pub fn as_blob(&self) -> Result<&[u8]> {
+        match self {
+            Constant::Blob(bval) => Ok(bval),
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+    // my own extends
+    // the text of a value of any type but BLOB, which is concatenated or cast to a string
+    pub fn as_text(&self) -> Result<String> {
+        match self {
+            Constant::String(sval) => Ok(sval.clone()),
+            Constant::Blob(_) | Constant::Null => Err(From::from(ConstantError::TypeError)),
+            _ => Ok(self.to_string()),
+        }
+    }
+    // my own extends
+    fn text_value(&self) -> Option<String> {
+        match self {
+            Constant::String(sval) => Some(sval.clone()),
+            Constant::Date(dval) => Some(dval.format("%Y-%m-%d").to_string()),
+            Constant::Timestamp(tsval) => Some(tsval.format(TIMESTAMP_FORMAT).to_string()),
+            Constant::Time(tval) => Some(tval.format(TIME_FORMAT).to_string()),
+            _ => None,
+        }
+    }
+    // my own extends
+    // the value of an integer or a DECIMAL, which is compared exactly
+    fn exact_value(&self) -> Option<Decimal> {
+        match self {
+            Constant::I16(ival) => Some(Decimal::from(*ival)),
+            Constant::I32(ival) => Some(Decimal::from(*ival)),
+            Constant::I64(ival) => Some(Decimal::from(*ival)),
+            Constant::Decimal(dval) => Some(*dval),
+            _ => None,
+        }
+    }
+    // my own extends
+    fn float_value(&self) -> Option<f64> {
+        match self {
+            Constant::F64(fval) => Some(*fval),
+            _ => self
+                .exact_value()
+                .and_then(|dval| dval.normalize().to_f64()),
+        }
+    }
+    // my own extends
+    // numbers of any types are compared by value, approximately if one of them is a DOUBLE.
+    // NaN equals itself and is greater than any other number.
+    fn numeric_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Some(l), Some(r)) = (self.exact_value(), other.exact_value()) {
+            return Some(l.cmp(&r));
+        }
+        let (l, r) = (self.float_value()?, other.float_value()?);
+        Some(
+            l.partial_cmp(&r)
+                .unwrap_or_else(|| l.is_nan().cmp(&r.is_nan())),
+        )
+    }
+    fn type_order(&self) -> i32 {
+        match self {
+            // NULL sorts first
+            Constant::Null => -1,
+            Constant::I16(_)
+            | Constant::I32(_)
+            | Constant::I64(_)
+            | Constant::F64(_)
+            | Constant::Decimal(_) => 0,
+            Constant::String(_)
+            | Constant::Date(_)
+            | Constant::Timestamp(_)
+            | Constant::Time(_) => 1,
+            Constant::Bool(_) => 2,
+            Constant::Blob(_) => 3,
+        }
+    }
     // extends by exercise 3.17
     pub fn as_field_type(&self, fldtype: FieldType) -> Result<Self> {
+        if self.is_null() {
+            return Ok(Constant::Null);
+        }
         match fldtype {
             FieldType::SMALLINT => self.as_i16().map(|x| Constant::I16(x)),
             FieldType::INTEGER => self.as_i32().map(|x| Constant::I32(x)),
-            FieldType::VARCHAR => self.as_string().map(|x| Constant::String(x.to_string())),
+            FieldType::VARCHAR | FieldType::TEXT => {
+                self.as_string().map(|x| Constant::String(x.to_string()))
+            }
             FieldType::BOOL => self.as_bool().map(|x| Constant::Bool(x)),
             FieldType::DATE => self.as_date().map(|x| Constant::Date(x)),
+            FieldType::BLOB => self.as_blob().map(|x| Constant::Blob(x.to_vec())),
+            FieldType::BIGINT => self.as_i64().map(Constant::I64),
+            FieldType::DOUBLE => self.as_f64().map(Constant::F64),
+            FieldType::DECIMAL => self.as_decimal().map(Constant::Decimal),
+            FieldType::TIMESTAMP => self.as_timestamp().map(Constant::Timestamp),
+            FieldType::TIME => self.as_time().map(Constant::Time),
         }
     }
 }
@@ -1,13 +1,27 @@
 use anyhow::Result;
 use core::fmt;
+use itertools::Itertools;
 use std::sync::{Arc, Mutex};
 
-use super::{constant::Constant, scan::Scan};
-use crate::record::schema::Schema;
+use super::{
+    constant::{Constant, ConstantError},
+    scalarfn::{castfn::CastFn, Function},
+    scan::Scan,
+};
+use crate::record::schema::{FieldInfo, FieldType, Schema};
+
+// my own extends
+// the largest precision of a DECIMAL, which an i64 holds
+pub const MAX_DECIMAL_PRECISION: usize = 18;
+// the digits added to the scale of the dividend by a division of DECIMALs
+pub const DIV_SCALE_INCREMENT: u32 = 4;
 
 #[derive(Debug)]
 pub enum ExpressionError {
     InvalidExpression,
+    // my own extends
+    FieldNotFound(String),
+    TypeMismatch(String),
 }
 
 impl std::error::Error for ExpressionError {}
@@ -17,7 +31,185 @@ impl fmt::Display for ExpressionError {
             ExpressionError::InvalidExpression => {
                 write!(f, "invalid expression")
             }
+            ExpressionError::FieldNotFound(fldname) => {
+                write!(f, "field not found: {}", fldname)
+            }
+            ExpressionError::TypeMismatch(expr) => {
+                write!(f, "type mismatch: {}", expr)
+            }
+        }
+    }
+}
+
+// my own extends
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    // string concatenation (||)
+    Concat,
+}
+
+impl fmt::Display for ArithOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArithOp::Add => write!(f, "+"),
+            ArithOp::Sub => write!(f, "-"),
+            ArithOp::Mul => write!(f, "*"),
+            ArithOp::Div => write!(f, "/"),
+            ArithOp::Mod => write!(f, "%"),
+            ArithOp::Concat => write!(f, "||"),
+        }
+    }
+}
+
+impl ArithOp {
+    // the binding strength of the operator, the same as the parser
+    pub fn precedence(&self) -> i32 {
+        match self {
+            ArithOp::Concat => 1,
+            ArithOp::Add | ArithOp::Sub => 2,
+            ArithOp::Mul | ArithOp::Div | ArithOp::Mod => 3,
+        }
+    }
+    // the operands are not NULL.
+    // an integer division truncates, and a division by zero is NULL.
+    // numbers are computed in the wider type of the operands, and fail if they overflow it.
+    pub fn apply(&self, lhs: &Constant, rhs: &Constant) -> Result<Constant> {
+        if *self == ArithOp::Concat {
+            return Ok(Constant::new_string(format!(
+                "{}{}",
+                lhs.as_text()?,
+                rhs.as_text()?
+            )));
+        }
+        let rank = numeric_rank(lhs)
+            .zip(numeric_rank(rhs))
+            .map(|(l, r)| l.max(r))
+            .ok_or(ConstantError::TypeError)?;
+        let overflow = || ConstantError::OutOfRange(format!("{} {} {}", lhs, self, rhs));
+        match rank {
+            // DOUBLE
+            4 => {
+                let (l, r) = (lhs.as_f64()?, rhs.as_f64()?);
+                let val = match self {
+                    ArithOp::Add => l + r,
+                    ArithOp::Sub => l - r,
+                    ArithOp::Mul => l * r,
+                    ArithOp::Div | ArithOp::Mod if r == 0.0 => return Ok(Constant::Null),
+                    ArithOp::Div => l / r,
+                    ArithOp::Mod => l % r,
+                    ArithOp::Concat => unreachable!(),
+                };
+                Ok(Constant::new_f64(val))
+            }
+            // DECIMAL
+            3 => {
+                let (l, r) = (lhs.as_decimal()?, rhs.as_decimal()?);
+                let val = match self {
+                    ArithOp::Add => l.checked_add(r),
+                    ArithOp::Sub => l.checked_sub(r),
+                    ArithOp::Mul => l.checked_mul(r),
+                    ArithOp::Div | ArithOp::Mod if r.is_zero() => return Ok(Constant::Null),
+                    ArithOp::Div => l
+                        .checked_div(r)
+                        .map(|v| v.round_dp(l.scale() + DIV_SCALE_INCREMENT)),
+                    ArithOp::Mod => l.checked_rem(r),
+                    ArithOp::Concat => unreachable!(),
+                };
+                val.map(Constant::new_decimal)
+                    .ok_or_else(|| From::from(overflow()))
+            }
+            // SMALLINT, INTEGER or BIGINT
+            _ => {
+                let (l, r) = (lhs.as_i64()?, rhs.as_i64()?);
+                let val = match self {
+                    ArithOp::Add => l.checked_add(r),
+                    ArithOp::Sub => l.checked_sub(r),
+                    ArithOp::Mul => l.checked_mul(r),
+                    ArithOp::Div | ArithOp::Mod if r == 0 => return Ok(Constant::Null),
+                    ArithOp::Div => l.checked_div(r),
+                    ArithOp::Mod => l.checked_rem(r),
+                    ArithOp::Concat => unreachable!(),
+                }
+                .ok_or_else(overflow)?;
+                match rank {
+                    0 => i16::try_from(val).map(Constant::new_i16).ok(),
+                    1 => i32::try_from(val).map(Constant::new_i32).ok(),
+                    _ => Some(Constant::new_i64(val)),
+                }
+                .ok_or_else(|| From::from(overflow()))
+            }
+        }
+    }
+    // the type of the result of the operands of the types
+    fn result_info(&self, lhs: &FieldInfo, rhs: &FieldInfo) -> Option<FieldInfo> {
+        let nullable = lhs.nullable || rhs.nullable || matches!(self, ArithOp::Div | ArithOp::Mod);
+        if *self == ArithOp::Concat {
+            let (l, r) = (text_length(lhs)?, text_length(rhs)?);
+            let mut fi = if lhs.fld_type == FieldType::TEXT || rhs.fld_type == FieldType::TEXT {
+                FieldInfo::new(FieldType::TEXT, 0)
+            } else {
+                FieldInfo::new(FieldType::VARCHAR, l + r)
+            };
+            fi.nullable = nullable;
+            return Some(fi);
         }
+        let rank = lhs
+            .fld_type
+            .numeric_rank()?
+            .max(rhs.fld_type.numeric_rank()?);
+        let mut fi = match rank {
+            0 => FieldInfo::new(FieldType::SMALLINT, 0),
+            1 => FieldInfo::new(FieldType::INTEGER, 0),
+            2 => FieldInfo::new(FieldType::BIGINT, 0),
+            3 => {
+                let (ls, rs) = (lhs.scale, rhs.scale);
+                let scale = match self {
+                    ArithOp::Mul => ls + rs,
+                    ArithOp::Div => ls + DIV_SCALE_INCREMENT as usize,
+                    _ => ls.max(rs),
+                };
+                let mut fi = FieldInfo::new(FieldType::DECIMAL, MAX_DECIMAL_PRECISION);
+                fi.scale = scale.min(MAX_DECIMAL_PRECISION);
+                fi
+            }
+            _ => FieldInfo::new(FieldType::DOUBLE, 0),
+        };
+        fi.nullable = nullable;
+        Some(fi)
+    }
+}
+
+// the same order as FieldType::numeric_rank
+fn numeric_rank(val: &Constant) -> Option<i32> {
+    match val {
+        Constant::I16(_) => Some(0),
+        Constant::I32(_) => Some(1),
+        Constant::I64(_) => Some(2),
+        Constant::Decimal(_) => Some(3),
+        Constant::F64(_) => Some(4),
+        _ => None,
+    }
+}
+// the longest text of a value of the type
+fn text_length(fi: &FieldInfo) -> Option<usize> {
+    match fi.fld_type {
+        FieldType::VARCHAR | FieldType::TEXT => Some(fi.length),
+        FieldType::SMALLINT => Some(6),
+        FieldType::INTEGER => Some(11),
+        FieldType::BIGINT => Some(20),
+        FieldType::DOUBLE => Some(24),
+        // the sign and the decimal point
+        FieldType::DECIMAL => Some(fi.length + 2),
+        FieldType::BOOL => Some(5),
+        FieldType::DATE => Some(10),
+        FieldType::TIMESTAMP => Some(26),
+        FieldType::TIME => Some(15),
+        FieldType::BLOB => None,
     }
 }
 
@@ -25,6 +217,12 @@ impl fmt::Display for ExpressionError {
 pub enum Expression {
     Val(Constant),
     Fldname(String),
+    // my own extends
+    Binary(Box<Expression>, ArithOp, Box<Expression>),
+    // unary minus
+    Neg(Box<Expression>),
+    // scalar function (e.g. upper(sname))
+    Call(Function, Vec<Expression>),
 }
 
 impl fmt::Display for Expression {
@@ -32,6 +230,38 @@ impl fmt::Display for Expression {
         match self {
             Expression::Val(val) => write!(f, "{}", val.to_string()),
             Expression::Fldname(fldname) => write!(f, "{}", fldname),
+            // an operand is parenthesized only if the parser needs it
+            Expression::Binary(lhs, op, rhs) => {
+                match lhs.as_ref() {
+                    Expression::Binary(_, lop, _) if lop.precedence() < op.precedence() => {
+                        write!(f, "({})", lhs)?
+                    }
+                    _ => write!(f, "{}", lhs)?,
+                }
+                write!(f, " {} ", op)?;
+                match rhs.as_ref() {
+                    Expression::Binary(_, rop, _) if rop.precedence() <= op.precedence() => {
+                        write!(f, "({})", rhs)
+                    }
+                    _ => write!(f, "{}", rhs),
+                }
+            }
+            Expression::Neg(expr) => match expr.as_ref() {
+                Expression::Fldname(_) | Expression::Call(_, _) => write!(f, "-{}", expr),
+                _ => write!(f, "-({})", expr),
+            },
+            Expression::Call(
+                Function::Cast {
+                    fld_type,
+                    length,
+                    scale,
+                },
+                args,
+            ) => {
+                let castfn = CastFn::new(*fld_type, *length, *scale);
+                write!(f, "cast({} as {})", args.iter().join(", "), castfn)
+            }
+            Expression::Call(func, args) => write!(f, "{}({})", func, args.iter().join(", ")),
         }
     }
 }
@@ -44,33 +274,311 @@ impl Expression {
         Expression::Fldname(fldname)
     }
     pub fn is_fldname(&self) -> bool {
-        match self {
-            Expression::Val(_) => false,
-            Expression::Fldname(_) => true,
-        }
+        matches!(self, Expression::Fldname(_))
     }
     pub fn as_constant(&self) -> Option<&Constant> {
         match self {
             Expression::Val(c) => Some(&c),
-            Expression::Fldname(_) => None,
+            _ => None,
         }
     }
     pub fn as_field_name(&self) -> Result<&str> {
         match self {
-            Expression::Val(_) => Err(From::from(ExpressionError::InvalidExpression)),
             Expression::Fldname(s) => Ok(&s),
+            _ => Err(From::from(ExpressionError::InvalidExpression)),
         }
     }
     pub fn evaluate(&self, s: Arc<Mutex<dyn Scan>>) -> Result<Constant> {
         match self {
             Expression::Val(val) => Ok(val.clone()),
             Expression::Fldname(fldname) => s.lock().unwrap().get_val(fldname),
+            Expression::Binary(lhs, op, rhs) => {
+                let lhsval = lhs.evaluate(Arc::clone(&s))?;
+                let rhsval = rhs.evaluate(Arc::clone(&s))?;
+                if lhsval.is_null() || rhsval.is_null() {
+                    return Ok(Constant::Null);
+                }
+                op.apply(&lhsval, &rhsval)
+            }
+            Expression::Call(func, args) => {
+                let mut vals = vec![];
+                for arg in args.iter() {
+                    vals.push(arg.evaluate(Arc::clone(&s))?);
+                }
+                func.function().apply(&vals)
+            }
+            Expression::Neg(expr) => match expr.evaluate(s)? {
+                Constant::Null => Ok(Constant::Null),
+                Constant::I16(ival) => ival
+                    .checked_neg()
+                    .map(Constant::new_i16)
+                    .ok_or_else(|| From::from(ConstantError::OutOfRange(format!("-({})", ival)))),
+                Constant::I32(ival) => ival
+                    .checked_neg()
+                    .map(Constant::new_i32)
+                    .ok_or_else(|| From::from(ConstantError::OutOfRange(format!("-({})", ival)))),
+                Constant::I64(ival) => ival
+                    .checked_neg()
+                    .map(Constant::new_i64)
+                    .ok_or_else(|| From::from(ConstantError::OutOfRange(format!("-({})", ival)))),
+                Constant::F64(fval) => Ok(Constant::new_f64(-fval)),
+                Constant::Decimal(dval) => Ok(Constant::new_decimal(-dval)),
+                _ => Err(From::from(ConstantError::TypeError)),
+            },
         }
     }
     pub fn applies_to(&self, sch: Arc<Schema>) -> bool {
         match self {
             Expression::Val(_) => true,
             Expression::Fldname(fldname) => sch.has_field(fldname),
+            Expression::Binary(lhs, _, rhs) => {
+                lhs.applies_to(Arc::clone(&sch)) && rhs.applies_to(sch)
+            }
+            Expression::Neg(expr) => expr.applies_to(sch),
+            Expression::Call(_, args) => args.iter().all(|arg| arg.applies_to(Arc::clone(&sch))),
         }
     }
+    // my own extends
+    pub fn new_binary(lhs: Expression, op: ArithOp, rhs: Expression) -> Self {
+        Expression::Binary(Box::new(lhs), op, Box::new(rhs))
+    }
+    pub fn new_neg(expr: Expression) -> Self {
+        Expression::Neg(Box::new(expr))
+    }
+    pub fn new_call(func: Function, args: Vec<Expression>) -> Self {
+        Expression::Call(func, args)
+    }
+    // the fields which the expression refers to
+    pub fn field_names(&self) -> Vec<&str> {
+        match self {
+            Expression::Val(_) => vec![],
+            Expression::Fldname(fldname) => vec![fldname.as_str()],
+            Expression::Binary(lhs, _, rhs) => {
+                let mut names = lhs.field_names();
+                names.extend(rhs.field_names());
+                names
+            }
+            Expression::Neg(expr) => expr.field_names(),
+            Expression::Call(_, args) => args.iter().flat_map(|arg| arg.field_names()).collect(),
+        }
+    }
+    // the expression whose field names are resolved by the schema, see Schema::resolve
+    pub fn resolve(&self, sch: &Schema) -> Result<Self> {
+        match self {
+            Expression::Val(_) => Ok(self.clone()),
+            Expression::Fldname(fldname) => Ok(Expression::Fldname(sch.resolve(fldname)?)),
+            Expression::Binary(lhs, op, rhs) => Ok(Expression::new_binary(
+                lhs.resolve(sch)?,
+                *op,
+                rhs.resolve(sch)?,
+            )),
+            Expression::Neg(expr) => Ok(Expression::new_neg(expr.resolve(sch)?)),
+            Expression::Call(func, args) => {
+                let mut resolved = vec![];
+                for arg in args.iter() {
+                    resolved.push(arg.resolve(sch)?);
+                }
+                let call = Expression::new_call(*func, resolved);
+                // the value of COALESCE is converted to the widest type of its arguments,
+                // which is only known by their types. an error is left to the type checking.
+                if let Expression::Call(Function::Coalesce { .. }, args) = &call {
+                    let fld_type = call.field_info(sch).ok().map(|fi| fi.fld_type);
+                    return Ok(Expression::new_call(
+                        Function::Coalesce { fld_type },
+                        args.clone(),
+                    ));
+                }
+                Ok(call)
+            }
+        }
+    }
+    // type checking: the type of the value of the expression over a record of the schema.
+    // a NULL takes the type of the other operand, and is a VARCHAR by itself.
+    pub fn field_info(&self, sch: &Schema) -> Result<FieldInfo> {
+        let mismatch = || From::from(ExpressionError::TypeMismatch(self.to_string()));
+        match self {
+            Expression::Val(val) => Ok(constant_info(val)),
+            Expression::Fldname(fldname) => sch
+                .info()
+                .get(fldname)
+                .cloned()
+                .ok_or_else(|| From::from(ExpressionError::FieldNotFound(fldname.to_string()))),
+            Expression::Binary(lhs, op, rhs) => {
+                let mut linfo = lhs.field_info(sch)?;
+                let mut rinfo = rhs.field_info(sch)?;
+                if lhs.as_constant().is_some_and(|c| c.is_null()) {
+                    linfo = FieldInfo {
+                        nullable: true,
+                        ..rinfo
+                    };
+                } else if rhs.as_constant().is_some_and(|c| c.is_null()) {
+                    rinfo = FieldInfo {
+                        nullable: true,
+                        ..linfo
+                    };
+                }
+                op.result_info(&linfo, &rinfo).ok_or_else(mismatch)
+            }
+            Expression::Neg(expr) => {
+                let info = expr.field_info(sch)?;
+                if expr.as_constant().is_some_and(|c| c.is_null()) {
+                    return Ok(info);
+                }
+                info.fld_type
+                    .numeric_rank()
+                    .map(|_| info)
+                    .ok_or_else(mismatch)
+            }
+            Expression::Call(func, args) => {
+                let mut infos = vec![];
+                for arg in args.iter() {
+                    // a NULL can be an argument of COALESCE of any type
+                    if matches!(func, Function::Coalesce { .. })
+                        && arg.as_constant().is_some_and(|c| c.is_null())
+                    {
+                        continue;
+                    }
+                    infos.push(arg.field_info(sch)?);
+                }
+                let mut info = func.function().field_info(&infos).ok_or_else(mismatch)?;
+                if infos.len() < args.len() {
+                    info.nullable = true;
+                }
+                Ok(info)
+            }
+        }
+    }
+    // my own extends
+    // type checking of the expression as the new value of a field of the schema.
+    // a NULL can be the value of a field of any type.
+    pub fn check_assignable(&self, sch: &Schema, fldname: &str) -> Result<()> {
+        let info = self.field_info(sch)?;
+        if self.as_constant().is_some_and(|c| c.is_null()) {
+            return Ok(());
+        }
+        let fldtype = sch
+            .info()
+            .get(fldname)
+            .map(|fi| fi.fld_type)
+            .ok_or_else(|| ExpressionError::FieldNotFound(fldname.to_string()))?;
+        if !fldtype.is_assignable_from(info.fld_type) {
+            return Err(From::from(ExpressionError::TypeMismatch(format!(
+                "{} = {}",
+                fldname, self
+            ))));
+        }
+
+        Ok(())
+    }
+}
+
+fn constant_info(val: &Constant) -> FieldInfo {
+    let mut fi = match val {
+        Constant::I16(_) => FieldInfo::new(FieldType::SMALLINT, 0),
+        Constant::I32(_) => FieldInfo::new(FieldType::INTEGER, 0),
+        Constant::I64(_) => FieldInfo::new(FieldType::BIGINT, 0),
+        Constant::F64(_) => FieldInfo::new(FieldType::DOUBLE, 0),
+        Constant::Decimal(dval) => {
+            let mut fi = FieldInfo::new(FieldType::DECIMAL, MAX_DECIMAL_PRECISION);
+            fi.scale = (dval.scale() as usize).min(MAX_DECIMAL_PRECISION);
+            fi
+        }
+        Constant::String(sval) => FieldInfo::new(FieldType::VARCHAR, sval.chars().count()),
+        Constant::Bool(_) => FieldInfo::new(FieldType::BOOL, 0),
+        Constant::Date(_) => FieldInfo::new(FieldType::DATE, 0),
+        Constant::Timestamp(_) => FieldInfo::new(FieldType::TIMESTAMP, 0),
+        Constant::Time(_) => FieldInfo::new(FieldType::TIME, 0),
+        Constant::Blob(_) => FieldInfo::new(FieldType::BLOB, 0),
+        Constant::Null => FieldInfo::new(FieldType::VARCHAR, 0),
+    };
+    fi.nullable = val.is_null();
+    fi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[test]
+    fn arithmetic_test() -> Result<()> {
+        let add = ArithOp::Add;
+        assert_eq!(
+            add.apply(&Constant::I16(1), &Constant::I32(2))?,
+            Constant::I32(3)
+        );
+        assert!(add
+            .apply(&Constant::I16(i16::MAX), &Constant::I16(1))
+            .is_err());
+        let dec = Decimal::from_str("1.50")?;
+        let val = ArithOp::Mul.apply(&Constant::Decimal(dec), &Constant::I32(3))?;
+        println!("1.50 * 3 = {}", val);
+        assert_eq!(val, Constant::Decimal(Decimal::from_str("4.50")?));
+        let val = ArithOp::Div.apply(&Constant::I32(7), &Constant::I32(2))?;
+        assert_eq!(val, Constant::I32(3));
+        let val = ArithOp::Div.apply(&Constant::I32(7), &Constant::F64(2.0))?;
+        assert_eq!(val, Constant::F64(3.5));
+        let val = ArithOp::Mod.apply(&Constant::I32(7), &Constant::I32(0))?;
+        assert_eq!(val, Constant::Null);
+        let val =
+            ArithOp::Concat.apply(&Constant::new_string("no".to_string()), &Constant::I32(7))?;
+        assert_eq!(val, Constant::new_string("no7".to_string()));
+        assert!(add
+            .apply(&Constant::new_string("a".to_string()), &Constant::I32(1))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_info_test() -> Result<()> {
+        let mut sch = Schema::new();
+        sch.add_i16_field("A");
+        sch.add_i32_field("B");
+        sch.add_string_field("C", 10);
+        sch.add_decimal_field("D", 8, 2);
+        sch.set_not_null("A");
+        sch.set_not_null("B");
+
+        let a = || Expression::new_fldname("A".to_string());
+        let b = || Expression::new_fldname("B".to_string());
+        let c = || Expression::new_fldname("C".to_string());
+        let d = || Expression::new_fldname("D".to_string());
+
+        let expr = Expression::new_binary(a(), ArithOp::Add, b());
+        let fi = expr.field_info(&sch)?;
+        println!("{}: {:?}", expr, fi);
+        assert_eq!(fi.fld_type, FieldType::INTEGER);
+        assert!(!fi.nullable);
+
+        let expr = Expression::new_binary(d(), ArithOp::Mul, d());
+        let fi = expr.field_info(&sch)?;
+        println!("{}: {:?}", expr, fi);
+        assert_eq!((fi.fld_type, fi.scale), (FieldType::DECIMAL, 4));
+
+        let expr = Expression::new_binary(c(), ArithOp::Concat, a());
+        let fi = expr.field_info(&sch)?;
+        println!("{}: {:?}", expr, fi);
+        assert_eq!((fi.fld_type, fi.length), (FieldType::VARCHAR, 16));
+
+        let expr = Expression::new_neg(Expression::new_binary(c(), ArithOp::Add, a()));
+        let err = expr.field_info(&sch).unwrap_err();
+        println!("{}: {}", expr, err);
+        let expr =
+            Expression::new_binary(a(), ArithOp::Add, Expression::new_fldname("E".to_string()));
+        let err = expr.field_info(&sch).unwrap_err();
+        println!("{}: {}", expr, err);
+
+        // (A + B) * -(A - B)
+        let expr = Expression::new_binary(
+            Expression::new_binary(a(), ArithOp::Add, b()),
+            ArithOp::Mul,
+            Expression::new_neg(Expression::new_binary(a(), ArithOp::Sub, b())),
+        );
+        println!("{}", expr);
+        assert_eq!(expr.to_string(), "(A + B) * -(A - B)");
+
+        Ok(())
+    }
 }
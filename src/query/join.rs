@@ -0,0 +1,65 @@
+use core::fmt;
+use std::sync::Arc;
+
+use crate::record::schema::Schema;
+
+// my own extends
+// the kind of JOIN ... ON. the outer joins also return the records of the preserved side
+// which match no record of the other side, padding the fields of the other side with NULL.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JoinType::Inner => write!(f, "join"),
+            JoinType::Left => write!(f, "left join"),
+            JoinType::Right => write!(f, "right join"),
+            JoinType::Full => write!(f, "full join"),
+        }
+    }
+}
+
+impl JoinType {
+    // all the records of the left hand side are returned
+    pub fn preserves_lhs(&self) -> bool {
+        matches!(self, JoinType::Left | JoinType::Full)
+    }
+    // all the records of the right hand side are returned
+    pub fn preserves_rhs(&self) -> bool {
+        matches!(self, JoinType::Right | JoinType::Full)
+    }
+}
+
+// my own extends
+// the side of a join whose fields are NULL in the current record
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum JoinSide {
+    Lhs,
+    Rhs,
+}
+
+// my own extends
+// the schema of a join, where the fields of the side which may be padded are nullable
+pub fn join_schema(sch1: Arc<Schema>, sch2: Arc<Schema>, join_type: JoinType) -> Schema {
+    let mut sch = Schema::new();
+    sch.add_all(Arc::clone(&sch1));
+    sch.add_all(Arc::clone(&sch2));
+    if join_type.preserves_lhs() {
+        sch2.fields()
+            .iter()
+            .for_each(|fldname| sch.set_nullable(fldname));
+    }
+    if join_type.preserves_rhs() {
+        sch1.fields()
+            .iter()
+            .for_each(|fldname| sch.set_nullable(fldname));
+    }
+
+    sch
+}
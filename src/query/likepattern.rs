@@ -0,0 +1,200 @@
+use anyhow::Result;
+use core::fmt;
+
+// my own extends
+// the escape character of a LIKE pattern without ESCAPE, as in MySQL
+pub const DEFAULT_ESCAPE: char = '\\';
+
+#[derive(Debug)]
+pub enum LikePatternError {
+    TrailingEscape(String),
+}
+
+impl std::error::Error for LikePatternError {}
+impl fmt::Display for LikePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LikePatternError::TrailingEscape(pattern) => {
+                write!(f, "pattern ends with the escape character: {}", pattern)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum PatternChar {
+    Char(char),
+    // _
+    AnyChar,
+    // %
+    AnyString,
+}
+
+// my own extends
+// the pattern of LIKE, where % matches any string and _ matches any single character.
+// the escape character makes the next character match just itself (e.g. 100\%).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LikePattern {
+    chars: Vec<PatternChar>,
+}
+
+// the text of the pattern with the default escape character
+impl fmt::Display for LikePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.chars.iter() {
+            match c {
+                PatternChar::Char(c) if matches!(*c, '%' | '_' | DEFAULT_ESCAPE) => {
+                    write!(f, "{}{}", DEFAULT_ESCAPE, c)?
+                }
+                PatternChar::Char(c) => write!(f, "{}", c)?,
+                PatternChar::AnyChar => write!(f, "_")?,
+                PatternChar::AnyString => write!(f, "%")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LikePattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        Self::new_with(pattern, DEFAULT_ESCAPE)
+    }
+    pub fn new_with(pattern: &str, escape: char) -> Result<Self> {
+        let mut chars = vec![];
+        let mut iter = pattern.chars();
+        while let Some(c) = iter.next() {
+            let pc = if c == escape {
+                match iter.next() {
+                    Some(next) => PatternChar::Char(next),
+                    None => {
+                        return Err(From::from(LikePatternError::TrailingEscape(
+                            pattern.to_string(),
+                        )))
+                    }
+                }
+            } else if c == '%' {
+                PatternChar::AnyString
+            } else if c == '_' {
+                PatternChar::AnyChar
+            } else {
+                PatternChar::Char(c)
+            };
+            chars.push(pc);
+        }
+
+        Ok(Self { chars })
+    }
+    pub fn matches(&self, s: &str) -> bool {
+        let s = s.chars().collect::<Vec<char>>();
+        let (mut i, mut p) = (0, 0);
+        // the position after the last % in the pattern, and the position in s it starts from
+        let mut backtrack = None;
+        while i < s.len() {
+            match self.chars.get(p) {
+                Some(PatternChar::AnyString) => {
+                    p += 1;
+                    backtrack = Some((p, i));
+                    continue;
+                }
+                Some(PatternChar::AnyChar) => {
+                    i += 1;
+                    p += 1;
+                    continue;
+                }
+                Some(PatternChar::Char(c)) if *c == s[i] => {
+                    i += 1;
+                    p += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            // let the last % match one more character
+            match backtrack {
+                Some((bp, bi)) => {
+                    p = bp;
+                    i = bi + 1;
+                    backtrack = Some((bp, i));
+                }
+                None => return false,
+            }
+        }
+
+        self.chars[p..].iter().all(|c| *c == PatternChar::AnyString)
+    }
+    // the string which all the matching strings start with (e.g. "ab" for ab%c)
+    pub fn prefix(&self) -> String {
+        self.chars
+            .iter()
+            .map_while(|c| match c {
+                PatternChar::Char(c) => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// the least string which is greater than all the strings starting with the prefix
+// (e.g. "ac" for "ab"), or None if there is no such string.
+pub fn next_prefix(prefix: &str) -> Option<String> {
+    let mut chars = prefix.chars().collect::<Vec<char>>();
+    while let Some(c) = chars.pop() {
+        // skipping the surrogates, which are not characters
+        if let Some(next) = (c as u32 + 1..=char::MAX as u32).find_map(char::from_u32) {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_test() -> Result<()> {
+        let cases = vec![
+            ("abc", "abc", true),
+            ("abc", "abd", false),
+            ("a%", "abc", true),
+            ("a%", "a", true),
+            ("%c", "abc", true),
+            ("%b%", "abc", true),
+            ("a_c", "abc", true),
+            ("a_c", "ac", false),
+            ("%a%a%", "banana", true),
+            ("%ab%b", "aabab", true),
+            ("%ab%b", "aaba", false),
+            ("100\\%", "100%", true),
+            ("100\\%", "1000", false),
+            ("a\\_%", "a_b", true),
+            ("a\\_%", "ab", false),
+            ("", "", true),
+            ("%", "", true),
+            ("_", "", false),
+            ("日本%", "日本語", true),
+        ];
+        for (pattern, s, expected) in cases {
+            let p = LikePattern::new(pattern)?;
+            println!("{:?} LIKE {:?} => {}", s, pattern, p.matches(s));
+            assert_eq!(p.matches(s), expected);
+        }
+
+        let p = LikePattern::new_with("10!%!!%_", '!')?;
+        assert_eq!(p.to_string(), "10\\%!%_");
+        assert_eq!(p.prefix(), "10%!");
+        assert!(p.matches("10%!xyz"));
+        assert!(LikePattern::new("abc\\").is_err());
+
+        assert_eq!(next_prefix("ab"), Some("ac".to_string()));
+        assert_eq!(
+            next_prefix(&format!("a{}", char::MAX)),
+            Some("b".to_string())
+        );
+        assert_eq!(next_prefix("\u{d7ff}"), Some("\u{e000}".to_string()));
+        assert_eq!(next_prefix(""), None);
+
+        Ok(())
+    }
+}
@@ -1,12 +1,28 @@
+use anyhow::Result;
 use core::fmt;
 use std::sync::{Arc, Mutex};
 
-use super::{scan::Scan, term::Term};
-use crate::{plan::plan::Plan, query::constant::Constant, record::schema::Schema};
+use super::{
+    scan::Scan,
+    term::{Operator, Term},
+};
+use crate::{
+    index::{RangeBound, SearchRange},
+    plan::plan::Plan,
+    query::{
+        constant::Constant,
+        likepattern::{next_prefix, LikePattern},
+    },
+    record::schema::Schema,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Predicate {
     terms: Vec<Term>,
+    // my own extends
+    // each disjunction is satisfied if any of its predicates is satisfied.
+    // NOT is pushed down into the terms (De Morgan), so it is never stored.
+    disjunctions: Vec<Vec<Predicate>>,
 }
 
 impl fmt::Display for Predicate {
@@ -15,32 +31,84 @@ impl fmt::Display for Predicate {
         for t in self.terms.iter() {
             result.push(t.to_string());
         }
+        for d in self.disjunctions.iter() {
+            let ps = d.iter().map(|p| p.to_string()).collect::<Vec<String>>();
+            result.push(format!("({})", ps.join(" or ")));
+        }
         write!(f, "{}", result.join(" and "))
     }
 }
 
 impl Predicate {
     pub fn new_empty() -> Self {
-        Self { terms: vec![] }
+        Self {
+            terms: vec![],
+            disjunctions: vec![],
+        }
     }
     pub fn new(t: Term) -> Self {
-        Self { terms: vec![t] }
+        Self {
+            terms: vec![t],
+            disjunctions: vec![],
+        }
     }
     pub fn conjoin_with(&mut self, pred: &mut Predicate) {
-        self.terms.append(&mut pred.terms)
+        self.terms.append(&mut pred.terms);
+        self.disjunctions.append(&mut pred.disjunctions);
     }
     pub fn is_satisfied(&self, s: Arc<Mutex<dyn Scan>>) -> bool {
+        self.evaluate(s) == Some(true)
+    }
+    // my own extends
+    // three-valued logic: None means unknown.
+    // false and unknown = false, true or unknown = true
+    pub fn evaluate(&self, s: Arc<Mutex<dyn Scan>>) -> Option<bool> {
+        let mut result = Some(true);
         for t in self.terms.iter() {
-            if !t.is_satisfied(Arc::clone(&s)) {
-                return false;
+            match t.evaluate(Arc::clone(&s)) {
+                Some(false) => return Some(false),
+                None => result = None,
+                Some(true) => {}
             }
         }
-        true
+        for d in self.disjunctions.iter() {
+            let mut dresult = Some(false);
+            for p in d.iter() {
+                match p.evaluate(Arc::clone(&s)) {
+                    Some(true) => {
+                        dresult = Some(true);
+                        break;
+                    }
+                    None => dresult = None,
+                    Some(false) => {}
+                }
+            }
+            match dresult {
+                Some(false) => return Some(false),
+                None => result = None,
+                Some(true) => {}
+            }
+        }
+        result
     }
     pub fn reduction_factor(&self, p: Arc<dyn Plan>) -> i32 {
-        let mut factor = 1;
+        let mut factor: i32 = 1;
         for t in self.terms.iter() {
-            factor *= t.reduction_factor(Arc::clone(&p));
+            factor = factor.saturating_mul(t.reduction_factor(Arc::clone(&p)));
+        }
+        for d in self.disjunctions.iter() {
+            // the probability that a record satisfies none of the predicates
+            let mut unselected = 1.0;
+            for pred in d.iter() {
+                unselected *= 1.0 - 1.0 / pred.reduction_factor(Arc::clone(&p)) as f64;
+            }
+            let selected = 1.0 - unselected;
+            let dfactor = if selected > 0.0 {
+                (1.0 / selected).round().min(i32::MAX as f64) as i32
+            } else {
+                i32::MAX
+            };
+            factor = factor.saturating_mul(dfactor);
         }
         factor
     }
@@ -51,7 +119,12 @@ impl Predicate {
                 result.terms.push(t.clone());
             }
         }
-        if result.terms.is_empty() {
+        for d in self.disjunctions.iter() {
+            if d.iter().all(|p| p.applies_to(Arc::clone(&sch))) {
+                result.disjunctions.push(d.clone());
+            }
+        }
+        if result.is_empty() {
             return None;
         } else {
             return Some(result);
@@ -70,7 +143,16 @@ impl Predicate {
                 result.terms.push(t.clone());
             }
         }
-        if result.terms.is_empty() {
+        for d in self.disjunctions.iter() {
+            let applies_to = |sch: Arc<Schema>| d.iter().all(|p| p.applies_to(Arc::clone(&sch)));
+            if !applies_to(Arc::clone(&sch1))
+                && !applies_to(Arc::clone(&sch2))
+                && applies_to(Arc::new(newsch.clone()))
+            {
+                result.disjunctions.push(d.clone());
+            }
+        }
+        if result.is_empty() {
             return None;
         } else {
             return Some(result);
@@ -99,4 +181,140 @@ impl Predicate {
     pub fn terms(&self) -> &Vec<Term> {
         &self.terms
     }
+    pub fn new_disjunction(preds: Vec<Predicate>) -> Self {
+        let mut disjunction = vec![];
+        for mut pred in preds {
+            // flatten (a or b) or c into a or b or c
+            if pred.terms.is_empty() && pred.disjunctions.len() == 1 {
+                disjunction.append(&mut pred.disjunctions.remove(0));
+            } else {
+                disjunction.push(pred);
+            }
+        }
+        Self {
+            terms: vec![],
+            disjunctions: vec![disjunction],
+        }
+    }
+    pub fn negate(&self) -> Self {
+        // not (t1 and t2 and (p1 or p2)) = not t1 or not t2 or (not p1 and not p2)
+        let mut preds = vec![];
+        for t in self.terms.iter() {
+            preds.push(Predicate::new(t.negate()));
+        }
+        for d in self.disjunctions.iter() {
+            let mut pred = Predicate::new_empty();
+            for p in d.iter() {
+                pred.conjoin_with(&mut p.negate());
+            }
+            preds.push(pred);
+        }
+        if preds.len() == 1 {
+            return preds.remove(0);
+        }
+        Predicate::new_disjunction(preds)
+    }
+    pub fn range_with_constant(&self, fldname: &str) -> Option<SearchRange> {
+        let mut range = SearchRange::new_unbounded();
+        let mut found = false;
+        for t in self.terms.iter() {
+            if let Some((op, val)) = t.compares_with_constant(fldname) {
+                match op {
+                    Operator::Lt => range.restrict_high(RangeBound::Excluded(val.clone())),
+                    Operator::Le => range.restrict_high(RangeBound::Included(val.clone())),
+                    Operator::Gt => range.restrict_low(RangeBound::Excluded(val.clone())),
+                    Operator::Ge => range.restrict_low(RangeBound::Included(val.clone())),
+                    // the strings starting with the prefix of the pattern (e.g. 'ab%' => ['ab', 'ac'))
+                    Operator::Like => {
+                        let prefix = match val.as_string().map(LikePattern::new) {
+                            Ok(Ok(pattern)) => pattern.prefix(),
+                            _ => continue,
+                        };
+                        if prefix.is_empty() {
+                            continue;
+                        }
+                        if let Some(next) = next_prefix(&prefix) {
+                            range.restrict_high(RangeBound::Excluded(Constant::String(next)));
+                        }
+                        range.restrict_low(RangeBound::Included(Constant::String(prefix)));
+                    }
+                    Operator::Eq
+                    | Operator::Ne
+                    | Operator::IsNull
+                    | Operator::IsNotNull
+                    | Operator::NotLike => continue,
+                }
+                found = true;
+            }
+        }
+        if found {
+            Some(range)
+        } else {
+            None
+        }
+    }
+    // the values of "fldname IN (v1, v2, ...)", that is, a disjunction of equalities
+    // with constants. the values are sorted and distinct.
+    pub fn equates_with_constants(&self, fldname: &str) -> Option<Vec<Constant>> {
+        for d in self.disjunctions.iter() {
+            let vals = d
+                .iter()
+                .map(|p| match (p.terms.as_slice(), p.disjunctions.is_empty()) {
+                    ([t], true) => t.equates_with_constant(fldname).cloned(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<Constant>>>();
+            if let Some(mut vals) = vals {
+                vals.sort();
+                vals.dedup();
+                return Some(vals);
+            }
+        }
+        None
+    }
+    pub fn disjunctions(&self) -> &Vec<Vec<Predicate>> {
+        &self.disjunctions
+    }
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.disjunctions.is_empty()
+    }
+    pub fn applies_to(&self, sch: Arc<Schema>) -> bool {
+        self.terms.iter().all(|t| t.applies_to(Arc::clone(&sch)))
+            && self
+                .disjunctions
+                .iter()
+                .all(|d| d.iter().all(|p| p.applies_to(Arc::clone(&sch))))
+    }
+    // my own extends
+    // type checking of the expressions of the terms against the schema of the records
+    pub fn check_types(&self, sch: &Schema) -> Result<()> {
+        for t in self.terms.iter() {
+            t.check_types(sch)?;
+        }
+        for d in self.disjunctions.iter() {
+            for p in d.iter() {
+                p.check_types(sch)?;
+            }
+        }
+        Ok(())
+    }
+    // the predicate whose field names are resolved by the schema, see Schema::resolve
+    pub fn resolve(&self, sch: &Schema) -> Result<Self> {
+        let mut terms = vec![];
+        for t in self.terms.iter() {
+            terms.push(t.resolve(sch)?);
+        }
+        let mut disjunctions = vec![];
+        for d in self.disjunctions.iter() {
+            let mut preds = vec![];
+            for p in d.iter() {
+                preds.push(p.resolve(sch)?);
+            }
+            disjunctions.push(preds);
+        }
+        Ok(Self {
+            terms,
+            disjunctions,
+        })
+    }
 }
@@ -5,7 +5,12 @@ use std::sync::{Arc, Mutex};
 
 use crate::{materialize::sortscan::SortScan, record::tablescan::TableScan};
 
-use super::{constant::Constant, scan::Scan};
+use super::{
+    constant::Constant,
+    join::{JoinSide, JoinType},
+    predicate::Predicate,
+    scan::Scan,
+};
 
 #[derive(Debug)]
 pub enum ProductScanError {
@@ -31,6 +36,100 @@ impl fmt::Display for ProductScanError {
 pub struct ProductScan {
     s1: Arc<Mutex<dyn Scan>>,
     s2: Arc<Mutex<dyn Scan>>,
+    // my own extends
+    outer: Option<OuterJoin>,
+}
+
+// my own extends
+// the state of a join by the nested loops, where s1 is the outer loop.
+// the predicate is evaluated on the current pair of the records.
+#[derive(Clone)]
+struct OuterJoin {
+    join_type: JoinType,
+    pred: Predicate,
+    pair: Arc<Mutex<dyn Scan>>,
+    hasmore1: bool,
+    // moves s1 to the next record at the next call
+    advance1: bool,
+    // whether the current record of s1 has matched any record of s2
+    matched1: bool,
+    // the position of s2 from 1, and whether each record of s2 has matched any record of s1
+    pos2: usize,
+    matched2: Vec<bool>,
+    // returning the records of s2 which have matched nothing, after s1 is over
+    rest2: bool,
+    padded: Option<JoinSide>,
+}
+
+impl OuterJoin {
+    fn new(join_type: JoinType, pred: Predicate, pair: Arc<Mutex<dyn Scan>>) -> Self {
+        Self {
+            join_type,
+            pred,
+            pair,
+            hasmore1: false,
+            advance1: true,
+            matched1: false,
+            pos2: 0,
+            matched2: vec![],
+            rest2: false,
+            padded: None,
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::new(self.join_type, self.pred.clone(), Arc::clone(&self.pair));
+    }
+    fn next(&mut self, s1: &Arc<Mutex<dyn Scan>>, s2: &Arc<Mutex<dyn Scan>>) -> bool {
+        loop {
+            if self.rest2 {
+                while s2.lock().unwrap().next() {
+                    self.pos2 += 1;
+                    if !self.matched2.get(self.pos2 - 1).copied().unwrap_or(false) {
+                        self.padded = Some(JoinSide::Lhs);
+                        return true;
+                    }
+                }
+                return false;
+            }
+            if self.advance1 {
+                self.advance1 = false;
+                self.hasmore1 = s1.lock().unwrap().next();
+                self.matched1 = false;
+            }
+            if !self.hasmore1 {
+                if !self.join_type.preserves_rhs() {
+                    return false;
+                }
+                self.rest2 = true;
+                self.pos2 = 0;
+                s2.lock().unwrap().before_first().unwrap();
+                continue;
+            }
+            if s2.lock().unwrap().next() {
+                self.pos2 += 1;
+                self.padded = None;
+                if self.pred.is_satisfied(Arc::clone(&self.pair)) {
+                    self.matched1 = true;
+                    if self.join_type.preserves_rhs() {
+                        if self.matched2.len() < self.pos2 {
+                            self.matched2.resize(self.pos2, false);
+                        }
+                        self.matched2[self.pos2 - 1] = true;
+                    }
+                    return true;
+                }
+                continue;
+            }
+            // s2 is over for the current record of s1
+            self.pos2 = 0;
+            s2.lock().unwrap().before_first().unwrap();
+            self.advance1 = true;
+            if self.join_type.preserves_lhs() && !self.matched1 {
+                self.padded = Some(JoinSide::Rhs);
+                return true;
+            }
+        }
+    }
 }
 
 impl Scan for ProductScan {
@@ -38,10 +137,21 @@ impl Scan for ProductScan {
         let mut s1 = self.s1.lock().unwrap();
         let mut s2 = self.s2.lock().unwrap();
         s1.before_first()?;
-        s1.next();
+        // my own extends
+        match self.outer.as_mut() {
+            Some(outer) => outer.reset(),
+            None => {
+                s1.next();
+            }
+        }
         s2.before_first()
     }
     fn next(&mut self) -> bool {
+        // my own extends
+        if let Some(outer) = self.outer.as_mut() {
+            return outer.next(&self.s1, &self.s2);
+        }
+
         let mut s2 = self.s2.lock().unwrap();
         if s2.next() {
             true
@@ -51,6 +161,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_i16();
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_i16(fldname)
@@ -59,6 +172,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_i32(&mut self, fldname: &str) -> Result<i32> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_i32();
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_i32(fldname)
@@ -67,6 +183,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_string(&mut self, fldname: &str) -> Result<String> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_string().map(|s| s.to_string());
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_string(fldname)
@@ -75,6 +194,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_bool(&mut self, fldname: &str) -> Result<bool> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_bool();
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_bool(fldname)
@@ -83,6 +205,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
+        if self.is_padded(fldname) {
+            return Constant::Null.as_date();
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_date(fldname)
@@ -91,6 +216,9 @@ impl Scan for ProductScan {
         }
     }
     fn get_val(&mut self, fldname: &str) -> Result<Constant> {
+        if self.is_padded(fldname) {
+            return Ok(Constant::Null);
+        }
         let mut s1 = self.s1.lock().unwrap();
         if s1.has_field(fldname) {
             s1.get_val(fldname)
@@ -121,7 +249,38 @@ impl Scan for ProductScan {
 impl ProductScan {
     pub fn new(s1: Arc<Mutex<dyn Scan>>, s2: Arc<Mutex<dyn Scan>>) -> Self {
         s1.lock().unwrap().next();
-        Self { s1, s2 }
+        Self {
+            s1,
+            s2,
+            outer: None,
+        }
+    }
+    // my own extends
+    // the join of the records satisfying the predicate, which are padded with NULL
+    // for the preserved records matching nothing
+    pub fn new_outer(
+        s1: Arc<Mutex<dyn Scan>>,
+        s2: Arc<Mutex<dyn Scan>>,
+        pred: Predicate,
+        join_type: JoinType,
+    ) -> Self {
+        let pair = Arc::new(Mutex::new(Self {
+            s1: Arc::clone(&s1),
+            s2: Arc::clone(&s2),
+            outer: None,
+        }));
+        Self {
+            s1,
+            s2,
+            outer: Some(OuterJoin::new(join_type, pred, pair)),
+        }
+    }
+    fn is_padded(&self, fldname: &str) -> bool {
+        match self.outer.as_ref().and_then(|outer| outer.padded) {
+            Some(JoinSide::Lhs) => self.s1.lock().unwrap().has_field(fldname),
+            Some(JoinSide::Rhs) => self.s2.lock().unwrap().has_field(fldname),
+            None => false,
+        }
     }
 }
 
@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::{materialize::sortscan::SortScan, record::tablescan::TableScan};
 
-use super::{constant::Constant, scan::Scan, updatescan::UpdateScan};
+use super::{constant::Constant, expression::Expression, scan::Scan, updatescan::UpdateScan};
 
 #[derive(Debug)]
 pub enum ProjectScanError {
@@ -31,6 +31,9 @@ impl fmt::Display for ProjectScanError {
 pub struct ProjectScan {
     s: Arc<Mutex<dyn Scan>>,
     fieldlist: Vec<String>,
+    // my own extends
+    // the computed fields and their expressions over the records of the underlying scan
+    exprs: Vec<(String, Expression)>,
 }
 
 impl Scan for ProjectScan {
@@ -41,7 +44,10 @@ impl Scan for ProjectScan {
         self.s.lock().unwrap().next()
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            let val = val?;
+            val.as_i16()
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_i16(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -50,7 +56,10 @@ impl Scan for ProjectScan {
         }
     }
     fn get_i32(&mut self, fldname: &str) -> anyhow::Result<i32> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            let val = val?;
+            val.as_i32()
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_i32(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -59,7 +68,10 @@ impl Scan for ProjectScan {
         }
     }
     fn get_string(&mut self, fldname: &str) -> anyhow::Result<String> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            let val = val?;
+            val.as_string().map(|s| s.to_string())
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_string(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -68,7 +80,10 @@ impl Scan for ProjectScan {
         }
     }
     fn get_bool(&mut self, fldname: &str) -> Result<bool> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            let val = val?;
+            val.as_bool()
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_bool(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -77,7 +92,10 @@ impl Scan for ProjectScan {
         }
     }
     fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            let val = val?;
+            val.as_date()
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_date(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -86,7 +104,9 @@ impl Scan for ProjectScan {
         }
     }
     fn get_val(&mut self, fldname: &str) -> Result<Constant> {
-        if self.has_field(fldname) {
+        if let Some(val) = self.computed_val(fldname) {
+            val
+        } else if self.has_field(fldname) {
             self.s.lock().unwrap().get_val(fldname)
         } else {
             Err(From::from(ProjectScanError::FieldNotFoundError(
@@ -113,7 +133,25 @@ impl Scan for ProjectScan {
 
 impl ProjectScan {
     pub fn new(s: Arc<Mutex<dyn Scan>>, fieldlist: Vec<String>) -> Self {
-        Self { s, fieldlist }
+        Self::new_with(s, fieldlist, vec![])
+    }
+    // my own extends
+    pub fn new_with(
+        s: Arc<Mutex<dyn Scan>>,
+        fieldlist: Vec<String>,
+        exprs: Vec<(String, Expression)>,
+    ) -> Self {
+        Self {
+            s,
+            fieldlist,
+            exprs,
+        }
+    }
+    fn computed_val(&self, fldname: &str) -> Option<Result<Constant>> {
+        self.exprs
+            .iter()
+            .find(|(name, _)| name == fldname)
+            .map(|(_, expr)| expr.evaluate(Arc::clone(&self.s)))
     }
 }
 
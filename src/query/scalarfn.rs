@@ -0,0 +1,134 @@
+use anyhow::Result;
+use core::fmt;
+use std::sync::Arc;
+
+use super::constant::Constant;
+use crate::record::schema::{FieldInfo, FieldType};
+
+use self::{
+    castfn::CastFn,
+    coalescefn::CoalesceFn,
+    datefn::{DateAddFn, MonthFn, YearFn},
+    numericfn::AbsFn,
+    stringfn::{LengthFn, LowerFn, SubstrFn, TrimFn, UpperFn},
+};
+
+pub mod castfn;
+pub mod coalescefn;
+pub mod datefn;
+pub mod numericfn;
+pub mod stringfn;
+
+// my own extends
+pub trait ScalarFn {
+    // type checking: the type of the result for the arguments of the types,
+    // or None if the function can't be applied to them.
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo>;
+    fn apply(&self, args: &[Constant]) -> Result<Constant>;
+}
+
+// my own extends
+// scalar function in sql (e.g. upper(sname), year(birthday)), which is called by its name.
+// CAST has its own syntax, CAST(expression AS type).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Function {
+    Upper,
+    Lower,
+    Length,
+    Substr,
+    Trim,
+    Abs,
+    // the type of the result, which is set when the expression is resolved by a schema
+    Coalesce {
+        fld_type: Option<FieldType>,
+    },
+    Year,
+    Month,
+    DateAdd,
+    Cast {
+        fld_type: FieldType,
+        length: usize,
+        scale: usize,
+    },
+}
+
+// the functions which can be called by their names
+const FUNCTIONS: [Function; 10] = [
+    Function::Upper,
+    Function::Lower,
+    Function::Length,
+    Function::Substr,
+    Function::Trim,
+    Function::Abs,
+    Function::Coalesce { fld_type: None },
+    Function::Year,
+    Function::Month,
+    Function::DateAdd,
+];
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Function {
+    pub fn from_name(name: &str) -> Option<Self> {
+        FUNCTIONS
+            .into_iter()
+            .find(|func| func.name().eq_ignore_ascii_case(name))
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Upper => "upper",
+            Function::Lower => "lower",
+            Function::Length => "length",
+            Function::Substr => "substr",
+            Function::Trim => "trim",
+            Function::Abs => "abs",
+            Function::Coalesce { .. } => "coalesce",
+            Function::Year => "year",
+            Function::Month => "month",
+            Function::DateAdd => "date_add",
+            Function::Cast { .. } => "cast",
+        }
+    }
+    pub fn function(&self) -> Arc<dyn ScalarFn> {
+        match self {
+            Function::Upper => Arc::new(UpperFn),
+            Function::Lower => Arc::new(LowerFn),
+            Function::Length => Arc::new(LengthFn),
+            Function::Substr => Arc::new(SubstrFn),
+            Function::Trim => Arc::new(TrimFn),
+            Function::Abs => Arc::new(AbsFn),
+            Function::Coalesce { fld_type } => Arc::new(CoalesceFn::new(*fld_type)),
+            Function::Year => Arc::new(YearFn),
+            Function::Month => Arc::new(MonthFn),
+            Function::DateAdd => Arc::new(DateAddFn),
+            Function::Cast {
+                fld_type,
+                length,
+                scale,
+            } => Arc::new(CastFn::new(*fld_type, *length, *scale)),
+        }
+    }
+}
+
+// most functions are NULL if any of their arguments is NULL
+fn has_null(args: &[Constant]) -> bool {
+    args.iter().any(|arg| arg.is_null())
+}
+fn is_string(fi: &FieldInfo) -> bool {
+    matches!(fi.fld_type, FieldType::VARCHAR | FieldType::TEXT)
+}
+fn is_integer(fi: &FieldInfo) -> bool {
+    matches!(
+        fi.fld_type,
+        FieldType::SMALLINT | FieldType::INTEGER | FieldType::BIGINT
+    )
+}
+// the type of the result, which is nullable if any of the arguments is
+fn nullable_info(mut fi: FieldInfo, args: &[FieldInfo]) -> FieldInfo {
+    fi.nullable = args.iter().any(|arg| arg.nullable);
+    fi
+}
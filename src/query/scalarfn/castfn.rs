@@ -0,0 +1,136 @@
+use anyhow::Result;
+use core::fmt;
+use rust_decimal::{prelude::ToPrimitive, Decimal, RoundingStrategy};
+use std::str::FromStr;
+
+use super::ScalarFn;
+use crate::{
+    query::constant::{Constant, ConstantError},
+    record::schema::{FieldInfo, FieldType},
+};
+
+// CAST(x AS type), which also converts a string to a number, and a value to a string.
+// a number is rounded to the scale of the type, and a string is truncated to its length.
+pub struct CastFn {
+    fld_type: FieldType,
+    length: usize,
+    scale: usize,
+}
+
+// the name of the type, as written in sql
+impl fmt::Display for CastFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.fld_type {
+            FieldType::SMALLINT => write!(f, "smallint"),
+            FieldType::INTEGER => write!(f, "integer"),
+            FieldType::VARCHAR => write!(f, "varchar({})", self.length),
+            FieldType::BOOL => write!(f, "bool"),
+            FieldType::DATE => write!(f, "date"),
+            FieldType::TEXT => write!(f, "text"),
+            FieldType::BLOB => write!(f, "blob"),
+            FieldType::BIGINT => write!(f, "bigint"),
+            FieldType::DOUBLE => write!(f, "double"),
+            FieldType::DECIMAL => write!(f, "decimal({}, {})", self.length, self.scale),
+            FieldType::TIMESTAMP => write!(f, "timestamp"),
+            FieldType::TIME => write!(f, "time"),
+        }
+    }
+}
+
+impl CastFn {
+    pub fn new(fld_type: FieldType, length: usize, scale: usize) -> Self {
+        Self {
+            fld_type,
+            length,
+            scale,
+        }
+    }
+    fn to_integer(&self, val: &Constant) -> Result<i64> {
+        let out_of_range = || From::from(ConstantError::OutOfRange(val.to_string()));
+        match val {
+            Constant::String(sval) => sval
+                .trim()
+                .parse()
+                .map_err(|_| From::from(ConstantError::TypeError)),
+            Constant::Decimal(dval) => dval
+                .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+                .to_i64()
+                .ok_or_else(out_of_range),
+            Constant::F64(fval) => fval.round().to_i64().ok_or_else(out_of_range),
+            Constant::Bool(bval) => Ok(*bval as i64),
+            _ => val.as_i64(),
+        }
+    }
+}
+
+impl ScalarFn for CastFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            // a BLOB is only cast to a BLOB
+            [x] if (x.fld_type == FieldType::BLOB) == (self.fld_type == FieldType::BLOB) => {
+                let mut fi = FieldInfo::new_with(self.fld_type, self.length, x.nullable);
+                fi.scale = self.scale;
+                Some(fi)
+            }
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        let val = &args[0];
+        if val.is_null() {
+            return Ok(Constant::Null);
+        }
+        let type_error = || From::from(ConstantError::TypeError);
+        let out_of_range = || From::from(ConstantError::OutOfRange(val.to_string()));
+        match self.fld_type {
+            FieldType::VARCHAR => Ok(Constant::new_string(
+                val.as_text()?.chars().take(self.length).collect(),
+            )),
+            FieldType::TEXT => val.as_text().map(Constant::new_string),
+            FieldType::SMALLINT => i16::try_from(self.to_integer(val)?)
+                .map(Constant::new_i16)
+                .map_err(|_| out_of_range()),
+            FieldType::INTEGER => i32::try_from(self.to_integer(val)?)
+                .map(Constant::new_i32)
+                .map_err(|_| out_of_range()),
+            FieldType::BIGINT => self.to_integer(val).map(Constant::new_i64),
+            FieldType::DOUBLE => match val {
+                Constant::String(sval) => sval
+                    .trim()
+                    .parse()
+                    .map(Constant::new_f64)
+                    .map_err(|_| type_error()),
+                _ => val.as_f64().map(Constant::new_f64),
+            },
+            FieldType::DECIMAL => {
+                let dval = match val {
+                    Constant::String(sval) => {
+                        Decimal::from_str(sval.trim()).map_err(|_| type_error())?
+                    }
+                    _ => val.as_decimal()?,
+                };
+                let unscaled = Constant::new_decimal(dval).as_unscaled(self.length, self.scale)?;
+                Ok(Constant::new_decimal(Decimal::new(
+                    unscaled,
+                    self.scale as u32,
+                )))
+            }
+            FieldType::BOOL => match val {
+                Constant::String(sval) if sval.trim().eq_ignore_ascii_case("true") => {
+                    Ok(Constant::new_bool(true))
+                }
+                Constant::String(sval) if sval.trim().eq_ignore_ascii_case("false") => {
+                    Ok(Constant::new_bool(false))
+                }
+                _ => val.as_bool().map(Constant::new_bool),
+            },
+            FieldType::DATE => match val {
+                Constant::Timestamp(tsval) => Ok(Constant::new_date(tsval.date())),
+                _ => val.as_date().map(Constant::new_date),
+            },
+            FieldType::TIMESTAMP => val.as_timestamp().map(Constant::new_timestamp),
+            FieldType::TIME => val.as_time().map(Constant::new_time),
+            FieldType::BLOB => val.as_blob().map(|b| Constant::new_blob(b.to_vec())),
+        }
+    }
+}
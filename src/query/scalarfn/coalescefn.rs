@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+use super::{is_string, ScalarFn};
+use crate::{
+    query::{constant::Constant, expression::MAX_DECIMAL_PRECISION},
+    record::schema::{FieldInfo, FieldType},
+};
+
+// the first argument which is not NULL.
+// the arguments are of the same type, or of the types which are converted to the widest of them
+// (e.g. SMALLINT and DECIMAL, VARCHAR and TEXT, or DATE and TIMESTAMP).
+// the value is converted to the type of the result, if the types of the arguments are known.
+pub struct CoalesceFn {
+    fld_type: Option<FieldType>,
+}
+
+impl CoalesceFn {
+    pub fn new(fld_type: Option<FieldType>) -> Self {
+        Self { fld_type }
+    }
+}
+
+impl ScalarFn for CoalesceFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        let (first, rest) = args.split_first()?;
+        let mut result = *first;
+        for arg in rest {
+            result = common_info(&result, arg)?;
+        }
+        // NULL only if all of them are
+        result.nullable = args.iter().all(|arg| arg.nullable);
+        Some(result)
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        let val = args
+            .iter()
+            .find(|arg| !arg.is_null())
+            .cloned()
+            .unwrap_or(Constant::Null);
+        match self.fld_type {
+            Some(fld_type) => val.as_field_type(fld_type),
+            None => Ok(val),
+        }
+    }
+}
+
+fn common_info(lhs: &FieldInfo, rhs: &FieldInfo) -> Option<FieldInfo> {
+    if let (Some(l), Some(r)) = (lhs.fld_type.numeric_rank(), rhs.fld_type.numeric_rank()) {
+        let mut fi = if l >= r { *lhs } else { *rhs };
+        if fi.fld_type == FieldType::DECIMAL {
+            if lhs.fld_type == rhs.fld_type {
+                fi.length = lhs.length.max(rhs.length);
+                fi.scale = lhs.scale.max(rhs.scale);
+            } else {
+                // an integer may have more digits
+                fi.length = MAX_DECIMAL_PRECISION;
+            }
+        }
+        return Some(fi);
+    }
+    if is_string(lhs) && is_string(rhs) {
+        if lhs.fld_type == FieldType::TEXT {
+            return Some(*lhs);
+        }
+        if rhs.fld_type == FieldType::TEXT {
+            return Some(*rhs);
+        }
+        return Some(FieldInfo::new(
+            FieldType::VARCHAR,
+            lhs.length.max(rhs.length),
+        ));
+    }
+    match (lhs.fld_type, rhs.fld_type) {
+        (l, r) if l == r => Some(*lhs),
+        (FieldType::DATE, FieldType::TIMESTAMP) => Some(*rhs),
+        (FieldType::TIMESTAMP, FieldType::DATE) => Some(*lhs),
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use chrono::{Datelike, TimeDelta};
+
+use super::{has_null, is_integer, nullable_info, ScalarFn};
+use crate::{
+    query::constant::{Constant, ConstantError},
+    record::schema::{FieldInfo, FieldType},
+};
+
+fn is_date(fi: &FieldInfo) -> bool {
+    matches!(fi.fld_type, FieldType::DATE | FieldType::TIMESTAMP)
+}
+
+pub struct YearFn;
+
+impl ScalarFn for YearFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [d] if is_date(d) => Some(nullable_info(FieldInfo::new(FieldType::INTEGER, 0), args)),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        Ok(Constant::new_i32(args[0].as_timestamp()?.year()))
+    }
+}
+
+pub struct MonthFn;
+
+impl ScalarFn for MonthFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [d] if is_date(d) => Some(nullable_info(FieldInfo::new(FieldType::INTEGER, 0), args)),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        Ok(Constant::new_i32(args[0].as_timestamp()?.month() as i32))
+    }
+}
+
+// DATE_ADD(d, days), which adds the days (or subtracts if negative) to a date or a timestamp
+pub struct DateAddFn;
+
+impl ScalarFn for DateAddFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [d, days] if is_date(d) && is_integer(days) => Some(nullable_info(*d, args)),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        let days = args[1].as_i64()?;
+        let overflow = || {
+            From::from(ConstantError::OutOfRange(format!(
+                "date_add({}, {})",
+                args[0], days
+            )))
+        };
+        let delta = TimeDelta::try_days(days).ok_or_else(overflow)?;
+        match &args[0] {
+            Constant::Date(dval) => dval
+                .checked_add_signed(delta)
+                .map(Constant::new_date)
+                .ok_or_else(overflow),
+            Constant::Timestamp(tsval) => tsval
+                .checked_add_signed(delta)
+                .map(Constant::new_timestamp)
+                .ok_or_else(overflow),
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+}
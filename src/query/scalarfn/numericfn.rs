@@ -0,0 +1,42 @@
+use anyhow::Result;
+
+use super::{has_null, ScalarFn};
+use crate::{
+    query::constant::{Constant, ConstantError},
+    record::schema::FieldInfo,
+};
+
+pub struct AbsFn;
+
+impl ScalarFn for AbsFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [n] if n.fld_type.numeric_rank().is_some() => Some(*n),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        // the absolute value of the smallest integer overflows
+        let overflow = || From::from(ConstantError::OutOfRange(format!("abs({})", args[0])));
+        match &args[0] {
+            Constant::I16(ival) => ival
+                .checked_abs()
+                .map(Constant::new_i16)
+                .ok_or_else(overflow),
+            Constant::I32(ival) => ival
+                .checked_abs()
+                .map(Constant::new_i32)
+                .ok_or_else(overflow),
+            Constant::I64(ival) => ival
+                .checked_abs()
+                .map(Constant::new_i64)
+                .ok_or_else(overflow),
+            Constant::F64(fval) => Ok(Constant::new_f64(fval.abs())),
+            Constant::Decimal(dval) => Ok(Constant::new_decimal(dval.abs())),
+            _ => Err(From::from(ConstantError::TypeError)),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+
+use super::{has_null, is_integer, is_string, nullable_info, ScalarFn};
+use crate::{
+    query::constant::{Constant, ConstantError},
+    record::schema::{FieldInfo, FieldType},
+};
+
+pub struct UpperFn;
+
+impl ScalarFn for UpperFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [s] if is_string(s) => Some(*s),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        Ok(Constant::new_string(args[0].as_string()?.to_uppercase()))
+    }
+}
+
+pub struct LowerFn;
+
+impl ScalarFn for LowerFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [s] if is_string(s) => Some(*s),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        Ok(Constant::new_string(args[0].as_string()?.to_lowercase()))
+    }
+}
+
+// the number of the characters
+pub struct LengthFn;
+
+impl ScalarFn for LengthFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [s] if is_string(s) => Some(nullable_info(FieldInfo::new(FieldType::INTEGER, 0), args)),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        let len = args[0].as_string()?.chars().count();
+        i32::try_from(len)
+            .map(Constant::new_i32)
+            .map_err(|_| From::from(ConstantError::OutOfRange(len.to_string())))
+    }
+}
+
+// SUBSTR(s, start [, length]), where the first character is at 1, as in SQL.
+// the characters before the first one are counted in the length, but not returned.
+pub struct SubstrFn;
+
+impl ScalarFn for SubstrFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [s, start] if is_string(s) && is_integer(start) => Some(nullable_info(*s, args)),
+            [s, start, len] if is_string(s) && is_integer(start) && is_integer(len) => {
+                Some(nullable_info(*s, args))
+            }
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        let s = args[0].as_string()?;
+        let start = args[1].as_i64()?;
+        let end = match args.get(2) {
+            Some(len) => {
+                let len = len.as_i64()?;
+                if len < 0 {
+                    return Err(From::from(ConstantError::OutOfRange(len.to_string())));
+                }
+                start.saturating_add(len)
+            }
+            None => i64::MAX,
+        };
+        let skip = start.max(1) - 1;
+        let take = end.max(1) - 1 - skip;
+        let sub = s
+            .chars()
+            .skip(skip.try_into().unwrap_or(usize::MAX))
+            .take(take.try_into().unwrap_or(usize::MAX))
+            .collect();
+        Ok(Constant::new_string(sub))
+    }
+}
+
+// removes the leading and trailing spaces
+pub struct TrimFn;
+
+impl ScalarFn for TrimFn {
+    fn field_info(&self, args: &[FieldInfo]) -> Option<FieldInfo> {
+        match args {
+            [s] if is_string(s) => Some(*s),
+            _ => None,
+        }
+    }
+    fn apply(&self, args: &[Constant]) -> Result<Constant> {
+        if has_null(args) {
+            return Ok(Constant::Null);
+        }
+        Ok(Constant::new_string(
+            args[0].as_string()?.trim_matches(' ').to_string(),
+        ))
+    }
+}
@@ -1,37 +1,164 @@
+use anyhow::Result;
 use core::fmt;
 use std::{
     cmp::*,
     sync::{Arc, Mutex},
 };
 
-use super::{constant::Constant, expression::Expression, scan::Scan};
-use crate::{plan::plan::Plan, record::schema::Schema};
+use super::{
+    constant::Constant,
+    expression::{Expression, ExpressionError},
+    likepattern::LikePattern,
+    scan::Scan,
+};
+use crate::{
+    plan::plan::Plan,
+    record::schema::{FieldType, Schema},
+};
+
+// my own extends
+// the estimated reduction factor of a range term (e.g. GradYear > 2020),
+// which selects about a third of the records as System R does.
+pub const RANGE_REDUCTION_FACTOR: i32 = 3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    // unary operators, whose rhs is always NULL
+    IsNull,
+    IsNotNull,
+    // my own extends
+    // the rhs is a pattern with the default escape character
+    Like,
+    NotLike,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operator::Eq => write!(f, "="),
+            Operator::Ne => write!(f, "<>"),
+            Operator::Lt => write!(f, "<"),
+            Operator::Le => write!(f, "<="),
+            Operator::Gt => write!(f, ">"),
+            Operator::Ge => write!(f, ">="),
+            Operator::IsNull => write!(f, " is null"),
+            Operator::IsNotNull => write!(f, " is not null"),
+            Operator::Like => write!(f, " like "),
+            Operator::NotLike => write!(f, " not like "),
+        }
+    }
+}
+
+impl Operator {
+    pub fn negate(&self) -> Self {
+        match self {
+            Operator::Eq => Operator::Ne,
+            Operator::Ne => Operator::Eq,
+            Operator::Lt => Operator::Ge,
+            Operator::Le => Operator::Gt,
+            Operator::Gt => Operator::Le,
+            Operator::Ge => Operator::Lt,
+            Operator::IsNull => Operator::IsNotNull,
+            Operator::IsNotNull => Operator::IsNull,
+            Operator::Like => Operator::NotLike,
+            Operator::NotLike => Operator::Like,
+        }
+    }
+    // the operator when the both sides are swapped (e.g. 1 < a => a > 1)
+    // NOTE: LIKE is not commutative, so the caller must not swap its sides.
+    pub fn commute(&self) -> Self {
+        match self {
+            Operator::Eq => Operator::Eq,
+            Operator::Ne => Operator::Ne,
+            Operator::Lt => Operator::Gt,
+            Operator::Le => Operator::Ge,
+            Operator::Gt => Operator::Lt,
+            Operator::Ge => Operator::Le,
+            Operator::IsNull => Operator::IsNull,
+            Operator::IsNotNull => Operator::IsNotNull,
+            Operator::Like => Operator::Like,
+            Operator::NotLike => Operator::NotLike,
+        }
+    }
+    // three-valued logic: None means unknown, which is the result of comparing with NULL
+    pub fn apply(&self, lhs: &Constant, rhs: &Constant) -> Option<bool> {
+        match self {
+            Operator::IsNull => return Some(lhs.is_null()),
+            Operator::IsNotNull => return Some(!lhs.is_null()),
+            _ => {}
+        }
+        if lhs.is_null() || rhs.is_null() {
+            return None;
+        }
+        let result = match self {
+            Operator::Eq => lhs == rhs,
+            Operator::Ne => lhs != rhs,
+            Operator::Lt => lhs < rhs,
+            Operator::Le => lhs <= rhs,
+            Operator::Gt => lhs > rhs,
+            Operator::Ge => lhs >= rhs,
+            Operator::Like | Operator::NotLike => {
+                // NOTE: a malformed pattern is unknown, as a value which can't be computed.
+                let pattern = LikePattern::new(rhs.as_string().ok()?).ok()?;
+                pattern.matches(lhs.as_string().ok()?) == (*self == Operator::Like)
+            }
+            Operator::IsNull | Operator::IsNotNull => unreachable!(),
+        };
+        Some(result)
+    }
+    pub fn is_unary(&self) -> bool {
+        matches!(self, Operator::IsNull | Operator::IsNotNull)
+    }
+    pub fn is_like(&self) -> bool {
+        matches!(self, Operator::Like | Operator::NotLike)
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Term {
     lhs: Expression,
+    op: Operator,
     rhs: Expression,
 }
 
 impl fmt::Display for Term {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}={}", self.lhs.to_string(), self.rhs.to_string())
+        if self.op.is_unary() {
+            return write!(f, "{}{}", self.lhs, self.op);
+        }
+        write!(
+            f,
+            "{}{}{}",
+            self.lhs.to_string(),
+            self.op,
+            self.rhs.to_string()
+        )
     }
 }
 
 impl Term {
     pub fn new(lhs: Expression, rhs: Expression) -> Self {
-        Self { lhs, rhs }
+        Self::new_with(lhs, Operator::Eq, rhs)
+    }
+    pub fn new_with(lhs: Expression, op: Operator, rhs: Expression) -> Self {
+        Self { lhs, op, rhs }
     }
     pub fn is_satisfied(&self, s: Arc<Mutex<dyn Scan>>) -> bool {
-        let lhsval = self.lhs.evaluate(Arc::clone(&s));
-        let rhsval = self.rhs.evaluate(Arc::clone(&s));
-        lhsval.unwrap() == rhsval.unwrap()
+        self.evaluate(s) == Some(true)
     }
     pub fn applies_to(&self, sch: Arc<Schema>) -> bool {
         self.lhs.applies_to(Arc::clone(&sch)) && self.rhs.applies_to(Arc::clone(&sch))
     }
     pub fn reduction_factor(&self, p: Arc<dyn Plan>) -> i32 {
+        if self.op != Operator::Eq {
+            return self.range_reduction_factor(p);
+        }
         match (&self.lhs, &self.rhs) {
             (Expression::Fldname(lhs_name), Expression::Fldname(rhs_name)) => {
                 return max(p.distinct_values(&lhs_name), p.distinct_values(&rhs_name));
@@ -43,16 +170,27 @@ impl Term {
                 return p.distinct_values(&rhs_name);
             }
             (Expression::Val(lhs_val), Expression::Val(rhs_val)) => {
-                if lhs_val == rhs_val {
+                if self.op.apply(lhs_val, rhs_val) == Some(true) {
                     return 1;
                 } else {
                     return i32::MAX;
                 }
             }
+            // my own extends
+            // the values of a computed expression are not known
+            (Expression::Fldname(fldname), _) | (_, Expression::Fldname(fldname)) => {
+                p.distinct_values(fldname)
+            }
+            _ => 1,
         }
     }
     pub fn equates_with_constant(&self, fldname: &str) -> Option<&Constant> {
+        if self.op != Operator::Eq {
+            return None;
+        }
         match (&self.lhs, &self.rhs) {
+            // NOTE: "fldname = NULL" is never satisfied, so it can't be used for an index.
+            (_, Expression::Val(Constant::Null)) | (Expression::Val(Constant::Null), _) => None,
             (Expression::Fldname(lhs_name), Expression::Val(_)) => {
                 if lhs_name == fldname {
                     return self.rhs.as_constant();
@@ -69,6 +207,9 @@ impl Term {
         }
     }
     pub fn equates_with_field(&self, fldname: &str) -> Option<&str> {
+        if self.op != Operator::Eq {
+            return None;
+        }
         match (&self.lhs, &self.rhs) {
             (Expression::Fldname(lhs_name), Expression::Fldname(rhs_name)) => {
                 if lhs_name == fldname {
@@ -83,10 +224,89 @@ impl Term {
         }
     }
     // my own extends
+    pub fn new_unary(lhs: Expression, op: Operator) -> Self {
+        Self::new_with(lhs, op, Expression::Val(Constant::Null))
+    }
+    // three-valued logic: None means unknown
+    pub fn evaluate(&self, s: Arc<Mutex<dyn Scan>>) -> Option<bool> {
+        // NOTE: an expression which can't be computed (e.g. an overflow) is unknown.
+        let lhsval = self.lhs.evaluate(Arc::clone(&s)).ok()?;
+        let rhsval = self.rhs.evaluate(Arc::clone(&s)).ok()?;
+        self.op.apply(&lhsval, &rhsval)
+    }
     pub fn lhs(&self) -> &Expression {
         &self.lhs
     }
     pub fn rhs(&self) -> &Expression {
         &self.rhs
     }
+    pub fn op(&self) -> Operator {
+        self.op
+    }
+    pub fn negate(&self) -> Self {
+        Self::new_with(self.lhs.clone(), self.op.negate(), self.rhs.clone())
+    }
+    // returns the operator and the constant of "fldname op constant"
+    pub fn compares_with_constant(&self, fldname: &str) -> Option<(Operator, &Constant)> {
+        match (&self.lhs, &self.rhs) {
+            (_, Expression::Val(Constant::Null)) | (Expression::Val(Constant::Null), _) => None,
+            (Expression::Fldname(lhs_name), Expression::Val(val)) if lhs_name == fldname => {
+                Some((self.op, val))
+            }
+            (Expression::Val(val), Expression::Fldname(rhs_name))
+                if rhs_name == fldname && !self.op.is_like() =>
+            {
+                Some((self.op.commute(), val))
+            }
+            _ => None,
+        }
+    }
+    pub fn check_types(&self, sch: &Schema) -> Result<()> {
+        let lhs_info = self.lhs.field_info(sch)?;
+        let rhs_info = self.rhs.field_info(sch)?;
+        if self.op.is_like() {
+            for fi in [lhs_info, rhs_info] {
+                if !matches!(fi.fld_type, FieldType::VARCHAR | FieldType::TEXT) {
+                    return Err(From::from(ExpressionError::TypeMismatch(self.to_string())));
+                }
+            }
+        }
+        Ok(())
+    }
+    pub fn resolve(&self, sch: &Schema) -> Result<Self> {
+        Ok(Self::new_with(
+            self.lhs.resolve(sch)?,
+            self.op,
+            self.rhs.resolve(sch)?,
+        ))
+    }
+    fn range_reduction_factor(&self, p: Arc<dyn Plan>) -> i32 {
+        let distinct_values = match (&self.lhs, &self.rhs) {
+            (Expression::Fldname(lhs_name), Expression::Fldname(rhs_name)) => {
+                max(p.distinct_values(lhs_name), p.distinct_values(rhs_name))
+            }
+            (Expression::Fldname(fldname), Expression::Val(_))
+            | (Expression::Val(_), Expression::Fldname(fldname)) => p.distinct_values(fldname),
+            (Expression::Val(lhs_val), Expression::Val(rhs_val)) => {
+                if self.op.apply(lhs_val, rhs_val) == Some(true) {
+                    return 1;
+                } else {
+                    return i32::MAX;
+                }
+            }
+            (Expression::Fldname(fldname), _) | (_, Expression::Fldname(fldname)) => {
+                p.distinct_values(fldname)
+            }
+            _ => 1,
+        };
+        match self.op {
+            // almost all records are selected.
+            Operator::Ne | Operator::IsNotNull | Operator::NotLike => return 1,
+            // NULL is treated as one of the distinct values.
+            Operator::IsNull => return max(distinct_values, 1),
+            _ => {}
+        }
+
+        distinct_values.clamp(1, RANGE_REDUCTION_FACTOR)
+    }
 }
@@ -10,6 +10,8 @@ pub enum ConnectionError {
     CommitFailed,
     RollbackFailed,
     CloseFailed,
+    // my own extends
+    TransactionAborted,
 }
 
 impl std::error::Error for ConnectionError {}
@@ -31,10 +33,27 @@ impl fmt::Display for ConnectionError {
             ConnectionError::CloseFailed => {
                 write!(f, "failed to close")
             }
+            ConnectionError::TransactionAborted => {
+                write!(
+                    f,
+                    "transaction aborted, commands ignored until end of transaction block"
+                )
+            }
         }
     }
 }
 
+// my own extends
+// a transaction block is opened by BEGIN, and ended by COMMIT or ROLLBACK.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TxBlock {
+    // in autocommit mode, each statement is committed on its own
+    None,
+    Open,
+    // a statement of the block failed and the transaction has been rolled back
+    Failed,
+}
+
 pub trait ConnectionAdapter<'a> {
     type Stmt: StatementAdapter<'a>;
     type Res;
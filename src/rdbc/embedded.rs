@@ -12,7 +12,7 @@ mod tests {
 
     use super::{
         super::{
-            super::server::config::{BufferMgr, QueryPlanner, SimpleDBConfig},
+            super::server::config::{BufferMgr, ConcurrencyMgr, QueryPlanner, SimpleDBConfig},
             connectionadapter::ConnectionAdapter,
             driveradapter::DriverAdapter,
             resultsetadapter::ResultSetAdapter,
@@ -80,6 +80,7 @@ mod tests {
             num_of_buffers: 8,
             buffer_manager: BufferMgr::Naive,
             query_planner: QueryPlanner::Basic,
+            concurrency_mgr: ConcurrencyMgr::Locking,
         });
         // connect database
         let mut conn = d.connect("_test/rdbc")?;
@@ -132,7 +133,7 @@ mod tests {
                     DataType::Int32 => {
                         print!("{:width$} ", results.get_i32(fldname)?, width = w);
                     }
-                    DataType::Varchar => {
+                    DataType::Varchar | DataType::Text => {
                         print!("{:width$} ", results.get_string(fldname)?, width = w);
                     }
                     DataType::Bool => {
@@ -141,6 +142,24 @@ mod tests {
                     DataType::Date => {
                         print!("{:width$} ", results.get_date(fldname)?, width = w);
                     }
+                    DataType::Blob => {
+                        print!("{:width$} ", results.get_blob(fldname)?.len(), width = w);
+                    }
+                    DataType::BigInt => {
+                        print!("{:width$} ", results.get_i64(fldname)?, width = w);
+                    }
+                    DataType::Double => {
+                        print!("{:width$} ", results.get_f64(fldname)?, width = w);
+                    }
+                    DataType::Decimal => {
+                        print!("{:width$} ", results.get_decimal(fldname)?, width = w);
+                    }
+                    DataType::Timestamp => {
+                        print!("{:width$} ", results.get_timestamp(fldname)?, width = w);
+                    }
+                    DataType::Time => {
+                        print!("{:width$} ", results.get_time(fldname)?, width = w);
+                    }
                 }
             }
             println!();
@@ -161,4 +180,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn transaction_block_test() -> Result<()> {
+        if Path::new("_test/rdbc_txblock").exists() {
+            fs::remove_dir_all("_test/rdbc_txblock")?;
+        }
+
+        let sqls = vec![
+            ("CREATE TABLE T (A integer);", true),
+            // rolled back to the savepoint
+            ("BEGIN;", true),
+            ("INSERT INTO T (A) VALUES (1);", true),
+            ("SAVEPOINT s1;", true),
+            ("INSERT INTO T (A) VALUES (2);", true),
+            ("ROLLBACK TO SAVEPOINT s1;", true),
+            ("INSERT INTO T (A) VALUES (3);", true),
+            ("ROLLBACK TO SAVEPOINT s2;", false),
+            ("COMMIT;", true),
+            // a failed statement aborts the whole block
+            ("BEGIN;", true),
+            ("INSERT INTO T (A) VALUES (4);", true),
+            ("INSERT INTO T (A) VALUE (5);", false),
+            ("INSERT INTO T (A) VALUES (6);", false),
+            ("COMMIT;", true),
+            // rolled back
+            ("BEGIN;", true),
+            ("INSERT INTO T (A) VALUES (7);", true),
+            ("ROLLBACK;", true),
+            // committed on its own
+            ("INSERT INTO T (A) VALUES (8);", true),
+        ];
+
+        let d = EmbeddedDriver::new(SimpleDBConfig {
+            block_size: 400,
+            num_of_buffers: 8,
+            buffer_manager: BufferMgr::Naive,
+            query_planner: QueryPlanner::Basic,
+            concurrency_mgr: ConcurrencyMgr::Locking,
+        });
+        let mut conn = d.connect("_test/rdbc_txblock")?;
+        for (sql, ok) in sqls {
+            let res = conn.create_statement(sql)?.execute_update();
+            println!("< {}", sql);
+            println!("> {:?} ({:?})", res, conn.tx_block());
+            assert_eq!(ok, res.is_ok());
+        }
+        conn.close()?;
+
+        let mut conn = d.connect("_test/rdbc_txblock")?;
+        let mut stmt = conn.create_statement("SELECT A FROM T;")?;
+        let mut results = stmt.execute_query()?;
+        let mut vals = vec![];
+        while results.next() {
+            vals.push(results.get_i32("A")?);
+        }
+        results.close()?;
+        println!("> {:?}", vals);
+        assert_eq!(vec![1, 3, 8], vals);
+
+        Ok(())
+    }
+
+    #[test]
+    fn autocommit_test() -> Result<()> {
+        if Path::new("_test/rdbc_autocommit").exists() {
+            fs::remove_dir_all("_test/rdbc_autocommit")?;
+        }
+
+        let d = EmbeddedDriver::new(SimpleDBConfig {
+            block_size: 400,
+            num_of_buffers: 8,
+            buffer_manager: BufferMgr::Naive,
+            query_planner: QueryPlanner::Basic,
+            concurrency_mgr: ConcurrencyMgr::Locking,
+        });
+        let mut conn = d.connect("_test/rdbc_autocommit")?;
+        assert!(conn.autocommit());
+        conn.create_statement("CREATE TABLE T (A integer);")?
+            .execute_update()?;
+        conn.set_autocommit(false)?;
+
+        // not committed until asked
+        conn.create_statement("INSERT INTO T (A) VALUES (1);")?
+            .execute_update()?;
+        conn.rollback()?;
+
+        // each statement is committed, and a failed one is rolled back
+        conn.set_autocommit(true)?;
+        conn.create_statement("INSERT INTO T (A) VALUES (2);")?
+            .execute_update()?;
+        conn.rollback()?;
+        assert!(conn
+            .create_statement("INSERT INTO T (A) VALUE (3);")?
+            .execute_update()
+            .is_err());
+
+        // turning autocommit on commits the running transaction
+        conn.set_autocommit(false)?;
+        conn.create_statement("INSERT INTO T (A) VALUES (4);")?
+            .execute_update()?;
+        conn.set_autocommit(true)?;
+        conn.rollback()?;
+
+        let mut stmt = conn.create_statement("SELECT A FROM T;")?;
+        let mut results = stmt.execute_query()?;
+        let mut vals = vec![];
+        while results.next() {
+            vals.push(results.get_i32("A")?);
+        }
+        results.close()?;
+        println!("> {:?}", vals);
+        assert_eq!(vec![2, 4], vals);
+
+        Ok(())
+    }
 }
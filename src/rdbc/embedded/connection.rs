@@ -8,15 +8,20 @@ use std::{
 use super::statement::EmbeddedStatement;
 use crate::{
     metadata::indexmanager::IndexInfo,
-    rdbc::connectionadapter::{ConnectionAdapter, ConnectionError},
+    parser::tcl::TCL,
+    rdbc::connectionadapter::{ConnectionAdapter, ConnectionError, TxBlock},
     record::schema::Schema,
     server::simpledb::SimpleDB,
-    tx::transaction::Transaction,
+    tx::{concurrency::isolation::IsolationLevel, transaction::Transaction},
 };
 
 pub struct EmbeddedConnection {
     db: SimpleDB,
     current_tx: Arc<Mutex<Transaction>>,
+    // my own extends
+    block: TxBlock,
+    // my own extends
+    autocommit: bool,
 }
 
 impl EmbeddedConnection {
@@ -26,15 +31,18 @@ impl EmbeddedConnection {
         Self {
             db,
             current_tx: Arc::new(Mutex::new(tx)),
+            block: TxBlock::None,
+            autocommit: true,
         }
     }
     pub fn commit(&mut self) -> Result<()> {
+        self.block = TxBlock::None;
         if self.current_tx.lock().unwrap().commit().is_err() {
             return Err(From::from(ConnectionError::CommitFailed));
         }
         self.dump_statistics();
 
-        if let Ok(tx) = self.db.new_tx() {
+        if let Ok(tx) = self.new_tx() {
             self.current_tx = Arc::new(Mutex::new(tx));
             return Ok(());
         }
@@ -42,12 +50,13 @@ impl EmbeddedConnection {
         Err(From::from(ConnectionError::StartNewTransactionFailed))
     }
     pub fn rollback(&mut self) -> Result<()> {
+        self.block = TxBlock::None;
         if self.current_tx.lock().unwrap().rollback().is_err() {
             return Err(From::from(ConnectionError::RollbackFailed));
         }
         self.dump_statistics();
 
-        if let Ok(tx) = self.db.new_tx() {
+        if let Ok(tx) = self.new_tx() {
             self.current_tx = Arc::new(Mutex::new(tx));
 
             return Ok(());
@@ -55,6 +64,97 @@ impl EmbeddedConnection {
 
         Err(From::from(ConnectionError::StartNewTransactionFailed))
     }
+    // my own extends
+    // turning autocommit on commits the running transaction, as if it were a statement.
+    pub fn set_autocommit(&mut self, autocommit: bool) -> Result<()> {
+        let turned_on = autocommit && !self.autocommit;
+        self.autocommit = autocommit;
+        if turned_on {
+            return self.finish_statement();
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn autocommit(&self) -> bool {
+        self.autocommit
+    }
+    // my own extends
+    pub fn tx_block(&self) -> TxBlock {
+        self.block
+    }
+    // my own extends
+    // BEGIN, COMMIT, ROLLBACK, SAVEPOINT and ROLLBACK TO SAVEPOINT.
+    // COMMIT of a failed block only ends it, its transaction has already been rolled back.
+    pub fn execute_tcl(&mut self, tcl: TCL) -> Result<()> {
+        match (tcl, self.block) {
+            (TCL::Commit | TCL::Rollback, TxBlock::Failed) => {
+                self.block = TxBlock::None;
+                Ok(())
+            }
+            (_, TxBlock::Failed) => Err(From::from(ConnectionError::TransactionAborted)),
+            (TCL::Begin, _) => {
+                self.block = TxBlock::Open;
+                Ok(())
+            }
+            (TCL::Commit, _) => self.commit(),
+            (TCL::Rollback, _) => self.rollback(),
+            (TCL::Savepoint(name), _) => self.current_tx.lock().unwrap().savepoint(&name),
+            (TCL::RollbackToSavepoint(name), _) => {
+                self.current_tx.lock().unwrap().rollback_to_savepoint(&name)
+            }
+            (TCL::SetIsolationLevel(level), _) => {
+                self.set_isolation_level(level);
+                Ok(())
+            }
+        }
+    }
+    // my own extends
+    // a statement can run unless the block has failed.
+    pub fn check_tx_block(&self) -> Result<()> {
+        if self.block == TxBlock::Failed {
+            return Err(From::from(ConnectionError::TransactionAborted));
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // in autocommit mode, a statement that succeeded is committed, unless it is in a transaction block.
+    // otherwise the transaction goes on until COMMIT or ROLLBACK.
+    pub fn finish_statement(&mut self) -> Result<()> {
+        if self.autocommit && self.block == TxBlock::None {
+            return self.commit();
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // a statement that failed rolls the transaction back, and with it the whole block.
+    pub fn fail_statement(&mut self) -> Result<()> {
+        let block = self.block;
+        self.rollback()?;
+        if block != TxBlock::None {
+            self.block = TxBlock::Failed;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // the level is kept by the connection: the next transactions start with it, too.
+    pub fn set_isolation_level(&mut self, level: IsolationLevel) {
+        self.current_tx.lock().unwrap().set_isolation_level(level);
+    }
+    // my own extends
+    pub fn isolation_level(&self) -> IsolationLevel {
+        self.current_tx.lock().unwrap().isolation_level()
+    }
+    // my own extends
+    fn new_tx(&self) -> Result<Transaction> {
+        let mut tx = self.db.new_tx()?;
+        tx.set_isolation_level(self.isolation_level());
+
+        Ok(tx)
+    }
     pub fn get_transaction(&self) -> Arc<Mutex<Transaction>> {
         Arc::clone(&self.current_tx)
     }
@@ -127,8 +227,12 @@ impl<'a> ConnectionAdapter<'a> for EmbeddedConnection {
             .and_then(|planner| Ok(EmbeddedStatement::new(self, planner, sql)))
             .or_else(|_| Err(From::from(ConnectionError::CreateStatementFailed)))
     }
+    // a transaction block that is still open is not committed.
     fn close(&mut self) -> Result<Self::Res> {
-        self.commit()
-            .or_else(|_| Err(From::from(ConnectionError::CloseFailed)))
+        let res = match self.block {
+            TxBlock::None => self.commit(),
+            _ => self.rollback(),
+        };
+        res.map_err(|_| From::from(ConnectionError::CloseFailed))
     }
 }
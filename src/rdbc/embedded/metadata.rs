@@ -30,6 +30,13 @@ impl ResultSetMetaDataAdapter for EmbeddedMetaData {
                 FieldType::VARCHAR => Some(DataType::Varchar),
                 FieldType::BOOL => Some(DataType::Bool),
                 FieldType::DATE => Some(DataType::Date),
+                FieldType::TEXT => Some(DataType::Text),
+                FieldType::BLOB => Some(DataType::Blob),
+                FieldType::BIGINT => Some(DataType::BigInt),
+                FieldType::DOUBLE => Some(DataType::Double),
+                FieldType::DECIMAL => Some(DataType::Decimal),
+                FieldType::TIMESTAMP => Some(DataType::Timestamp),
+                FieldType::TIME => Some(DataType::Time),
             };
         }
 
@@ -41,8 +48,15 @@ impl ResultSetMetaDataAdapter for EmbeddedMetaData {
                 FieldType::SMALLINT => 6, // WANTFIX
                 FieldType::INTEGER => 6,  // WANTFIX
                 FieldType::VARCHAR => self.sch.length(fldname),
-                FieldType::BOOL => 5,  // length of false
-                FieldType::DATE => 10, // length of YYYY-MM-DD
+                FieldType::BOOL => 5,    // length of false
+                FieldType::DATE => 10,   // length of YYYY-MM-DD
+                FieldType::TEXT => 20,   // WANTFIX
+                FieldType::BLOB => 20,   // WANTFIX
+                FieldType::BIGINT => 20, // length of i64::MIN
+                FieldType::DOUBLE => 20, // WANTFIX
+                FieldType::DECIMAL => self.sch.length(fldname) + 2, // sign and point
+                FieldType::TIMESTAMP => 26, // length of YYYY-MM-DD hh:mm:ss.ffffff
+                FieldType::TIME => 15,   // length of hh:mm:ss.ffffff
             };
 
             return Some(max(fldname.len(), fldlength) + 1);
@@ -1,15 +1,13 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
 use std::sync::{Arc, Mutex};
 
 use super::{connection::EmbeddedConnection, metadata::EmbeddedMetaData};
 use crate::{
     plan::plan::Plan,
-    query::scan::Scan,
-    rdbc::{
-        connectionadapter::ConnectionAdapter,
-        resultsetadapter::{ResultSetAdapter, ResultSetError},
-    },
+    query::{constant::Constant, scan::Scan},
+    rdbc::resultsetadapter::{ResultSetAdapter, ResultSetError},
     record::schema::Schema,
 };
 
@@ -28,6 +26,18 @@ impl<'a> EmbeddedResultSet<'a> {
 
         Err(From::from(ResultSetError::ScanFailed))
     }
+    // my own extends
+    fn get_val_as<T>(&mut self, fldname: &str, conv: impl Fn(&Constant) -> Result<T>) -> Result<T> {
+        let val = self.s.lock().unwrap().get_val(fldname);
+        match val.and_then(|v| conv(&v)) {
+            Ok(v) => Ok(v),
+            Err(_) => self.conn.fail_statement().and_then(|_| {
+                Err(From::from(ResultSetError::UnknownField(
+                    fldname.to_string(),
+                )))
+            }),
+        }
+    }
 }
 
 impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
@@ -38,6 +48,12 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     type StringValue = String;
     type BoolValue = bool;
     type DateValue = NaiveDate;
+    type BlobValue = Vec<u8>;
+    type Int64Value = i64;
+    type Float64Value = f64;
+    type DecimalValue = Decimal;
+    type TimestampValue = NaiveDateTime;
+    type TimeValue = NaiveTime;
     type Res = ();
 
     fn next(&self) -> Self::Next {
@@ -45,7 +61,7 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     }
     fn get_i16(&mut self, fldname: &str) -> Result<Self::Int16Value> {
         self.s.lock().unwrap().get_i16(fldname).or_else(|_| {
-            self.conn.rollback().and_then(|_| {
+            self.conn.fail_statement().and_then(|_| {
                 Err(From::from(ResultSetError::UnknownField(
                     fldname.to_string(),
                 )))
@@ -54,7 +70,7 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     }
     fn get_i32(&mut self, fldname: &str) -> Result<Self::Int32Value> {
         self.s.lock().unwrap().get_i32(fldname).or_else(|_| {
-            self.conn.rollback().and_then(|_| {
+            self.conn.fail_statement().and_then(|_| {
                 Err(From::from(ResultSetError::UnknownField(
                     fldname.to_string(),
                 )))
@@ -63,7 +79,7 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     }
     fn get_string(&mut self, fldname: &str) -> Result<Self::StringValue> {
         self.s.lock().unwrap().get_string(fldname).or_else(|_| {
-            self.conn.rollback().and_then(|_| {
+            self.conn.fail_statement().and_then(|_| {
                 Err(From::from(ResultSetError::UnknownField(
                     fldname.to_string(),
                 )))
@@ -72,7 +88,7 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     }
     fn get_bool(&mut self, fldname: &str) -> Result<Self::BoolValue> {
         self.s.lock().unwrap().get_bool(fldname).or_else(|_| {
-            self.conn.rollback().and_then(|_| {
+            self.conn.fail_statement().and_then(|_| {
                 Err(From::from(ResultSetError::UnknownField(
                     fldname.to_string(),
                 )))
@@ -81,19 +97,48 @@ impl<'a> ResultSetAdapter for EmbeddedResultSet<'a> {
     }
     fn get_date(&mut self, fldname: &str) -> Result<Self::DateValue> {
         self.s.lock().unwrap().get_date(fldname).or_else(|_| {
-            self.conn.rollback().and_then(|_| {
+            self.conn.fail_statement().and_then(|_| {
                 Err(From::from(ResultSetError::UnknownField(
                     fldname.to_string(),
                 )))
             })
         })
     }
+    fn get_blob(&mut self, fldname: &str) -> Result<Self::BlobValue> {
+        // scans have no getter for a BLOB, so take it out of the constant
+        self.get_val_as(fldname, |v| Ok(v.as_blob()?.to_vec()))
+    }
+    fn get_i64(&mut self, fldname: &str) -> Result<Self::Int64Value> {
+        self.get_val_as(fldname, Constant::as_i64)
+    }
+    fn get_f64(&mut self, fldname: &str) -> Result<Self::Float64Value> {
+        self.get_val_as(fldname, Constant::as_f64)
+    }
+    fn get_decimal(&mut self, fldname: &str) -> Result<Self::DecimalValue> {
+        self.get_val_as(fldname, Constant::as_decimal)
+    }
+    fn get_timestamp(&mut self, fldname: &str) -> Result<Self::TimestampValue> {
+        self.get_val_as(fldname, Constant::as_timestamp)
+    }
+    fn get_time(&mut self, fldname: &str) -> Result<Self::TimeValue> {
+        self.get_val_as(fldname, Constant::as_time)
+    }
+    fn is_null(&mut self, fldname: &str) -> Result<Self::BoolValue> {
+        match self.s.lock().unwrap().get_val(fldname) {
+            Ok(val) => Ok(val.is_null()),
+            Err(_) => self.conn.fail_statement().and_then(|_| {
+                Err(From::from(ResultSetError::UnknownField(
+                    fldname.to_string(),
+                )))
+            }),
+        }
+    }
     fn get_meta_data(&self) -> Result<Self::Meta> {
         Ok(EmbeddedMetaData::new(Arc::clone(&self.sch)))
     }
     fn close(&mut self) -> Result<Self::Res> {
         match self.s.lock().unwrap().close() {
-            Ok(_) => self.conn.close(),
+            Ok(_) => self.conn.finish_statement(),
             Err(_) => Err(From::from(ResultSetError::CloseFailed)),
         }
     }
@@ -4,7 +4,6 @@ use super::connection::EmbeddedConnection;
 use super::planrepr::EmbeddedPlanRepr;
 use super::resultset::EmbeddedResultSet;
 use crate::plan::planner::Planner;
-use crate::rdbc::connectionadapter::ConnectionAdapter;
 use crate::rdbc::statementadapter::{StatementAdapter, StatementError};
 
 pub struct EmbeddedStatement<'a> {
@@ -25,12 +24,13 @@ impl<'a> EmbeddedStatement<'a> {
         &self.sql
     }
     pub fn explain_plan(&mut self) -> Result<EmbeddedPlanRepr> {
+        self.conn.check_tx_block()?;
         let tx = self.conn.get_transaction();
         match self.planner.create_query_plan(&self.sql, tx) {
             Ok(pln) => Ok(EmbeddedPlanRepr::new(pln.repr())),
             Err(_) => self
                 .conn
-                .rollback()
+                .fail_statement()
                 .and_then(|_| Err(From::from(StatementError::RuntimeError))),
         }
     }
@@ -42,26 +42,32 @@ impl<'a> StatementAdapter<'a> for EmbeddedStatement<'a> {
     type Res = ();
 
     fn execute_query(&'a mut self) -> Result<Self::Set> {
+        self.conn.check_tx_block()?;
         let tx = self.conn.get_transaction();
         match self.planner.create_query_plan(&self.sql, tx) {
             Ok(pln) => EmbeddedResultSet::new(pln, &mut self.conn),
             Err(_) => self
                 .conn
-                .rollback()
+                .fail_statement()
                 .and_then(|_| Err(From::from(StatementError::RuntimeError))),
         }
     }
     fn execute_update(&mut self) -> Result<Self::Aeffected> {
+        // my own extends
+        if let Some(tcl) = self.planner.transaction_control(&self.sql) {
+            return self.conn.execute_tcl(tcl).and(Ok(0));
+        }
+        self.conn.check_tx_block()?;
         let tx = self.conn.get_transaction();
         match self.planner.execute_update(&self.sql, tx) {
-            Ok(affected) => self.conn.commit().and_then(|_| Ok(affected)),
+            Ok(affected) => self.conn.finish_statement().map(|_| affected),
             Err(_) => self
                 .conn
-                .rollback()
+                .fail_statement()
                 .and_then(|_| Err(From::from(StatementError::RuntimeError))),
         }
     }
     fn close(&mut self) -> Result<Self::Res> {
-        self.conn.close()
+        self.conn.finish_statement()
     }
 }
@@ -1,3 +1,4 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -20,6 +21,10 @@ impl Schema {
     pub fn length(&self, fldname: &str) -> usize {
         self.info.get(fldname).unwrap().length
     }
+    // my own extends
+    pub fn scale(&self, fldname: &str) -> usize {
+        self.info.get(fldname).unwrap().scale
+    }
     pub fn add_field(&mut self, fldname: &str) {
         self.fields.push(fldname.to_string());
     }
@@ -54,13 +59,32 @@ impl<'a> From<remote_capnp::schema::Reader<'a>> for Schema {
 impl From<Schema> for record::schema::Schema {
     fn from(sch: Schema) -> Self {
         let mut result = Self::new();
-        for (fldname, FieldInfo { fld_type, length }) in sch.info.into_iter() {
+        for (
+            fldname,
+            FieldInfo {
+                fld_type,
+                length,
+                nullable,
+                scale,
+            },
+        ) in sch.info.into_iter()
+        {
             match fld_type {
                 FieldType::SMALLINT => result.add_i16_field(&fldname),
                 FieldType::INTEGER => result.add_i32_field(&fldname),
                 FieldType::VARCHAR => result.add_string_field(&fldname, length),
                 FieldType::BOOL => result.add_bool_field(&fldname),
                 FieldType::DATE => result.add_date_field(&fldname),
+                FieldType::TEXT => result.add_text_field(&fldname),
+                FieldType::BLOB => result.add_blob_field(&fldname),
+                FieldType::BIGINT => result.add_i64_field(&fldname),
+                FieldType::DOUBLE => result.add_f64_field(&fldname),
+                FieldType::DECIMAL => result.add_decimal_field(&fldname, length, scale),
+                FieldType::TIMESTAMP => result.add_timestamp_field(&fldname),
+                FieldType::TIME => result.add_time_field(&fldname),
+            }
+            if !nullable {
+                result.set_not_null(&fldname);
             }
         }
         result
@@ -71,18 +95,25 @@ impl From<Schema> for record::schema::Schema {
 pub struct FieldInfo {
     fld_type: FieldType,
     length: usize,
+    nullable: bool,
+    // my own extends
+    scale: usize,
 }
 impl FieldInfo {
     pub fn new_int32() -> Self {
         Self {
             fld_type: FieldType::INTEGER,
             length: 0,
+            nullable: true,
+            scale: 0,
         }
     }
     pub fn new_string(length: usize) -> Self {
         Self {
             fld_type: FieldType::VARCHAR,
             length,
+            nullable: true,
+            scale: 0,
         }
     }
 }
@@ -91,6 +122,8 @@ impl<'a> From<remote_capnp::field_info::Reader<'a>> for FieldInfo {
         Self {
             fld_type: fi.get_type().unwrap().into(),
             length: fi.get_length() as usize,
+            nullable: fi.get_nullable(),
+            scale: fi.get_scale() as usize,
         }
     }
 }
@@ -99,6 +132,8 @@ impl From<FieldInfo> for record::schema::FieldInfo {
         Self {
             fld_type: record::schema::FieldType::from(fi.fld_type),
             length: fi.length,
+            nullable: fi.nullable,
+            scale: fi.scale,
         }
     }
 }
@@ -110,6 +145,14 @@ pub enum FieldType {
     VARCHAR,
     BOOL,
     DATE,
+    // my own extends
+    TEXT,
+    BLOB,
+    BIGINT,
+    DOUBLE,
+    DECIMAL,
+    TIMESTAMP,
+    TIME,
 }
 impl<'a> From<remote_capnp::FieldType> for FieldType {
     fn from(ft: remote_capnp::FieldType) -> Self {
@@ -119,6 +162,13 @@ impl<'a> From<remote_capnp::FieldType> for FieldType {
             remote_capnp::FieldType::Varchar => Self::VARCHAR,
             remote_capnp::FieldType::Bool => Self::BOOL,
             remote_capnp::FieldType::Date => Self::DATE,
+            remote_capnp::FieldType::Text => Self::TEXT,
+            remote_capnp::FieldType::Blob => Self::BLOB,
+            remote_capnp::FieldType::BigInt => Self::BIGINT,
+            remote_capnp::FieldType::Double => Self::DOUBLE,
+            remote_capnp::FieldType::Decimal => Self::DECIMAL,
+            remote_capnp::FieldType::Timestamp => Self::TIMESTAMP,
+            remote_capnp::FieldType::Time => Self::TIME,
         }
     }
 }
@@ -130,10 +180,39 @@ impl From<FieldType> for record::schema::FieldType {
             FieldType::VARCHAR => Self::VARCHAR,
             FieldType::BOOL => Self::BOOL,
             FieldType::DATE => Self::DATE,
+            FieldType::TEXT => Self::TEXT,
+            FieldType::BLOB => Self::BLOB,
+            FieldType::BIGINT => Self::BIGINT,
+            FieldType::DOUBLE => Self::DOUBLE,
+            FieldType::DECIMAL => Self::DECIMAL,
+            FieldType::TIMESTAMP => Self::TIMESTAMP,
+            FieldType::TIME => Self::TIME,
         }
     }
 }
 
+// my own extends
+impl<'a> From<remote_capnp::time::Reader<'a>> for NaiveTime {
+    fn from(t: remote_capnp::time::Reader<'a>) -> Self {
+        let hour = t.get_hour() as u32;
+        let minute = t.get_minute() as u32;
+        let second = t.get_second() as u32;
+        let micro = t.get_microsecond();
+        NaiveTime::from_hms_micro_opt(hour, minute, second, micro).unwrap()
+    }
+}
+// my own extends
+impl<'a> From<remote_capnp::timestamp::Reader<'a>> for NaiveDateTime {
+    fn from(ts: remote_capnp::timestamp::Reader<'a>) -> Self {
+        let d = ts.get_date().unwrap();
+        let year = d.get_year() as i32;
+        let month = d.get_month() as u32;
+        let day = d.get_day() as u32;
+        let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        date.and_time(NaiveTime::from(ts.get_time().unwrap()))
+    }
+}
+
 pub struct IndexInfo {
     fldname: String,
     idxname: String,
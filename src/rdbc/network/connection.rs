@@ -6,6 +6,7 @@ use crate::{
     rdbc::{connectionadapter::ConnectionAdapter, model::IndexInfo},
     record::schema::{FieldType, Schema},
     remote_capnp::{self, remote_connection, tx_box},
+    tx::concurrency::isolation::IsolationLevel,
 };
 
 pub struct NetworkConnection {
@@ -29,6 +30,27 @@ impl NetworkConnection {
 
         Ok(tx_num)
     }
+    // my own extends
+    pub async fn set_isolation_level(&mut self, level: IsolationLevel) -> Result<()> {
+        let mut request = self.conn.set_isolation_level_request();
+        request.get().set_level(match level {
+            IsolationLevel::ReadUncommitted => remote_capnp::IsolationLevel::ReadUncommitted,
+            IsolationLevel::ReadCommitted => remote_capnp::IsolationLevel::ReadCommitted,
+            IsolationLevel::RepeatableRead => remote_capnp::IsolationLevel::RepeatableRead,
+            IsolationLevel::Serializable => remote_capnp::IsolationLevel::Serializable,
+        });
+        request.send().promise.await?;
+
+        Ok(())
+    }
+    // my own extends
+    pub async fn set_autocommit(&mut self, autocommit: bool) -> Result<()> {
+        let mut request = self.conn.set_autocommit_request();
+        request.get().set_autocommit(autocommit);
+        request.send().promise.await?;
+
+        Ok(())
+    }
     pub async fn get_table_schema(&self, tblname: &str) -> Result<Arc<Schema>> {
         let mut schema = Schema::new();
 
@@ -38,11 +60,13 @@ impl NetworkConnection {
         let sch = reply.get()?.get_sch()?;
 
         let mut map = HashMap::new();
+        let mut scales = HashMap::new();
         let entries = sch.get_info()?.get_entries()?;
         for i in 0..entries.len() {
             let entry = entries.get(i as u32);
             let fldname = entry.get_key()?.to_str().unwrap();
             let val = entry.get_value()?;
+            scales.insert(fldname, val.get_scale());
             match val.get_type()? {
                 remote_capnp::FieldType::SmallInt => {
                     map.insert(fldname, (FieldType::SMALLINT, val.get_length()));
@@ -59,6 +83,27 @@ impl NetworkConnection {
                 remote_capnp::FieldType::Date => {
                     map.insert(fldname, (FieldType::DATE, val.get_length()));
                 }
+                remote_capnp::FieldType::Text => {
+                    map.insert(fldname, (FieldType::TEXT, val.get_length()));
+                }
+                remote_capnp::FieldType::Blob => {
+                    map.insert(fldname, (FieldType::BLOB, val.get_length()));
+                }
+                remote_capnp::FieldType::BigInt => {
+                    map.insert(fldname, (FieldType::BIGINT, val.get_length()));
+                }
+                remote_capnp::FieldType::Double => {
+                    map.insert(fldname, (FieldType::DOUBLE, val.get_length()));
+                }
+                remote_capnp::FieldType::Decimal => {
+                    map.insert(fldname, (FieldType::DECIMAL, val.get_length()));
+                }
+                remote_capnp::FieldType::Timestamp => {
+                    map.insert(fldname, (FieldType::TIMESTAMP, val.get_length()));
+                }
+                remote_capnp::FieldType::Time => {
+                    map.insert(fldname, (FieldType::TIME, val.get_length()));
+                }
             }
         }
         let fields = sch.get_fields()?;
@@ -67,6 +112,9 @@ impl NetworkConnection {
             if let Some((t, l)) = map.get(fldname) {
                 schema.add_field(fldname, t.clone(), *l as usize);
             }
+            if let Some(scale) = scales.get(fldname) {
+                schema.set_scale(fldname, *scale as usize);
+            }
         }
 
         Ok(Arc::new(schema))
@@ -48,6 +48,13 @@ impl ResultSetMetaDataAdapter for NetworkResultSetMetaData {
                 FieldType::VARCHAR => return Some(DataType::Varchar),
                 FieldType::BOOL => return Some(DataType::Bool),
                 FieldType::DATE => return Some(DataType::Date),
+                FieldType::TEXT => return Some(DataType::Text),
+                FieldType::BLOB => return Some(DataType::Blob),
+                FieldType::BIGINT => return Some(DataType::BigInt),
+                FieldType::DOUBLE => return Some(DataType::Double),
+                FieldType::DECIMAL => return Some(DataType::Decimal),
+                FieldType::TIMESTAMP => return Some(DataType::Timestamp),
+                FieldType::TIME => return Some(DataType::Time),
             }
         }
 
@@ -59,8 +66,15 @@ impl ResultSetMetaDataAdapter for NetworkResultSetMetaData {
                 FieldType::SMALLINT => 6, // WANTFIX
                 FieldType::INTEGER => 6,  // WANTFIX
                 FieldType::VARCHAR => self.sch.length(fldname),
-                FieldType::BOOL => 5,  // length of false
-                FieldType::DATE => 10, // length of YYYY-MM-DD
+                FieldType::BOOL => 5,    // length of false
+                FieldType::DATE => 10,   // length of YYYY-MM-DD
+                FieldType::TEXT => 20,   // WANTFIX
+                FieldType::BLOB => 20,   // WANTFIX
+                FieldType::BIGINT => 20, // length of i64::MIN
+                FieldType::DOUBLE => 20, // WANTFIX
+                FieldType::DECIMAL => self.sch.length(fldname) + 2, // sign and point
+                FieldType::TIMESTAMP => 26, // length of YYYY-MM-DD hh:mm:ss.ffffff
+                FieldType::TIME => 15,   // length of hh:mm:ss.ffffff
             };
 
             return Some(max(fldname.len(), fldlength) + 1);
@@ -1,16 +1,64 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use itertools::Itertools;
-use std::sync::Arc;
+use rust_decimal::Decimal;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::Arc,
+};
 
-use crate::{query, remote_capnp::remote_statement, repr, repr::planrepr::PlanRepr};
+use crate::{
+    index,
+    materialize::recordcomparator::SortOrder,
+    query,
+    query::{expression::ArithOp, join::JoinType, scalarfn::Function, term::Operator},
+    record::schema::FieldType,
+    remote_capnp,
+    remote_capnp::remote_statement,
+    repr,
+    repr::planrepr::PlanRepr,
+};
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Clone)]
 pub enum Constant {
     I16(i16),
     I32(i32),
     String(String),
     Bool(bool),
     Date(NaiveDate),
+    Null,
+    // my own extends
+    Blob(Vec<u8>),
+    I64(i64),
+    F64(f64),
+    Decimal(Decimal),
+    Timestamp(NaiveDateTime),
+    Time(NaiveTime),
+}
+// my own extends
+// numbers are compared by value across their types, as in query::constant::Constant.
+impl PartialEq for Constant {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Constant {}
+impl PartialOrd for Constant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Constant {
+    fn cmp(&self, other: &Self) -> Ordering {
+        query::constant::Constant::from(self.clone())
+            .cmp(&query::constant::Constant::from(other.clone()))
+    }
+}
+impl Hash for Constant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        query::constant::Constant::from(self.clone()).hash(state);
+    }
 }
 impl<'a> From<remote_statement::constant::Reader<'a>> for Constant {
     fn from(c: remote_statement::constant::Reader<'a>) -> Self {
@@ -26,6 +74,18 @@ impl<'a> From<remote_statement::constant::Reader<'a>> for Constant {
                 let day = v.get_day() as u32;
                 Self::Date(NaiveDate::from_ymd_opt(year, month, day).unwrap())
             }
+            remote_statement::constant::Null(_) => Self::Null,
+            remote_statement::constant::Blob(v) => Self::Blob(v.unwrap().to_vec()),
+            remote_statement::constant::Int64(v) => Self::I64(v),
+            remote_statement::constant::Float64(v) => Self::F64(v),
+            remote_statement::constant::Decimal(v) => {
+                let v = v.unwrap().to_str().unwrap();
+                Self::Decimal(Decimal::from_str(v).unwrap())
+            }
+            remote_statement::constant::Timestamp(v) => {
+                Self::Timestamp(NaiveDateTime::from(v.unwrap()))
+            }
+            remote_statement::constant::Time(v) => Self::Time(NaiveTime::from(v.unwrap())),
         }
     }
 }
@@ -37,6 +97,38 @@ impl From<Constant> for query::constant::Constant {
             Constant::String(s) => Self::String(s),
             Constant::Bool(v) => Self::Bool(v),
             Constant::Date(v) => Self::Date(v),
+            Constant::Null => Self::Null,
+            Constant::Blob(v) => Self::Blob(v),
+            Constant::I64(v) => Self::I64(v),
+            Constant::F64(v) => Self::F64(v),
+            Constant::Decimal(v) => Self::Decimal(v),
+            Constant::Timestamp(v) => Self::Timestamp(v),
+            Constant::Time(v) => Self::Time(v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RangeBound {
+    Included(Constant),
+    Excluded(Constant),
+    Unbounded,
+}
+impl<'a> From<remote_statement::range_bound::Reader<'a>> for RangeBound {
+    fn from(b: remote_statement::range_bound::Reader<'a>) -> Self {
+        match b.which().unwrap() {
+            remote_statement::range_bound::Included(v) => Self::Included(v.unwrap().into()),
+            remote_statement::range_bound::Excluded(v) => Self::Excluded(v.unwrap().into()),
+            remote_statement::range_bound::Unbounded(_) => Self::Unbounded,
+        }
+    }
+}
+impl From<RangeBound> for index::RangeBound {
+    fn from(b: RangeBound) -> Self {
+        match b {
+            RangeBound::Included(v) => Self::Included(v.into()),
+            RangeBound::Excluded(v) => Self::Excluded(v.into()),
+            RangeBound::Unbounded => Self::Unbounded,
         }
     }
 }
@@ -44,6 +136,7 @@ impl From<Constant> for query::constant::Constant {
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Predicate {
     terms: Vec<Term>,
+    disjunctions: Vec<Vec<Predicate>>,
 }
 impl<'a> From<remote_statement::predicate::Reader<'a>> for Predicate {
     fn from(pred: remote_statement::predicate::Reader<'a>) -> Self {
@@ -53,7 +146,22 @@ impl<'a> From<remote_statement::predicate::Reader<'a>> for Predicate {
             .into_iter()
             .map(|t| Term::from(t))
             .collect_vec();
-        Self { terms }
+        let disjunctions = pred
+            .get_disjunctions()
+            .unwrap()
+            .into_iter()
+            .map(|d| {
+                d.get_preds()
+                    .unwrap()
+                    .into_iter()
+                    .map(|p| Predicate::from(p))
+                    .collect_vec()
+            })
+            .collect_vec();
+        Self {
+            terms,
+            disjunctions,
+        }
     }
 }
 impl From<Predicate> for query::predicate::Predicate {
@@ -61,30 +169,50 @@ impl From<Predicate> for query::predicate::Predicate {
         let terms = pred.terms.into_iter().map(|t| t.into()).collect_vec();
         let mut result = Self::new_empty();
         result.init_with_terms(terms);
+        for d in pred.disjunctions.into_iter() {
+            let preds = d.into_iter().map(|p| p.into()).collect_vec();
+            result.conjoin_with(&mut Self::new_disjunction(preds));
+        }
         result
     }
 }
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Term {
     lhs: Expression,
+    op: Operator,
     rhs: Expression,
 }
 impl<'a> From<remote_statement::term::Reader<'a>> for Term {
     fn from(t: remote_statement::term::Reader<'a>) -> Self {
         let lhs = Expression::from(t.get_lhs().unwrap());
         let rhs = Expression::from(t.get_rhs().unwrap());
-        Self { lhs, rhs }
+        let op = match t.get_op().unwrap() {
+            remote_statement::Operator::Eq => Operator::Eq,
+            remote_statement::Operator::Ne => Operator::Ne,
+            remote_statement::Operator::Lt => Operator::Lt,
+            remote_statement::Operator::Le => Operator::Le,
+            remote_statement::Operator::Gt => Operator::Gt,
+            remote_statement::Operator::Ge => Operator::Ge,
+            remote_statement::Operator::IsNull => Operator::IsNull,
+            remote_statement::Operator::IsNotNull => Operator::IsNotNull,
+            remote_statement::Operator::Like => Operator::Like,
+            remote_statement::Operator::NotLike => Operator::NotLike,
+        };
+        Self { lhs, op, rhs }
     }
 }
 impl From<Term> for query::term::Term {
     fn from(t: Term) -> Self {
-        Self::new(t.lhs.into(), t.rhs.into())
+        Self::new_with(t.lhs.into(), t.op, t.rhs.into())
     }
 }
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Expression {
     Val(Constant),
     Fldname(String),
+    Binary(Box<Expression>, ArithOp, Box<Expression>),
+    Neg(Box<Expression>),
+    Call(Function, Vec<Expression>),
 }
 impl<'a> From<remote_statement::expression::Reader<'a>> for Expression {
     fn from(expr: remote_statement::expression::Reader<'a>) -> Self {
@@ -97,6 +225,60 @@ impl<'a> From<remote_statement::expression::Reader<'a>> for Expression {
                 let s = s.unwrap().to_string().unwrap();
                 Self::Fldname(s)
             }
+            remote_statement::expression::Binary(b) => {
+                let b = b.unwrap();
+                let lhs = Expression::from(b.get_lhs().unwrap());
+                let rhs = Expression::from(b.get_rhs().unwrap());
+                let op = match b.get_op().unwrap() {
+                    remote_statement::ArithOp::Add => ArithOp::Add,
+                    remote_statement::ArithOp::Sub => ArithOp::Sub,
+                    remote_statement::ArithOp::Mul => ArithOp::Mul,
+                    remote_statement::ArithOp::Div => ArithOp::Div,
+                    remote_statement::ArithOp::Mod => ArithOp::Mod,
+                    remote_statement::ArithOp::Concat => ArithOp::Concat,
+                };
+                Self::Binary(Box::new(lhs), op, Box::new(rhs))
+            }
+            remote_statement::expression::Neg(e) => {
+                let e = Expression::from(e.unwrap());
+                Self::Neg(Box::new(e))
+            }
+            remote_statement::expression::Call(c) => {
+                let c = c.unwrap();
+                let name = c.get_name().unwrap().to_string().unwrap();
+                let args = c
+                    .get_args()
+                    .unwrap()
+                    .into_iter()
+                    .map(Expression::from)
+                    .collect();
+                let func = match Function::from_name(&name) {
+                    Some(func) => func,
+                    None => {
+                        let t = c.get_cast_type().unwrap();
+                        let fld_type = match t.get_type().unwrap() {
+                            remote_capnp::FieldType::SmallInt => FieldType::SMALLINT,
+                            remote_capnp::FieldType::Integer => FieldType::INTEGER,
+                            remote_capnp::FieldType::Varchar => FieldType::VARCHAR,
+                            remote_capnp::FieldType::Bool => FieldType::BOOL,
+                            remote_capnp::FieldType::Date => FieldType::DATE,
+                            remote_capnp::FieldType::Text => FieldType::TEXT,
+                            remote_capnp::FieldType::Blob => FieldType::BLOB,
+                            remote_capnp::FieldType::BigInt => FieldType::BIGINT,
+                            remote_capnp::FieldType::Double => FieldType::DOUBLE,
+                            remote_capnp::FieldType::Decimal => FieldType::DECIMAL,
+                            remote_capnp::FieldType::Timestamp => FieldType::TIMESTAMP,
+                            remote_capnp::FieldType::Time => FieldType::TIME,
+                        };
+                        Function::Cast {
+                            fld_type,
+                            length: t.get_length() as usize,
+                            scale: t.get_scale() as usize,
+                        }
+                    }
+                };
+                Self::Call(func, args)
+            }
         }
     }
 }
@@ -105,22 +287,43 @@ impl From<Expression> for query::expression::Expression {
         match expr {
             Expression::Val(v) => Self::Val(v.into()),
             Expression::Fldname(s) => Self::Fldname(s),
+            Expression::Binary(lhs, op, rhs) => Self::new_binary((*lhs).into(), op, (*rhs).into()),
+            Expression::Neg(e) => Self::new_neg((*e).into()),
+            Expression::Call(func, args) => {
+                Self::new_call(func, args.into_iter().map(|arg| arg.into()).collect())
+            }
         }
     }
 }
 
+fn join_type(join_type: remote_statement::JoinType) -> JoinType {
+    match join_type {
+        remote_statement::JoinType::Inner => JoinType::Inner,
+        remote_statement::JoinType::Left => JoinType::Left,
+        remote_statement::JoinType::Right => JoinType::Right,
+        remote_statement::JoinType::Full => JoinType::Full,
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Operation {
     IndexJoinScan {
         idxname: String,
         idxfldname: String,
         joinfld: String,
+        join_type: JoinType,
     },
     IndexSelectScan {
         idxname: String,
         idxfldname: String,
         val: Constant,
     },
+    IndexRangeScan {
+        idxname: String,
+        idxfldname: String,
+        // the low and high bounds of the ranges
+        ranges: Vec<(RangeBound, RangeBound)>,
+    },
     GroupByScan {
         fields: Vec<String>,
         aggfns: Vec<(String, Constant)>,
@@ -129,12 +332,18 @@ pub enum Operation {
     MergeJoinScan {
         fldname1: String,
         fldname2: String,
+        join_type: JoinType,
     },
     SortScan {
         compflds: Vec<String>,
+        orders: Vec<SortOrder>,
     },
     MultibufferProductScan,
     ProductScan,
+    ProductJoinScan {
+        join_type: JoinType,
+        pred: Predicate,
+    },
     ProjectScan,
     SelectScan {
         pred: Predicate,
@@ -151,10 +360,12 @@ impl<'a> From<remote_statement::plan_repr::operation::Reader<'a>> for Operation
                 let idxname = v.get_idxname().unwrap().to_string().unwrap();
                 let idxfldname = v.get_idxfldname().unwrap().to_string().unwrap();
                 let joinfld = v.get_joinfld().unwrap().to_string().unwrap();
+                let join_type = join_type(v.get_join_type().unwrap());
                 Self::IndexJoinScan {
                     idxname,
                     idxfldname,
                     joinfld,
+                    join_type,
                 }
             }
             remote_statement::plan_repr::operation::IndexSelectScan(v) => {
@@ -168,6 +379,26 @@ impl<'a> From<remote_statement::plan_repr::operation::Reader<'a>> for Operation
                     val,
                 }
             }
+            remote_statement::plan_repr::operation::IndexRangeScan(v) => {
+                let v = v.unwrap();
+                let idxname = v.get_idxname().unwrap().to_string().unwrap();
+                let idxfldname = v.get_idxfldname().unwrap().to_string().unwrap();
+                let ranges = v
+                    .get_ranges()
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| {
+                        let low = RangeBound::from(r.get_low().unwrap());
+                        let high = RangeBound::from(r.get_high().unwrap());
+                        (low, high)
+                    })
+                    .collect_vec();
+                Self::IndexRangeScan {
+                    idxname,
+                    idxfldname,
+                    ranges,
+                }
+            }
             remote_statement::plan_repr::operation::GroupByScan(v) => {
                 let v = v.unwrap();
                 let fields = v
@@ -193,7 +424,12 @@ impl<'a> From<remote_statement::plan_repr::operation::Reader<'a>> for Operation
                 let v = v.unwrap();
                 let fldname1 = v.get_fldname1().unwrap().to_string().unwrap();
                 let fldname2 = v.get_fldname2().unwrap().to_string().unwrap();
-                Self::MergeJoinScan { fldname1, fldname2 }
+                let join_type = join_type(v.get_join_type().unwrap());
+                Self::MergeJoinScan {
+                    fldname1,
+                    fldname2,
+                    join_type,
+                }
             }
             remote_statement::plan_repr::operation::SortScan(v) => {
                 let v = v.unwrap();
@@ -203,12 +439,27 @@ impl<'a> From<remote_statement::plan_repr::operation::Reader<'a>> for Operation
                     .into_iter()
                     .map(|s| s.unwrap().to_string().unwrap())
                     .collect_vec();
-                Self::SortScan { compflds }
+                let orders = v
+                    .get_orders()
+                    .unwrap()
+                    .into_iter()
+                    .map(|o| match o.unwrap() {
+                        remote_statement::SortOrder::Asc => SortOrder::Asc,
+                        remote_statement::SortOrder::Desc => SortOrder::Desc,
+                    })
+                    .collect_vec();
+                Self::SortScan { compflds, orders }
             }
             remote_statement::plan_repr::operation::MultibufferProductScan(_) => {
                 Self::MultibufferProductScan
             }
             remote_statement::plan_repr::operation::ProductScan(_) => Self::ProductScan,
+            remote_statement::plan_repr::operation::ProductJoinScan(v) => {
+                let v = v.unwrap();
+                let join_type = join_type(v.get_join_type().unwrap());
+                let pred = Predicate::from(v.get_pred().unwrap());
+                Self::ProductJoinScan { join_type, pred }
+            }
             remote_statement::plan_repr::operation::ProjectScan(_) => Self::ProjectScan,
             remote_statement::plan_repr::operation::SelectScan(v) => {
                 let v = v.unwrap();
@@ -230,10 +481,12 @@ impl From<Operation> for repr::planrepr::Operation {
                 idxname,
                 idxfldname,
                 joinfld,
+                join_type,
             } => Self::IndexJoinScan {
                 idxname,
                 idxfldname,
                 joinfld,
+                join_type,
             },
             Operation::IndexSelectScan {
                 idxname,
@@ -244,17 +497,39 @@ impl From<Operation> for repr::planrepr::Operation {
                 idxfldname,
                 val: val.into(),
             },
+            Operation::IndexRangeScan {
+                idxname,
+                idxfldname,
+                ranges,
+            } => Self::IndexRangeScan {
+                idxname,
+                idxfldname,
+                ranges: ranges
+                    .into_iter()
+                    .map(|(low, high)| index::SearchRange::new(low.into(), high.into()))
+                    .collect_vec(),
+            },
             Operation::GroupByScan { fields, aggfns } => Self::GroupByScan {
                 fields,
                 aggfns: aggfns.into_iter().map(|(s, v)| (s, v.into())).collect_vec(),
             },
             Operation::Materialize => Self::Materialize,
-            Operation::MergeJoinScan { fldname1, fldname2 } => {
-                Self::MergeJoinScan { fldname1, fldname2 }
-            }
-            Operation::SortScan { compflds } => Self::SortScan { compflds },
+            Operation::MergeJoinScan {
+                fldname1,
+                fldname2,
+                join_type,
+            } => Self::MergeJoinScan {
+                fldname1,
+                fldname2,
+                join_type,
+            },
+            Operation::SortScan { compflds, orders } => Self::SortScan { compflds, orders },
             Operation::MultibufferProductScan => Self::MultibufferProductScan,
             Operation::ProductScan => Self::ProductScan,
+            Operation::ProductJoinScan { join_type, pred } => Self::ProductJoinScan {
+                join_type,
+                pred: pred.into(),
+            },
             Operation::ProjectScan => Self::ProjectScan,
             Operation::SelectScan { pred } => Self::SelectScan { pred: pred.into() },
             Operation::TableScan { tblname } => Self::TableScan { tblname },
@@ -1,14 +1,18 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use log::trace;
-use std::collections::HashMap;
+use rust_decimal::Decimal;
+use std::{collections::HashMap, str::FromStr};
 
 use super::{connection::ResponseImpl, metadata::NetworkResultSetMetaData};
 use crate::{
     rdbc::{
         resultsetadapter::ResultSetAdapter, resultsetmetadataadapter::ResultSetMetaDataAdapter,
     },
-    remote_capnp::{bool_box, date_box, int16_box, int32_box, remote_result_set, string_box},
+    remote_capnp::{
+        blob_box, bool_box, date_box, decimal_box, float64_box, int16_box, int32_box, int64_box,
+        remote_result_set, string_box, time_box, timestamp_box,
+    },
 };
 
 pub struct NextImpl {
@@ -94,13 +98,108 @@ impl DateValueImpl {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+// my own extends
+pub struct BlobValueImpl {
+    client: blob_box::Client,
+}
+impl BlobValueImpl {
+    pub fn new(client: blob_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<Vec<u8>> {
+        let reply = self.client.read_request().send().promise.await?;
+        Ok(reply.get()?.get_val()?.to_vec())
+    }
+}
+
+// my own extends
+pub struct Int64ValueImpl {
+    client: int64_box::Client,
+}
+impl Int64ValueImpl {
+    pub fn new(client: int64_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<i64> {
+        let reply = self.client.read_request().send().promise.await?;
+        Ok(reply.get()?.get_val())
+    }
+}
+
+// my own extends
+pub struct Float64ValueImpl {
+    client: float64_box::Client,
+}
+impl Float64ValueImpl {
+    pub fn new(client: float64_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<f64> {
+        let reply = self.client.read_request().send().promise.await?;
+        Ok(reply.get()?.get_val())
+    }
+}
+
+// my own extends
+// a decimal is sent as text, so that it is not rounded.
+pub struct DecimalValueImpl {
+    client: decimal_box::Client,
+}
+impl DecimalValueImpl {
+    pub fn new(client: decimal_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<Decimal> {
+        let reply = self.client.read_request().send().promise.await?;
+        let val = reply.get()?.get_val()?.to_str()?;
+
+        Ok(Decimal::from_str(val)?)
+    }
+}
+
+// my own extends
+pub struct TimestampValueImpl {
+    client: timestamp_box::Client,
+}
+impl TimestampValueImpl {
+    pub fn new(client: timestamp_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<NaiveDateTime> {
+        let reply = self.client.read_request().send().promise.await?;
+        Ok(NaiveDateTime::from(reply.get()?.get_val()?))
+    }
+}
+
+// my own extends
+pub struct TimeValueImpl {
+    client: time_box::Client,
+}
+impl TimeValueImpl {
+    pub fn new(client: time_box::Client) -> Self {
+        Self { client }
+    }
+    pub async fn get_value(&self) -> Result<NaiveTime> {
+        let reply = self.client.read_request().send().promise.await?;
+        Ok(NaiveTime::from(reply.get()?.get_val()?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Value {
     Int16(i16),
     Int32(i32),
     String(String),
     Bool(bool),
     Date(NaiveDate),
+    Null,
+    // my own extends
+    Blob(Vec<u8>),
+    Int64(i64),
+    Float64(f64),
+    Decimal(Decimal),
+    Timestamp(NaiveDateTime),
+    Time(NaiveTime),
 }
 
 pub struct NetworkResultSet {
@@ -141,6 +240,27 @@ impl NetworkResultSet {
                 Some(Value::Date(v)) => {
                     result.insert(fldname, Value::Date(*v));
                 }
+                Some(Value::Null) => {
+                    result.insert(fldname, Value::Null);
+                }
+                Some(Value::Blob(v)) => {
+                    result.insert(fldname, Value::Blob(v.clone()));
+                }
+                Some(Value::Int64(v)) => {
+                    result.insert(fldname, Value::Int64(*v));
+                }
+                Some(Value::Float64(v)) => {
+                    result.insert(fldname, Value::Float64(*v));
+                }
+                Some(Value::Decimal(v)) => {
+                    result.insert(fldname, Value::Decimal(*v));
+                }
+                Some(Value::Timestamp(v)) => {
+                    result.insert(fldname, Value::Timestamp(*v));
+                }
+                Some(Value::Time(v)) => {
+                    result.insert(fldname, Value::Time(*v));
+                }
                 None => {
                     panic!("field missing: {}", fldname);
                 }
@@ -189,6 +309,27 @@ impl NetworkResultSet {
                     Some(Value::Date(v)) => {
                         result.insert(fldname, Value::Date(*v));
                     }
+                    Some(Value::Null) => {
+                        result.insert(fldname, Value::Null);
+                    }
+                    Some(Value::Blob(v)) => {
+                        result.insert(fldname, Value::Blob(v.clone()));
+                    }
+                    Some(Value::Int64(v)) => {
+                        result.insert(fldname, Value::Int64(*v));
+                    }
+                    Some(Value::Float64(v)) => {
+                        result.insert(fldname, Value::Float64(*v));
+                    }
+                    Some(Value::Decimal(v)) => {
+                        result.insert(fldname, Value::Decimal(*v));
+                    }
+                    Some(Value::Timestamp(v)) => {
+                        result.insert(fldname, Value::Timestamp(*v));
+                    }
+                    Some(Value::Time(v)) => {
+                        result.insert(fldname, Value::Time(*v));
+                    }
                     None => {
                         panic!("field missing: {} at index {}", fldname, i);
                     }
@@ -224,6 +365,18 @@ impl NetworkResultSet {
                     let day = v.get_day() as u32;
                     Value::Date(NaiveDate::from_ymd_opt(year, month, day).unwrap())
                 }
+                remote_result_set::value::Null(_) => Value::Null,
+                remote_result_set::value::Blob(v) => Value::Blob(v.unwrap().to_vec()),
+                remote_result_set::value::Int64(v) => Value::Int64(v),
+                remote_result_set::value::Float64(v) => Value::Float64(v),
+                remote_result_set::value::Decimal(v) => {
+                    let v = v.unwrap().to_str().unwrap();
+                    Value::Decimal(Decimal::from_str(v).expect("parse decimal"))
+                }
+                remote_result_set::value::Timestamp(v) => {
+                    Value::Timestamp(NaiveDateTime::from(v.unwrap()))
+                }
+                remote_result_set::value::Time(v) => Value::Time(NaiveTime::from(v.unwrap())),
             };
             result.insert(key, val);
         }
@@ -240,6 +393,12 @@ impl ResultSetAdapter for NetworkResultSet {
     type StringValue = StringValueImpl;
     type BoolValue = BoolValueImpl;
     type DateValue = DateValueImpl;
+    type BlobValue = BlobValueImpl;
+    type Int64Value = Int64ValueImpl;
+    type Float64Value = Float64ValueImpl;
+    type DecimalValue = DecimalValueImpl;
+    type TimestampValue = TimestampValueImpl;
+    type TimeValue = TimeValueImpl;
     type Res = ResponseImpl;
 
     fn next(&self) -> Self::Next {
@@ -282,6 +441,55 @@ impl ResultSetAdapter for NetworkResultSet {
 
         Ok(Self::DateValue::new(val))
     }
+    fn get_blob(&mut self, fldname: &str) -> Result<Self::BlobValue> {
+        let mut request = self.resultset.get_blob_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::BlobValue::new(val))
+    }
+    fn get_i64(&mut self, fldname: &str) -> Result<Self::Int64Value> {
+        let mut request = self.resultset.get_int64_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::Int64Value::new(val))
+    }
+    fn get_f64(&mut self, fldname: &str) -> Result<Self::Float64Value> {
+        let mut request = self.resultset.get_float64_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::Float64Value::new(val))
+    }
+    fn get_decimal(&mut self, fldname: &str) -> Result<Self::DecimalValue> {
+        let mut request = self.resultset.get_decimal_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::DecimalValue::new(val))
+    }
+    fn get_timestamp(&mut self, fldname: &str) -> Result<Self::TimestampValue> {
+        let mut request = self.resultset.get_timestamp_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::TimestampValue::new(val))
+    }
+    fn get_time(&mut self, fldname: &str) -> Result<Self::TimeValue> {
+        let mut request = self.resultset.get_time_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::TimeValue::new(val))
+    }
+    fn is_null(&mut self, fldname: &str) -> Result<Self::BoolValue> {
+        let mut request = self.resultset.is_null_request();
+        request.get().set_fldname(fldname);
+        let val = request.send().pipeline.get_val();
+
+        Ok(Self::BoolValue::new(val))
+    }
     fn get_meta_data(&self) -> Result<Self::Meta> {
         let request = self.resultset.get_metadata_request();
         let meta = request.send().pipeline.get_metadata();
@@ -39,6 +39,13 @@ pub trait ResultSetAdapter {
     type StringValue;
     type BoolValue;
     type DateValue;
+    // my own extends
+    type BlobValue;
+    type Int64Value;
+    type Float64Value;
+    type DecimalValue;
+    type TimestampValue;
+    type TimeValue;
     type Res;
 
     fn next(&self) -> Self::Next;
@@ -47,6 +54,15 @@ pub trait ResultSetAdapter {
     fn get_string(&mut self, fldname: &str) -> Result<Self::StringValue>;
     fn get_bool(&mut self, fldname: &str) -> Result<Self::BoolValue>;
     fn get_date(&mut self, fldname: &str) -> Result<Self::DateValue>;
+    // my own extends
+    fn get_blob(&mut self, fldname: &str) -> Result<Self::BlobValue>;
+    fn get_i64(&mut self, fldname: &str) -> Result<Self::Int64Value>;
+    fn get_f64(&mut self, fldname: &str) -> Result<Self::Float64Value>;
+    fn get_decimal(&mut self, fldname: &str) -> Result<Self::DecimalValue>;
+    fn get_timestamp(&mut self, fldname: &str) -> Result<Self::TimestampValue>;
+    fn get_time(&mut self, fldname: &str) -> Result<Self::TimeValue>;
+    // my own extends
+    fn is_null(&mut self, fldname: &str) -> Result<Self::BoolValue>;
     fn get_meta_data(&self) -> Result<Self::Meta>;
     fn close(&mut self) -> Result<Self::Res>;
 }
@@ -4,6 +4,14 @@ pub enum DataType {
     Varchar,
     Bool,
     Date,
+    // my own extends
+    Text,
+    Blob,
+    BigInt,
+    Double,
+    Decimal,
+    Timestamp,
+    Time,
 }
 
 pub trait ResultSetMetaDataAdapter {
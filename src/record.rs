@@ -1,5 +1,7 @@
 pub mod layout;
+pub mod overflow;
 pub mod recordpage;
 pub mod rid;
 pub mod schema;
+pub mod slottedpage;
 pub mod tablescan;
@@ -1,36 +1,82 @@
-use std::{collections::HashMap, mem, sync::Arc};
+use num_derive::FromPrimitive;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    sync::Arc,
+};
 
 use super::schema::{FieldType, Schema};
 use crate::file::page::Page;
 
+// my own extends
+// the null bitmap follows the empty/inuse flag, one bit per field.
+const NULL_BITMAP_OFFSET: usize = mem::size_of::<i32>();
+const BITS_PER_WORD: usize = 32;
+
+// my own extends
+// how the records of a table are stored in its blocks, chosen when the table is created.
+#[derive(FromPrimitive, Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum RecordFormat {
+    // fixed-size slots, where a VARCHAR(n) always takes the bytes of n characters
+    #[default]
+    Fixed = 0,
+    // slotted pages of variable-length records, see SlottedPage
+    Slotted = 1,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Layout {
     schema: Arc<Schema>,
     offsets: HashMap<String, usize>,
     slotsize: usize,
+    // my own extends
+    nullbits: HashMap<String, usize>,
+    format: RecordFormat,
+    // the names in the table of the fields renamed by qualify
+    originals: HashMap<String, String>,
 }
 
 impl Layout {
     pub fn new(schema: Arc<Schema>) -> Self {
+        Self::new_with_format(schema, RecordFormat::Fixed)
+    }
+    // my own extends
+    // in the slotted format, a VARCHAR field only holds the offset of its string,
+    // so the slot size is the size of the fixed-length part of a record.
+    pub fn new_with_format(schema: Arc<Schema>, format: RecordFormat) -> Self {
         let mut offsets = HashMap::new();
-        let mut pos = mem::size_of::<i32>(); // space for the empty/inuse flag
+        // space for the empty/inuse flag (the record length if slotted) and the null bitmap
+        let mut pos = NULL_BITMAP_OFFSET + null_bitmap_size(&schema);
         for fldname in schema.fields() {
             offsets.insert(fldname.to_string(), pos);
-            pos += lengthin_bytes(&schema, fldname.to_string())
+            pos += lengthin_bytes(&schema, fldname.to_string(), format)
         }
+        let nullbits = null_bits(&offsets);
 
         Self {
             schema,
             offsets,
             slotsize: pos,
+            nullbits,
+            format,
+            originals: HashMap::new(),
         }
     }
 
-    pub fn new_with(schema: Arc<Schema>, offsets: HashMap<String, usize>, slotsize: usize) -> Self {
+    pub fn new_with(
+        schema: Arc<Schema>,
+        offsets: HashMap<String, usize>,
+        slotsize: usize,
+        format: RecordFormat,
+    ) -> Self {
+        let nullbits = null_bits(&offsets);
         Self {
             schema,
             offsets,
             slotsize,
+            nullbits,
+            format,
+            originals: HashMap::new(),
         }
     }
     pub fn schema(&self) -> Arc<Schema> {
@@ -42,15 +88,96 @@ impl Layout {
     pub fn slot_size(&self) -> usize {
         self.slotsize
     }
+    // my own extends
+    pub fn format(&self) -> RecordFormat {
+        self.format
+    }
+    // the fields in the order of their offsets
+    pub fn sorted_fields(&self) -> Vec<String> {
+        let mut fields = self.schema.fields().clone();
+        fields.sort_by_key(|fldname| self.offset(fldname));
+        fields
+    }
+    // my own extends
+    // the TEXT and BLOB fields, whose values are in the overflow file
+    pub fn large_objects(&self) -> Vec<String> {
+        self.schema
+            .fields()
+            .iter()
+            .filter(|fldname| self.schema.field_type(fldname).is_large_object())
+            .cloned()
+            .collect()
+    }
+    // my own extends
+    // the layout of a table referred to by the qualifier (its alias or name) in a query,
+    // whose fields are renamed as Schema::qualify. the records are read as they are.
+    pub fn qualify(&self, qualifier: &str, qualified: &HashSet<String>) -> Self {
+        let schema = self.schema.qualify(qualifier, qualified);
+        let mut offsets = HashMap::new();
+        let mut originals = HashMap::new();
+        for (fldname, name) in self.schema.fields().iter().zip(schema.fields()) {
+            offsets.insert(name.to_string(), self.offset(fldname));
+            if name != fldname {
+                originals.insert(name.to_string(), self.field_name(fldname).to_string());
+            }
+        }
+        let mut layout = Self::new_with(Arc::new(schema), offsets, self.slotsize, self.format);
+        layout.originals = originals;
+        layout
+    }
+    // my own extends
+    // the name of the field in the table. the rows of the records, saved as versions
+    // by any transaction, are keyed by these names.
+    pub fn field_name<'a>(&'a self, fldname: &'a str) -> &'a str {
+        self.originals
+            .get(fldname)
+            .map(|name| name.as_str())
+            .unwrap_or(fldname)
+    }
+    // returns the offset of the bitmap word and the mask of the field's null bit
+    pub fn null_bit(&self, fldname: &str) -> (usize, i32) {
+        let bit = *self.nullbits.get(fldname).unwrap();
+        let offset = NULL_BITMAP_OFFSET + (bit / BITS_PER_WORD) * mem::size_of::<i32>();
+        (offset, 1 << (bit % BITS_PER_WORD))
+    }
+    // returns the offsets of all bitmap words
+    pub fn null_bitmap(&self) -> Vec<usize> {
+        (0..null_bitmap_size(&self.schema) / mem::size_of::<i32>())
+            .map(|i| NULL_BITMAP_OFFSET + i * mem::size_of::<i32>())
+            .collect()
+    }
+}
+
+fn null_bitmap_size(schema: &Schema) -> usize {
+    let words = schema.fields().len().div_ceil(BITS_PER_WORD);
+    words * mem::size_of::<i32>()
+}
+
+// the fields are numbered in the order of their offsets,
+// so that the same bits are assigned when the layout is read from the catalog.
+fn null_bits(offsets: &HashMap<String, usize>) -> HashMap<String, usize> {
+    let mut fields = offsets.iter().collect::<Vec<(&String, &usize)>>();
+    fields.sort_by_key(|(_, offset)| **offset);
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(bit, (fldname, _))| (fldname.to_string(), bit))
+        .collect()
 }
 
-fn lengthin_bytes(schema: &Schema, fldname: String) -> usize {
+fn lengthin_bytes(schema: &Schema, fldname: String, format: RecordFormat) -> usize {
     let fldtype = schema.field_type(&fldname);
     match fldtype {
         FieldType::SMALLINT => mem::size_of::<i16>(),
         FieldType::INTEGER => mem::size_of::<i32>(),
+        FieldType::VARCHAR if format == RecordFormat::Slotted => mem::size_of::<i32>(),
         FieldType::VARCHAR => Page::max_length(schema.length(&fldname)),
         FieldType::BOOL => mem::size_of::<bool>(),
         FieldType::DATE => mem::size_of::<u32>(), // NOTE: u16(year) + u8(month) + u8(day)
+        // the length of the value and the first block of its chain
+        FieldType::TEXT | FieldType::BLOB => 2 * mem::size_of::<i32>(),
+        FieldType::BIGINT | FieldType::DECIMAL => mem::size_of::<i64>(),
+        FieldType::DOUBLE => mem::size_of::<f64>(),
+        FieldType::TIMESTAMP | FieldType::TIME => mem::size_of::<i64>(),
     }
 }
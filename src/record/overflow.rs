@@ -0,0 +1,99 @@
+use anyhow::Result;
+use core::fmt;
+use std::mem;
+
+use crate::{file::block_id::BlockId, tx::transaction::Transaction};
+
+#[derive(Debug)]
+pub enum OverflowError {
+    BrokenChain(String),
+}
+
+impl std::error::Error for OverflowError {}
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowError::BrokenChain(filename) => {
+                write!(f, "broken chain of overflow blocks: {}", filename)
+            }
+        }
+    }
+}
+
+// my own extends
+// the values of the TEXT and BLOB fields of a table, which do not fit in its records.
+// a value is stored in a chain of blocks of the overflow file of the table:
+//
+//   | next block | data ... |
+//
+// the record holds the length of the value and the first block of its chain.
+// the chain belongs to the slot: a new value is written over the blocks of the old one,
+// and the chain only grows, so that its blocks are reused when the slot is updated or reused.
+// the chain is read without locks, since only the writer of the record changes it.
+const NEXT_OFFSET: i32 = 0;
+const DATA_OFFSET: i32 = mem::size_of::<i32>() as i32;
+pub const NO_BLOCK: i32 = -1;
+
+// if you change the name, you must change TableMgr, too.
+pub fn overflow_file(tblfile: &str) -> String {
+    format!("{}.ovf", tblfile.strip_suffix(".tbl").unwrap_or(tblfile))
+}
+
+// the value of the chain starting at the block, of the given length
+pub fn read(tx: &mut Transaction, tblfile: &str, head: i32, len: i32) -> Result<Vec<u8>> {
+    let filename = overflow_file(tblfile);
+    let piece = (tx.block_size() - DATA_OFFSET) as usize;
+    let len = len as usize;
+    let mut val = Vec::with_capacity(len);
+    let mut blknum = head;
+    while val.len() < len {
+        if blknum == NO_BLOCK {
+            return Err(From::from(OverflowError::BrokenChain(filename)));
+        }
+        let blk = BlockId::new(&filename, blknum);
+        let n = piece.min(len - val.len());
+        tx.pin(&blk)?;
+        blknum = tx.peek(&blk, |p| {
+            val.extend_from_slice(p.get_raw_bytes(DATA_OFFSET as usize, n)?);
+            p.get_i32(NEXT_OFFSET as usize)
+        })?;
+        tx.unpin(&blk)?;
+    }
+
+    Ok(val)
+}
+
+// writes the value over the chain starting at the block, which is extended if it is too short.
+// returns the first block of the chain, which is appended if the slot has none yet.
+pub fn write(tx: &mut Transaction, tblfile: &str, mut head: i32, val: &[u8]) -> Result<i32> {
+    let filename = overflow_file(tblfile);
+    let piece = (tx.block_size() - DATA_OFFSET) as usize;
+    let mut prev: Option<BlockId> = None;
+    let mut blknum = head;
+    for chunk in val.chunks(piece) {
+        let blk = if blknum == NO_BLOCK {
+            let blk = tx.append(&filename)?;
+            tx.pin(&blk)?;
+            tx.set_i32(&blk, NEXT_OFFSET, NO_BLOCK, true)?;
+            match &prev {
+                Some(prev) => {
+                    tx.pin(prev)?;
+                    tx.set_i32(prev, NEXT_OFFSET, blk.number(), true)?;
+                    tx.unpin(prev)?;
+                }
+                None => head = blk.number(),
+            }
+            blk
+        } else {
+            let blk = BlockId::new(&filename, blknum);
+            tx.pin(&blk)?;
+            blk
+        };
+        tx.set_bytes(&blk, DATA_OFFSET, chunk, true)?;
+        blknum = tx.peek(&blk, |p| p.get_i32(NEXT_OFFSET as usize))?;
+        tx.unpin(&blk)?;
+        prev = Some(blk);
+    }
+
+    Ok(head)
+}
@@ -1,16 +1,35 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use std::sync::{Arc, Mutex};
+use rust_decimal::Decimal;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
 
-use super::{layout::Layout, schema::FieldType};
-use crate::{file::block_id::BlockId, tx::transaction::Transaction};
+use super::{
+    layout::{Layout, RecordFormat},
+    overflow::{self, NO_BLOCK},
+    schema::FieldType,
+    slottedpage::{self, SlottedPage},
+};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    query::constant::Constant,
+    tx::{
+        mvcc::versionstore::{Row, VisibleVersion},
+        transaction::Transaction,
+    },
+};
 
 #[derive(FromPrimitive, Debug, Eq, PartialEq, Clone, Copy)]
 pub enum SlotFlag {
     EMPTY = 0,
     USED = 1,
+    // my own extends
+    // the slot of a record moved to a slotted page from another block, not a record of its own
+    MOVED = 2,
 }
 
 #[derive(Debug, Clone)]
@@ -27,64 +46,269 @@ impl RecordPage {
         Ok(Self { tx, blk, layout })
     }
     pub fn get_i16(&mut self, slot: i32, fldname: &str) -> Result<i16> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.get_i16(&self.blk, fldpos)
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_i16(blk, rec + offset))
     }
     pub fn get_i32(&mut self, slot: i32, fldname: &str) -> Result<i32> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.get_i32(&self.blk, fldpos)
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_i32(blk, rec + offset))
     }
     pub fn get_string(&mut self, slot: i32, fldname: &str) -> Result<String> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.get_string(&self.blk, fldpos)
+        if self.layout.schema().field_type(fldname) == FieldType::TEXT {
+            return Ok(String::from_utf8(self.get_blob(slot, fldname)?)?);
+        }
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        let slotted = self.layout.format() == RecordFormat::Slotted;
+        self.with_record(slot, |tx, blk, rec| {
+            if slotted {
+                // the string follows the fixed-length part of the record
+                let strpos = tx.get_i32(blk, rec + offset)?;
+                return tx.get_string(blk, rec + strpos);
+            }
+            tx.get_string(blk, rec + offset)
+        })
     }
     pub fn get_bool(&mut self, slot: i32, fldname: &str) -> Result<bool> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.get_bool(&self.blk, fldpos)
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_bool(blk, rec + offset))
     }
     pub fn get_date(&mut self, slot: i32, fldname: &str) -> Result<NaiveDate> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.get_date(&self.blk, fldpos)
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_date(blk, rec + offset))
+    }
+    // my own extends
+    pub fn get_i64(&mut self, slot: i32, fldname: &str) -> Result<i64> {
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_i64(blk, rec + offset))
+    }
+    // my own extends
+    pub fn get_f64(&mut self, slot: i32, fldname: &str) -> Result<f64> {
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_f64(blk, rec + offset))
+    }
+    // my own extends
+    pub fn get_timestamp(&mut self, slot: i32, fldname: &str) -> Result<NaiveDateTime> {
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_timestamp(blk, rec + offset))
+    }
+    // my own extends
+    pub fn get_time(&mut self, slot: i32, fldname: &str) -> Result<NaiveTime> {
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| tx.get_time(blk, rec + offset))
+    }
+    // my own extends
+    pub fn get_decimal(&mut self, slot: i32, fldname: &str) -> Result<Decimal> {
+        let scale = self.layout.schema().scale(fldname) as u32;
+        Ok(Decimal::new(self.get_i64(slot, fldname)?, scale))
+    }
+    // my own extends
+    // the value of a TEXT or BLOB field, from the overflow file
+    pub fn get_blob(&mut self, slot: i32, fldname: &str) -> Result<Vec<u8>> {
+        self.s_lock(slot)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            let len = tx.get_i32(blk, rec + offset)?;
+            let head = tx.get_i32(blk, rec + offset + mem::size_of::<i32>() as i32)?;
+            overflow::read(tx, &blk.file_name(), head, len)
+        })
     }
     pub fn set_i16(&mut self, slot: i32, fldname: &str, val: i16) -> Result<()> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.set_i16(&self.blk, fldpos as i32, val, true)
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_i16(blk, rec + offset, val, true)
+        })
     }
     pub fn set_i32(&mut self, slot: i32, fldname: &str, val: i32) -> Result<()> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.set_i32(&self.blk, fldpos as i32, val, true)
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_i32(blk, rec + offset, val, true)
+        })
     }
     pub fn set_string(&mut self, slot: i32, fldname: &str, val: String) -> Result<()> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.set_string(&self.blk, fldpos, &val, true)
+        if self.layout.schema().field_type(fldname) == FieldType::TEXT {
+            return self.set_blob(slot, fldname, val.as_bytes());
+        }
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        if self.layout.format() == RecordFormat::Slotted {
+            return self.slotted().set_string(slot, fldname, val);
+        }
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_string(blk, rec + offset, &val, true)
+        })
     }
     pub fn set_bool(&mut self, slot: i32, fldname: &str, val: bool) -> Result<()> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.set_bool(&self.blk, fldpos as i32, val, true)
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_bool(blk, rec + offset, val, true)
+        })
     }
     pub fn set_date(&mut self, slot: i32, fldname: &str, val: NaiveDate) -> Result<()> {
-        let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
-        let mut tx = self.tx.lock().unwrap();
-        tx.set_date(&self.blk, fldpos as i32, val, true)
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_date(blk, rec + offset, val, true)
+        })
+    }
+    // my own extends
+    pub fn set_i64(&mut self, slot: i32, fldname: &str, val: i64) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_i64(blk, rec + offset, val, true)
+        })
+    }
+    // my own extends
+    pub fn set_f64(&mut self, slot: i32, fldname: &str, val: f64) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_f64(blk, rec + offset, val, true)
+        })
+    }
+    // my own extends
+    pub fn set_timestamp(&mut self, slot: i32, fldname: &str, val: NaiveDateTime) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_timestamp(blk, rec + offset, val, true)
+        })
+    }
+    // my own extends
+    pub fn set_time(&mut self, slot: i32, fldname: &str, val: NaiveTime) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            tx.set_time(blk, rec + offset, val, true)
+        })
+    }
+    // my own extends
+    // the value is rounded to the scale of the field
+    pub fn set_decimal(&mut self, slot: i32, fldname: &str, val: Decimal) -> Result<()> {
+        let sch = self.layout.schema();
+        let unscaled =
+            Constant::new_decimal(val).as_unscaled(sch.length(fldname), sch.scale(fldname))?;
+        self.set_i64(slot, fldname, unscaled)
+    }
+    // my own extends
+    // the value is written over the chain of the slot, whose first block is kept in the record
+    pub fn set_blob(&mut self, slot: i32, fldname: &str, val: &[u8]) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        self.clear_null(slot, fldname)?;
+        let offset = self.layout.offset(fldname) as i32;
+        self.with_record(slot, |tx, blk, rec| {
+            let fldpos = rec + offset;
+            let headpos = fldpos + mem::size_of::<i32>() as i32;
+            let head = tx.get_i32(blk, headpos)?;
+            let newhead = overflow::write(tx, &blk.file_name(), head, val)?;
+            tx.set_i32(blk, fldpos, val.len() as i32, true)?;
+            if newhead != head {
+                tx.set_i32(blk, headpos, newhead, true)?;
+            }
+
+            Ok(())
+        })
+    }
+    // my own extends
+    pub fn is_null(&mut self, slot: i32, fldname: &str) -> Result<bool> {
+        let (pos, mask) = self.layout.null_bit(fldname);
+        self.s_lock(slot)?;
+        let bits = self.with_record(slot, |tx, blk, rec| tx.get_i32(blk, rec + pos as i32))?;
+        Ok(bits & mask != 0)
+    }
+    pub fn set_null(&mut self, slot: i32, fldname: &str) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        let (pos, mask) = self.layout.null_bit(fldname);
+        self.with_record(slot, |tx, blk, rec| {
+            let bitpos = rec + pos as i32;
+            let bits = tx.get_i32(blk, bitpos)?;
+            if bits & mask == 0 {
+                tx.set_i32(blk, bitpos, bits | mask, true)?;
+            }
+            Ok(())
+        })
+    }
+    fn clear_null(&mut self, slot: i32, fldname: &str) -> Result<()> {
+        let (pos, mask) = self.layout.null_bit(fldname);
+        self.with_record(slot, |tx, blk, rec| {
+            let bitpos = rec + pos as i32;
+            let bits = tx.get_i32(blk, bitpos)?;
+            if bits & mask != 0 {
+                tx.set_i32(blk, bitpos, bits & !mask, true)?;
+            }
+            Ok(())
+        })
+    }
+    fn clear_null_bitmap(&mut self, slot: i32) -> Result<()> {
+        let bitmap = self.layout.null_bitmap();
+        self.with_record(slot, |tx, blk, rec| {
+            for pos in bitmap {
+                let bitpos = rec + pos as i32;
+                if tx.get_i32(blk, bitpos)? != 0 {
+                    tx.set_i32(blk, bitpos, 0, true)?;
+                }
+            }
+            Ok(())
+        })
     }
     pub fn delete(&mut self, slot: i32) -> Result<()> {
+        self.x_lock(slot)?;
+        self.save_version(slot, false)?;
+        // the slot a moved record has in another block is free again, too
+        if let Some((blk, moved)) = self.forward(slot)? {
+            let mut tx = self.tx.lock().unwrap();
+            tx.pin(&blk)?;
+            let pos = slottedpage::flag_offset(moved);
+            let result = tx.set_i32(&blk, pos, SlotFlag::EMPTY as i32, true);
+            tx.unpin(&blk)?;
+            result?;
+        }
         self.set_flag(slot, SlotFlag::EMPTY)
     }
     pub fn format(&mut self) -> Result<()> {
+        if self.layout.format() == RecordFormat::Slotted {
+            return self.slotted().format();
+        }
         let mut slot: i32 = 0;
         while self.is_valid_slot(slot) {
             let mut tx = self.tx.lock().unwrap();
 
             tx.set_i32(&self.blk, self.offset(slot), SlotFlag::EMPTY as i32, false)?;
+            for pos in self.layout.null_bitmap() {
+                tx.set_i32(&self.blk, self.offset(slot) + pos as i32, 0, false)?;
+            }
             let sch = self.layout.schema();
             for fldname in sch.fields() {
                 let fldpos = self.offset(slot) + self.layout.offset(fldname) as i32;
@@ -109,6 +333,24 @@ impl RecordPage {
                             false,
                         )?;
                     }
+                    FieldType::TEXT | FieldType::BLOB => {
+                        tx.set_i32(&self.blk, fldpos, 0, false)?;
+                        let headpos = fldpos + mem::size_of::<i32>() as i32;
+                        tx.set_i32(&self.blk, headpos, NO_BLOCK, false)?;
+                    }
+                    FieldType::BIGINT | FieldType::DECIMAL => {
+                        tx.set_i64(&self.blk, fldpos, 0, false)?;
+                    }
+                    FieldType::DOUBLE => {
+                        tx.set_f64(&self.blk, fldpos, 0.0, false)?;
+                    }
+                    FieldType::TIMESTAMP => {
+                        // NOTE: default 1970-01-01 00:00:00
+                        tx.set_timestamp(&self.blk, fldpos, NaiveDateTime::default(), false)?;
+                    }
+                    FieldType::TIME => {
+                        tx.set_time(&self.blk, fldpos, NaiveTime::MIN, false)?;
+                    }
                 }
             }
             slot += 1;
@@ -119,27 +361,140 @@ impl RecordPage {
         self.search_after(slot, SlotFlag::USED)
     }
     pub fn insert_after(&mut self, slot: i32) -> Option<i32> {
-        if let Some(newslot) = self.search_after(slot, SlotFlag::EMPTY) {
+        let found = match self.layout.format() {
+            RecordFormat::Fixed => self.search_after(slot, SlotFlag::EMPTY),
+            RecordFormat::Slotted => self.slotted().insert_after(slot).unwrap(),
+        };
+        if let Some(newslot) = found {
+            self.save_version(newslot, true).unwrap();
             self.set_flag(newslot, SlotFlag::USED).unwrap();
+            // a reused slot may have the null bits of the deleted record
+            self.clear_null_bitmap(newslot).unwrap();
+            // and its large objects, whose chains are kept for the new ones
+            self.clear_large_objects(newslot).unwrap();
             return Some(newslot);
         }
 
         None
     }
+    // my own extends
+    fn clear_large_objects(&mut self, slot: i32) -> Result<()> {
+        let layout = Arc::clone(&self.layout);
+        self.with_record(slot, |tx, blk, rec| {
+            for fldname in layout.large_objects() {
+                let fldpos = rec + layout.offset(&fldname) as i32;
+                if tx.get_i32(blk, fldpos)? != 0 {
+                    tx.set_i32(blk, fldpos, 0, true)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
     pub fn block(&self) -> &BlockId {
         &self.blk
     }
+    // my own extends
+    // the record as it is in the page, read without locks. None for an empty slot.
+    pub fn peek_row(&mut self, slot: i32) -> Result<Option<Row>> {
+        let layout = Arc::clone(&self.layout);
+        let mut tx = self.tx.lock().unwrap();
+        if layout.format() == RecordFormat::Slotted {
+            // the records of a slotted page are moved by a writer holding the page
+            // for a moment, and cannot be read until it is done.
+            tx.instant_is_lock_block(&self.blk)?;
+        }
+        read_record(&mut tx, &self.blk, &layout, slot)
+    }
+    // my own extends
+    // the record as the snapshot of the transaction sees it. None if it did not exist then.
+    pub fn visible_row(&mut self, slot: i32) -> Result<Option<Row>> {
+        loop {
+            let version = self.tx.lock().unwrap().visible_version(&self.blk, slot);
+            match version {
+                VisibleVersion::Saved(row) => return Ok(row),
+                VisibleVersion::OnDisk(epoch) => {
+                    let row = self.peek_row(slot)?;
+                    // retry if a writer has saved the record meanwhile
+                    if self.tx.lock().unwrap().version_epoch() == epoch {
+                        return Ok(row);
+                    }
+                }
+            }
+        }
+    }
+    // my own extends
+    // the next record the snapshot sees, with the slot.
+    // the flags in the page are not enough: a slot may be emptied or filled after the snapshot.
+    pub fn visible_after(&mut self, mut slot: i32) -> Result<Option<(i32, Row)>> {
+        slot += 1;
+        while self.is_valid_slot(slot) {
+            if let Some(row) = self.visible_row(slot)? {
+                return Ok(Some((slot, row)));
+            }
+            slot += 1;
+        }
+
+        Ok(None)
+    }
+    // my own extends
+    // a record moved to another block is locked there, too,
+    // so that the page it is in is not compacted while it is used.
+    fn s_lock(&mut self, slot: i32) -> Result<()> {
+        self.tx.lock().unwrap().s_lock_record(&self.blk, slot)?;
+        if let Some((blk, moved)) = self.forward(slot)? {
+            self.tx.lock().unwrap().s_lock_record(&blk, moved)?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    fn x_lock(&mut self, slot: i32) -> Result<()> {
+        self.tx.lock().unwrap().x_lock_record(&self.blk, slot)?;
+        if let Some((blk, moved)) = self.forward(slot)? {
+            self.tx.lock().unwrap().x_lock_record(&blk, moved)?;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    fn save_version(&mut self, slot: i32, inserting: bool) -> Result<()> {
+        let blk = self.blk.clone();
+        let layout = Arc::clone(&self.layout);
+        let mut tx = self.tx.lock().unwrap();
+        tx.save_version(&self.blk, slot, inserting, |tx| {
+            read_record(tx, &blk, &layout, slot)
+        })
+    }
     fn set_flag(&mut self, slot: i32, flag: SlotFlag) -> Result<()> {
-        let offset = self.offset(slot);
+        let pos = self.flag_offset(slot);
         let mut tx = self.tx.lock().unwrap();
 
-        tx.set_i32(&self.blk, offset, flag as i32, true)
+        tx.set_i32(&self.blk, pos, flag as i32, true)
     }
     fn search_after(&mut self, mut slot: i32, flag: SlotFlag) -> Option<i32> {
         slot += 1;
         while self.is_valid_slot(slot) {
             let mut tx = self.tx.lock().unwrap();
-            let flg = tx.get_i32(&self.blk, self.offset(slot)).unwrap();
+            match flag {
+                // every slot looked at is locked, so that the records inserted or deleted
+                // by a running transaction are waited for.
+                SlotFlag::USED => tx.s_lock_record(&self.blk, slot).unwrap(),
+                // but a free slot held by another transaction is passed over.
+                // the records moved from other blocks are only in slotted pages
+                SlotFlag::MOVED => return None,
+                SlotFlag::EMPTY => {
+                    let offset = self.flag_offset(slot) as usize;
+                    let flg = tx.peek(&self.blk, |p| p.get_i32(offset)).unwrap();
+                    if FromPrimitive::from_i32(flg) != Some(SlotFlag::EMPTY)
+                        || !tx.try_x_lock_record(&self.blk, slot).unwrap()
+                    {
+                        slot += 1;
+                        continue;
+                    }
+                }
+            }
+            let flg = tx.get_i32(&self.blk, self.flag_offset(slot)).unwrap();
             if FromPrimitive::from_i32(flg) == Some(flag) {
                 return Some(slot);
             }
@@ -149,11 +504,207 @@ impl RecordPage {
         None
     }
     fn is_valid_slot(&self, slot: i32) -> bool {
+        if self.layout.format() == RecordFormat::Slotted {
+            return slot < self.slotted().num_slots().unwrap();
+        }
         self.offset(slot + 1) as i32 <= self.tx.lock().unwrap().block_size()
     }
     fn offset(&self, slot: i32) -> i32 {
         slot * self.layout.slot_size() as i32
     }
+    // my own extends
+    // where the record of the slot starts. a record of a slotted page may be anywhere in it.
+    fn record_offset(&self, slot: i32) -> Result<i32> {
+        match self.layout.format() {
+            RecordFormat::Fixed => Ok(self.offset(slot)),
+            RecordFormat::Slotted => self.slotted().record_offset(slot),
+        }
+    }
+    // my own extends
+    // where the record of the slot has been moved to, if it has
+    fn forward(&self, slot: i32) -> Result<Option<(BlockId, i32)>> {
+        match self.layout.format() {
+            RecordFormat::Fixed => Ok(None),
+            RecordFormat::Slotted => self.slotted().forward(slot),
+        }
+    }
+    // my own extends
+    // runs f on the record of the slot where it is, in the block it has been moved to if it has,
+    // which stays pinned meanwhile.
+    fn with_record<T, F>(&self, slot: i32, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction, &BlockId, i32) -> Result<T>,
+    {
+        let (blk, moved) = match self.forward(slot)? {
+            Some(found) => found,
+            None => {
+                let rec = self.record_offset(slot)?;
+                return f(&mut self.tx.lock().unwrap(), &self.blk, rec);
+            }
+        };
+        let mut tx = self.tx.lock().unwrap();
+        tx.pin(&blk)?;
+        let result = tx
+            .peek(&blk, |p| slottedpage::record_offset_in(p, moved))
+            .and_then(|rec| f(&mut tx, &blk, rec));
+        tx.unpin(&blk)?;
+
+        result
+    }
+    // my own extends
+    fn flag_offset(&self, slot: i32) -> i32 {
+        match self.layout.format() {
+            RecordFormat::Fixed => self.offset(slot),
+            RecordFormat::Slotted => slottedpage::flag_offset(slot),
+        }
+    }
+    // my own extends
+    fn slotted(&self) -> SlottedPage {
+        SlottedPage::new(
+            Arc::clone(&self.tx),
+            self.blk.clone(),
+            Arc::clone(&self.layout),
+        )
+    }
+}
+
+// my own extends
+// the record with the values of its large objects, read without locks. None for an empty slot.
+fn read_record(
+    tx: &mut Transaction,
+    blk: &BlockId,
+    layout: &Layout,
+    slot: i32,
+) -> Result<Option<Row>> {
+    if layout.format() == RecordFormat::Slotted {
+        if let Some((blknum, moved)) = tx.peek(blk, |p| slottedpage::forwarded(p, slot))? {
+            let blk = BlockId::new(&blk.file_name(), blknum);
+            return read_moved(tx, &blk, layout, moved).map(Some);
+        }
+    }
+    let found = tx.peek(blk, |p| {
+        let row = match read_slot(p, layout, slot)? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let rec = match layout.format() {
+            RecordFormat::Fixed => slot * layout.slot_size() as i32,
+            RecordFormat::Slotted => slottedpage::record_offset_in(p, slot)?,
+        } as usize;
+        let chains = large_objects(p, layout, &row, rec)?;
+        Ok(Some((row, chains)))
+    })?;
+
+    match found {
+        Some((row, chains)) => read_large_objects(tx, blk, layout, row, chains).map(Some),
+        None => Ok(None),
+    }
+}
+
+// my own extends
+// a record moved to another block, whose slot there is not a record of its own.
+// the page is pinned while it is read, once no writer is compacting it.
+fn read_moved(tx: &mut Transaction, blk: &BlockId, layout: &Layout, slot: i32) -> Result<Row> {
+    tx.pin(blk)?;
+    let found = tx.instant_is_lock_block(blk).and_then(|_| {
+        tx.peek(blk, |p| {
+            let rec = slottedpage::record_offset_in(p, slot)? as usize;
+            let row = slottedpage::read_fields(p, layout, rec)?;
+            let chains = large_objects(p, layout, &row, rec)?;
+            Ok((row, chains))
+        })
+    });
+    tx.unpin(blk)?;
+    let (row, chains) = found?;
+
+    read_large_objects(tx, blk, layout, row, chains)
+}
+
+// my own extends
+// where the values of the large objects of the record at rec are, unless they are null
+fn large_objects(
+    p: &Page,
+    layout: &Layout,
+    row: &Row,
+    rec: usize,
+) -> Result<Vec<(String, i32, i32)>> {
+    let mut chains = vec![];
+    for fldname in layout.large_objects() {
+        if !row.contains_key(layout.field_name(&fldname)) {
+            let fldpos = rec + layout.offset(&fldname);
+            let len = p.get_i32(fldpos)?;
+            let head = p.get_i32(fldpos + mem::size_of::<i32>())?;
+            chains.push((fldname, len, head));
+        }
+    }
+
+    Ok(chains)
+}
+
+// my own extends
+fn read_large_objects(
+    tx: &mut Transaction,
+    blk: &BlockId,
+    layout: &Layout,
+    mut row: Row,
+    chains: Vec<(String, i32, i32)>,
+) -> Result<Row> {
+    for (fldname, len, head) in chains {
+        let bytes = overflow::read(tx, &blk.file_name(), head, len)?;
+        let val = match layout.schema().field_type(&fldname) {
+            FieldType::TEXT => Constant::new_string(String::from_utf8(bytes)?),
+            _ => Constant::new_blob(bytes),
+        };
+        row.insert(layout.field_name(&fldname).to_string(), val);
+    }
+
+    Ok(row)
+}
+
+// my own extends
+// the record without the values of its large objects, which are not in the page
+fn read_slot(p: &Page, layout: &Layout, slot: i32) -> Result<Option<Row>> {
+    match layout.format() {
+        RecordFormat::Fixed => read_row(p, layout, slot * layout.slot_size() as i32),
+        RecordFormat::Slotted => slottedpage::read_row(p, layout, slot),
+    }
+}
+
+// my own extends
+fn read_row(p: &Page, layout: &Layout, offset: i32) -> Result<Option<Row>> {
+    let offset = offset as usize;
+    if FromPrimitive::from_i32(p.get_i32(offset)?) != Some(SlotFlag::USED) {
+        return Ok(None);
+    }
+
+    let mut row = Row::new();
+    let sch = layout.schema();
+    for fldname in sch.fields() {
+        let (pos, mask) = layout.null_bit(fldname);
+        if p.get_i32(offset + pos)? & mask != 0 {
+            row.insert(layout.field_name(fldname).to_string(), Constant::Null);
+            continue;
+        }
+        let fldpos = offset + layout.offset(fldname);
+        let val = match sch.field_type(fldname) {
+            FieldType::SMALLINT => Constant::new_i16(p.get_i16(fldpos)?),
+            FieldType::INTEGER => Constant::new_i32(p.get_i32(fldpos)?),
+            FieldType::VARCHAR => Constant::new_string(p.get_string(fldpos)?),
+            FieldType::BOOL => Constant::new_bool(p.get_bool(fldpos)?),
+            FieldType::DATE => Constant::new_date(p.get_date(fldpos)?),
+            FieldType::TEXT | FieldType::BLOB => continue,
+            FieldType::BIGINT => Constant::new_i64(p.get_i64(fldpos)?),
+            FieldType::DOUBLE => Constant::new_f64(p.get_f64(fldpos)?),
+            FieldType::DECIMAL => {
+                Constant::new_decimal(Decimal::new(p.get_i64(fldpos)?, sch.scale(fldname) as u32))
+            }
+            FieldType::TIMESTAMP => Constant::new_timestamp(p.get_timestamp(fldpos)?),
+            FieldType::TIME => Constant::new_time(p.get_time(fldpos)?),
+        };
+        row.insert(layout.field_name(fldname).to_string(), val);
+    }
+
+    Ok(Some(row))
 }
 
 #[cfg(test)]
@@ -231,4 +782,303 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn row_lock_test() -> Result<()> {
+        if Path::new("_test/recordpage_rowlock").exists() {
+            fs::remove_dir_all("_test/recordpage_rowlock")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/recordpage_rowlock", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let blk = tx.lock().unwrap().append("testfile")?;
+        let mut rp = RecordPage::new(Arc::clone(&tx), blk.clone(), Arc::clone(&layout))?;
+        rp.format()?;
+        for n in 0..3 {
+            let slot = rp.insert_after(n - 1).unwrap();
+            rp.set_i32(slot, "A", n)?;
+        }
+        tx.lock().unwrap().unpin(&blk)?;
+        tx.lock().unwrap().commit()?;
+
+        // two writers of the same block do not wait for each other
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp1 = RecordPage::new(Arc::clone(&tx1), blk.clone(), Arc::clone(&layout))?;
+        let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp2 = RecordPage::new(Arc::clone(&tx2), blk.clone(), Arc::clone(&layout))?;
+        rp1.set_i32(0, "A", 10)?;
+        println!("Tx 1: updated slot 0");
+        rp2.set_i32(1, "A", 11)?;
+        println!("Tx 2: updated slot 1");
+        rp2.delete(2)?;
+        println!("Tx 2: deleted slot 2");
+
+        // the slot freed by Tx 2 is passed over until Tx 2 commits
+        let slot = rp1.insert_after(-1).unwrap();
+        println!("Tx 1: inserted into slot {}", slot);
+        assert_eq!(3, slot);
+        rp1.set_i32(slot, "A", 13)?;
+
+        tx2.lock().unwrap().unpin(&blk)?;
+        tx2.lock().unwrap().commit()?;
+        tx1.lock().unwrap().unpin(&blk)?;
+        tx1.lock().unwrap().commit()?;
+
+        let tx3 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp3 = RecordPage::new(Arc::clone(&tx3), blk.clone(), Arc::clone(&layout))?;
+        let mut vals = vec![];
+        let mut next_slot = rp3.next_after(-1);
+        while let Some(slot) = next_slot {
+            vals.push(rp3.get_i32(slot, "A")?);
+            next_slot = rp3.next_after(slot);
+        }
+        println!("Tx 3: {:?}", vals);
+        assert_eq!(vec![10, 11, 13], vals);
+        tx3.lock().unwrap().unpin(&blk)?;
+        tx3.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn slotted_test() -> Result<()> {
+        if Path::new("_test/recordpage_slotted").exists() {
+            fs::remove_dir_all("_test/recordpage_slotted")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/recordpage_slotted", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        sch.add_string_field("B", 40);
+        let sch = Arc::new(sch);
+        let fixed = Arc::new(Layout::new(Arc::clone(&sch)));
+        let slotted = Arc::new(Layout::new_with_format(
+            Arc::clone(&sch),
+            RecordFormat::Slotted,
+        ));
+        println!(
+            "slot size: fixed {}, slotted {}",
+            fixed.slot_size(),
+            slotted.slot_size()
+        );
+
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut counts = vec![];
+        let mut blocks = vec![];
+        for (filename, layout) in [("fixedfile", fixed), ("slottedfile", Arc::clone(&slotted))] {
+            let blk = tx1.lock().unwrap().append(filename)?;
+            tx1.lock().unwrap().pin(&blk)?;
+            let mut rp = RecordPage::new(Arc::clone(&tx1), blk.clone(), layout)?;
+            rp.format()?;
+            let mut count = 0;
+            let mut next_slot = rp.insert_after(-1);
+            while let Some(slot) = next_slot {
+                rp.set_i32(slot, "A", slot)?;
+                rp.set_string(slot, "B", format!("rec{}", slot))?;
+                count += 1;
+                next_slot = rp.insert_after(slot);
+            }
+            println!("{} records of short strings fit in {}", count, blk);
+            counts.push(count);
+            blocks.push(blk);
+        }
+        assert!(counts[1] > counts[0]);
+        let blk = blocks[1].clone();
+        for blk in &blocks {
+            tx1.lock().unwrap().unpin(blk)?;
+        }
+        tx1.lock().unwrap().commit()?;
+
+        println!("Deleting the even records and lengthening the odd ones.");
+        let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        tx2.lock().unwrap().pin(&blk)?;
+        let mut rp = RecordPage::new(Arc::clone(&tx2), blk.clone(), Arc::clone(&slotted))?;
+        let mut next_slot = rp.next_after(-1);
+        while let Some(slot) = next_slot {
+            if slot % 2 == 0 {
+                rp.delete(slot)?;
+            } else {
+                // the records are moved to make room for the longer strings
+                rp.set_string(slot, "B", format!("a longer record {}", slot))?;
+            }
+            next_slot = rp.next_after(slot);
+        }
+        next_slot = rp.next_after(-1);
+        while let Some(slot) = next_slot {
+            let a = rp.get_i32(slot, "A")?;
+            let b = rp.get_string(slot, "B")?;
+            println!("slot {}: {{{}, {}}}", slot, a, b);
+            assert_eq!(a, slot);
+            assert_eq!(b, format!("a longer record {}", slot));
+            next_slot = rp.next_after(slot);
+        }
+        tx2.lock().unwrap().unpin(&blk)?;
+        tx2.lock().unwrap().rollback()?;
+
+        println!("After the rollback.");
+        let tx3 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        tx3.lock().unwrap().pin(&blk)?;
+        let mut rp = RecordPage::new(Arc::clone(&tx3), blk.clone(), slotted)?;
+        let mut count = 0;
+        next_slot = rp.next_after(-1);
+        while let Some(slot) = next_slot {
+            let a = rp.get_i32(slot, "A")?;
+            let b = rp.get_string(slot, "B")?;
+            println!("slot {}: {{{}, {}}}", slot, a, b);
+            assert_eq!(b, format!("rec{}", a));
+            count += 1;
+            next_slot = rp.next_after(slot);
+        }
+        assert_eq!(count, counts[1]);
+        tx3.lock().unwrap().unpin(&blk)?;
+        tx3.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn slotted_full_page_test() -> Result<()> {
+        if Path::new("_test/recordpage_slottedfull").exists() {
+            fs::remove_dir_all("_test/recordpage_slottedfull")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/recordpage_slottedfull", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        sch.add_string_field("B", 40);
+        let layout = Arc::new(Layout::new_with_format(
+            Arc::new(sch),
+            RecordFormat::Slotted,
+        ));
+        let longest = |slot: i32| format!("{:0>40}", slot);
+
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let blk = tx1.lock().unwrap().append("testfile")?;
+        let mut rp = RecordPage::new(Arc::clone(&tx1), blk.clone(), Arc::clone(&layout))?;
+        rp.format()?;
+        let mut slots = vec![];
+        let mut next_slot = rp.insert_after(-1);
+        while let Some(slot) = next_slot {
+            rp.set_i32(slot, "A", slot)?;
+            rp.set_string(slot, "B", format!("rec{}", slot))?;
+            slots.push(slot);
+            next_slot = rp.insert_after(slot);
+        }
+        println!("{} records fill {}", slots.len(), blk);
+        tx1.lock().unwrap().unpin(&blk)?;
+        tx1.lock().unwrap().commit()?;
+
+        // the records that do not fit in the page any longer are moved to other blocks,
+        // and are still found by their slots
+        for commit in [false, true] {
+            let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+            let mut rp = RecordPage::new(Arc::clone(&tx2), blk.clone(), Arc::clone(&layout))?;
+            for slot in &slots {
+                rp.set_string(*slot, "B", longest(*slot))?;
+            }
+            for slot in &slots {
+                assert_eq!(*slot, rp.get_i32(*slot, "A")?);
+                assert_eq!(longest(*slot), rp.get_string(*slot, "B")?);
+            }
+            let size = tx2.lock().unwrap().size("testfile")?;
+            println!("the records take {} blocks", size);
+            assert!(size > 1);
+            tx2.lock().unwrap().unpin(&blk)?;
+            if commit {
+                tx2.lock().unwrap().commit()?;
+            } else {
+                tx2.lock().unwrap().rollback()?;
+            }
+
+            let tx3 = Arc::new(Mutex::new(simpledb.new_tx()?));
+            let mut rp = RecordPage::new(Arc::clone(&tx3), blk.clone(), Arc::clone(&layout))?;
+            let mut count = 0;
+            let mut next_slot = rp.next_after(-1);
+            while let Some(slot) = next_slot {
+                let expected = match commit {
+                    true => longest(slot),
+                    false => format!("rec{}", slot),
+                };
+                assert_eq!(slot, rp.get_i32(slot, "A")?);
+                assert_eq!(expected, rp.get_string(slot, "B")?);
+                count += 1;
+                next_slot = rp.next_after(slot);
+            }
+            assert_eq!(slots.len(), count);
+            tx3.lock().unwrap().unpin(&blk)?;
+            tx3.lock().unwrap().commit()?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn slotted_lock_test() -> Result<()> {
+        if Path::new("_test/recordpage_slottedlock").exists() {
+            fs::remove_dir_all("_test/recordpage_slottedlock")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/recordpage_slottedlock", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        sch.add_string_field("B", 40);
+        let layout = Arc::new(Layout::new_with_format(
+            Arc::new(sch),
+            RecordFormat::Slotted,
+        ));
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let blk = tx.lock().unwrap().append("testfile")?;
+        let mut rp = RecordPage::new(Arc::clone(&tx), blk.clone(), Arc::clone(&layout))?;
+        rp.format()?;
+        for n in 0..2 {
+            let slot = rp.insert_after(n - 1).unwrap();
+            rp.set_i32(slot, "A", n)?;
+        }
+        tx.lock().unwrap().unpin(&blk)?;
+        tx.lock().unwrap().commit()?;
+
+        // the page is only latched while a slot is taken, the new records are locked
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp1 = RecordPage::new(Arc::clone(&tx1), blk.clone(), Arc::clone(&layout))?;
+        let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp2 = RecordPage::new(Arc::clone(&tx2), blk.clone(), Arc::clone(&layout))?;
+        let slot1 = rp1.insert_after(-1).unwrap();
+        println!("Tx 1: inserted into slot {}", slot1);
+        rp1.set_i32(slot1, "A", 2)?;
+        rp1.set_string(slot1, "B", "a string longer than the default".to_string())?;
+        let slot2 = rp2.insert_after(-1).unwrap();
+        println!("Tx 2: inserted into slot {}", slot2);
+        assert_eq!((2, 3), (slot1, slot2));
+        rp2.set_i32(slot2, "A", 3)?;
+        // and the records Tx 1 has not touched can be read
+        assert_eq!(0, rp2.get_i32(0, "A")?);
+        println!("Tx 2: read slot 0");
+        // but not the one it inserted
+        assert!(rp2.get_i32(slot1, "A").is_err());
+        println!("Tx 2: gave up waiting for slot {}", slot1);
+        tx2.lock().unwrap().unpin(&blk)?;
+        tx2.lock().unwrap().rollback()?;
+
+        // the slot taken by the rolled back insert is free again
+        tx1.lock().unwrap().unpin(&blk)?;
+        tx1.lock().unwrap().commit()?;
+        let tx3 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut rp3 = RecordPage::new(Arc::clone(&tx3), blk.clone(), Arc::clone(&layout))?;
+        assert_eq!(
+            "a string longer than the default",
+            rp3.get_string(slot1, "B")?
+        );
+        let slot = rp3.insert_after(-1).unwrap();
+        println!("Tx 3: inserted into slot {}", slot);
+        assert_eq!(slot2, slot);
+        tx3.lock().unwrap().unpin(&blk)?;
+        tx3.lock().unwrap().commit()?;
+
+        Ok(())
+    }
 }
@@ -1,10 +1,43 @@
+use core::fmt;
 use num_derive::FromPrimitive;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+// my own extends
+#[derive(Debug)]
+pub enum SchemaError {
+    AmbiguousField(String),
+    FieldNotFound(String),
+}
+
+impl std::error::Error for SchemaError {}
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::AmbiguousField(fldname) => {
+                write!(f, "ambiguous field: {}", fldname)
+            }
+            SchemaError::FieldNotFound(fldname) => {
+                write!(f, "field not found: {}", fldname)
+            }
+        }
+    }
+}
+
+// the name of a field qualified by the alias (or the name) of its table, e.g. e1.name
+pub fn qualified_name(qualifier: &str, fldname: &str) -> String {
+    format!("{}.{}", qualifier, fldname)
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Schema {
     fields: Vec<String>,
     info: HashMap<String, FieldInfo>,
+    // my own extends
+    // the alias (or the name) of the table of each field, if known
+    qualifiers: HashMap<String, String>,
 }
 
 impl Schema {
@@ -12,6 +45,7 @@ impl Schema {
         Self {
             fields: vec![],
             info: HashMap::new(),
+            qualifiers: HashMap::new(),
         }
     }
     pub fn add_field(&mut self, fldname: &str, fld_type: FieldType, length: usize) {
@@ -34,10 +68,48 @@ impl Schema {
     pub fn add_date_field(&mut self, fldname: &str) {
         self.add_field(fldname, FieldType::DATE, 0)
     }
+    // my own extends
+    pub fn add_text_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::TEXT, 0)
+    }
+    // my own extends
+    pub fn add_blob_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::BLOB, 0)
+    }
+    // my own extends
+    pub fn add_i64_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::BIGINT, 0)
+    }
+    // my own extends
+    pub fn add_f64_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::DOUBLE, 0)
+    }
+    // my own extends
+    pub fn add_timestamp_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::TIMESTAMP, 0)
+    }
+    // my own extends
+    pub fn add_time_field(&mut self, fldname: &str) {
+        self.add_field(fldname, FieldType::TIME, 0)
+    }
+    // my own extends
+    // the precision is kept as the length of the field
+    pub fn add_decimal_field(&mut self, fldname: &str, precision: usize, scale: usize) {
+        self.add_field(fldname, FieldType::DECIMAL, precision);
+        self.set_scale(fldname, scale);
+    }
     pub fn add(&mut self, fldname: &str, sch: Arc<Schema>) {
         let fld_type = sch.field_type(fldname);
         let length = sch.length(fldname);
-        self.add_field(fldname, fld_type, length)
+        self.add_field(fldname, fld_type, length);
+        self.set_scale(fldname, sch.scale(fldname));
+        if !sch.is_nullable(fldname) {
+            self.set_not_null(fldname);
+        }
+        if let Some(qualifier) = sch.qualifiers.get(fldname) {
+            self.qualifiers
+                .insert(fldname.to_string(), qualifier.to_string());
+        }
     }
     pub fn add_all(&mut self, sch: Arc<Schema>) {
         for fldname in sch.fields().iter() {
@@ -60,9 +132,80 @@ impl Schema {
     pub fn info(&self) -> &HashMap<String, FieldInfo> {
         &self.info
     }
+    pub fn set_not_null(&mut self, fldname: &str) {
+        if let Some(fi) = self.info.get_mut(fldname) {
+            fi.nullable = false;
+        }
+    }
+    // my own extends
+    pub fn set_nullable(&mut self, fldname: &str) {
+        if let Some(fi) = self.info.get_mut(fldname) {
+            fi.nullable = true;
+        }
+    }
+    pub fn is_nullable(&self, fldname: &str) -> bool {
+        self.info.get(fldname).unwrap().nullable
+    }
+    // my own extends
+    // the number of digits after the decimal point of a DECIMAL field
+    pub fn set_scale(&mut self, fldname: &str, scale: usize) {
+        if let Some(fi) = self.info.get_mut(fldname) {
+            fi.scale = scale;
+        }
+    }
+    pub fn scale(&self, fldname: &str) -> usize {
+        self.info.get(fldname).unwrap().scale
+    }
+    // my own extends
+    // the schema of a table (or a view) referred to by the qualifier, which is its alias or name.
+    // the fields in qualified, which the other tables of the query also have,
+    // are renamed to qualifier.fldname.
+    pub fn qualify(&self, qualifier: &str, qualified: &HashSet<String>) -> Schema {
+        let mut sch = Schema::new();
+        for fldname in self.fields.iter() {
+            let name = if qualified.contains(fldname) {
+                qualified_name(qualifier, fldname)
+            } else {
+                fldname.to_string()
+            };
+            sch.fields.push(name.clone());
+            sch.info.insert(name.clone(), self.info[fldname]);
+            sch.qualifiers.insert(name, qualifier.to_string());
+        }
+        sch
+    }
+    pub fn qualifier(&self, fldname: &str) -> Option<&str> {
+        self.qualifiers.get(fldname).map(|q| q.as_str())
+    }
+    // the field which a name of the query refers to, where the name is either
+    // a field of the schema, qualifier.fldname, or a fldname qualified in the schema.
+    // a bare name which refers to no field is returned as it is, and type checking reports it.
+    pub fn resolve(&self, name: &str) -> Result<String, SchemaError> {
+        if self.has_field(name) {
+            return Ok(name.to_string());
+        }
+        let found = self
+            .fields
+            .iter()
+            .filter(|fldname| match self.qualifier(fldname) {
+                Some(qualifier) => {
+                    let prefix = format!("{}.", qualifier);
+                    let bare = fldname.strip_prefix(&prefix).unwrap_or(fldname);
+                    name == bare || name == qualified_name(qualifier, bare)
+                }
+                None => false,
+            })
+            .collect::<Vec<&String>>();
+        match found.as_slice() {
+            [] if name.contains('.') => Err(SchemaError::FieldNotFound(name.to_string())),
+            [] => Ok(name.to_string()),
+            [fldname] => Ok(fldname.to_string()),
+            _ => Err(SchemaError::AmbiguousField(name.to_string())),
+        }
+    }
 }
 
-#[derive(FromPrimitive, Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(FromPrimitive, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum FieldType {
     // i16
     SMALLINT = 1,
@@ -74,16 +217,92 @@ pub enum FieldType {
     BOOL = 4,
     // date
     DATE = 5,
+    // my own extends
+    // String, of any length, in the overflow file
+    TEXT = 6,
+    // Vec<u8>, of any length, in the overflow file
+    BLOB = 7,
+    // i64
+    BIGINT = 8,
+    // f64
+    DOUBLE = 9,
+    // Decimal, stored as an i64 in units of its scale
+    DECIMAL = 10,
+    // NaiveDateTime, stored as an i64 in microseconds since 1970-01-01 00:00:00
+    TIMESTAMP = 11,
+    // NaiveTime, stored as an i64 in microseconds since midnight
+    TIME = 12,
+}
+
+impl FieldType {
+    // my own extends
+    // the large objects, whose record only holds where they are in the overflow file
+    pub fn is_large_object(&self) -> bool {
+        matches!(self, FieldType::TEXT | FieldType::BLOB)
+    }
+    // my own extends
+    // the order of the numeric types, a value of which is converted to a greater one
+    pub fn numeric_rank(&self) -> Option<i32> {
+        match self {
+            FieldType::SMALLINT => Some(0),
+            FieldType::INTEGER => Some(1),
+            FieldType::BIGINT => Some(2),
+            FieldType::DECIMAL => Some(3),
+            FieldType::DOUBLE => Some(4),
+            _ => None,
+        }
+    }
+    // my own extends
+    // whether a value of the type can be stored in a field of this type.
+    // a string is accepted as the text of a date or a time, which is parsed when it is stored.
+    pub fn is_assignable_from(&self, other: FieldType) -> bool {
+        match self {
+            FieldType::SMALLINT | FieldType::INTEGER => matches!(
+                other,
+                FieldType::SMALLINT | FieldType::INTEGER | FieldType::BIGINT
+            ),
+            FieldType::BIGINT => matches!(
+                other,
+                FieldType::SMALLINT | FieldType::INTEGER | FieldType::BIGINT | FieldType::DECIMAL
+            ),
+            FieldType::DECIMAL | FieldType::DOUBLE => other.numeric_rank().is_some(),
+            FieldType::VARCHAR | FieldType::TEXT => {
+                matches!(other, FieldType::VARCHAR | FieldType::TEXT)
+            }
+            FieldType::DATE => matches!(other, FieldType::DATE | FieldType::VARCHAR),
+            FieldType::TIMESTAMP => matches!(
+                other,
+                FieldType::TIMESTAMP | FieldType::DATE | FieldType::VARCHAR
+            ),
+            FieldType::TIME => matches!(
+                other,
+                FieldType::TIME | FieldType::TIMESTAMP | FieldType::VARCHAR
+            ),
+            FieldType::BOOL | FieldType::BLOB => *self == other,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct FieldInfo {
     pub fld_type: FieldType,
     pub length: usize,
+    // my own extends
+    pub nullable: bool,
+    // my own extends
+    pub scale: usize,
 }
 
 impl FieldInfo {
     pub fn new(fld_type: FieldType, length: usize) -> Self {
-        Self { fld_type, length }
+        Self::new_with(fld_type, length, true)
+    }
+    pub fn new_with(fld_type: FieldType, length: usize, nullable: bool) -> Self {
+        Self {
+            fld_type,
+            length,
+            nullable,
+            scale: 0,
+        }
     }
 }
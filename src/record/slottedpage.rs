@@ -0,0 +1,645 @@
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use core::fmt;
+use num_traits::FromPrimitive;
+use rust_decimal::Decimal;
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{layout::Layout, overflow::NO_BLOCK, recordpage::SlotFlag, schema::FieldType};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    query::constant::Constant,
+    tx::{mvcc::versionstore::Row, transaction::Transaction},
+};
+
+#[derive(Debug)]
+pub enum SlottedPageError {
+    NoSpace,
+}
+
+impl std::error::Error for SlottedPageError {}
+impl fmt::Display for SlottedPageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlottedPageError::NoSpace => {
+                write!(f, "no space left in the page for the record")
+            }
+        }
+    }
+}
+
+// my own extends
+// a page of variable-length records, for the tables created in the slotted format.
+// the header and the slot directory grow from the top of the block, the records from the bottom:
+//
+//   | number of slots | end of free space | flag, offset | flag, offset | ... free ... | records |
+//
+// a record starts with its allocated length, followed by the null bitmap and the fields
+// at their offsets in the layout. a VARCHAR field holds the offset of its string in the record,
+// and the strings follow the fixed-length part, so that a string only takes its own bytes.
+// a record is moved when it grows or the page is compacted, but keeps its slot and so its RID.
+// a record that no longer fits in its page is moved to another block of the table,
+// where its slot is flagged MOVED, and the record in its own slot is its address there.
+// the directory and the free space are changed by page actions (see Transaction),
+// the records by the transactions that have locked them.
+const NUM_SLOTS_OFFSET: i32 = 0;
+const FREE_END_OFFSET: i32 = mem::size_of::<i32>() as i32;
+const DIRECTORY_OFFSET: i32 = 2 * mem::size_of::<i32>() as i32;
+const SLOT_ENTRY_SIZE: i32 = 2 * mem::size_of::<i32>() as i32;
+const LARGE_OBJECT_SIZE: usize = 2 * mem::size_of::<i32>();
+// a record moved to another block leaves its address in its place:
+// FORWARDED instead of its length, then the block number and the slot it has there.
+const FORWARDED: i32 = -1;
+const FORWARD_SIZE: usize = 3 * mem::size_of::<i32>();
+
+#[derive(Debug, Clone)]
+pub struct SlottedPage {
+    tx: Arc<Mutex<Transaction>>,
+    blk: BlockId,
+    layout: Arc<Layout>,
+}
+
+impl SlottedPage {
+    // the block is pinned by the record page
+    pub fn new(tx: Arc<Mutex<Transaction>>, blk: BlockId, layout: Arc<Layout>) -> Self {
+        Self { tx, blk, layout }
+    }
+    // the new block is locked by the transaction appending it until it finishes,
+    // so that no other one inserts into the page before it is formatted.
+    pub fn format(&mut self) -> Result<()> {
+        let mut tx = self.tx.lock().unwrap();
+        let blocksize = tx.block_size();
+        tx.set_i32(&self.blk, NUM_SLOTS_OFFSET, 0, false)?;
+        tx.set_i32(&self.blk, FREE_END_OFFSET, blocksize, false)
+    }
+    // the directory is read without locks: the records are only moved by a transaction
+    // holding the whole page, and the caller has locked a record of it.
+    pub fn num_slots(&self) -> Result<i32> {
+        self.peek_i32(NUM_SLOTS_OFFSET)
+    }
+    pub fn record_offset(&self, slot: i32) -> Result<i32> {
+        self.peek_i32(flag_offset(slot) + mem::size_of::<i32>() as i32)
+    }
+    // the block and the slot the record has been moved to, if it has
+    pub fn forward(&self, slot: i32) -> Result<Option<(BlockId, i32)>> {
+        let moved = self
+            .tx
+            .lock()
+            .unwrap()
+            .peek(&self.blk, |p| forwarded(p, slot))?;
+
+        Ok(moved.map(|(blknum, slot)| (BlockId::new(&self.blk.file_name(), blknum), slot)))
+    }
+    // the record is rewritten in its own space if the new string fits in it, moved in the page
+    // if there is room, and moved to another block of the table otherwise.
+    pub fn set_string(&mut self, slot: i32, fldname: &str, val: String) -> Result<()> {
+        let (blk, moved) = match self.forward(slot)? {
+            Some(found) => found,
+            None => return self.update_string(slot, fldname, val, None),
+        };
+        self.tx.lock().unwrap().pin(&blk)?;
+        let mut page =
+            SlottedPage::new(Arc::clone(&self.tx), blk.clone(), Arc::clone(&self.layout));
+        let result = page.update_string(moved, fldname, val, Some((&self.blk, slot)));
+        self.tx.lock().unwrap().unpin(&blk)?;
+
+        result
+    }
+    // home is the slot the record is addressed by, if it has been moved to this page.
+    fn update_string(
+        &mut self,
+        slot: i32,
+        fldname: &str,
+        val: String,
+        home: Option<(&BlockId, i32)>,
+    ) -> Result<()> {
+        let rec = self.record_offset(slot)?;
+        let mut image = self.read_image(rec)?;
+        image
+            .vals
+            .insert(fldname.to_string(), Constant::new_string(val));
+        let len = image.length(&self.layout);
+        let allocated = self.peek_i32(rec)?;
+        if len <= allocated {
+            return self.write_image(rec, &image, allocated);
+        }
+
+        // the space is taken by a page action, and the record written by this transaction,
+        // whose rollback puts it back where it was.
+        if let Some(newrec) = self.page_action(|page| page.grow(rec, allocated, len))? {
+            self.write_image(newrec, &image, len)?;
+            return self.set_record_offset(slot, newrec);
+        }
+        // the address of the record is left in the slot it is addressed by, so it keeps its RID.
+        // a record with a string is always long enough for it.
+        let (blknum, newslot) = self.move_out(&image, len)?;
+        let address = forward_address(blknum, newslot)?;
+        match home {
+            None => self
+                .tx
+                .lock()
+                .unwrap()
+                .set_bytes(&self.blk, rec, &address, true),
+            Some((blk, homeslot)) => {
+                let mut tx = self.tx.lock().unwrap();
+                let homerec = tx.peek(blk, |p| record_offset_in(p, homeslot))?;
+                tx.set_bytes(blk, homerec, &address, true)?;
+                drop(tx);
+                // the chains of its large objects have gone with the record
+                self.write_image(rec, &RecordImage::new(&self.layout), allocated)?;
+                self.set_flag(slot, SlotFlag::EMPTY)
+            }
+        }
+    }
+    // moves the record to the last block of the table, or to a new one,
+    // and returns the block number and the slot it has there.
+    fn move_out(&mut self, image: &RecordImage, len: i32) -> Result<(i32, i32)> {
+        let filename = self.blk.file_name();
+        let size = self.tx.lock().unwrap().peek_size(&filename)?;
+        if size - 1 != self.blk.number() {
+            let blk = BlockId::new(&filename, size - 1);
+            if let Some(slot) = self.move_to(&blk, image, len, false)? {
+                return Ok((blk.number(), slot));
+            }
+        }
+        let blk = self.tx.lock().unwrap().append(&filename)?;
+        match self.move_to(&blk, image, len, true)? {
+            Some(slot) => Ok((blk.number(), slot)),
+            None => Err(From::from(SlottedPageError::NoSpace)),
+        }
+    }
+    fn move_to(
+        &mut self,
+        blk: &BlockId,
+        image: &RecordImage,
+        len: i32,
+        format: bool,
+    ) -> Result<Option<i32>> {
+        self.tx.lock().unwrap().pin(blk)?;
+        let mut page =
+            SlottedPage::new(Arc::clone(&self.tx), blk.clone(), Arc::clone(&self.layout));
+        let result = page.insert_moved(image, len, format);
+        self.tx.lock().unwrap().unpin(blk)?;
+
+        result
+    }
+    // a new slot for a record moved from another page, locked by the transaction,
+    // which writes the record. None if the page is full.
+    fn insert_moved(&mut self, image: &RecordImage, len: i32, format: bool) -> Result<Option<i32>> {
+        if format {
+            self.format()?;
+        }
+        let empty = RecordImage::new(&self.layout);
+        let found = self.page_action(|page| page.new_slot(&empty, len, len))?;
+        if let Some(slot) = found {
+            let rec = self.record_offset(slot)?;
+            self.write_image(rec, image, len)?;
+            self.set_flag(slot, SlotFlag::MOVED)?;
+        }
+
+        Ok(found)
+    }
+    // returns an empty slot after the given one, locked by the transaction,
+    // with a record of the default values. None if the page is full.
+    // the slot and the space are taken by a page action, so the other transactions can insert
+    // into the page, or read it, as soon as it is done. a slot another transaction
+    // is inserting into or deleting from is passed over, like a locked slot of a fixed page.
+    pub fn insert_after(&mut self, slot: i32) -> Result<Option<i32>> {
+        self.page_action(|page| page.take_slot(slot))
+    }
+    fn take_slot(&mut self, slot: i32) -> Result<Option<i32>> {
+        let mut image = RecordImage::new(&self.layout);
+        let len = image.length(&self.layout);
+        // room is kept for the longest values, unless such a record would not fit in any page
+        let capacity = self.tx.lock().unwrap().block_size() - DIRECTORY_OFFSET - SLOT_ENTRY_SIZE;
+        let needed = self.max_record_length().min(capacity).max(len);
+        if !self.reserve(needed)? {
+            return Ok(None);
+        }
+        for s in (slot + 1)..self.num_slots()? {
+            if self.flag(s)? != Some(SlotFlag::EMPTY)
+                || !self.tx.lock().unwrap().try_x_lock_record(&self.blk, s)?
+            {
+                continue;
+            }
+            let old = self.record_offset(s)?;
+            if !self.layout.large_objects().is_empty() && self.peek_i32(old)? != FORWARDED {
+                // the deleted record is still there, with the chains of its large objects
+                image.keep_chains(&self.layout, &self.read_image(old)?);
+            }
+            let rec = self.allocate(len)?;
+            self.write_image(rec, &image, len)?;
+            self.set_record_offset(s, rec)?;
+            return Ok(Some(s));
+        }
+
+        self.new_slot(&image, len, needed)
+    }
+    // a slot at the end of the directory, with the image in len bytes
+    fn new_slot(&mut self, image: &RecordImage, len: i32, needed: i32) -> Result<Option<i32>> {
+        let numslots = self.num_slots()?;
+        if !self.reserve(needed + SLOT_ENTRY_SIZE)?
+            || !self
+                .tx
+                .lock()
+                .unwrap()
+                .try_x_lock_record(&self.blk, numslots)?
+        {
+            return Ok(None);
+        }
+        self.tx.lock().unwrap().set_i32(
+            &self.blk,
+            flag_offset(numslots),
+            SlotFlag::EMPTY as i32,
+            true,
+        )?;
+        let rec = self.allocate(len)?;
+        self.write_image(rec, image, len)?;
+        self.set_record_offset(numslots, rec)?;
+        // the directory is read without the latch, so the slot is counted last
+        self.tx
+            .lock()
+            .unwrap()
+            .set_i32(&self.blk, NUM_SLOTS_OFFSET, numslots + 1, true)?;
+
+        Ok(Some(numslots))
+    }
+    // the space of a longer record. None if there is not enough.
+    fn grow(&mut self, rec: i32, allocated: i32, len: i32) -> Result<Option<i32>> {
+        // the record at the end of the free space, usually the one just inserted, grows into it
+        if rec == self.peek_i32(FREE_END_OFFSET)? && self.free_space()? >= len - allocated {
+            let newrec = rec + allocated - len;
+            self.tx
+                .lock()
+                .unwrap()
+                .set_i32(&self.blk, FREE_END_OFFSET, newrec, true)?;
+            return Ok(Some(newrec));
+        }
+        if !self.reserve(len)? {
+            return Ok(None);
+        }
+
+        Ok(Some(self.allocate(len)?))
+    }
+    // runs f under the latch of the page, as an action of its own.
+    fn page_action<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.tx.lock().unwrap().begin_page_action(&self.blk)?;
+        let result = f(self);
+        self.tx
+            .lock()
+            .unwrap()
+            .end_page_action(&self.blk, result.is_ok())?;
+
+        result
+    }
+    fn max_record_length(&self) -> i32 {
+        let sch = self.layout.schema();
+        let strings = sch
+            .fields()
+            .iter()
+            .filter(|fldname| sch.field_type(fldname) == FieldType::VARCHAR)
+            .map(|fldname| Page::max_length(sch.length(fldname)))
+            .sum::<usize>();
+        (self.layout.slot_size() + strings) as i32
+    }
+    fn free_space(&self) -> Result<i32> {
+        let numslots = self.num_slots()?;
+        let free_end = self.peek_i32(FREE_END_OFFSET)?;
+        Ok(free_end - (DIRECTORY_OFFSET + numslots * SLOT_ENTRY_SIZE))
+    }
+    // compacts the page if the free space is not enough. false if it is still not enough.
+    // the records are only moved while no other transaction uses the page,
+    // and this one has not updated any of them.
+    fn reserve(&mut self, len: i32) -> Result<bool> {
+        if self.free_space()? >= len {
+            return Ok(true);
+        }
+        if !self.tx.lock().unwrap().try_x_lock_block(&self.blk)? {
+            return Ok(false);
+        }
+        let compacted = self.compact();
+        self.tx.lock().unwrap().release_x_lock_block(&self.blk)?;
+        compacted?;
+
+        Ok(self.free_space()? >= len)
+    }
+    fn allocate(&mut self, len: i32) -> Result<i32> {
+        let rec = self.peek_i32(FREE_END_OFFSET)? - len;
+        self.tx
+            .lock()
+            .unwrap()
+            .set_i32(&self.blk, FREE_END_OFFSET, rec, true)?;
+
+        Ok(rec)
+    }
+    // the records are packed at the bottom of the block in the order of their slots,
+    // so that the space of the deleted and the moved records is free again.
+    // a deleted record with large objects is kept, so that their chains are reused with the slot.
+    fn compact(&mut self) -> Result<()> {
+        let numslots = self.num_slots()?;
+        let keep_deleted = !self.layout.large_objects().is_empty();
+        let mut records = vec![];
+        for slot in 0..numslots {
+            let flag = self.flag(slot)?;
+            if !matches!(flag, Some(SlotFlag::USED | SlotFlag::MOVED)) && !keep_deleted {
+                continue;
+            }
+            let rec = self.record_offset(slot)?;
+            // the address of a record moved away is kept as it is
+            let bytes = if self.peek_i32(rec)? == FORWARDED {
+                self.tx.lock().unwrap().peek(&self.blk, |p| {
+                    Ok(p.get_raw_bytes(rec as usize, FORWARD_SIZE)?.to_vec())
+                })?
+            } else {
+                let image = self.read_image(rec)?;
+                image.to_bytes(&self.layout, image.length(&self.layout) as usize)?
+            };
+            records.push((slot, bytes));
+        }
+        let mut end = self.tx.lock().unwrap().block_size();
+        for (slot, bytes) in records {
+            end -= bytes.len() as i32;
+            self.tx
+                .lock()
+                .unwrap()
+                .set_bytes(&self.blk, end, &bytes, true)?;
+            self.set_record_offset(slot, end)?;
+        }
+
+        self.tx
+            .lock()
+            .unwrap()
+            .set_i32(&self.blk, FREE_END_OFFSET, end, true)
+    }
+    fn flag(&self, slot: i32) -> Result<Option<SlotFlag>> {
+        Ok(FromPrimitive::from_i32(self.peek_i32(flag_offset(slot))?))
+    }
+    fn set_flag(&mut self, slot: i32, flag: SlotFlag) -> Result<()> {
+        self.tx
+            .lock()
+            .unwrap()
+            .set_i32(&self.blk, flag_offset(slot), flag as i32, true)
+    }
+    fn set_record_offset(&mut self, slot: i32, rec: i32) -> Result<()> {
+        let pos = flag_offset(slot) + mem::size_of::<i32>() as i32;
+        self.tx.lock().unwrap().set_i32(&self.blk, pos, rec, true)
+    }
+    fn read_image(&self, rec: i32) -> Result<RecordImage> {
+        let layout = Arc::clone(&self.layout);
+        self.tx
+            .lock()
+            .unwrap()
+            .peek(&self.blk, |p| RecordImage::read(p, &layout, rec as usize))
+    }
+    fn write_image(&mut self, rec: i32, image: &RecordImage, len: i32) -> Result<()> {
+        let bytes = image.to_bytes(&self.layout, len as usize)?;
+        self.tx
+            .lock()
+            .unwrap()
+            .set_bytes(&self.blk, rec, &bytes, true)
+    }
+    fn peek_i32(&self, pos: i32) -> Result<i32> {
+        self.tx
+            .lock()
+            .unwrap()
+            .peek(&self.blk, |p| p.get_i32(pos as usize))
+    }
+}
+
+// the position of the flag of a slot, which is followed by the offset of its record
+pub fn flag_offset(slot: i32) -> i32 {
+    DIRECTORY_OFFSET + slot * SLOT_ENTRY_SIZE
+}
+
+// the block and the slot the record of a used slot has been moved to, if it has
+pub fn forwarded(p: &Page, slot: i32) -> Result<Option<(i32, i32)>> {
+    if slot >= p.get_i32(NUM_SLOTS_OFFSET as usize)?
+        || FromPrimitive::from_i32(p.get_i32(flag_offset(slot) as usize)?) != Some(SlotFlag::USED)
+    {
+        return Ok(None);
+    }
+    let rec = record_offset_in(p, slot)? as usize;
+    if p.get_i32(rec)? != FORWARDED {
+        return Ok(None);
+    }
+    let blknum = p.get_i32(rec + mem::size_of::<i32>())?;
+    let moved = p.get_i32(rec + 2 * mem::size_of::<i32>())?;
+
+    Ok(Some((blknum, moved)))
+}
+
+// the record in a slotted page, read without locks. None for an empty slot.
+// the values of the large objects are not in the page, and left to the caller.
+pub fn read_row(p: &Page, layout: &Layout, slot: i32) -> Result<Option<Row>> {
+    if slot >= p.get_i32(NUM_SLOTS_OFFSET as usize)? {
+        return Ok(None);
+    }
+    let pos = flag_offset(slot) as usize;
+    if FromPrimitive::from_i32(p.get_i32(pos)?) != Some(SlotFlag::USED) {
+        return Ok(None);
+    }
+
+    let rec = record_offset_in(p, slot)? as usize;
+    Ok(Some(read_fields(p, layout, rec)?))
+}
+
+// the values of the record at rec, whatever its slot is
+pub fn read_fields(p: &Page, layout: &Layout, rec: usize) -> Result<Row> {
+    let mut row = Row::new();
+    let sch = layout.schema();
+    for fldname in sch.fields() {
+        let (bitpos, mask) = layout.null_bit(fldname);
+        let name = layout.field_name(fldname).to_string();
+        if p.get_i32(rec + bitpos)? & mask != 0 {
+            row.insert(name, Constant::Null);
+            continue;
+        }
+        if !sch.field_type(fldname).is_large_object() {
+            row.insert(name, read_val(p, layout, rec, fldname)?);
+        }
+    }
+
+    Ok(row)
+}
+
+// where the record of the slot starts in the page
+pub fn record_offset_in(p: &Page, slot: i32) -> Result<i32> {
+    p.get_i32(flag_offset(slot) as usize + mem::size_of::<i32>())
+}
+
+fn read_val(p: &Page, layout: &Layout, rec: usize, fldname: &str) -> Result<Constant> {
+    let fldpos = rec + layout.offset(fldname);
+    let val = match layout.schema().field_type(fldname) {
+        FieldType::SMALLINT => Constant::new_i16(p.get_i16(fldpos)?),
+        FieldType::INTEGER => Constant::new_i32(p.get_i32(fldpos)?),
+        FieldType::VARCHAR => {
+            let strpos = rec + p.get_i32(fldpos)? as usize;
+            Constant::new_string(p.get_string(strpos)?)
+        }
+        FieldType::BOOL => Constant::new_bool(p.get_bool(fldpos)?),
+        FieldType::DATE => Constant::new_date(p.get_date(fldpos)?),
+        // the length and the first block of the chain, as they are
+        FieldType::TEXT | FieldType::BLOB => {
+            Constant::new_blob(p.get_raw_bytes(fldpos, LARGE_OBJECT_SIZE)?.to_vec())
+        }
+        FieldType::BIGINT => Constant::new_i64(p.get_i64(fldpos)?),
+        FieldType::DOUBLE => Constant::new_f64(p.get_f64(fldpos)?),
+        FieldType::DECIMAL => Constant::new_decimal(Decimal::new(
+            p.get_i64(fldpos)?,
+            layout.schema().scale(fldname) as u32,
+        )),
+        FieldType::TIMESTAMP => Constant::new_timestamp(p.get_timestamp(fldpos)?),
+        FieldType::TIME => Constant::new_time(p.get_time(fldpos)?),
+    };
+
+    Ok(val)
+}
+
+// the values of a record as they are stored, whatever its null bits are
+#[derive(Debug, Clone)]
+struct RecordImage {
+    nullbits: Vec<i32>,
+    vals: HashMap<String, Constant>,
+}
+
+impl RecordImage {
+    // the same values as a slot formatted by RecordPage
+    fn new(layout: &Layout) -> Self {
+        let sch = layout.schema();
+        let vals = sch
+            .fields()
+            .iter()
+            .map(|fldname| {
+                let val = match sch.field_type(fldname) {
+                    FieldType::SMALLINT => Constant::new_i16(0),
+                    FieldType::INTEGER => Constant::new_i32(0),
+                    FieldType::VARCHAR => Constant::new_string("".to_string()),
+                    FieldType::BOOL => Constant::new_bool(false),
+                    FieldType::DATE => Constant::new_date(
+                        NaiveDate::from_ymd_opt(0, 1, 1).unwrap(), // NOTE: default 0000-01-01
+                    ),
+                    FieldType::TEXT | FieldType::BLOB => large_object(0, NO_BLOCK),
+                    FieldType::BIGINT => Constant::new_i64(0),
+                    FieldType::DOUBLE => Constant::new_f64(0.0),
+                    FieldType::DECIMAL => Constant::new_decimal(Decimal::ZERO),
+                    // NOTE: default 1970-01-01 00:00:00
+                    FieldType::TIMESTAMP => Constant::new_timestamp(NaiveDateTime::default()),
+                    FieldType::TIME => Constant::new_time(NaiveTime::MIN),
+                };
+                (fldname.clone(), val)
+            })
+            .collect();
+
+        Self {
+            nullbits: vec![0; layout.null_bitmap().len()],
+            vals,
+        }
+    }
+    fn read(p: &Page, layout: &Layout, rec: usize) -> Result<Self> {
+        let mut nullbits = vec![];
+        for pos in layout.null_bitmap() {
+            nullbits.push(p.get_i32(rec + pos)?);
+        }
+        let mut vals = HashMap::new();
+        for fldname in layout.schema().fields() {
+            vals.insert(fldname.clone(), read_val(p, layout, rec, fldname)?);
+        }
+
+        Ok(Self { nullbits, vals })
+    }
+    // the large objects of the record are empty, but keep the chains of the old record
+    fn keep_chains(&mut self, layout: &Layout, old: &RecordImage) {
+        for fldname in layout.large_objects() {
+            let header = Page::new_from_bytes(old.vals[&fldname].as_blob().unwrap().to_vec());
+            let head = header.get_i32(mem::size_of::<i32>()).unwrap();
+            self.vals.insert(fldname, large_object(0, head));
+        }
+    }
+    fn length(&self, layout: &Layout) -> i32 {
+        let sch = layout.schema();
+        let strings = sch
+            .fields()
+            .iter()
+            .filter(|fldname| sch.field_type(fldname) == FieldType::VARCHAR)
+            .map(|fldname| Page::max_length(self.vals[fldname].as_string().unwrap().len()))
+            .sum::<usize>();
+        (layout.slot_size() + strings) as i32
+    }
+    fn to_bytes(&self, layout: &Layout, len: usize) -> Result<Vec<u8>> {
+        let mut p = Page::new_from_size(len);
+        p.set_i32(0, len as i32)?;
+        for (pos, bits) in layout.null_bitmap().into_iter().zip(&self.nullbits) {
+            p.set_i32(pos, *bits)?;
+        }
+        let sch = layout.schema();
+        let mut strpos = layout.slot_size();
+        for fldname in layout.sorted_fields() {
+            let fldpos = layout.offset(&fldname);
+            let val = &self.vals[&fldname];
+            match sch.field_type(&fldname) {
+                FieldType::SMALLINT => {
+                    p.set_i16(fldpos, val.as_i16()?)?;
+                }
+                FieldType::INTEGER => {
+                    p.set_i32(fldpos, val.as_i32()?)?;
+                }
+                FieldType::VARCHAR => {
+                    p.set_i32(fldpos, strpos as i32)?;
+                    strpos = p.set_string(strpos, val.as_string()?.to_string())?;
+                }
+                FieldType::BOOL => {
+                    p.set_bool(fldpos, val.as_bool()?)?;
+                }
+                FieldType::DATE => {
+                    p.set_date(fldpos, val.as_date()?)?;
+                }
+                FieldType::TEXT | FieldType::BLOB => {
+                    p.set_raw_bytes(fldpos, val.as_blob()?)?;
+                }
+                FieldType::BIGINT => {
+                    p.set_i64(fldpos, val.as_i64()?)?;
+                }
+                FieldType::DOUBLE => {
+                    p.set_f64(fldpos, val.as_f64()?)?;
+                }
+                FieldType::DECIMAL => {
+                    let unscaled = val.as_unscaled(sch.length(&fldname), sch.scale(&fldname))?;
+                    p.set_i64(fldpos, unscaled)?;
+                }
+                FieldType::TIMESTAMP => {
+                    p.set_timestamp(fldpos, val.as_timestamp()?)?;
+                }
+                FieldType::TIME => {
+                    p.set_time(fldpos, val.as_time()?)?;
+                }
+            }
+        }
+
+        Ok(p.contents().clone())
+    }
+}
+
+// the address a moved record leaves in its place
+fn forward_address(blknum: i32, slot: i32) -> Result<Vec<u8>> {
+    let mut p = Page::new_from_size(FORWARD_SIZE);
+    p.set_i32(0, FORWARDED)?;
+    p.set_i32(mem::size_of::<i32>(), blknum)?;
+    p.set_i32(2 * mem::size_of::<i32>(), slot)?;
+
+    Ok(p.contents().clone())
+}
+
+// the header of a large object in a record image
+fn large_object(len: i32, head: i32) -> Constant {
+    let mut p = Page::new_from_size(LARGE_OBJECT_SIZE);
+    p.set_i32(0, len).unwrap();
+    p.set_i32(mem::size_of::<i32>(), head).unwrap();
+    Constant::new_blob(p.contents().clone())
+}
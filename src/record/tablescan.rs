@@ -1,6 +1,7 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use core::fmt;
+use rust_decimal::Decimal;
 use std::sync::{Arc, Mutex};
 
 use super::{layout::Layout, recordpage::RecordPage, rid::RID, schema::FieldType};
@@ -8,13 +9,14 @@ use crate::{
     file::block_id::BlockId,
     materialize::sortscan::SortScan,
     query::{constant::Constant, scan::Scan, updatescan::UpdateScan},
-    tx::transaction::Transaction,
+    tx::{mvcc::versionstore::Row, transaction::Transaction},
 };
 
 #[derive(Debug)]
 pub enum TableScanError {
     NoRecordPage,
     DowncastError,
+    NotNullViolation(String),
 }
 
 impl std::error::Error for TableScanError {}
@@ -27,6 +29,9 @@ impl fmt::Display for TableScanError {
             TableScanError::DowncastError => {
                 write!(f, "downcast error")
             }
+            TableScanError::NotNullViolation(fldname) => {
+                write!(f, "null value in not null field: {}", fldname)
+            }
         }
     }
 }
@@ -38,6 +43,10 @@ pub struct TableScan {
     rp: Option<RecordPage>,
     filename: String,
     currentslot: i32,
+    // my own extends
+    // in MVCC mode, the current record as the snapshot sees it
+    mvcc: bool,
+    row: Option<Row>,
 }
 
 impl Scan for TableScan {
@@ -45,6 +54,9 @@ impl Scan for TableScan {
         self.move_to_block(0)
     }
     fn next(&mut self) -> bool {
+        if self.mvcc {
+            return self.next_visible();
+        }
         self.currentslot = self
             .rp
             .as_mut()
@@ -53,7 +65,16 @@ impl Scan for TableScan {
             .unwrap_or(-1);
         while self.currentslot < 0 {
             if self.at_last_block() {
-                return false;
+                // no block can be appended behind the scan, so that it sees no phantoms
+                self.tx
+                    .lock()
+                    .unwrap()
+                    .s_lock_end_of_file(&self.filename)
+                    .unwrap();
+                // unless one was appended while waiting for the lock
+                if self.at_last_block() {
+                    return false;
+                }
             }
             self.move_to_block(self.rp.as_ref().unwrap().block().number() + 1)
                 .unwrap();
@@ -68,34 +89,52 @@ impl Scan for TableScan {
         true
     }
     fn get_i16(&mut self, fldname: &str) -> Result<i16> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_i16();
+        }
         self.rp.as_mut().unwrap().get_i16(self.currentslot, fldname)
     }
     fn get_i32(&mut self, fldname: &str) -> Result<i32> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_i32();
+        }
         self.rp.as_mut().unwrap().get_i32(self.currentslot, fldname)
     }
     fn get_string(&mut self, fldname: &str) -> Result<String> {
+        if self.mvcc {
+            return Ok(self.cached_val(fldname).as_string()?.to_string());
+        }
         self.rp
             .as_mut()
             .unwrap()
             .get_string(self.currentslot, fldname)
     }
     fn get_bool(&mut self, fldname: &str) -> Result<bool> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_bool();
+        }
         self.rp
             .as_mut()
             .unwrap()
             .get_bool(self.currentslot, fldname)
     }
     fn get_date(&mut self, fldname: &str) -> Result<NaiveDate> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_date();
+        }
         self.rp
             .as_mut()
             .unwrap()
             .get_date(self.currentslot, fldname)
     }
     fn get_val(&mut self, fldname: &str) -> Result<Constant> {
+        if self.is_null(fldname)? {
+            return Ok(Constant::Null);
+        }
         return match self.layout.schema().field_type(fldname) {
             FieldType::SMALLINT => Ok(Constant::new_i16(self.get_i16(fldname).unwrap_or(0))),
             FieldType::INTEGER => Ok(Constant::new_i32(self.get_i32(fldname).unwrap_or(0))),
-            FieldType::VARCHAR => Ok(Constant::new_string(
+            FieldType::VARCHAR | FieldType::TEXT => Ok(Constant::new_string(
                 self.get_string(fldname).unwrap_or("".to_string()),
             )),
             FieldType::BOOL => Ok(Constant::new_bool(
@@ -105,6 +144,12 @@ impl Scan for TableScan {
                 self.get_date(fldname)
                     .unwrap_or(NaiveDate::from_ymd_opt(0, 1, 1).unwrap()), // NOTE: default 0000-01-01
             )),
+            FieldType::BLOB => Ok(Constant::new_blob(self.get_blob(fldname)?)),
+            FieldType::BIGINT => Ok(Constant::new_i64(self.get_i64(fldname)?)),
+            FieldType::DOUBLE => Ok(Constant::new_f64(self.get_f64(fldname)?)),
+            FieldType::DECIMAL => Ok(Constant::new_decimal(self.get_decimal(fldname)?)),
+            FieldType::TIMESTAMP => Ok(Constant::new_timestamp(self.get_timestamp(fldname)?)),
+            FieldType::TIME => Ok(Constant::new_time(self.get_time(fldname)?)),
         };
     }
     fn has_field(&self, fldname: &str) -> bool {
@@ -137,33 +182,51 @@ impl UpdateScan for TableScan {
         self.rp
             .as_mut()
             .unwrap()
-            .set_i16(self.currentslot, fldname, val)
+            .set_i16(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_i16(val));
+
+        Ok(())
     }
     fn set_i32(&mut self, fldname: &str, val: i32) -> Result<()> {
         self.rp
             .as_mut()
             .unwrap()
-            .set_i32(self.currentslot, fldname, val)
+            .set_i32(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_i32(val));
+
+        Ok(())
     }
     fn set_string(&mut self, fldname: &str, val: String) -> Result<()> {
         self.rp
             .as_mut()
             .unwrap()
-            .set_string(self.currentslot, fldname, val)
+            .set_string(self.currentslot, fldname, val.clone())?;
+        self.cache_val(fldname, Constant::new_string(val));
+
+        Ok(())
     }
     fn set_bool(&mut self, fldname: &str, val: bool) -> Result<()> {
         self.rp
             .as_mut()
             .unwrap()
-            .set_bool(self.currentslot, fldname, val)
+            .set_bool(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_bool(val));
+
+        Ok(())
     }
     fn set_date(&mut self, fldname: &str, val: NaiveDate) -> Result<()> {
         self.rp
             .as_mut()
             .unwrap()
-            .set_date(self.currentslot, fldname, val)
+            .set_date(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_date(val));
+
+        Ok(())
     }
     fn set_val(&mut self, fldname: &str, val: Constant) -> Result<()> {
+        if val.is_null() {
+            return self.set_null(fldname);
+        }
         match self.layout.schema().field_type(fldname) {
             FieldType::SMALLINT => {
                 self.set_i16(fldname, val.as_i16().unwrap())?;
@@ -171,7 +234,7 @@ impl UpdateScan for TableScan {
             FieldType::INTEGER => {
                 self.set_i32(fldname, val.as_i32().unwrap())?;
             }
-            FieldType::VARCHAR => {
+            FieldType::VARCHAR | FieldType::TEXT => {
                 self.set_string(fldname, val.as_string().unwrap().to_string())?;
             }
             FieldType::BOOL => {
@@ -180,6 +243,24 @@ impl UpdateScan for TableScan {
             FieldType::DATE => {
                 self.set_date(fldname, val.as_date().unwrap())?;
             }
+            FieldType::BLOB => {
+                self.set_blob(fldname, val.as_blob().unwrap())?;
+            }
+            FieldType::BIGINT => {
+                self.set_i64(fldname, val.as_i64()?)?;
+            }
+            FieldType::DOUBLE => {
+                self.set_f64(fldname, val.as_f64()?)?;
+            }
+            FieldType::DECIMAL => {
+                self.set_decimal(fldname, val.as_decimal()?)?;
+            }
+            FieldType::TIMESTAMP => {
+                self.set_timestamp(fldname, val.as_timestamp()?)?;
+            }
+            FieldType::TIME => {
+                self.set_time(fldname, val.as_time()?)?;
+            }
         }
 
         Ok(())
@@ -204,17 +285,26 @@ impl UpdateScan for TableScan {
                 .insert_after(self.currentslot)
                 .unwrap_or(-1);
         }
+        if self.mvcc {
+            self.row = self.rp.as_mut().unwrap().peek_row(self.currentslot)?;
+        }
 
         Ok(())
     }
     fn delete(&mut self) -> Result<()> {
-        self.rp.as_mut().unwrap().delete(self.currentslot)
+        self.rp.as_mut().unwrap().delete(self.currentslot)?;
+        self.row = None;
+
+        Ok(())
     }
     fn move_to_rid(&mut self, rid: RID) -> Result<()> {
         self.close()?;
         let blk = BlockId::new(&self.filename, rid.block_number());
         self.rp = RecordPage::new(Arc::clone(&self.tx), blk, Arc::clone(&self.layout))?.into();
         self.currentslot = rid.slot();
+        if self.mvcc {
+            self.row = self.rp.as_mut().unwrap().visible_row(self.currentslot)?;
+        }
 
         Ok(())
     }
@@ -232,15 +322,18 @@ impl UpdateScan for TableScan {
 impl TableScan {
     pub fn new(tx: Arc<Mutex<Transaction>>, tblname: &str, layout: Arc<Layout>) -> Result<Self> {
         let filename = format!("{}.tbl", tblname);
+        let mvcc = tx.lock().unwrap().is_mvcc();
         let mut scan = Self {
             tx,
             layout,
             rp: None, // dummy
             filename,
             currentslot: -1, // dummy
+            mvcc,
+            row: None,
         };
 
-        if scan.tx.lock().unwrap().size(&scan.filename)? == 0 {
+        if scan.tx.lock().unwrap().peek_size(&scan.filename)? == 0 {
             scan.move_to_new_block()?;
         } else {
             scan.move_to_block(0)?;
@@ -249,11 +342,185 @@ impl TableScan {
         Ok(scan)
     }
 
+    // my own extends
+    pub fn is_null(&mut self, fldname: &str) -> Result<bool> {
+        if self.mvcc {
+            return Ok(self.cached_val(fldname).is_null());
+        }
+        self.rp.as_mut().unwrap().is_null(self.currentslot, fldname)
+    }
+    pub fn set_null(&mut self, fldname: &str) -> Result<()> {
+        if !self.layout.schema().is_nullable(fldname) {
+            return Err(From::from(TableScanError::NotNullViolation(
+                fldname.to_string(),
+            )));
+        }
+        self.rp
+            .as_mut()
+            .unwrap()
+            .set_null(self.currentslot, fldname)?;
+        self.cache_val(fldname, Constant::Null);
+
+        Ok(())
+    }
+    // my own extends
+    // false when the record the scan was moved to does not exist in the snapshot.
+    pub fn is_visible(&self) -> bool {
+        !self.mvcc || self.row.is_some()
+    }
+    // my own extends
+    fn next_visible(&mut self) -> bool {
+        loop {
+            let rp = self.rp.as_mut().unwrap();
+            if let Some((slot, row)) = rp.visible_after(self.currentslot).unwrap() {
+                self.currentslot = slot;
+                self.row = Some(row);
+                return true;
+            }
+            if self.at_last_block() {
+                self.row = None;
+                return false;
+            }
+            self.move_to_block(self.rp.as_ref().unwrap().block().number() + 1)
+                .unwrap();
+        }
+    }
+    // my own extends
+    pub fn get_blob(&mut self, fldname: &str) -> Result<Vec<u8>> {
+        if self.mvcc {
+            return Ok(self.cached_val(fldname).as_blob()?.to_vec());
+        }
+        self.rp
+            .as_mut()
+            .unwrap()
+            .get_blob(self.currentslot, fldname)
+    }
+    // my own extends
+    pub fn set_blob(&mut self, fldname: &str, val: &[u8]) -> Result<()> {
+        self.rp
+            .as_mut()
+            .unwrap()
+            .set_blob(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_blob(val.to_vec()));
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_i64(&mut self, fldname: &str) -> Result<i64> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_i64();
+        }
+        self.rp.as_mut().unwrap().get_i64(self.currentslot, fldname)
+    }
+    // my own extends
+    pub fn set_i64(&mut self, fldname: &str, val: i64) -> Result<()> {
+        self.rp
+            .as_mut()
+            .unwrap()
+            .set_i64(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_i64(val));
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_f64(&mut self, fldname: &str) -> Result<f64> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_f64();
+        }
+        self.rp.as_mut().unwrap().get_f64(self.currentslot, fldname)
+    }
+    // my own extends
+    pub fn set_f64(&mut self, fldname: &str, val: f64) -> Result<()> {
+        self.rp
+            .as_mut()
+            .unwrap()
+            .set_f64(self.currentslot, fldname, val)?;
+        self.cache_val(fldname, Constant::new_f64(val));
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_decimal(&mut self, fldname: &str) -> Result<Decimal> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_decimal();
+        }
+        self.rp
+            .as_mut()
+            .unwrap()
+            .get_decimal(self.currentslot, fldname)
+    }
+    // my own extends
+    // the value is rounded to the scale of the field
+    pub fn set_decimal(&mut self, fldname: &str, val: Decimal) -> Result<()> {
+        let rp = self.rp.as_mut().unwrap();
+        rp.set_decimal(self.currentslot, fldname, val)?;
+        let val = rp.get_decimal(self.currentslot, fldname)?;
+        self.cache_val(fldname, Constant::new_decimal(val));
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_timestamp(&mut self, fldname: &str) -> Result<NaiveDateTime> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_timestamp();
+        }
+        self.rp
+            .as_mut()
+            .unwrap()
+            .get_timestamp(self.currentslot, fldname)
+    }
+    // my own extends
+    // the value is truncated to microseconds
+    pub fn set_timestamp(&mut self, fldname: &str, val: NaiveDateTime) -> Result<()> {
+        let rp = self.rp.as_mut().unwrap();
+        rp.set_timestamp(self.currentslot, fldname, val)?;
+        let val = rp.get_timestamp(self.currentslot, fldname)?;
+        self.cache_val(fldname, Constant::new_timestamp(val));
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_time(&mut self, fldname: &str) -> Result<NaiveTime> {
+        if self.mvcc {
+            return self.cached_val(fldname).as_time();
+        }
+        self.rp
+            .as_mut()
+            .unwrap()
+            .get_time(self.currentslot, fldname)
+    }
+    // my own extends
+    // the value is truncated to microseconds
+    pub fn set_time(&mut self, fldname: &str, val: NaiveTime) -> Result<()> {
+        let rp = self.rp.as_mut().unwrap();
+        rp.set_time(self.currentslot, fldname, val)?;
+        let val = rp.get_time(self.currentslot, fldname)?;
+        self.cache_val(fldname, Constant::new_time(val));
+
+        Ok(())
+    }
+    // my own extends
+    // the row is keyed by the names of the fields in the table, see Layout::field_name
+    fn cached_val(&self, fldname: &str) -> Constant {
+        self.row
+            .as_ref()
+            .and_then(|row| row.get(self.layout.field_name(fldname)))
+            .cloned()
+            .unwrap_or(Constant::Null)
+    }
+    // my own extends
+    fn cache_val(&mut self, fldname: &str, val: Constant) {
+        if let Some(row) = self.row.as_mut() {
+            row.insert(self.layout.field_name(fldname).to_string(), val);
+        }
+    }
+
     fn move_to_block(&mut self, blknum: i32) -> Result<()> {
         self.close()?;
         let blk = BlockId::new(&self.filename, blknum);
         self.rp = RecordPage::new(Arc::clone(&self.tx), blk, Arc::clone(&self.layout))?.into();
         self.currentslot = -1;
+        self.row = None;
 
         Ok(())
     }
@@ -266,9 +533,10 @@ impl TableScan {
 
         Ok(())
     }
+    // the end of the file is locked by next and append, when a scan gets there.
     fn at_last_block(&self) -> bool {
         self.rp.as_ref().unwrap().block().number()
-            == self.tx.lock().unwrap().size(&self.filename).unwrap() - 1
+            >= self.tx.lock().unwrap().peek_size(&self.filename).unwrap() - 1
     }
 }
 
@@ -278,7 +546,11 @@ mod tests {
     use std::{fs, path::Path};
 
     use super::*;
-    use crate::{record::schema::Schema, server::simpledb::SimpleDB};
+    use crate::{
+        record::{layout::RecordFormat, schema::Schema},
+        server::simpledb::SimpleDB,
+        tx::concurrency::isolation::IsolationLevel,
+    };
 
     #[test]
     fn unit_test() -> Result<()> {
@@ -334,4 +606,407 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn slotted_test() -> Result<()> {
+        if Path::new("_test/tablescan_slotted").exists() {
+            fs::remove_dir_all("_test/tablescan_slotted")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_slotted", 400, 8);
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        sch.add_string_field("B", 40);
+        let layout = Arc::new(Layout::new_with_format(
+            Arc::new(sch),
+            RecordFormat::Slotted,
+        ));
+
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", layout)?;
+        println!("Filling the table with 50 records.");
+        let mut rids = vec![];
+        for n in 0..50 {
+            ts.insert()?;
+            ts.set_i32("A", n)?;
+            ts.set_string("B", format!("rec{}", n))?;
+            rids.push(ts.get_rid()?);
+        }
+        println!("the last record is at {}", rids[49]);
+        println!("Lengthening the records with odd A-values.");
+        ts.before_first()?;
+        while ts.next() {
+            let a = ts.get_i32("A")?;
+            if a % 2 == 1 {
+                ts.set_string("B", format!("record{}", a))?;
+            }
+        }
+        println!("Reading the records by their RIDs.");
+        for (n, rid) in rids.iter().enumerate() {
+            ts.move_to_rid(*rid)?;
+            let a = ts.get_i32("A")?;
+            let b = ts.get_string("B")?;
+            println!("slot {}: {{{}, {}}}", rid, a, b);
+            assert_eq!(a, n as i32);
+            if a % 2 == 1 {
+                assert_eq!(b, format!("record{}", a));
+            } else {
+                assert_eq!(b, format!("rec{}", a));
+            }
+        }
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_object_test() -> Result<()> {
+        if Path::new("_test/tablescan_lob").exists() {
+            fs::remove_dir_all("_test/tablescan_lob")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_lob", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        sch.add_text_field("B");
+        sch.add_blob_field("C");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let text = |n: usize| -> String { "abcdefghij".repeat(n) };
+        let blob = |n: usize| -> Vec<u8> { (0..n).map(|i| i as u8).collect() };
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        println!("Inserting values longer than a block.");
+        let mut rids = vec![];
+        for n in 0..3 {
+            ts.insert()?;
+            ts.set_i32("A", n)?;
+            ts.set_string("B", text(100 * (n as usize + 1)))?;
+            ts.set_blob("C", &blob(500))?;
+            rids.push(ts.get_rid()?);
+        }
+        for (n, rid) in rids.iter().enumerate() {
+            ts.move_to_rid(*rid)?;
+            let b = ts.get_string("B")?;
+            let c = ts.get_blob("C")?;
+            println!("slot {}: B: {} bytes, C: {} bytes", rid, b.len(), c.len());
+            assert_eq!(b, text(100 * (n + 1)));
+            assert_eq!(c, blob(500));
+        }
+        println!("Shortening and lengthening the values.");
+        ts.move_to_rid(rids[0])?;
+        ts.set_string("B", "short".to_string())?;
+        ts.set_blob("C", &blob(2000))?;
+        assert_eq!(ts.get_string("B")?, "short");
+        assert_eq!(ts.get_blob("C")?, blob(2000));
+        println!("Deleting a record and reusing its slot.");
+        ts.move_to_rid(rids[1])?;
+        ts.delete()?;
+        ts.insert()?;
+        ts.set_i32("A", 9)?;
+        println!("the new record is at {}", ts.get_rid()?);
+        assert_eq!(ts.get_string("B")?, "");
+        assert!(ts.get_blob("C")?.is_empty());
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        println!("Rolling back an update.");
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rids[2])?;
+        ts.set_string("B", text(200))?;
+        ts.close()?;
+        tx.lock().unwrap().rollback()?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rids[2])?;
+        assert_eq!(ts.get_string("B")?, text(300));
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn numeric_test() -> Result<()> {
+        if Path::new("_test/tablescan_numeric").exists() {
+            fs::remove_dir_all("_test/tablescan_numeric")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_numeric", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i64_field("A");
+        sch.add_f64_field("B");
+        sch.add_decimal_field("C", 10, 2);
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.insert()?;
+        ts.set_i64("A", 3_000_000_000)?;
+        ts.set_f64("B", 0.1 + 0.2)?;
+        ts.set_decimal("C", Decimal::new(1005, 3))?;
+        let rid = ts.get_rid()?;
+        println!(
+            "A: {}, B: {}, C: {}",
+            ts.get_i64("A")?,
+            ts.get_f64("B")?,
+            ts.get_decimal("C")?
+        );
+        assert_eq!(ts.get_i64("A")?, 3_000_000_000);
+        assert_eq!(ts.get_f64("B")?, 0.1 + 0.2);
+        // rounded half away from zero to the scale of the field
+        assert_eq!(ts.get_decimal("C")?, Decimal::new(101, 2));
+        // DECIMAL(10, 2) holds at most 8 integral digits
+        assert!(ts.set_decimal("C", Decimal::new(100_000_000, 0)).is_err());
+        ts.set_decimal("C", Decimal::new(-9_999_999_999, 2))?;
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        println!("Rolling back an update.");
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rid)?;
+        ts.set_i64("A", -1)?;
+        ts.set_f64("B", f64::NAN)?;
+        ts.close()?;
+        tx.lock().unwrap().rollback()?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rid)?;
+        assert_eq!(ts.get_i64("A")?, 3_000_000_000);
+        assert_eq!(ts.get_f64("B")?, 0.1 + 0.2);
+        assert_eq!(ts.get_decimal("C")?, Decimal::new(-9_999_999_999, 2));
+        // numbers of different types compare by value
+        assert_eq!(ts.get_val("C")?, Constant::new_f64(-99_999_999.99));
+        assert!(ts.get_val("A")? > Constant::new_i32(i32::MAX));
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn temporal_test() -> Result<()> {
+        if Path::new("_test/tablescan_temporal").exists() {
+            fs::remove_dir_all("_test/tablescan_temporal")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_temporal", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_timestamp_field("A");
+        sch.add_time_field("B");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let day = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.insert()?;
+        // the nanoseconds are truncated to microseconds
+        ts.set_timestamp("A", day.and_hms_nano_opt(23, 59, 59, 123_456_789).unwrap())?;
+        ts.set_val("B", Constant::new_string("08:30:00".to_string()))?;
+        let rid = ts.get_rid()?;
+        println!("A: {}, B: {}", ts.get_val("A")?, ts.get_val("B")?);
+        assert_eq!(
+            ts.get_timestamp("A")?,
+            day.and_hms_micro_opt(23, 59, 59, 123_456).unwrap()
+        );
+        assert_eq!(
+            ts.get_time("B")?,
+            NaiveTime::from_hms_opt(8, 30, 0).unwrap()
+        );
+        assert_eq!(
+            ts.get_val("A")?,
+            Constant::new_string("2024-02-29 23:59:59.123456".to_string())
+        );
+        assert!(ts.get_val("A")? > Constant::new_date(day));
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        println!("Rolling back an update.");
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rid)?;
+        ts.set_timestamp("A", NaiveDateTime::default())?;
+        ts.set_time("B", NaiveTime::MIN)?;
+        ts.close()?;
+        tx.lock().unwrap().rollback()?;
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        ts.move_to_rid(rid)?;
+        assert_eq!(
+            ts.get_timestamp("A")?,
+            day.and_hms_micro_opt(23, 59, 59, 123_456).unwrap()
+        );
+        assert_eq!(
+            ts.get_time("B")?,
+            NaiveTime::from_hms_opt(8, 30, 0).unwrap()
+        );
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn phantom_test() -> Result<()> {
+        if Path::new("_test/tablescan_phantom").exists() {
+            fs::remove_dir_all("_test/tablescan_phantom")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_phantom", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        for n in 0..3 {
+            ts.insert()?;
+            ts.set_i32("A", n)?;
+        }
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        let count = |tx: &Arc<Mutex<Transaction>>| -> Result<i32> {
+            let mut ts = TableScan::new(Arc::clone(tx), "T", Arc::clone(&layout))?;
+            let mut count = 0;
+            while ts.next() {
+                count += 1;
+            }
+            ts.close()?;
+            Ok(count)
+        };
+
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let first = count(&tx1)?;
+        println!("Tx 1: {} records", first);
+
+        // the free slots have been read by Tx 1, and the end of the file is locked
+        let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx2), "T", Arc::clone(&layout))?;
+        let result = ts.insert();
+        println!("Tx 2: insert: {:?}", result);
+        assert!(result.is_err());
+        ts.close()?;
+        tx2.lock().unwrap().rollback()?;
+
+        let second = count(&tx1)?;
+        println!("Tx 1: {} records", second);
+        assert_eq!(first, second);
+        tx1.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn isolation_test() -> Result<()> {
+        if Path::new("_test/tablescan_isolation").exists() {
+            fs::remove_dir_all("_test/tablescan_isolation")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tablescan_isolation", 400, 8);
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx), "T", Arc::clone(&layout))?;
+        for n in 0..3 {
+            ts.insert()?;
+            ts.set_i32("A", n)?;
+        }
+        ts.close()?;
+        tx.lock().unwrap().commit()?;
+
+        let values = |tx: &Arc<Mutex<Transaction>>| -> Result<Vec<i32>> {
+            let mut ts = TableScan::new(Arc::clone(tx), "T", Arc::clone(&layout))?;
+            let mut values = vec![];
+            while ts.next() {
+                values.push(ts.get_i32("A")?);
+            }
+            ts.close()?;
+            Ok(values)
+        };
+        let update = |tx: &Arc<Mutex<Transaction>>, val: i32| -> Result<()> {
+            let mut ts = TableScan::new(Arc::clone(tx), "T", Arc::clone(&layout))?;
+            ts.next();
+            ts.set_i32("A", val)?;
+            ts.close()
+        };
+
+        // read committed: the read locks are given back, so the record can be updated
+        let tx1 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        tx1.lock()
+            .unwrap()
+            .set_isolation_level(IsolationLevel::ReadCommitted);
+        let first = values(&tx1)?;
+        println!(
+            "Tx 1 ({}): {:?}",
+            tx1.lock().unwrap().isolation_level(),
+            first
+        );
+        let tx2 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        update(&tx2, 100)?;
+        tx2.lock().unwrap().commit()?;
+        let second = values(&tx1)?;
+        println!(
+            "Tx 1 ({}): {:?}",
+            tx1.lock().unwrap().isolation_level(),
+            second
+        );
+        assert_eq!(vec![100, 1, 2], second);
+        tx1.lock().unwrap().commit()?;
+
+        // read uncommitted: the changes of a running transaction are seen
+        let tx3 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        update(&tx3, 200)?;
+        let tx4 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        tx4.lock()
+            .unwrap()
+            .set_isolation_level(IsolationLevel::ReadUncommitted);
+        let dirty = values(&tx4)?;
+        println!(
+            "Tx 4 ({}): {:?}",
+            tx4.lock().unwrap().isolation_level(),
+            dirty
+        );
+        assert_eq!(vec![200, 1, 2], dirty);
+        tx4.lock().unwrap().commit()?;
+        tx3.lock().unwrap().rollback()?;
+
+        // repeatable read: the end of the file is not locked, so a record can be appended
+        let tx5 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        tx5.lock()
+            .unwrap()
+            .set_isolation_level(IsolationLevel::RepeatableRead);
+        let first = values(&tx5)?;
+        println!(
+            "Tx 5 ({}): {:?}",
+            tx5.lock().unwrap().isolation_level(),
+            first
+        );
+        let tx6 = Arc::new(Mutex::new(simpledb.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx6), "T", Arc::clone(&layout))?;
+        ts.insert()?;
+        ts.set_i32("A", 300)?;
+        ts.close()?;
+        tx6.lock().unwrap().commit()?;
+        let second = values(&tx5)?;
+        println!(
+            "Tx 5 ({}): {:?}",
+            tx5.lock().unwrap().isolation_level(),
+            second
+        );
+        assert_eq!(first.len() + 1, second.len());
+        tx5.lock().unwrap().commit()?;
+
+        Ok(())
+    }
 }
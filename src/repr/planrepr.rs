@@ -1,6 +1,10 @@
 use std::sync::Arc;
 
-use crate::query::{constant::Constant, predicate::Predicate};
+use crate::{
+    index::SearchRange,
+    materialize::recordcomparator::SortOrder,
+    query::{constant::Constant, join::JoinType, predicate::Predicate},
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Operation {
@@ -8,12 +12,18 @@ pub enum Operation {
         idxname: String,
         idxfldname: String,
         joinfld: String,
+        join_type: JoinType,
     },
     IndexSelectScan {
         idxname: String,
         idxfldname: String,
         val: Constant,
     },
+    IndexRangeScan {
+        idxname: String,
+        idxfldname: String,
+        ranges: Vec<SearchRange>,
+    },
     GroupByScan {
         fields: Vec<String>,
         aggfns: Vec<(String, Constant)>,
@@ -22,12 +32,19 @@ pub enum Operation {
     MergeJoinScan {
         fldname1: String,
         fldname2: String,
+        join_type: JoinType,
     },
     SortScan {
         compflds: Vec<String>,
+        orders: Vec<SortOrder>,
     },
     MultibufferProductScan,
     ProductScan,
+    // my own extends
+    ProductJoinScan {
+        join_type: JoinType,
+        pred: Predicate,
+    },
     ProjectScan,
     SelectScan {
         pred: Predicate,
@@ -14,6 +14,9 @@ pub struct SimpleDBConfig {
     pub buffer_manager: BufferMgr,
     // query planner
     pub query_planner: QueryPlanner,
+    // my own extends
+    // concurrency manager
+    pub concurrency_mgr: ConcurrencyMgr,
 }
 
 arg_enum! {
@@ -41,3 +44,12 @@ arg_enum! {
         Heuristic,
     }
 }
+
+// my own extends
+arg_enum! {
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum ConcurrencyMgr {
+        Locking,
+        MVCC,
+    }
+}
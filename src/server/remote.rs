@@ -1,6 +1,6 @@
 use capnp::capability::Promise;
 use capnp_rpc::pry;
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use log::{debug, info, trace};
 use std::{
     cell::RefCell,
@@ -10,16 +10,29 @@ use std::{
 
 use super::simpledb::SimpleDB;
 use crate::{
+    index::RangeBound,
+    materialize::recordcomparator::SortOrder,
+    parser::tcl::TCL,
     plan::{plan::Plan, planner::Planner},
-    query::{constant::Constant, expression::Expression, scan::Scan},
+    query::{
+        constant::Constant,
+        expression::{ArithOp, Expression},
+        join::JoinType,
+        predicate::Predicate,
+        scalarfn::Function,
+        scan::Scan,
+        term::Operator,
+    },
+    rdbc::connectionadapter::{ConnectionError, TxBlock},
     record::schema::{FieldType, Schema},
     remote_capnp::{
-        self, affected, bool_box, date_box, int16_box, int32_box, remote_connection, remote_driver,
-        remote_meta_data, remote_result_set, remote_statement, schema, string_box, tx_box,
+        self, affected, blob_box, bool_box, date_box, decimal_box, float64_box, int16_box,
+        int32_box, int64_box, remote_connection, remote_driver, remote_meta_data,
+        remote_result_set, remote_statement, schema, string_box, time_box, timestamp_box, tx_box,
     },
     repr,
     repr::planrepr::PlanRepr,
-    tx::transaction::Transaction,
+    tx::{concurrency::isolation::IsolationLevel, transaction::Transaction},
 };
 
 const MAJOR_VERSION: i32 = 0;
@@ -79,6 +92,10 @@ impl remote_driver::Server for RemoteDriverImpl {
 pub struct ConnectionInternal {
     db: Arc<Mutex<SimpleDB>>,
     current_tx: Arc<Mutex<Transaction>>,
+    // my own extends
+    block: TxBlock,
+    // my own extends
+    autocommit: bool,
 }
 impl ConnectionInternal {
     pub fn close(&mut self) -> anyhow::Result<()> {
@@ -88,17 +105,21 @@ impl ConnectionInternal {
         let tx_num = self.current_tx.lock().unwrap().tx_num();
         trace!("close tx: {}", tx_num);
         self.current_tx.lock().unwrap().commit()?;
+        self.checkpoint_if_needed()?;
         self.renew_tx()
     }
     pub fn commit(&mut self) -> anyhow::Result<()> {
+        self.block = TxBlock::None;
         self.dump_statistics();
 
         // Essential body
         let tx_num = self.current_tx.lock().unwrap().tx_num();
         trace!("commit tx: {}", tx_num);
-        self.current_tx.lock().unwrap().commit()
+        self.current_tx.lock().unwrap().commit()?;
+        self.checkpoint_if_needed()
     }
     pub fn rollback(&mut self) -> anyhow::Result<()> {
+        self.block = TxBlock::None;
         self.dump_statistics();
 
         // Essential body
@@ -107,13 +128,80 @@ impl ConnectionInternal {
         self.current_tx.lock().unwrap().rollback()
     }
     pub fn renew_tx(&mut self) -> anyhow::Result<()> {
-        let new_tx = self.db.lock().unwrap().new_tx()?;
+        let level = self.current_tx.lock().unwrap().isolation_level();
+        let mut new_tx = self.db.lock().unwrap().new_tx()?;
+        new_tx.set_isolation_level(level);
         trace!("start new tx: {}", new_tx.tx_num());
         self.current_tx = Arc::new(Mutex::new(new_tx));
 
         Ok(())
     }
     // my own extends
+    // the same transaction blocks as EmbeddedConnection::execute_tcl.
+    pub fn execute_tcl(&mut self, tcl: TCL) -> anyhow::Result<()> {
+        match (tcl, self.block) {
+            (TCL::Commit | TCL::Rollback, TxBlock::Failed) => {
+                self.block = TxBlock::None;
+                Ok(())
+            }
+            (_, TxBlock::Failed) => Err(From::from(ConnectionError::TransactionAborted)),
+            (TCL::Begin, _) => {
+                self.block = TxBlock::Open;
+                Ok(())
+            }
+            (TCL::Commit, _) => self.commit().and_then(|_| self.renew_tx()),
+            (TCL::Rollback, _) => self.rollback().and_then(|_| self.renew_tx()),
+            (TCL::Savepoint(name), _) => self.current_tx.lock().unwrap().savepoint(&name),
+            (TCL::RollbackToSavepoint(name), _) => {
+                self.current_tx.lock().unwrap().rollback_to_savepoint(&name)
+            }
+            (TCL::SetIsolationLevel(level), _) => {
+                self.current_tx.lock().unwrap().set_isolation_level(level);
+                Ok(())
+            }
+        }
+    }
+    // my own extends
+    // in autocommit mode, a statement is committed, unless it is in a transaction block.
+    pub fn finish_statement(&mut self) -> anyhow::Result<()> {
+        if self.autocommit && self.block == TxBlock::None {
+            return self.close();
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // the same as EmbeddedConnection::fail_statement.
+    pub fn fail_statement(&mut self) -> anyhow::Result<()> {
+        let block = self.block;
+        self.rollback()?;
+        self.renew_tx()?;
+        if block != TxBlock::None {
+            self.block = TxBlock::Failed;
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn set_autocommit(&mut self, autocommit: bool) -> anyhow::Result<()> {
+        let turned_on = autocommit && !self.autocommit;
+        self.autocommit = autocommit;
+        if turned_on {
+            return self.finish_statement();
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // the log may have grown enough by the committed transaction for a checkpoint.
+    fn checkpoint_if_needed(&self) -> anyhow::Result<()> {
+        if self.db.lock().unwrap().checkpoint_if_needed()? {
+            info!("checkpoint");
+        }
+
+        Ok(())
+    }
+    // my own extends
     pub fn current_tx_num(&self) -> i32 {
         self.current_tx.lock().unwrap().tx_num()
     }
@@ -187,6 +275,8 @@ impl RemoteConnectionImpl {
         let conn = ConnectionInternal {
             db,
             current_tx: Arc::new(Mutex::new(tx)),
+            block: TxBlock::None,
+            autocommit: true,
         };
 
         Self {
@@ -210,14 +300,25 @@ fn set_schema(schema: Arc<Schema>, sch: &mut schema::Builder) {
         entries.reborrow().get(i as u32).set_key(fldname).unwrap();
         let mut val = entries.reborrow().get(i as u32).init_value();
         val.reborrow().set_length(fi.length as i32);
-        let t = match fi.fld_type {
-            FieldType::SMALLINT => remote_capnp::FieldType::SmallInt,
-            FieldType::INTEGER => remote_capnp::FieldType::Integer,
-            FieldType::VARCHAR => remote_capnp::FieldType::Varchar,
-            FieldType::BOOL => remote_capnp::FieldType::Bool,
-            FieldType::DATE => remote_capnp::FieldType::Date,
-        };
-        val.reborrow().set_type(t);
+        val.reborrow().set_nullable(fi.nullable);
+        val.reborrow().set_scale(fi.scale as i32);
+        val.reborrow().set_type(remote_field_type(fi.fld_type));
+    }
+}
+fn remote_field_type(fld_type: FieldType) -> remote_capnp::FieldType {
+    match fld_type {
+        FieldType::SMALLINT => remote_capnp::FieldType::SmallInt,
+        FieldType::INTEGER => remote_capnp::FieldType::Integer,
+        FieldType::VARCHAR => remote_capnp::FieldType::Varchar,
+        FieldType::BOOL => remote_capnp::FieldType::Bool,
+        FieldType::DATE => remote_capnp::FieldType::Date,
+        FieldType::TEXT => remote_capnp::FieldType::Text,
+        FieldType::BLOB => remote_capnp::FieldType::Blob,
+        FieldType::BIGINT => remote_capnp::FieldType::BigInt,
+        FieldType::DOUBLE => remote_capnp::FieldType::Double,
+        FieldType::DECIMAL => remote_capnp::FieldType::Decimal,
+        FieldType::TIMESTAMP => remote_capnp::FieldType::Timestamp,
+        FieldType::TIME => remote_capnp::FieldType::Time,
     }
 }
 fn set_constant(cnst: &Constant, c: &mut remote_statement::constant::Builder) {
@@ -240,6 +341,57 @@ fn set_constant(cnst: &Constant, c: &mut remote_statement::constant::Builder) {
             dt.set_month(d.month() as u8);
             dt.set_day(d.day() as u8);
         }
+        Constant::Null => {
+            c.set_null(());
+        }
+        Constant::Blob(b) => {
+            c.set_blob(b);
+        }
+        Constant::I64(v) => {
+            c.set_int64(*v);
+        }
+        Constant::F64(v) => {
+            c.set_float64(*v);
+        }
+        Constant::Decimal(d) => {
+            c.set_decimal(d.to_string().as_str());
+        }
+        Constant::Timestamp(ts) => {
+            set_timestamp(ts, c.reborrow().init_timestamp());
+        }
+        Constant::Time(t) => {
+            set_time(t, c.reborrow().init_time());
+        }
+    }
+}
+// my own extends
+fn set_timestamp(ts: &NaiveDateTime, mut b: remote_capnp::timestamp::Builder) {
+    let mut dt = b.reborrow().init_date();
+    dt.set_year(ts.year() as i16);
+    dt.set_month(ts.month() as u8);
+    dt.set_day(ts.day() as u8);
+    set_time(&ts.time(), b.init_time());
+}
+// my own extends
+fn set_time(t: &NaiveTime, mut b: remote_capnp::time::Builder) {
+    b.set_hour(t.hour() as u8);
+    b.set_minute(t.minute() as u8);
+    b.set_second(t.second() as u8);
+    b.set_microsecond(t.nanosecond() / 1_000);
+}
+fn set_range_bound(bound: &RangeBound, b: &mut remote_statement::range_bound::Builder) {
+    match bound {
+        RangeBound::Included(c) => {
+            let mut v = b.reborrow().init_included();
+            set_constant(c, &mut v);
+        }
+        RangeBound::Excluded(c) => {
+            let mut v = b.reborrow().init_excluded();
+            set_constant(c, &mut v);
+        }
+        RangeBound::Unbounded => {
+            b.set_unbounded(());
+        }
     }
 }
 fn set_expression(expr: &Expression, e: &mut remote_statement::expression::Builder) {
@@ -251,6 +403,86 @@ fn set_expression(expr: &Expression, e: &mut remote_statement::expression::Build
             let mut v = e.reborrow().init_val();
             set_constant(c, &mut v);
         }
+        Expression::Binary(lhs, op, rhs) => {
+            let mut b = e.reborrow().init_binary();
+            set_expression(lhs, &mut b.reborrow().init_lhs());
+            set_expression(rhs, &mut b.reborrow().init_rhs());
+            let op = match op {
+                ArithOp::Add => remote_statement::ArithOp::Add,
+                ArithOp::Sub => remote_statement::ArithOp::Sub,
+                ArithOp::Mul => remote_statement::ArithOp::Mul,
+                ArithOp::Div => remote_statement::ArithOp::Div,
+                ArithOp::Mod => remote_statement::ArithOp::Mod,
+                ArithOp::Concat => remote_statement::ArithOp::Concat,
+            };
+            b.set_op(op);
+        }
+        Expression::Neg(expr) => {
+            set_expression(expr, &mut e.reborrow().init_neg());
+        }
+        Expression::Call(func, args) => {
+            let mut c = e.reborrow().init_call();
+            c.set_name(func.name());
+            let mut list = c.reborrow().init_args(args.len() as u32);
+            for (i, arg) in args.iter().enumerate() {
+                set_expression(arg, &mut list.reborrow().get(i as u32));
+            }
+            if let Function::Cast {
+                fld_type,
+                length,
+                scale,
+            } = func
+            {
+                let mut t = c.reborrow().init_cast_type();
+                t.set_type(remote_field_type(*fld_type));
+                t.set_length(*length as i32);
+                t.set_scale(*scale as i32);
+            }
+        }
+    }
+}
+fn set_predicate(pred: &Predicate, p: &mut remote_statement::predicate::Builder) {
+    let mut ts = p.reborrow().init_terms(pred.terms().len() as u32);
+    for (i, term) in pred.terms().iter().enumerate() {
+        let mut t = ts.reborrow().get(i as u32);
+        let mut lhs = t.reborrow().init_lhs();
+        set_expression(term.lhs(), &mut lhs);
+        let mut rhs = t.reborrow().init_rhs();
+        set_expression(term.rhs(), &mut rhs);
+        let op = match term.op() {
+            Operator::Eq => remote_statement::Operator::Eq,
+            Operator::Ne => remote_statement::Operator::Ne,
+            Operator::Lt => remote_statement::Operator::Lt,
+            Operator::Le => remote_statement::Operator::Le,
+            Operator::Gt => remote_statement::Operator::Gt,
+            Operator::Ge => remote_statement::Operator::Ge,
+            Operator::IsNull => remote_statement::Operator::IsNull,
+            Operator::IsNotNull => remote_statement::Operator::IsNotNull,
+            Operator::Like => remote_statement::Operator::Like,
+            Operator::NotLike => remote_statement::Operator::NotLike,
+        };
+        t.set_op(op);
+    }
+    let mut ds = p
+        .reborrow()
+        .init_disjunctions(pred.disjunctions().len() as u32);
+    for (i, disjunction) in pred.disjunctions().iter().enumerate() {
+        let d = ds.reborrow().get(i as u32);
+        let mut ps = d.init_preds(disjunction.len() as u32);
+        for (j, subpred) in disjunction.iter().enumerate() {
+            let mut sp = ps.reborrow().get(j as u32);
+            set_predicate(subpred, &mut sp);
+        }
+    }
+}
+
+// my own extends
+fn remote_join_type(join_type: JoinType) -> remote_statement::JoinType {
+    match join_type {
+        JoinType::Inner => remote_statement::JoinType::Inner,
+        JoinType::Left => remote_statement::JoinType::Left,
+        JoinType::Right => remote_statement::JoinType::Right,
+        JoinType::Full => remote_statement::JoinType::Full,
     }
 }
 
@@ -265,11 +497,13 @@ fn set_operation(
             idxname,
             idxfldname,
             joinfld,
+            join_type,
         } => {
             let mut op = op.init_index_join_scan();
             op.set_idxname(idxname.as_str());
             op.set_idxfldname(idxfldname.as_str());
             op.set_joinfld(joinfld.as_str());
+            op.set_join_type(remote_join_type(join_type));
         }
         repr::planrepr::Operation::IndexSelectScan {
             idxname,
@@ -282,6 +516,23 @@ fn set_operation(
             let mut v = op.init_val();
             set_constant(&val, &mut v);
         }
+        repr::planrepr::Operation::IndexRangeScan {
+            idxname,
+            idxfldname,
+            ranges,
+        } => {
+            let mut op = op.init_index_range_scan();
+            op.set_idxname(idxname.as_str());
+            op.set_idxfldname(idxfldname.as_str());
+            let mut rs = op.init_ranges(ranges.len() as u32);
+            for (i, range) in ranges.iter().enumerate() {
+                let mut r = rs.reborrow().get(i as u32);
+                let mut low = r.reborrow().init_low();
+                set_range_bound(range.low(), &mut low);
+                let mut high = r.init_high();
+                set_range_bound(range.high(), &mut high);
+            }
+        }
         repr::planrepr::Operation::GroupByScan { fields, aggfns } => {
             let mut op = op.init_group_by_scan();
             let mut flds = op.reborrow().init_fields(fields.len() as u32);
@@ -299,17 +550,30 @@ fn set_operation(
         repr::planrepr::Operation::Materialize => {
             op.init_materialize();
         }
-        repr::planrepr::Operation::MergeJoinScan { fldname1, fldname2 } => {
+        repr::planrepr::Operation::MergeJoinScan {
+            fldname1,
+            fldname2,
+            join_type,
+        } => {
             let mut op = op.init_merge_join_scan();
             op.set_fldname1(fldname1.as_str());
             op.set_fldname2(fldname2.as_str());
+            op.set_join_type(remote_join_type(join_type));
         }
-        repr::planrepr::Operation::SortScan { compflds } => {
-            let op = op.init_sort_scan();
-            let mut flds = op.init_compflds(compflds.len() as u32);
+        repr::planrepr::Operation::SortScan { compflds, orders } => {
+            let mut op = op.init_sort_scan();
+            let mut flds = op.reborrow().init_compflds(compflds.len() as u32);
             for (i, f) in compflds.into_iter().enumerate() {
                 flds.set(i as u32, f.as_str());
             }
+            let mut ords = op.reborrow().init_orders(orders.len() as u32);
+            for (i, o) in orders.into_iter().enumerate() {
+                let o = match o {
+                    SortOrder::Asc => remote_statement::SortOrder::Asc,
+                    SortOrder::Desc => remote_statement::SortOrder::Desc,
+                };
+                ords.set(i as u32, o);
+            }
         }
         repr::planrepr::Operation::MultibufferProductScan => {
             op.init_multibuffer_product_scan();
@@ -317,20 +581,19 @@ fn set_operation(
         repr::planrepr::Operation::ProductScan => {
             op.init_product_scan();
         }
+        repr::planrepr::Operation::ProductJoinScan { join_type, pred } => {
+            let mut op = op.init_product_join_scan();
+            op.set_join_type(remote_join_type(join_type));
+            let mut p = op.init_pred();
+            set_predicate(&pred, &mut p);
+        }
         repr::planrepr::Operation::ProjectScan => {
             op.init_project_scan();
         }
         repr::planrepr::Operation::SelectScan { pred } => {
             let op = op.init_select_scan();
-            let p = op.init_pred();
-            let mut ts = p.init_terms(pred.terms().len() as u32);
-            for (i, term) in pred.terms().into_iter().enumerate() {
-                let mut t = ts.reborrow().get(i as u32);
-                let mut lhs = t.reborrow().init_lhs();
-                set_expression(term.lhs(), &mut lhs);
-                let mut rhs = t.reborrow().init_rhs();
-                set_expression(term.rhs(), &mut rhs);
-            }
+            let mut p = op.init_pred();
+            set_predicate(&pred, &mut p);
         }
         repr::planrepr::Operation::TableScan { tblname } => {
             op.init_table_scan().set_tblname(tblname.as_str());
@@ -404,7 +667,13 @@ impl remote_connection::Server for RemoteConnectionImpl {
     ) -> Promise<(), capnp::Error> {
         trace!("close");
         let tx_num = self.conn.borrow().current_tx_num();
-        self.conn.borrow_mut().close().expect("close");
+        // a transaction block that is still open is not committed.
+        if self.conn.borrow().block == TxBlock::None {
+            self.conn.borrow_mut().close().expect("close");
+        } else {
+            self.conn.borrow_mut().rollback().expect("rollback");
+            self.conn.borrow_mut().renew_tx().expect("start new tx");
+        }
         let client: tx_box::Client = capnp_rpc::new_client(TxImpl::new(tx_num));
         results.get().set_res(client);
 
@@ -508,6 +777,46 @@ impl remote_connection::Server for RemoteConnectionImpl {
         Promise::ok(())
     }
 
+    // my own extends
+    fn set_isolation_level(
+        &mut self,
+        params: remote_connection::SetIsolationLevelParams,
+        _: remote_connection::SetIsolationLevelResults,
+    ) -> Promise<(), capnp::Error> {
+        let level = match pry!(pry!(params.get()).get_level()) {
+            remote_capnp::IsolationLevel::ReadUncommitted => IsolationLevel::ReadUncommitted,
+            remote_capnp::IsolationLevel::ReadCommitted => IsolationLevel::ReadCommitted,
+            remote_capnp::IsolationLevel::RepeatableRead => IsolationLevel::RepeatableRead,
+            remote_capnp::IsolationLevel::Serializable => IsolationLevel::Serializable,
+        };
+        trace!("set isolation level: {}", level);
+        self.conn
+            .borrow()
+            .current_tx
+            .lock()
+            .unwrap()
+            .set_isolation_level(level);
+
+        Promise::ok(())
+    }
+    // my own extends
+    fn set_autocommit(
+        &mut self,
+        params: remote_connection::SetAutocommitParams,
+        _: remote_connection::SetAutocommitResults,
+    ) -> Promise<(), capnp::Error> {
+        let autocommit = pry!(params.get()).get_autocommit();
+        trace!("set autocommit: {}", autocommit);
+        if let Err(e) = self.conn.borrow_mut().set_autocommit(autocommit) {
+            return Promise::err(capnp::Error::failed(format!(
+                "failed to set autocommit: {}",
+                e
+            )));
+        }
+
+        Promise::ok(())
+    }
+
     // extends for statistics by exercise 3.15
     fn nums_of_read_written_blocks(
         &mut self,
@@ -680,6 +989,120 @@ impl date_box::Server for DateBoxImpl {
     }
 }
 
+pub struct BlobBoxImpl {
+    val: Vec<u8>,
+}
+impl BlobBoxImpl {
+    pub fn new(val: Vec<u8>) -> Self {
+        Self { val }
+    }
+}
+impl blob_box::Server for BlobBoxImpl {
+    fn read(
+        &mut self,
+        _: blob_box::ReadParams,
+        mut results: blob_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        results.get().set_val(&self.val);
+        Promise::ok(())
+    }
+}
+
+pub struct Int64BoxImpl {
+    val: i64,
+}
+impl Int64BoxImpl {
+    pub fn new(val: i64) -> Self {
+        Self { val }
+    }
+}
+impl int64_box::Server for Int64BoxImpl {
+    fn read(
+        &mut self,
+        _: int64_box::ReadParams,
+        mut results: int64_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        results.get().set_val(self.val);
+        Promise::ok(())
+    }
+}
+
+pub struct Float64BoxImpl {
+    val: f64,
+}
+impl Float64BoxImpl {
+    pub fn new(val: f64) -> Self {
+        Self { val }
+    }
+}
+impl float64_box::Server for Float64BoxImpl {
+    fn read(
+        &mut self,
+        _: float64_box::ReadParams,
+        mut results: float64_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        results.get().set_val(self.val);
+        Promise::ok(())
+    }
+}
+
+pub struct DecimalBoxImpl {
+    val: String,
+}
+impl DecimalBoxImpl {
+    pub fn new(val: String) -> Self {
+        Self { val }
+    }
+}
+impl decimal_box::Server for DecimalBoxImpl {
+    fn read(
+        &mut self,
+        _: decimal_box::ReadParams,
+        mut results: decimal_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        results.get().set_val(self.val.as_str());
+        Promise::ok(())
+    }
+}
+
+pub struct TimestampBoxImpl {
+    val: NaiveDateTime,
+}
+impl TimestampBoxImpl {
+    pub fn new(val: NaiveDateTime) -> Self {
+        Self { val }
+    }
+}
+impl timestamp_box::Server for TimestampBoxImpl {
+    fn read(
+        &mut self,
+        _: timestamp_box::ReadParams,
+        mut results: timestamp_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        set_timestamp(&self.val, results.get().init_val());
+        Promise::ok(())
+    }
+}
+
+pub struct TimeBoxImpl {
+    val: NaiveTime,
+}
+impl TimeBoxImpl {
+    pub fn new(val: NaiveTime) -> Self {
+        Self { val }
+    }
+}
+impl time_box::Server for TimeBoxImpl {
+    fn read(
+        &mut self,
+        _: time_box::ReadParams,
+        mut results: time_box::ReadResults,
+    ) -> Promise<(), capnp::Error> {
+        set_time(&self.val, results.get().init_val());
+        Promise::ok(())
+    }
+}
+
 pub struct RemoteStatementImpl {
     sql: String,
     planner: Planner,
@@ -715,6 +1138,7 @@ impl remote_statement::Server for RemoteStatementImpl {
                 return Promise::ok(());
             }
             Err(e) => {
+                self.conn.borrow_mut().fail_statement().expect("rollback");
                 return Promise::err(capnp::Error::failed(format!(
                     "failed to create query plan: {}",
                     e
@@ -728,12 +1152,48 @@ impl remote_statement::Server for RemoteStatementImpl {
         mut results: remote_statement::ExecuteUpdateResults,
     ) -> Promise<(), capnp::Error> {
         trace!("execute update: {}", self.sql);
-        let affected = self
+        // my own extends
+        if let Some(tcl) = self.planner.transaction_control(&self.sql) {
+            let tx_num = self.conn.borrow().current_tx_num();
+            if let Err(e) = self.conn.borrow_mut().execute_tcl(tcl) {
+                return Promise::err(capnp::Error::failed(format!(
+                    "failed to control transaction: {}",
+                    e
+                )));
+            }
+            let affected: affected::Client = capnp_rpc::new_client(AffectedImpl::new(0, tx_num));
+            results.get().set_affected(affected);
+
+            return Promise::ok(());
+        }
+        if self.conn.borrow().block == TxBlock::Failed {
+            return Promise::err(capnp::Error::failed(
+                ConnectionError::TransactionAborted.to_string(),
+            ));
+        }
+        let res = self
             .planner
-            .execute_update(&self.sql, Arc::clone(&self.conn.borrow().current_tx))
-            .expect("execute update");
+            .execute_update(&self.sql, Arc::clone(&self.conn.borrow().current_tx));
+        let affected = match res {
+            Ok(affected) => affected,
+            Err(e) => {
+                let e = match self.conn.borrow_mut().fail_statement() {
+                    Ok(_) => e,
+                    Err(rollback_err) => rollback_err,
+                };
+                return Promise::err(capnp::Error::failed(format!(
+                    "failed to execute update: {}",
+                    e
+                )));
+            }
+        };
         let tx_num = self.conn.borrow().current_tx_num();
-        self.conn.borrow_mut().close().expect("close");
+        if let Err(e) = self.conn.borrow_mut().finish_statement() {
+            return Promise::err(capnp::Error::failed(format!(
+                "failed to commit update: {}",
+                e
+            )));
+        }
         let affected: affected::Client = capnp_rpc::new_client(AffectedImpl::new(affected, tx_num));
         results.get().set_affected(affected);
 
@@ -746,7 +1206,7 @@ impl remote_statement::Server for RemoteStatementImpl {
     ) -> Promise<(), capnp::Error> {
         trace!("close");
         let tx_num = self.conn.borrow().current_tx_num();
-        self.conn.borrow_mut().close().expect("close");
+        self.conn.borrow_mut().finish_statement().expect("close");
         let client: tx_box::Client = capnp_rpc::new_client(TxImpl::new(tx_num));
         results.get().set_res(client);
 
@@ -782,6 +1242,11 @@ impl RemoteResultSetImpl {
         let sch = plan.schema();
         Self { scan, sch, conn }
     }
+    // my own extends
+    fn field_is_null(&self, fldname: &str) -> bool {
+        let val = self.scan.lock().unwrap().get_val(fldname);
+        val.is_ok_and(|v| v.is_null())
+    }
 }
 
 impl remote_result_set::Server for RemoteResultSetImpl {
@@ -804,7 +1269,7 @@ impl remote_result_set::Server for RemoteResultSetImpl {
     ) -> Promise<(), capnp::Error> {
         trace!("close");
         let tx_num = self.conn.borrow().current_tx_num();
-        self.conn.borrow_mut().close().expect("close");
+        self.conn.borrow_mut().finish_statement().expect("close");
         let client: tx_box::Client = capnp_rpc::new_client(TxImpl::new(tx_num));
         results.get().set_res(client);
 
@@ -838,6 +1303,10 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                 .set_key(k.as_str())
                 .unwrap();
             let mut val = entries.reborrow().get(i as u32).init_value();
+            if self.field_is_null(k) {
+                val.reborrow().set_null(());
+                continue;
+            }
             match fi.fld_type {
                 FieldType::SMALLINT => {
                     if let Ok(v) = self.scan.lock().unwrap().get_i16(k) {
@@ -849,7 +1318,7 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                         val.reborrow().set_int32(v);
                     }
                 }
-                FieldType::VARCHAR => {
+                FieldType::VARCHAR | FieldType::TEXT => {
                     if let Ok(s) = self.scan.lock().unwrap().get_string(k) {
                         val.reborrow().set_string(s.as_str());
                     }
@@ -867,6 +1336,48 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                         dt.set_day(v.day() as u8);
                     }
                 }
+                FieldType::BLOB => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(b) = v.as_blob() {
+                            val.reborrow().set_blob(b);
+                        }
+                    }
+                }
+                FieldType::BIGINT => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(v) = v.as_i64() {
+                            val.reborrow().set_int64(v);
+                        }
+                    }
+                }
+                FieldType::DOUBLE => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(v) = v.as_f64() {
+                            val.reborrow().set_float64(v);
+                        }
+                    }
+                }
+                FieldType::DECIMAL => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(d) = v.as_decimal() {
+                            val.reborrow().set_decimal(d.to_string().as_str());
+                        }
+                    }
+                }
+                FieldType::TIMESTAMP => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(ts) = v.as_timestamp() {
+                            set_timestamp(&ts, val.reborrow().init_timestamp());
+                        }
+                    }
+                }
+                FieldType::TIME => {
+                    if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                        if let Ok(t) = v.as_time() {
+                            set_time(&t, val.reborrow().init_time());
+                        }
+                    }
+                }
             }
         }
 
@@ -894,6 +1405,10 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                         .set_key(k.as_str())
                         .unwrap();
                     let mut val = entries.reborrow().get(i as u32).init_value();
+                    if self.field_is_null(k) {
+                        val.reborrow().set_null(());
+                        continue;
+                    }
                     match fi.fld_type {
                         FieldType::SMALLINT => {
                             if let Ok(v) = self.scan.lock().unwrap().get_i16(k) {
@@ -905,7 +1420,7 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                                 val.reborrow().set_int32(v);
                             }
                         }
-                        FieldType::VARCHAR => {
+                        FieldType::VARCHAR | FieldType::TEXT => {
                             if let Ok(s) = self.scan.lock().unwrap().get_string(k) {
                                 val.reborrow().set_string(s.as_str());
                             }
@@ -923,6 +1438,48 @@ impl remote_result_set::Server for RemoteResultSetImpl {
                                 dt.set_day(v.day() as u8);
                             }
                         }
+                        FieldType::BLOB => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(b) = v.as_blob() {
+                                    val.reborrow().set_blob(b);
+                                }
+                            }
+                        }
+                        FieldType::BIGINT => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(v) = v.as_i64() {
+                                    val.reborrow().set_int64(v);
+                                }
+                            }
+                        }
+                        FieldType::DOUBLE => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(v) = v.as_f64() {
+                                    val.reborrow().set_float64(v);
+                                }
+                            }
+                        }
+                        FieldType::DECIMAL => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(d) = v.as_decimal() {
+                                    val.reborrow().set_decimal(d.to_string().as_str());
+                                }
+                            }
+                        }
+                        FieldType::TIMESTAMP => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(ts) = v.as_timestamp() {
+                                    set_timestamp(&ts, val.reborrow().init_timestamp());
+                                }
+                            }
+                        }
+                        FieldType::TIME => {
+                            if let Ok(v) = self.scan.lock().unwrap().get_val(k) {
+                                if let Ok(t) = v.as_time() {
+                                    set_time(&t, val.reborrow().init_time());
+                                }
+                            }
+                        }
                     }
                 }
                 c += 1;
@@ -1026,6 +1583,133 @@ impl remote_result_set::Server for RemoteResultSetImpl {
         let val: date_box::Client = capnp_rpc::new_client(DateBoxImpl::new(val));
         results.get().set_val(val);
 
+        Promise::ok(())
+    }
+    fn get_blob(
+        &mut self,
+        params: remote_result_set::GetBlobParams,
+        mut results: remote_result_set::GetBlobResults,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get blob value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_blob().map(|b| b.to_vec()))
+            .expect("get blob");
+        let val: blob_box::Client = capnp_rpc::new_client(BlobBoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn get_int64(
+        &mut self,
+        params: remote_result_set::GetInt64Params,
+        mut results: remote_result_set::GetInt64Results,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get int64 value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_i64())
+            .expect("get int64");
+        let val: int64_box::Client = capnp_rpc::new_client(Int64BoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn get_float64(
+        &mut self,
+        params: remote_result_set::GetFloat64Params,
+        mut results: remote_result_set::GetFloat64Results,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get float64 value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_f64())
+            .expect("get float64");
+        let val: float64_box::Client = capnp_rpc::new_client(Float64BoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn get_decimal(
+        &mut self,
+        params: remote_result_set::GetDecimalParams,
+        mut results: remote_result_set::GetDecimalResults,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get decimal value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_decimal().map(|d| d.to_string()))
+            .expect("get decimal");
+        let val: decimal_box::Client = capnp_rpc::new_client(DecimalBoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn get_timestamp(
+        &mut self,
+        params: remote_result_set::GetTimestampParams,
+        mut results: remote_result_set::GetTimestampResults,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get timestamp value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_timestamp())
+            .expect("get timestamp");
+        let val: timestamp_box::Client = capnp_rpc::new_client(TimestampBoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn get_time(
+        &mut self,
+        params: remote_result_set::GetTimeParams,
+        mut results: remote_result_set::GetTimeResults,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("get time value: {}", fldname);
+        let val = self
+            .scan
+            .lock()
+            .unwrap()
+            .get_val(fldname)
+            .and_then(|v| v.as_time())
+            .expect("get time");
+        let val: time_box::Client = capnp_rpc::new_client(TimeBoxImpl::new(val));
+        results.get().set_val(val);
+
+        Promise::ok(())
+    }
+    fn is_null(
+        &mut self,
+        params: remote_result_set::IsNullParams,
+        mut results: remote_result_set::IsNullResults,
+    ) -> Promise<(), capnp::Error> {
+        let fldname = pry!(pry!(params.get()).get_fldname()).to_str().unwrap();
+        debug!("is null: {}", fldname);
+        let val = self.field_is_null(fldname);
+        let val: bool_box::Client = capnp_rpc::new_client(BoolBoxImpl::new(val));
+        results.get().set_val(val);
+
         Promise::ok(())
     }
 }
@@ -25,7 +25,10 @@ use crate::{
         updateplanner::UpdatePlanner,
     },
     record::schema::Schema,
-    tx::{concurrency::locktable::LockTable, transaction::Transaction},
+    tx::{
+        concurrency::locktable::LockTable, mvcc::versionstore::VersionStore,
+        transaction::Transaction,
+    },
 };
 
 #[derive(Debug)]
@@ -57,6 +60,9 @@ impl fmt::Display for SimpleDBError {
 }
 
 pub const LOG_FILE: &str = "simpledb.log";
+// my own extends
+// the number of log records after which a checkpoint is taken, see checkpoint_if_needed
+pub const CHECKPOINT_LOG_RECORDS: i32 = 10_000;
 
 pub struct SimpleDB {
     // configure
@@ -66,7 +72,15 @@ pub struct SimpleDB {
 
     // base for static members
     next_tx_num: Arc<Mutex<i32>>,
+    active_txs: Arc<Mutex<Vec<i32>>>,
     locktbl: Arc<Mutex<LockTable>>,
+    // my own extends
+    // only in MVCC mode
+    versions: Option<Arc<Mutex<VersionStore>>>,
+    // my own extends
+    // the latest log sequence number at the last checkpoint
+    checkpoint_lsn: Arc<Mutex<i32>>,
+    checkpoint_log_records: i32,
 
     // managers
     fm: Arc<Mutex<FileMgr>>,
@@ -102,6 +116,7 @@ impl SimpleDB {
     }
     pub fn new_with(db_directory: &str, blocksize: i32, numbuffs: usize) -> Self {
         let next_tx_num = Arc::new(Mutex::new(0));
+        let active_txs = Arc::new(Mutex::new(vec![]));
         let locktbl = Arc::new(Mutex::new(LockTable::new()));
         let fm = Arc::new(Mutex::new(FileMgr::new(&db_directory, blocksize).unwrap()));
         let lm = Arc::new(Mutex::new(LogMgr::new(Arc::clone(&fm), LOG_FILE).unwrap()));
@@ -116,7 +131,11 @@ impl SimpleDB {
             blocksize,
             numbuffs,
             next_tx_num,
+            active_txs,
             locktbl,
+            versions: None,
+            checkpoint_lsn: Arc::new(Mutex::new(0)),
+            checkpoint_log_records: CHECKPOINT_LOG_RECORDS,
             fm,
             lm,
             bm,
@@ -140,12 +159,41 @@ impl SimpleDB {
     pub fn new_tx(&self) -> Result<Transaction> {
         Transaction::new(
             Arc::clone(&self.next_tx_num),
+            Arc::clone(&self.active_txs),
             Arc::clone(&self.locktbl),
             Arc::clone(&self.fm),
             Arc::clone(&self.lm),
             Arc::clone(&self.bm),
+            self.versions.as_ref().map(Arc::clone),
         )
     }
+    // my own extends
+    // non-quiescent checkpoint, so that recovery does not have to scan the whole log.
+    pub fn checkpoint(&self) -> Result<()> {
+        let mut tx = self.new_tx()?;
+        tx.checkpoint()?;
+        tx.commit()?;
+        *self.checkpoint_lsn.lock().unwrap() = self.lm.lock().unwrap().latest_lsn();
+
+        Ok(())
+    }
+    // my own extends
+    // a checkpoint once the log has grown by the number of records since the last one.
+    // returns whether it is taken.
+    pub fn checkpoint_if_needed(&self) -> Result<bool> {
+        let latest = self.lm.lock().unwrap().latest_lsn();
+        if latest - *self.checkpoint_lsn.lock().unwrap() < self.checkpoint_log_records {
+            return Ok(false);
+        }
+        trace!("checkpoint after log record {}", latest);
+        self.checkpoint()?;
+
+        Ok(true)
+    }
+    // my own extends
+    pub fn set_checkpoint_log_records(&mut self, nrecords: i32) {
+        self.checkpoint_log_records = nrecords;
+    }
     pub fn planner(&self) -> Result<Planner> {
         if let Some(qp) = self.qp.as_ref() {
             if let Some(up) = self.up.as_ref() {
@@ -159,6 +207,7 @@ impl SimpleDB {
     pub fn build_from(cfg: SimpleDBConfig) -> impl Fn(&str) -> Result<Self> {
         move |db_directory: &str| {
             let next_tx_num = Arc::new(Mutex::new(0));
+            let active_txs = Arc::new(Mutex::new(vec![]));
             let locktbl = Arc::new(Mutex::new(LockTable::new()));
             let fm = Arc::new(Mutex::new(
                 FileMgr::new(&db_directory, cfg.block_size).unwrap(),
@@ -203,13 +252,21 @@ impl SimpleDB {
                     Arc::new(Mutex::new(ClockUPBufferMgr::new(bm_fm, bm_lm, numsbuff)))
                 }
             };
+            let versions = match cfg.concurrency_mgr {
+                config::ConcurrencyMgr::Locking => None,
+                config::ConcurrencyMgr::MVCC => Some(Arc::new(Mutex::new(VersionStore::new()))),
+            };
 
             let mut db = Self {
                 db_directory: db_directory.to_string(),
                 blocksize: cfg.block_size,
                 numbuffs: cfg.num_of_buffers,
                 next_tx_num,
+                active_txs,
                 locktbl,
+                versions,
+                checkpoint_lsn: Arc::new(Mutex::new(0)),
+                checkpoint_log_records: CHECKPOINT_LOG_RECORDS,
                 fm,
                 lm,
                 bm,
@@ -231,7 +288,9 @@ impl SimpleDB {
             let next_table_num = Arc::new(Mutex::new(0));
             let qp_mdm = Arc::clone(&db.mdm.as_ref().unwrap());
             let qp: Arc<Mutex<dyn QueryPlanner>> = match cfg.query_planner {
-                config::QueryPlanner::Basic => Arc::new(Mutex::new(BasicQueryPlanner::new(qp_mdm))),
+                config::QueryPlanner::Basic => {
+                    Arc::new(Mutex::new(BasicQueryPlanner::new(next_table_num, qp_mdm)))
+                }
                 config::QueryPlanner::Heuristic => Arc::new(Mutex::new(
                     HeuristicQueryPlanner::new(next_table_num, qp_mdm),
                 )),
@@ -1,4 +1,5 @@
 pub mod bufferlist;
 pub mod concurrency;
+pub mod mvcc;
 pub mod recovery;
 pub mod transaction;
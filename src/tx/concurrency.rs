@@ -1,2 +1,3 @@
+pub mod isolation;
 pub mod locktable;
 pub mod manager;
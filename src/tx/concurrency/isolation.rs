@@ -0,0 +1,28 @@
+use core::fmt;
+
+// my own extends
+// the level decides how long the read locks of records, index keys and end of files are held.
+// write locks are always held until the transaction ends.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub enum IsolationLevel {
+    // reads no lock, so it sees the uncommitted changes of the others
+    ReadUncommitted,
+    // waits for the writers of a record, but gives back the read lock at once
+    ReadCommitted,
+    // holds the read locks of records, but locks no ranges
+    RepeatableRead,
+    // holds the read locks of records, index keys and end of files
+    #[default]
+    Serializable,
+}
+
+impl fmt::Display for IsolationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IsolationLevel::ReadUncommitted => write!(f, "READ UNCOMMITTED"),
+            IsolationLevel::ReadCommitted => write!(f, "READ COMMITTED"),
+            IsolationLevel::RepeatableRead => write!(f, "REPEATABLE READ"),
+            IsolationLevel::Serializable => write!(f, "SERIALIZABLE"),
+        }
+    }
+}
@@ -1,17 +1,20 @@
 use anyhow::Result;
 use core::fmt;
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex, MutexGuard},
-    thread,
-    time::Duration,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, SystemTime},
 };
 
 use crate::file::block_id::BlockId;
 
+const MAX_TIME: u64 = 10_000; // 10 sec
+
 #[derive(Debug)]
-enum LockTableError {
+pub enum LockTableError {
     LockAbort,
+    // my own extends
+    Deadlock(i32),
 }
 
 impl std::error::Error for LockTableError {}
@@ -21,70 +24,362 @@ impl fmt::Display for LockTableError {
             LockTableError::LockAbort => {
                 write!(f, "lock abort")
             }
+            LockTableError::Deadlock(txnum) => {
+                write!(
+                    f,
+                    "deadlock detected: transaction {} aborted, roll back and retry",
+                    txnum
+                )
+            }
+        }
+    }
+}
+
+// my own extends
+// the granularities of locking: a table file, a block of it and a record in a block.
+// an index key is locked with the gap below it, None being the gap at the end of a leaf.
+// the directory and the free space of a slotted page are latched, only while they change.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LockTarget {
+    Table(String),
+    Block(BlockId),
+    Record(BlockId, i32),
+    Key(String, Option<String>),
+    Page(BlockId),
+}
+
+// my own extends
+// IS and IX announce shared and exclusive locks on finer granularities,
+// and SIX is S with IX, taken when a transaction holding S goes on to update records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockMode {
+    IS,
+    IX,
+    S,
+    SIX,
+    X,
+}
+
+impl LockMode {
+    pub fn is_compatible(&self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            IS => other != X,
+            IX => other == IS || other == IX,
+            S => other == IS || other == S,
+            SIX => other == IS,
+            X => false,
+        }
+    }
+    // a held lock in self mode grants other mode, too
+    pub fn covers(&self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            IS => other == IS,
+            IX => other == IS || other == IX,
+            S => other == IS || other == S,
+            SIX => other != X,
+            X => true,
         }
     }
+    pub fn combine(&self, other: LockMode) -> LockMode {
+        if self.covers(other) {
+            *self
+        } else if other.covers(*self) {
+            other
+        } else {
+            LockMode::SIX
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+    // transactions holding a lock on each target, and their modes
+    locks: HashMap<LockTarget, Vec<(i32, LockMode)>>,
+    // wait-for graph: a waiting transaction and the transactions it waits for
+    waits_for: HashMap<i32, Vec<i32>>,
+    // transactions chosen to break a deadlock, told when they wake up
+    victims: HashSet<i32>,
 }
 
+impl LockState {
+    // the mode txnum needs to hold to also have mode, and the transactions it conflicts with
+    fn conflicts(&self, target: &LockTarget, mode: LockMode, txnum: i32) -> (LockMode, Vec<i32>) {
+        let holders = self.locks.get(target);
+        let newmode = holders
+            .and_then(|holders| holders.iter().find(|(t, _)| *t == txnum))
+            .map_or(mode, |(_, held)| held.combine(mode));
+        let blockers = holders
+            .into_iter()
+            .flatten()
+            .filter(|(t, held)| *t != txnum && !held.is_compatible(newmode))
+            .map(|(t, _)| *t)
+            .collect();
+
+        (newmode, blockers)
+    }
+    fn grant(&mut self, target: &LockTarget, mode: LockMode, txnum: i32) {
+        let holders = self.locks.entry(target.clone()).or_default();
+        holders.retain(|(t, _)| *t != txnum);
+        holders.push((txnum, mode));
+    }
+}
+
+// cloned tables share their state, so that a transaction can wait without holding the table.
 #[derive(Debug, Clone)]
 pub struct LockTable {
-    locks: Arc<Mutex<HashMap<BlockId, i32>>>,
+    state: Arc<Mutex<LockState>>,
+    cond: Arc<Condvar>,
 }
 
 impl LockTable {
     pub fn new() -> Self {
         Self {
-            locks: Arc::new(Mutex::new(HashMap::new())),
+            state: Arc::new(Mutex::new(LockState::default())),
+            cond: Arc::new(Condvar::new()),
         }
     }
     // synchronized
-    pub fn s_lock(&mut self, blk: &BlockId) -> Result<()> {
-        if let Ok(mut locks) = self.locks.try_lock() {
-            if !has_x_lock(&locks, blk) {
-                *locks.entry(blk.clone()).or_insert(0) += 1; // will not be negative
-                return Ok(());
-            }
+    // a lock already held by txnum is upgraded, e.g. S and IX make SIX.
+    pub fn lock(&self, target: &LockTarget, mode: LockMode, txnum: i32) -> Result<()> {
+        let mut state =
+            self.wait_for_lock(txnum, |state| state.conflicts(target, mode, txnum).1)?;
+        let (newmode, _) = state.conflicts(target, mode, txnum);
+        state.grant(target, newmode, txnum);
+
+        Ok(())
+    }
+    // synchronized
+    // my own extends
+    // false instead of waiting when another transaction holds a conflicting lock.
+    pub fn try_lock(&self, target: &LockTarget, mode: LockMode, txnum: i32) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let (newmode, blockers) = state.conflicts(target, mode, txnum);
+        if !blockers.is_empty() {
+            return false;
         }
+        state.grant(target, newmode, txnum);
 
-        Err(From::from(LockTableError::LockAbort))
+        true
     }
     // synchronized
-    pub fn x_lock(&mut self, blk: &BlockId) -> Result<()> {
-        if let Ok(mut locks) = self.locks.try_lock() {
-            if !has_other_s_locks(&locks, blk) {
-                *locks.entry(blk.clone()).or_insert(-1) = -1; // means eXclusive lock
-                return Ok(());
+    // my own extends
+    // an instant-duration lock: waits until the lock could be granted, without keeping it.
+    pub fn wait(&self, target: &LockTarget, mode: LockMode, txnum: i32) -> Result<()> {
+        let _state = self.wait_for_lock(txnum, |state| state.conflicts(target, mode, txnum).1)?;
+
+        Ok(())
+    }
+    // synchronized
+    // my own extends
+    // a lock taken for a moment goes back to the mode held before, if any.
+    pub fn restore(&self, target: &LockTarget, mode: Option<LockMode>, txnum: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(holders) = state.locks.get_mut(target) {
+            holders.retain(|(t, _)| *t != txnum);
+            if let Some(mode) = mode {
+                holders.push((txnum, mode));
+            }
+            if holders.is_empty() {
+                state.locks.remove(target);
             }
         }
-        thread::sleep(Duration::new(1, 0));
+        self.cond.notify_all();
 
-        Err(From::from(LockTableError::LockAbort))
+        Ok(())
     }
     // synchronized
-    pub fn unlock(&mut self, blk: &BlockId) -> Result<()> {
-        if let Ok(mut locks) = self.locks.lock() {
-            let val = get_lock_val(&locks, &blk);
-            if val > 1 {
-                locks.entry(blk.clone()).and_modify(|e| *e -= 1);
-            } else {
-                locks.remove(&blk);
-                // means notify_all
+    pub fn unlock(&self, target: &LockTarget, txnum: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(holders) = state.locks.get_mut(target) {
+            holders.retain(|(t, _)| *t != txnum);
+            if holders.is_empty() {
+                state.locks.remove(target);
             }
-            return Ok(());
         }
+        self.cond.notify_all();
 
-        Err(From::from(LockTableError::LockAbort))
+        Ok(())
     }
-}
+    // blocks until blockers() finds no conflicting transaction,
+    // and returns the state still locked so that the caller grants the lock atomically.
+    fn wait_for_lock<F>(&self, txnum: i32, blockers: F) -> Result<MutexGuard<'_, LockState>>
+    where
+        F: Fn(&LockState) -> Vec<i32>,
+    {
+        let timestamp = SystemTime::now();
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if state.victims.remove(&txnum) {
+                state.waits_for.remove(&txnum);
+                return Err(From::from(LockTableError::Deadlock(txnum)));
+            }
 
-fn has_x_lock(locks: &MutexGuard<HashMap<BlockId, i32>>, blk: &BlockId) -> bool {
-    get_lock_val(locks, blk) < 0
+            let waiting = blockers(&state);
+            if waiting.is_empty() {
+                state.waits_for.remove(&txnum);
+                return Ok(state);
+            }
+            state.waits_for.insert(txnum, waiting);
+
+            if let Some(cycle) = find_cycle(&state.waits_for, txnum) {
+                // the youngest transaction has done the least work
+                let victim = *cycle.iter().max().unwrap();
+                if victim == txnum {
+                    state.waits_for.remove(&txnum);
+                    return Err(From::from(LockTableError::Deadlock(txnum)));
+                }
+                state.victims.insert(victim);
+                self.cond.notify_all();
+            }
+
+            let elapsed = timestamp.elapsed().unwrap_or_default();
+            let max_time = Duration::from_millis(MAX_TIME);
+            if elapsed >= max_time {
+                state.waits_for.remove(&txnum);
+                return Err(From::from(LockTableError::LockAbort));
+            }
+            state = self.cond.wait_timeout(state, max_time - elapsed).unwrap().0;
+        }
+    }
 }
-fn has_other_s_locks(locks: &MutexGuard<HashMap<BlockId, i32>>, blk: &BlockId) -> bool {
-    get_lock_val(locks, blk) > 1
+
+// the transactions on a cycle of the wait-for graph through start, if any.
+fn find_cycle(waits_for: &HashMap<i32, Vec<i32>>, start: i32) -> Option<Vec<i32>> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![vec![start]];
+
+    while let Some(path) = stack.pop() {
+        let last = *path.last().unwrap();
+        for next in waits_for.get(&last).into_iter().flatten() {
+            if *next == start {
+                return Some(path);
+            }
+            if visited.insert(*next) {
+                let mut newpath = path.clone();
+                newpath.push(*next);
+                stack.push(newpath);
+            }
+        }
+    }
+
+    None
 }
-fn get_lock_val(locks: &MutexGuard<HashMap<BlockId, i32>>, blk: &BlockId) -> i32 {
-    match locks.get(&blk) {
-        Some(&ival) => ival,
-        None => 0,
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{thread, time::Instant};
+
+    #[test]
+    fn wait_and_notify_test() -> Result<()> {
+        let locktbl = LockTable::new();
+        let blk = LockTarget::Block(BlockId::new("testfile", 1));
+        locktbl.lock(&blk, LockMode::S, 1)?;
+        locktbl.lock(&blk, LockMode::X, 1)?;
+
+        let (tbl, b) = (locktbl.clone(), blk.clone());
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            tbl.lock(&b, LockMode::S, 2).unwrap();
+            println!("Tx 2: receive slock after {:?}", start.elapsed());
+            start.elapsed()
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        println!("Tx 1: unlock");
+        locktbl.unlock(&blk, 1)?;
+        let waited = handle.join().unwrap();
+        assert!(waited < Duration::from_millis(1000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn deadlock_test() -> Result<()> {
+        let locktbl = LockTable::new();
+        let blk1 = LockTarget::Block(BlockId::new("testfile", 1));
+        let blk2 = LockTarget::Block(BlockId::new("testfile", 2));
+        locktbl.lock(&blk1, LockMode::X, 1)?;
+        locktbl.lock(&blk2, LockMode::X, 2)?;
+
+        // Tx 2 waits for Tx 1
+        let (tbl, b1, b2) = (locktbl.clone(), blk1.clone(), blk2.clone());
+        let handle = thread::spawn(move || {
+            let result = tbl.lock(&b1, LockMode::X, 2);
+            println!("Tx 2: {:?}", result);
+            assert!(result.is_err());
+            // rollback
+            tbl.unlock(&b1, 2).unwrap();
+            tbl.unlock(&b2, 2).unwrap();
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        // Tx 1 waits for Tx 2, and the youngest, Tx 2, is aborted
+        let start = Instant::now();
+        locktbl.lock(&blk2, LockMode::X, 1)?;
+        println!("Tx 1: receive xlock after {:?}", start.elapsed());
+        handle.join().unwrap();
+
+        locktbl.unlock(&blk1, 1)?;
+        locktbl.unlock(&blk2, 1)?;
+
+        // Tx 3 waits for Tx 4, and the youngest is Tx 4 itself
+        locktbl.lock(&blk1, LockMode::X, 3)?;
+        locktbl.lock(&blk2, LockMode::X, 4)?;
+        let (tbl, b1, b2) = (locktbl.clone(), blk1.clone(), blk2.clone());
+        let handle = thread::spawn(move || {
+            tbl.lock(&b2, LockMode::X, 3).unwrap();
+            println!("Tx 3: receive xlock");
+            tbl.unlock(&b1, 3).unwrap();
+            tbl.unlock(&b2, 3).unwrap();
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        let err = locktbl.lock(&blk1, LockMode::X, 4).unwrap_err();
+        println!("Tx 4: {}", err);
+        match err.downcast_ref::<LockTableError>() {
+            Some(LockTableError::Deadlock(txnum)) => assert_eq!(4, *txnum),
+            _ => panic!("deadlock expected"),
+        }
+        // rollback
+        locktbl.unlock(&blk2, 4)?;
+        handle.join().unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn intention_lock_test() -> Result<()> {
+        let locktbl = LockTable::new();
+        let tbl = LockTarget::Table("testfile".to_string());
+        let blk = BlockId::new("testfile", 1);
+        let blktarget = LockTarget::Block(blk.clone());
+
+        // two writers of different records in the same block
+        for (txnum, slot) in [(1, 0), (2, 1)] {
+            locktbl.lock(&tbl, LockMode::IX, txnum)?;
+            locktbl.lock(&blktarget, LockMode::IX, txnum)?;
+            locktbl.lock(&LockTarget::Record(blk.clone(), slot), LockMode::X, txnum)?;
+            println!("Tx {}: receive xlock on record {}", txnum, slot);
+        }
+        assert!(!locktbl.try_lock(&LockTarget::Record(blk.clone(), 0), LockMode::S, 3));
+        assert!(locktbl.try_lock(&LockTarget::Record(blk.clone(), 2), LockMode::S, 3));
+        // a whole block read conflicts with the writers
+        assert!(!locktbl.try_lock(&blktarget, LockMode::S, 3));
+        assert!(locktbl.try_lock(&blktarget, LockMode::IS, 3));
+
+        // S and IX make SIX, which only lets readers of single records in
+        locktbl.unlock(&tbl, 1)?;
+        locktbl.unlock(&tbl, 2)?;
+        locktbl.lock(&tbl, LockMode::S, 4)?;
+        locktbl.lock(&tbl, LockMode::IX, 4)?;
+        assert!(locktbl.try_lock(&tbl, LockMode::IS, 5));
+        assert!(!locktbl.try_lock(&tbl, LockMode::S, 6));
+
+        Ok(())
     }
 }
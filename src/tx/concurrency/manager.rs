@@ -1,122 +1,253 @@
 use anyhow::Result;
-use core::fmt;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
-    thread,
-    time::{Duration, SystemTime},
 };
 
-use super::locktable::LockTable;
+use super::{
+    isolation::IsolationLevel,
+    locktable::{LockMode, LockTable, LockTarget},
+};
 use crate::file::block_id::BlockId;
 
-const MAX_TIME: i64 = 10_000; // 10 sec
-
-#[derive(Debug)]
-enum ConcurrencyMgrError {
-    LockAbort,
-}
-
-impl std::error::Error for ConcurrencyMgrError {}
-impl fmt::Display for ConcurrencyMgrError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ConcurrencyMgrError::LockAbort => {
-                write!(f, "lock abort")
-            }
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct ConcurrencyMgr {
     // static member (shared by all ConcurrentMgr)
     locktbl: Arc<Mutex<LockTable>>,
 
-    locks: Arc<Mutex<HashMap<BlockId, String>>>,
+    txnum: i32,
+    locks: Arc<Mutex<HashMap<LockTarget, LockMode>>>,
+    // my own extends
+    isolation: IsolationLevel,
+    // blocks with records locked exclusively, which are written record by record
+    record_blocks: Arc<Mutex<HashSet<BlockId>>>,
+    // slotted pages whose directory and free space this transaction is changing
+    latches: Arc<Mutex<HashSet<BlockId>>>,
 }
 
 impl ConcurrencyMgr {
-    pub fn new(locktbl: Arc<Mutex<LockTable>>) -> Self {
+    pub fn new(locktbl: Arc<Mutex<LockTable>>, txnum: i32) -> Self {
         Self {
             locktbl,
+            txnum,
             locks: Arc::new(Mutex::new(HashMap::new())),
+            isolation: IsolationLevel::default(),
+            record_blocks: Arc::new(Mutex::new(HashSet::new())),
+            latches: Arc::new(Mutex::new(HashSet::new())),
         }
     }
+    // my own extends
+    pub fn isolation_level(&self) -> IsolationLevel {
+        self.isolation
+    }
+    // my own extends
+    // the locks already held are kept, the level applies to the next reads.
+    pub fn set_isolation_level(&mut self, isolation: IsolationLevel) {
+        self.isolation = isolation;
+    }
+    // a block the transaction accesses record by record, under an intention lock,
+    // is protected by the record locks instead.
     pub fn s_lock(&mut self, blk: &BlockId) -> Result<()> {
-        if self.locks.lock().unwrap().get(blk).is_none() {
-            self.try_s_lock(blk)?;
-            self.locks
-                .lock()
-                .unwrap()
-                .insert(blk.clone(), "S".to_string());
+        let target = LockTarget::Block(blk.clone());
+        if self.held(&target).is_none() {
+            self.lock(&LockTarget::Table(blk.file_name()), LockMode::IS)?;
+            self.lock(&target, LockMode::S)?;
         }
 
         Ok(())
     }
+    // a write under an intention lock is only protected if it is to a record locked exclusively
+    // (as RecordPage does), otherwise the whole block is locked.
+    // a write to a latched page is protected by the latch.
     pub fn x_lock(&mut self, blk: &BlockId) -> Result<()> {
-        if !self.has_x_lock(blk) {
-            self.s_lock(blk)?;
-            self.try_x_lock(blk)?;
-            self.locks
-                .lock()
-                .unwrap()
-                .insert(blk.clone(), "X".to_string());
+        if self.latches.lock().unwrap().contains(blk) {
+            return Ok(());
+        }
+        let target = LockTarget::Block(blk.clone());
+        match self.held(&target) {
+            Some(LockMode::X) => Ok(()),
+            Some(LockMode::IX | LockMode::SIX)
+                if self.record_blocks.lock().unwrap().contains(blk) =>
+            {
+                Ok(())
+            }
+            _ => {
+                self.lock(&LockTarget::Table(blk.file_name()), LockMode::IX)?;
+                self.lock(&target, LockMode::X)
+            }
+        }
+    }
+    // my own extends
+    // the intention locks are taken at every level, so that the block is read record by record.
+    pub fn s_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<()> {
+        self.lock(&LockTarget::Table(blk.file_name()), LockMode::IS)?;
+        self.lock(&LockTarget::Block(blk.clone()), LockMode::IS)?;
+        let target = LockTarget::Record(blk.clone(), slot);
+        match self.isolation {
+            IsolationLevel::ReadUncommitted => Ok(()),
+            IsolationLevel::ReadCommitted => self.instant_lock(&target, LockMode::S),
+            IsolationLevel::RepeatableRead | IsolationLevel::Serializable => {
+                self.lock(&target, LockMode::S)
+            }
         }
+    }
+    // my own extends
+    pub fn x_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<()> {
+        self.lock(&LockTarget::Table(blk.file_name()), LockMode::IX)?;
+        self.lock(&LockTarget::Block(blk.clone()), LockMode::IX)?;
+        self.lock(&LockTarget::Record(blk.clone(), slot), LockMode::X)?;
+        self.record_blocks.lock().unwrap().insert(blk.clone());
 
         Ok(())
     }
-    pub fn release(&mut self) -> Result<()> {
-        for blk in self.locks.lock().unwrap().keys() {
-            self.locktbl.lock().unwrap().unlock(blk)?;
+    // my own extends
+    // false if another transaction holds the record or the whole block, instead of waiting.
+    pub fn try_x_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<bool> {
+        self.lock(&LockTarget::Table(blk.file_name()), LockMode::IX)?;
+        if !self.try_lock(&LockTarget::Block(blk.clone()), LockMode::IX)
+            || !self.try_lock(&LockTarget::Record(blk.clone(), slot), LockMode::X)
+        {
+            return Ok(false);
         }
-        self.locks.lock().unwrap().clear();
+        self.record_blocks.lock().unwrap().insert(blk.clone());
 
-        Ok(())
+        Ok(true)
     }
-    fn has_x_lock(&self, blk: &BlockId) -> bool {
-        if let Some(locktype) = self.locks.lock().unwrap().get(blk) {
-            return locktype.eq("X");
+    // my own extends
+    // the block is locked exclusively for a moment, while its records are moved,
+    // and goes back to the lock held before with release_x_lock_block.
+    // false if another transaction uses the block, or this one has locked a record of it
+    // exclusively: the undo of the record is written where the record was.
+    // the table is not locked, the records in it stay the same.
+    pub fn try_x_lock_block(&mut self, blk: &BlockId) -> Result<bool> {
+        if self.record_blocks.lock().unwrap().contains(blk) {
+            return Ok(false);
         }
-        false
+        let target = LockTarget::Block(blk.clone());
+
+        Ok(self.lock_table().try_lock(&target, LockMode::X, self.txnum))
+    }
+    // my own extends
+    pub fn release_x_lock_block(&mut self, blk: &BlockId) -> Result<()> {
+        let target = LockTarget::Block(blk.clone());
+        self.lock_table()
+            .restore(&target, self.held(&target), self.txnum)
     }
-    // NOTE: Because locktbl is static member, locking/unlocking the member must be here, not in LockTable's.
-    fn try_s_lock(&mut self, blk: &BlockId) -> Result<()> {
-        let timestamp = SystemTime::now();
+    // my own extends
+    // waits until no other transaction is changing the directory of the slotted page.
+    // the latch is given back by unlatch_page as soon as the change is done, not at commit.
+    pub fn latch_page(&mut self, blk: &BlockId) -> Result<()> {
+        let target = LockTarget::Page(blk.clone());
+        self.lock_table().lock(&target, LockMode::X, self.txnum)?;
+        self.latches.lock().unwrap().insert(blk.clone());
 
-        while !waiting_too_long(timestamp) {
-            if let Ok(mut locktbl) = self.locktbl.try_lock() {
-                if locktbl.s_lock(blk).is_ok() {
-                    return Ok(());
-                }
-            }
-            thread::sleep(Duration::from_millis(1000));
+        Ok(())
+    }
+    // my own extends
+    pub fn unlatch_page(&mut self, blk: &BlockId) -> Result<()> {
+        self.latches.lock().unwrap().remove(blk);
+        self.lock_table()
+            .unlock(&LockTarget::Page(blk.clone()), self.txnum)
+    }
+    // my own extends
+    // waits until no other transaction holds the block exclusively, without locking it.
+    pub fn instant_is_lock_block(&mut self, blk: &BlockId) -> Result<()> {
+        self.instant_lock(&LockTarget::Block(blk.clone()), LockMode::IS)
+    }
+    // my own extends
+    // the S lock of an index page is given back once the page has been read,
+    // and its keys are locked instead.
+    pub fn release_s_lock(&mut self, blk: &BlockId) -> Result<()> {
+        let target = LockTarget::Block(blk.clone());
+        if self.held(&target) == Some(LockMode::S) {
+            self.lock_table().unlock(&target, self.txnum)?;
+            self.locks.lock().unwrap().remove(&target);
         }
 
-        Err(From::from(ConcurrencyMgrError::LockAbort))
+        Ok(())
     }
-    // NOTE: Because locktbl is static member, locking/unlocking the member must be here, not in LockTable's.
-    fn try_x_lock(&mut self, blk: &BlockId) -> Result<()> {
-        let timestamp = SystemTime::now();
-
-        while !waiting_too_long(timestamp) {
-            if let Ok(mut locktbl) = self.locktbl.try_lock() {
-                if locktbl.x_lock(blk).is_ok() {
-                    return Ok(());
-                }
-            }
-            thread::sleep(Duration::from_millis(1000));
+    // my own extends
+    // only a serializable transaction locks the ranges it reads.
+    pub fn s_lock_key(&mut self, filename: &str, key: Option<String>) -> Result<()> {
+        if self.isolation != IsolationLevel::Serializable {
+            return Ok(());
+        }
+        self.lock(&LockTarget::Table(filename.to_string()), LockMode::IS)?;
+        self.lock(&LockTarget::Key(filename.to_string(), key), LockMode::S)
+    }
+    // my own extends
+    pub fn x_lock_key(&mut self, filename: &str, key: Option<String>) -> Result<()> {
+        self.lock(&LockTarget::Table(filename.to_string()), LockMode::IX)?;
+        self.lock(&LockTarget::Key(filename.to_string(), key), LockMode::X)
+    }
+    // my own extends
+    // the next key is only checked by an insert: nobody reads the gap being split.
+    pub fn instant_x_lock_key(&mut self, filename: &str, key: Option<String>) -> Result<()> {
+        self.lock(&LockTarget::Table(filename.to_string()), LockMode::IX)?;
+        self.instant_lock(&LockTarget::Key(filename.to_string(), key), LockMode::X)
+    }
+    // my own extends
+    // no block can be appended to the file until this transaction finishes.
+    pub fn s_lock_end_of_file(&mut self, eofblk: &BlockId) -> Result<()> {
+        if self.isolation != IsolationLevel::Serializable {
+            return Ok(());
+        }
+        self.s_lock(eofblk)
+    }
+    // my own extends
+    pub fn x_lock_table(&mut self, filename: &str) -> Result<()> {
+        self.lock(&LockTarget::Table(filename.to_string()), LockMode::X)
+    }
+    pub fn release(&mut self) -> Result<()> {
+        let locktbl = self.lock_table();
+        for target in self.locks.lock().unwrap().keys() {
+            locktbl.unlock(target, self.txnum)?;
         }
+        self.locks.lock().unwrap().clear();
+        self.record_blocks.lock().unwrap().clear();
 
-        Err(From::from(ConcurrencyMgrError::LockAbort))
+        Ok(())
     }
-}
+    fn held(&self, target: &LockTarget) -> Option<LockMode> {
+        self.locks.lock().unwrap().get(target).cloned()
+    }
+    fn lock(&mut self, target: &LockTarget, mode: LockMode) -> Result<()> {
+        let held = self.held(target);
+        if held.is_some_and(|held| held.covers(mode)) {
+            return Ok(());
+        }
+        let newmode = held.map_or(mode, |held| held.combine(mode));
+        self.lock_table().lock(target, newmode, self.txnum)?;
+        self.locks.lock().unwrap().insert(target.clone(), newmode);
 
-fn waiting_too_long(starttime: SystemTime) -> bool {
-    let now = SystemTime::now();
-    let diff = now.duration_since(starttime).unwrap();
+        Ok(())
+    }
+    // my own extends
+    fn try_lock(&mut self, target: &LockTarget, mode: LockMode) -> bool {
+        let held = self.held(target);
+        if held.is_some_and(|held| held.covers(mode)) {
+            return true;
+        }
+        let newmode = held.map_or(mode, |held| held.combine(mode));
+        if !self.lock_table().try_lock(target, newmode, self.txnum) {
+            return false;
+        }
+        self.locks.lock().unwrap().insert(target.clone(), newmode);
 
-    diff.as_millis() as i64 > MAX_TIME
+        true
+    }
+    // waits until the lock could be granted, without keeping it.
+    fn instant_lock(&mut self, target: &LockTarget, mode: LockMode) -> Result<()> {
+        if self.held(target).is_some_and(|held| held.covers(mode)) {
+            return Ok(());
+        }
+        self.lock_table().wait(target, mode, self.txnum)
+    }
+    // NOTE: Because locktbl is static member, it must not stay locked while waiting for a lock.
+    // The clone shares its state with the static member.
+    fn lock_table(&self) -> LockTable {
+        self.locktbl.lock().unwrap().clone()
+    }
 }
 
 // #[cfg(test)]
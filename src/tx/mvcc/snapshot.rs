@@ -0,0 +1,20 @@
+// my own extends
+// the transactions whose changes a transaction sees under snapshot isolation:
+// those finished before it started, and itself.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    txnum: i32,
+    active: Vec<i32>,
+}
+
+impl Snapshot {
+    pub fn new(txnum: i32, active: Vec<i32>) -> Self {
+        Self { txnum, active }
+    }
+    pub fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    pub fn is_visible(&self, creator: i32) -> bool {
+        creator == self.txnum || (creator < self.txnum && !self.active.contains(&creator))
+    }
+}
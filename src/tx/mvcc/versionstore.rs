@@ -0,0 +1,311 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use super::snapshot::Snapshot;
+use crate::{file::block_id::BlockId, query::constant::Constant};
+
+pub type Row = HashMap<String, Constant>;
+
+// the creator of the records written before any version was kept
+const ANCIENT_TX: i32 = 0;
+
+#[derive(Debug)]
+pub enum VersionStoreError {
+    WriteConflict(i32),
+}
+
+impl std::error::Error for VersionStoreError {}
+impl fmt::Display for VersionStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionStoreError::WriteConflict(txnum) => {
+                write!(
+                    f,
+                    "write conflict: the record was changed by transaction {} after the snapshot, roll back and retry",
+                    txnum
+                )
+            }
+        }
+    }
+}
+
+// the version of a record a snapshot sees
+#[derive(Debug, Clone)]
+pub enum VisibleVersion {
+    // the record in the page, valid while the epoch does not change
+    OnDisk(u64),
+    // a saved image, None when the record did not exist
+    Saved(Option<Row>),
+}
+
+#[derive(Debug, Clone)]
+struct Version {
+    creator: i32,
+    // the image of the record, None when it did not exist.
+    // unused for the newest version, which is the one in the page.
+    row: Option<Row>,
+}
+
+// my own extends
+// old versions of the records changed by running transactions, kept in memory:
+// after a crash no snapshot is older than the data on disk, so they need not be logged.
+#[derive(Debug, Default)]
+pub struct VersionStore {
+    chains: HashMap<(BlockId, i32), Vec<Version>>,
+    snapshots: HashMap<i32, Snapshot>,
+    // incremented whenever a record in a page stops being the newest version
+    epoch: u64,
+}
+
+impl VersionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn begin(&mut self, snapshot: Snapshot) {
+        self.snapshots.insert(snapshot.tx_number(), snapshot);
+    }
+    // a rolled back transaction has already restored the pages, so its versions are dropped.
+    pub fn finish(&mut self, txnum: i32, committed: bool) {
+        if !committed {
+            for chain in self.chains.values_mut() {
+                if chain.last().is_some_and(|v| v.creator == txnum) {
+                    chain.pop();
+                    if let Some(v) = chain.last_mut() {
+                        v.row = None;
+                    }
+                }
+            }
+            self.epoch += 1;
+        }
+        self.snapshots.remove(&txnum);
+        self.collect_garbage();
+    }
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+    pub fn visible(&self, blk: &BlockId, slot: i32, snapshot: &Snapshot) -> VisibleVersion {
+        match self.chains.get(&(blk.clone(), slot)) {
+            None => VisibleVersion::OnDisk(self.epoch),
+            Some(chain) => match chain.iter().rposition(|v| snapshot.is_visible(v.creator)) {
+                Some(i) if i == chain.len() - 1 => VisibleVersion::OnDisk(self.epoch),
+                Some(i) => VisibleVersion::Saved(chain[i].row.clone()),
+                None => VisibleVersion::Saved(None),
+            },
+        }
+    }
+    pub fn last_creator(&self, blk: &BlockId, slot: i32) -> i32 {
+        self.chains
+            .get(&(blk.clone(), slot))
+            .and_then(|chain| chain.last())
+            .map_or(ANCIENT_TX, |v| v.creator)
+    }
+    // keeps the record in the page as an older version, before creator changes it
+    pub fn save(&mut self, blk: &BlockId, slot: i32, creator: i32, row: Option<Row>) {
+        let chain = self.chains.entry((blk.clone(), slot)).or_insert_with(|| {
+            vec![Version {
+                creator: ANCIENT_TX,
+                row: None,
+            }]
+        });
+        if let Some(v) = chain.last_mut() {
+            v.row = row;
+        }
+        chain.push(Version { creator, row: None });
+        self.epoch += 1;
+    }
+    // a version is needed while it is the newest one some running transaction sees,
+    // or the newest finished one, which new transactions see, or the one in the page.
+    // the others are pruned, those below the oldest snapshot and those between two snapshots,
+    // so that a long transaction keeps one version of a record, however often it changes.
+    fn collect_garbage(&mut self) {
+        let snapshots = &self.snapshots;
+        self.chains.retain(|_, chain| {
+            let mut needed = vec![false; chain.len()];
+            needed[chain.len() - 1] = true;
+            if let Some(i) = chain
+                .iter()
+                .rposition(|v| !snapshots.contains_key(&v.creator))
+            {
+                needed[i] = true;
+            }
+            for snapshot in snapshots.values() {
+                if let Some(i) = chain.iter().rposition(|v| snapshot.is_visible(v.creator)) {
+                    needed[i] = true;
+                }
+            }
+            let mut needed = needed.into_iter();
+            chain.retain(|_| needed.next().unwrap());
+
+            chain.len() > 1 || snapshots.contains_key(&chain[0].creator)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use std::{
+        fs,
+        path::Path,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+    use crate::{
+        query::{scan::Scan, updatescan::UpdateScan},
+        record::{layout::Layout, schema::Schema, tablescan::TableScan},
+        server::{
+            config::{self, SimpleDBConfig},
+            simpledb::SimpleDB,
+        },
+        tx::transaction::Transaction,
+    };
+
+    fn scan_all(tx: &Arc<Mutex<Transaction>>, layout: &Arc<Layout>) -> Result<Vec<i32>> {
+        let mut ts = TableScan::new(Arc::clone(tx), "T", Arc::clone(layout))?;
+        let mut vals = vec![];
+        while ts.next() {
+            vals.push(ts.get_i32("A")?);
+        }
+        ts.close()?;
+        vals.sort();
+
+        Ok(vals)
+    }
+
+    #[test]
+    fn snapshot_test() -> Result<()> {
+        if Path::new("_test/mvcc").exists() {
+            fs::remove_dir_all("_test/mvcc")?;
+        }
+
+        let cfg = SimpleDBConfig {
+            block_size: 400,
+            num_of_buffers: 8,
+            buffer_manager: config::BufferMgr::LRU,
+            query_planner: config::QueryPlanner::Basic,
+            concurrency_mgr: config::ConcurrencyMgr::MVCC,
+        };
+        let db = SimpleDB::build_from(cfg)("_test/mvcc")?;
+        let mut sch = Schema::new();
+        sch.add_i32_field("A");
+        let layout = Arc::new(Layout::new(Arc::new(sch)));
+
+        let tx1 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx1), "T", Arc::clone(&layout))?;
+        for n in 1..=3 {
+            ts.insert()?;
+            ts.set_i32("A", n)?;
+        }
+        ts.close()?;
+        tx1.lock().unwrap().commit()?;
+
+        // the reader takes no lock, so the writers do not wait for it
+        let reader = Arc::new(Mutex::new(db.new_tx()?));
+        println!("reader: {:?}", scan_all(&reader, &layout)?);
+
+        let tx2 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx2), "T", Arc::clone(&layout))?;
+        while ts.next() {
+            if ts.get_i32("A")? == 1 {
+                ts.set_i32("A", 10)?;
+            }
+        }
+        ts.close()?;
+        println!("uncommitted update: {:?}", scan_all(&reader, &layout)?);
+        assert_eq!(vec![1, 2, 3], scan_all(&reader, &layout)?);
+        tx2.lock().unwrap().commit()?;
+
+        let tx3 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx3), "T", Arc::clone(&layout))?;
+        ts.insert()?;
+        ts.set_i32("A", 4)?;
+        ts.close()?;
+        tx3.lock().unwrap().commit()?;
+        println!("after two commits: {:?}", scan_all(&reader, &layout)?);
+        assert_eq!(vec![1, 2, 3], scan_all(&reader, &layout)?);
+
+        // the first committer wins
+        let mut ts = TableScan::new(Arc::clone(&reader), "T", Arc::clone(&layout))?;
+        let mut result = Ok(());
+        while ts.next() {
+            if ts.get_i32("A")? == 1 {
+                result = ts.set_i32("A", 100);
+            }
+        }
+        ts.close()?;
+        let err = result.unwrap_err();
+        println!("reader: {}", err);
+        match err.downcast_ref::<VersionStoreError>() {
+            Some(VersionStoreError::WriteConflict(txnum)) => {
+                assert_eq!(tx2.lock().unwrap().tx_num(), *txnum)
+            }
+            _ => panic!("write conflict expected"),
+        }
+        reader.lock().unwrap().rollback()?;
+
+        // a rolled back delete is seen by nobody
+        let reader = Arc::new(Mutex::new(db.new_tx()?));
+        let tx4 = Arc::new(Mutex::new(db.new_tx()?));
+        let mut ts = TableScan::new(Arc::clone(&tx4), "T", Arc::clone(&layout))?;
+        while ts.next() {
+            if ts.get_i32("A")? == 2 {
+                ts.delete()?;
+            }
+        }
+        ts.close()?;
+        println!("tx4: {:?}", scan_all(&tx4, &layout)?);
+        assert_eq!(vec![3, 4, 10], scan_all(&tx4, &layout)?);
+        tx4.lock().unwrap().rollback()?;
+        println!("reader: {:?}", scan_all(&reader, &layout)?);
+        assert_eq!(vec![2, 3, 4, 10], scan_all(&reader, &layout)?);
+        reader.lock().unwrap().commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_test() {
+        let blk = BlockId::new("T.tbl", 0);
+        let row = |n: i32| Some(HashMap::from([("A".to_string(), Constant::I32(n))]));
+        let saved =
+            |store: &VersionStore, snapshot: &Snapshot| match store.visible(&blk, 0, snapshot) {
+                VisibleVersion::Saved(Some(row)) => row.get("A").cloned(),
+                _ => None,
+            };
+
+        let mut store = VersionStore::new();
+        let reader = Snapshot::new(1, vec![]);
+        store.begin(reader.clone());
+        // the record changes 10 times while the reader runs, each writer to its number
+        let mut val = 1;
+        for txnum in 2..12 {
+            store.begin(Snapshot::new(txnum, vec![1]));
+            store.save(&blk, 0, txnum, row(val));
+            store.finish(txnum, true);
+            val = txnum;
+        }
+        // the version the reader sees, and the one in the page
+        assert_eq!(store.chains[&(blk.clone(), 0)].len(), 2);
+        assert_eq!(saved(&store, &reader), Some(Constant::I32(1)));
+
+        // a second reader sees the version in the page, and keeps it when it changes
+        let reader2 = Snapshot::new(12, vec![1]);
+        store.begin(reader2.clone());
+        for txnum in 13..16 {
+            store.begin(Snapshot::new(txnum, vec![1, 12]));
+            store.save(&blk, 0, txnum, row(val));
+            store.finish(txnum, true);
+            val = txnum;
+        }
+        assert_eq!(store.chains[&(blk.clone(), 0)].len(), 3);
+        assert_eq!(saved(&store, &reader), Some(Constant::I32(1)));
+        assert_eq!(saved(&store, &reader2), Some(Constant::I32(11)));
+
+        // below the oldest snapshot
+        store.finish(1, true);
+        assert_eq!(store.chains[&(blk.clone(), 0)].len(), 2);
+        store.finish(12, true);
+        assert!(store.chains.is_empty());
+    }
+}
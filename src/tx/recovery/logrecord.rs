@@ -4,16 +4,29 @@ use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use std::sync::{Arc, Mutex};
 
-use crate::{file::page::Page, tx::transaction::Transaction};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    tx::transaction::Transaction,
+};
 
 pub mod checkpoint_record;
 pub mod commit_record;
+pub mod delete_file_record;
+pub mod nq_checkpoint_record;
+pub mod rename_file_record;
 pub mod rollback_record;
+pub mod rollback_to_record;
+pub mod savepoint_record;
 pub mod set_bool_record;
+pub mod set_bytes_record;
 pub mod set_date_record;
+pub mod set_f64_record;
 pub mod set_i16_record;
 pub mod set_i32_record;
+pub mod set_i64_record;
 pub mod set_string_record;
+pub mod set_time_record;
+pub mod set_timestamp_record;
 pub mod start_record;
 
 #[derive(Debug)]
@@ -43,12 +56,45 @@ pub enum TxType {
     SETSTRING = 6,
     SETBOOL = 7,
     SETDATE = 8,
+    RENAMEFILE = 9,
+    DELETEFILE = 10,
+    NQCHECKPOINT = 11,
+    SAVEPOINT = 12,
+    ROLLBACKTO = 13,
+    SETBYTES = 14,
+    SETI64 = 15,
+    SETF64 = 16,
+    SETTIMESTAMP = 17,
+    SETTIME = 18,
 }
 
 pub trait LogRecord {
     fn op(&self) -> TxType;
     fn tx_number(&self) -> i32;
     fn undo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()>;
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()>;
+    // my own extends
+    // the block modified by an update record
+    fn block(&self) -> Option<&BlockId> {
+        None
+    }
+    // my own extends
+    // files whose older blocks are no longer theirs: they were flushed before
+    // the file was renamed, or the file was deleted. earlier updates on them must not be redone.
+    fn replaced_files(&self) -> Vec<String> {
+        vec![]
+    }
+    // my own extends
+    // the transactions that were active when a non-quiescent checkpoint started
+    fn active_tx_numbers(&self) -> Vec<i32> {
+        vec![]
+    }
+    // my own extends
+    // the name of the savepoint set or rolled back to
+    fn savepoint(&self) -> Option<&str> {
+        None
+    }
 }
 
 pub fn create_log_record(bytes: Vec<u8>) -> Result<Box<dyn LogRecord>> {
@@ -65,6 +111,20 @@ pub fn create_log_record(bytes: Vec<u8>) -> Result<Box<dyn LogRecord>> {
         Some(TxType::SETSTRING) => Ok(Box::new(set_string_record::SetStringRecord::new(p)?)),
         Some(TxType::SETBOOL) => Ok(Box::new(set_bool_record::SetBoolRecord::new(p)?)),
         Some(TxType::SETDATE) => Ok(Box::new(set_date_record::SetDateRecord::new(p)?)),
+        Some(TxType::RENAMEFILE) => Ok(Box::new(rename_file_record::RenameFileRecord::new(p)?)),
+        Some(TxType::DELETEFILE) => Ok(Box::new(delete_file_record::DeleteFileRecord::new(p)?)),
+        Some(TxType::NQCHECKPOINT) => {
+            Ok(Box::new(nq_checkpoint_record::NqCheckpointRecord::new(p)?))
+        }
+        Some(TxType::SAVEPOINT) => Ok(Box::new(savepoint_record::SavepointRecord::new(p)?)),
+        Some(TxType::ROLLBACKTO) => Ok(Box::new(rollback_to_record::RollbackToRecord::new(p)?)),
+        Some(TxType::SETBYTES) => Ok(Box::new(set_bytes_record::SetBytesRecord::new(p)?)),
+        Some(TxType::SETI64) => Ok(Box::new(set_i64_record::SetI64Record::new(p)?)),
+        Some(TxType::SETF64) => Ok(Box::new(set_f64_record::SetF64Record::new(p)?)),
+        Some(TxType::SETTIMESTAMP) => {
+            Ok(Box::new(set_timestamp_record::SetTimestampRecord::new(p)?))
+        }
+        Some(TxType::SETTIME) => Ok(Box::new(set_time_record::SetTimeRecord::new(p)?)),
         None => Err(From::from(LogRecordError::UnknownRecord)),
     }
 }
@@ -27,6 +27,11 @@ impl LogRecord for CheckpointRecord {
         // nop
         Ok(())
     }
+    // my own extends
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
 }
 impl CheckpointRecord {
     pub fn new() -> Result<Self> {
@@ -29,6 +29,11 @@ impl LogRecord for CommitRecord {
         // nop
         Ok(())
     }
+    // my own extends
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
 }
 impl CommitRecord {
     pub fn new(p: Page) -> Result<Self> {
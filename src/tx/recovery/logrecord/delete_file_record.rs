@@ -0,0 +1,63 @@
+use anyhow::Result;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{file::page::Page, log::manager::LogMgr, tx::transaction::Transaction};
+
+// my own extends
+pub struct DeleteFileRecord {
+    txnum: i32,
+    filename: String,
+}
+
+impl fmt::Display for DeleteFileRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<DELETEFILE {} {}>", self.txnum, self.filename)
+    }
+}
+
+impl LogRecord for DeleteFileRecord {
+    fn op(&self) -> TxType {
+        TxType::DELETEFILE
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop: a file is deleted only when it can no longer be rolled back
+        Ok(())
+    }
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop: the log is flushed before the file is deleted
+        Ok(())
+    }
+    fn replaced_files(&self) -> Vec<String> {
+        vec![self.filename.clone()]
+    }
+}
+impl DeleteFileRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let fpos = tpos + mem::size_of::<i32>();
+        let filename = p.get_string(fpos)?;
+
+        Ok(Self { txnum, filename })
+    }
+    pub fn write_to_log(lm: Arc<Mutex<LogMgr>>, txnum: i32, filename: &str) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let fpos = tpos + mem::size_of::<i32>();
+        let reclen = fpos + Page::max_length(filename.len());
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::DELETEFILE as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(fpos, filename.to_string())?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use core::fmt;
+use itertools::Itertools;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{file::page::Page, log::manager::LogMgr, tx::transaction::Transaction};
+
+// my own extends
+// non-quiescent checkpoint: the transactions that were active when it started.
+pub struct NqCheckpointRecord {
+    txnums: Vec<i32>,
+}
+
+impl fmt::Display for NqCheckpointRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<NQCKPT {}>", self.txnums.iter().join(" "))
+    }
+}
+
+impl LogRecord for NqCheckpointRecord {
+    fn op(&self) -> TxType {
+        TxType::NQCHECKPOINT
+    }
+    fn tx_number(&self) -> i32 {
+        -1 // dummy
+    }
+    fn undo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
+    fn active_tx_numbers(&self) -> Vec<i32> {
+        self.txnums.clone()
+    }
+}
+impl NqCheckpointRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let npos = mem::size_of::<i32>();
+        let n = p.get_i32(npos)?;
+        let mut txnums = vec![];
+        for i in 0..n as usize {
+            txnums.push(p.get_i32(npos + (i + 1) * mem::size_of::<i32>())?);
+        }
+
+        Ok(Self { txnums })
+    }
+    pub fn write_to_log(lm: Arc<Mutex<LogMgr>>, txnums: &[i32]) -> Result<i32> {
+        let npos = mem::size_of::<i32>();
+        let reclen = npos + (txnums.len() + 1) * mem::size_of::<i32>();
+
+        let mut p = Page::new_from_size(reclen);
+        p.set_i32(0, TxType::NQCHECKPOINT as i32)?;
+        p.set_i32(npos, txnums.len() as i32)?;
+        for (i, txnum) in txnums.iter().enumerate() {
+            p.set_i32(npos + (i + 1) * mem::size_of::<i32>(), *txnum)?;
+        }
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
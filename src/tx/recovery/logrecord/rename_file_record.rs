@@ -0,0 +1,67 @@
+use anyhow::Result;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{file::page::Page, log::manager::LogMgr, tx::transaction::Transaction};
+
+// my own extends
+pub struct RenameFileRecord {
+    txnum: i32,
+    from: String,
+    to: String,
+}
+
+impl fmt::Display for RenameFileRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<RENAMEFILE {} {} {}>", self.txnum, self.from, self.to)
+    }
+}
+
+impl LogRecord for RenameFileRecord {
+    fn op(&self) -> TxType {
+        TxType::RENAMEFILE
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().restore_file(&self.from, &self.to)
+    }
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop: the log is flushed before the file is renamed, so the rename has already happened.
+        Ok(())
+    }
+    fn replaced_files(&self) -> Vec<String> {
+        vec![self.from.clone(), self.to.clone()]
+    }
+}
+impl RenameFileRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let fpos = tpos + mem::size_of::<i32>();
+        let from = p.get_string(fpos)?;
+        let topos = fpos + Page::max_length(from.len());
+        let to = p.get_string(topos)?;
+
+        Ok(Self { txnum, from, to })
+    }
+    pub fn write_to_log(lm: Arc<Mutex<LogMgr>>, txnum: i32, from: &str, to: &str) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let fpos = tpos + mem::size_of::<i32>();
+        let topos = fpos + Page::max_length(from.len());
+        let reclen = topos + Page::max_length(to.len());
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::RENAMEFILE as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(fpos, from.to_string())?;
+        p.set_string(topos, to.to_string())?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
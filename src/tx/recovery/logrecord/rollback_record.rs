@@ -29,6 +29,11 @@ impl LogRecord for RollbackRecord {
         // nop
         Ok(())
     }
+    // my own extends
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
 }
 impl RollbackRecord {
     pub fn new(p: Page) -> Result<Self> {
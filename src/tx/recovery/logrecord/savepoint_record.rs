@@ -0,0 +1,64 @@
+use anyhow::Result;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{file::page::Page, log::manager::LogMgr, tx::transaction::Transaction};
+
+// my own extends
+// a named point of a transaction, which it can roll back to.
+pub struct SavepointRecord {
+    txnum: i32,
+    name: String,
+}
+
+impl fmt::Display for SavepointRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<SAVEPOINT {} {}>", self.txnum, self.name)
+    }
+}
+
+impl LogRecord for SavepointRecord {
+    fn op(&self) -> TxType {
+        TxType::SAVEPOINT
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
+    fn savepoint(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+}
+impl SavepointRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let npos = tpos + mem::size_of::<i32>();
+        let name = p.get_string(npos)?;
+
+        Ok(Self { txnum, name })
+    }
+    pub fn write_to_log(lm: Arc<Mutex<LogMgr>>, txnum: i32, name: &str) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let npos = tpos + mem::size_of::<i32>();
+        let reclen = npos + Page::max_length(name.len());
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::SAVEPOINT as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(npos, name.to_string())?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
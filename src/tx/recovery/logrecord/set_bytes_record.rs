@@ -0,0 +1,117 @@
+use anyhow::Result;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    log::manager::LogMgr,
+    tx::transaction::Transaction,
+};
+
+// my own extends
+// raw bytes of a page, for the records of slotted pages.
+pub struct SetBytesRecord {
+    txnum: i32,
+    offset: i32,
+    old_val: Vec<u8>,
+    new_val: Vec<u8>,
+    blk: BlockId,
+}
+impl fmt::Display for SetBytesRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "<SETBYTES {} {} {} {:?} {:?}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
+        )
+    }
+}
+
+impl LogRecord for SetBytesRecord {
+    fn op(&self) -> TxType {
+        TxType::SETBYTES
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_bytes(&self.blk, self.offset, &self.old_val, false)?; // don't log the undo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_bytes(&self.blk, self.offset, &self.new_val, false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
+}
+impl SetBytesRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let fpos = tpos + mem::size_of::<i32>();
+        let filename = p.get_string(fpos)?;
+        let bpos = fpos + Page::max_length(filename.len());
+        let blknum = p.get_i32(bpos)?;
+        let blk = BlockId::new(&filename, blknum);
+        let opos = bpos + mem::size_of::<i32>();
+        let offset = p.get_i32(opos)?;
+        let vpos = opos + mem::size_of::<i32>();
+        let old_val = p.get_bytes(vpos)?.to_vec();
+        let npos = vpos + Page::max_length(old_val.len());
+        let new_val = p.get_bytes(npos)?.to_vec();
+
+        Ok(Self {
+            txnum,
+            offset,
+            old_val,
+            new_val,
+            blk,
+        })
+    }
+    pub fn write_to_log(
+        lm: Arc<Mutex<LogMgr>>,
+        txnum: i32,
+        blk: &BlockId,
+        offset: i32,
+        old_val: Vec<u8>,
+        new_val: Vec<u8>,
+    ) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let fpos = tpos + mem::size_of::<i32>();
+        let bpos = fpos + Page::max_length(blk.file_name().len());
+        let opos = bpos + mem::size_of::<i32>();
+        let vpos = opos + mem::size_of::<i32>();
+        let npos = vpos + Page::max_length(old_val.len());
+        let reclen = npos + Page::max_length(new_val.len());
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::SETBYTES as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(fpos, blk.file_name())?;
+        p.set_i32(bpos, blk.number())?;
+        p.set_i32(opos, offset)?;
+        p.set_bytes(vpos, &old_val)?;
+        p.set_bytes(npos, &new_val)?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+    // the most bytes a record can carry, so that it fits in a log page with both values
+    pub fn max_bytes(blocksize: i32, blk: &BlockId) -> usize {
+        let header = 6 * mem::size_of::<i32>() + Page::max_length(blk.file_name().len());
+        // the length of the record and the boundary of the log page
+        let framing = 2 * mem::size_of::<i32>();
+        (blocksize as usize).saturating_sub(header + framing) / 2
+    }
+}
@@ -16,7 +16,8 @@ use crate::{
 pub struct SetDateRecord {
     txnum: i32,
     offset: i32,
-    val: NaiveDate,
+    old_val: NaiveDate,
+    new_val: NaiveDate,
     blk: BlockId,
 }
 
@@ -24,8 +25,8 @@ impl fmt::Display for SetDateRecord {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "<SETDATE {} {} {} {}>",
-            self.txnum, self.blk, self.offset, self.val
+            "<SETDATE {} {} {} {} {}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
         )
     }
 }
@@ -41,9 +42,21 @@ impl LogRecord for SetDateRecord {
         tx.lock().unwrap().pin(&self.blk)?;
         tx.lock()
             .unwrap()
-            .set_date(&self.blk, self.offset, self.val, false)?; // don't log the undo!
+            .set_date(&self.blk, self.offset, self.old_val, false)?; // don't log the undo!
         tx.lock().unwrap().unpin(&self.blk)
     }
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_date(&self.blk, self.offset, self.new_val, false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
 }
 impl SetDateRecord {
     pub fn new(p: Page) -> Result<Self> {
@@ -57,12 +70,15 @@ impl SetDateRecord {
         let opos = bpos + mem::size_of::<i32>();
         let offset = p.get_i32(opos)?;
         let vpos = opos + mem::size_of::<i32>();
-        let val = p.get_date(vpos)?;
+        let old_val = p.get_date(vpos)?;
+        let npos = vpos + mem::size_of::<i32>();
+        let new_val = p.get_date(npos)?;
 
         Ok(Self {
             txnum,
             offset,
-            val,
+            old_val,
+            new_val,
             blk,
         })
     }
@@ -71,14 +87,16 @@ impl SetDateRecord {
         txnum: i32,
         blk: &BlockId,
         offset: i32,
-        val: NaiveDate,
+        old_val: NaiveDate,
+        new_val: NaiveDate,
     ) -> Result<i32> {
         let tpos = mem::size_of::<i32>();
         let fpos = tpos + mem::size_of::<i32>();
         let bpos = fpos + Page::max_length(blk.file_name().len());
         let opos = bpos + mem::size_of::<i32>();
         let vpos = opos + mem::size_of::<i32>();
-        let reclen = vpos + mem::size_of::<i32>();
+        let npos = vpos + mem::size_of::<i32>();
+        let reclen = npos + mem::size_of::<i32>();
 
         let mut p = Page::new_from_size(reclen as usize);
         p.set_i32(0, TxType::SETDATE as i32)?;
@@ -86,7 +104,8 @@ impl SetDateRecord {
         p.set_string(fpos, blk.file_name())?;
         p.set_i32(bpos, blk.number() as i32)?;
         p.set_i32(opos, offset)?;
-        p.set_date(vpos, val)?;
+        p.set_date(vpos, old_val)?;
+        p.set_date(npos, new_val)?;
 
         lm.lock().unwrap().append(p.contents())
     }
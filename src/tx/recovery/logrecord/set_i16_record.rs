@@ -15,7 +15,8 @@ use crate::{
 pub struct SetI16Record {
     txnum: i32,
     offset: i32,
-    val: i16,
+    old_val: i16,
+    new_val: i16,
     blk: BlockId,
 }
 
@@ -23,8 +24,8 @@ impl fmt::Display for SetI16Record {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "<SETI16 {} {} {} {}>",
-            self.txnum, self.blk, self.offset, self.val
+            "<SETI16 {} {} {} {} {}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
         )
     }
 }
@@ -40,9 +41,21 @@ impl LogRecord for SetI16Record {
         tx.lock().unwrap().pin(&self.blk)?;
         tx.lock()
             .unwrap()
-            .set_i16(&self.blk, self.offset, self.val, false)?; // don't log the undo!
+            .set_i16(&self.blk, self.offset, self.old_val, false)?; // don't log the undo!
         tx.lock().unwrap().unpin(&self.blk)
     }
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_i16(&self.blk, self.offset, self.new_val, false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
 }
 impl SetI16Record {
     pub fn new(p: Page) -> Result<Self> {
@@ -56,12 +69,15 @@ impl SetI16Record {
         let opos = bpos + mem::size_of::<i32>();
         let offset = p.get_i32(opos)?;
         let vpos = opos + mem::size_of::<i32>();
-        let val = p.get_i16(vpos)?;
+        let old_val = p.get_i16(vpos)?;
+        let npos = vpos + mem::size_of::<i32>();
+        let new_val = p.get_i16(npos)?;
 
         Ok(Self {
             txnum,
             offset,
-            val,
+            old_val,
+            new_val,
             blk,
         })
     }
@@ -70,14 +86,16 @@ impl SetI16Record {
         txnum: i32,
         blk: &BlockId,
         offset: i32,
-        val: i16,
+        old_val: i16,
+        new_val: i16,
     ) -> Result<i32> {
         let tpos = mem::size_of::<i32>();
         let fpos = tpos + mem::size_of::<i32>();
         let bpos = fpos + Page::max_length(blk.file_name().len());
         let opos = bpos + mem::size_of::<i32>();
         let vpos = opos + mem::size_of::<i32>();
-        let reclen = vpos + mem::size_of::<i32>();
+        let npos = vpos + mem::size_of::<i32>();
+        let reclen = npos + mem::size_of::<i32>();
 
         let mut p = Page::new_from_size(reclen as usize);
         p.set_i32(0, TxType::SETI16 as i32)?;
@@ -85,7 +103,8 @@ impl SetI16Record {
         p.set_string(fpos, blk.file_name())?;
         p.set_i32(bpos, blk.number() as i32)?;
         p.set_i32(opos, offset)?;
-        p.set_i16(vpos, val)?;
+        p.set_i16(vpos, old_val)?;
+        p.set_i16(npos, new_val)?;
 
         lm.lock().unwrap().append(p.contents())
     }
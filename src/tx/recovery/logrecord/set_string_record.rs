@@ -15,15 +15,16 @@ use crate::{
 pub struct SetStringRecord {
     txnum: i32,
     offset: i32,
-    val: String,
+    old_val: String,
+    new_val: String,
     blk: BlockId,
 }
 impl fmt::Display for SetStringRecord {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "<SETSTRING {} {} {} {}>",
-            self.txnum, self.blk, self.offset, self.val
+            "<SETSTRING {} {} {} {} {}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
         )
     }
 }
@@ -39,9 +40,21 @@ impl LogRecord for SetStringRecord {
         tx.lock().unwrap().pin(&self.blk)?;
         tx.lock()
             .unwrap()
-            .set_string(&self.blk, self.offset, self.val.as_str(), false)?; // don't log the undo!
+            .set_string(&self.blk, self.offset, self.old_val.as_str(), false)?; // don't log the undo!
         tx.lock().unwrap().unpin(&self.blk)
     }
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_string(&self.blk, self.offset, self.new_val.as_str(), false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
 }
 impl SetStringRecord {
     pub fn new(p: Page) -> Result<Self> {
@@ -55,12 +68,15 @@ impl SetStringRecord {
         let opos = bpos + mem::size_of::<i32>();
         let offset = p.get_i32(opos)?;
         let vpos = opos + mem::size_of::<i32>();
-        let val = p.get_string(vpos)?;
+        let old_val = p.get_string(vpos)?;
+        let npos = vpos + Page::max_length(old_val.len());
+        let new_val = p.get_string(npos)?;
 
         Ok(Self {
             txnum,
             offset,
-            val,
+            old_val,
+            new_val,
             blk,
         })
     }
@@ -69,14 +85,16 @@ impl SetStringRecord {
         txnum: i32,
         blk: &BlockId,
         offset: i32,
-        val: String,
+        old_val: String,
+        new_val: String,
     ) -> Result<i32> {
         let tpos = mem::size_of::<i32>();
         let fpos = tpos + mem::size_of::<i32>();
         let bpos = fpos + Page::max_length(blk.file_name().len());
         let opos = bpos + mem::size_of::<i32>();
         let vpos = opos + mem::size_of::<i32>();
-        let reclen = vpos + Page::max_length(val.len());
+        let npos = vpos + Page::max_length(old_val.len());
+        let reclen = npos + Page::max_length(new_val.len());
 
         let mut p = Page::new_from_size(reclen as usize);
         p.set_i32(0, TxType::SETSTRING as i32)?;
@@ -84,7 +102,8 @@ impl SetStringRecord {
         p.set_string(fpos, blk.file_name())?;
         p.set_i32(bpos, blk.number() as i32)?;
         p.set_i32(opos, offset)?;
-        p.set_string(vpos, val)?;
+        p.set_string(vpos, old_val)?;
+        p.set_string(npos, new_val)?;
 
         lm.lock().unwrap().append(p.contents())
     }
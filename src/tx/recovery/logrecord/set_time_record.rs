@@ -0,0 +1,112 @@
+use anyhow::Result;
+use chrono::NaiveTime;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    log::manager::LogMgr,
+    tx::transaction::Transaction,
+};
+
+pub struct SetTimeRecord {
+    txnum: i32,
+    offset: i32,
+    old_val: NaiveTime,
+    new_val: NaiveTime,
+    blk: BlockId,
+}
+
+impl fmt::Display for SetTimeRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "<SETTIME {} {} {} {} {}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
+        )
+    }
+}
+
+impl LogRecord for SetTimeRecord {
+    fn op(&self) -> TxType {
+        TxType::SETTIME
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_time(&self.blk, self.offset, self.old_val, false)?; // don't log the undo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_time(&self.blk, self.offset, self.new_val, false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
+}
+impl SetTimeRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let fpos = tpos + mem::size_of::<i32>();
+        let filename = p.get_string(fpos)?;
+        let bpos = fpos + Page::max_length(filename.len());
+        let blknum = p.get_i32(bpos)?;
+        let blk = BlockId::new(&filename, blknum);
+        let opos = bpos + mem::size_of::<i32>();
+        let offset = p.get_i32(opos)?;
+        let vpos = opos + mem::size_of::<i32>();
+        let old_val = p.get_time(vpos)?;
+        let npos = vpos + mem::size_of::<i64>();
+        let new_val = p.get_time(npos)?;
+
+        Ok(Self {
+            txnum,
+            offset,
+            old_val,
+            new_val,
+            blk,
+        })
+    }
+    pub fn write_to_log(
+        lm: Arc<Mutex<LogMgr>>,
+        txnum: i32,
+        blk: &BlockId,
+        offset: i32,
+        old_val: NaiveTime,
+        new_val: NaiveTime,
+    ) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let fpos = tpos + mem::size_of::<i32>();
+        let bpos = fpos + Page::max_length(blk.file_name().len());
+        let opos = bpos + mem::size_of::<i32>();
+        let vpos = opos + mem::size_of::<i32>();
+        let npos = vpos + mem::size_of::<i64>();
+        let reclen = npos + mem::size_of::<i64>();
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::SETTIME as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(fpos, blk.file_name())?;
+        p.set_i32(bpos, blk.number())?;
+        p.set_i32(opos, offset)?;
+        p.set_time(vpos, old_val)?;
+        p.set_time(npos, new_val)?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use core::fmt;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use super::{LogRecord, TxType};
+use crate::{
+    file::{block_id::BlockId, page::Page},
+    log::manager::LogMgr,
+    tx::transaction::Transaction,
+};
+
+pub struct SetTimestampRecord {
+    txnum: i32,
+    offset: i32,
+    old_val: NaiveDateTime,
+    new_val: NaiveDateTime,
+    blk: BlockId,
+}
+
+impl fmt::Display for SetTimestampRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "<SETTIMESTAMP {} {} {} {} {}>",
+            self.txnum, self.blk, self.offset, self.old_val, self.new_val
+        )
+    }
+}
+
+impl LogRecord for SetTimestampRecord {
+    fn op(&self) -> TxType {
+        TxType::SETTIMESTAMP
+    }
+    fn tx_number(&self) -> i32 {
+        self.txnum
+    }
+    fn undo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_timestamp(&self.blk, self.offset, self.old_val, false)?; // don't log the undo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn redo(&mut self, tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        tx.lock().unwrap().pin(&self.blk)?;
+        tx.lock()
+            .unwrap()
+            .set_timestamp(&self.blk, self.offset, self.new_val, false)?; // don't log the redo!
+        tx.lock().unwrap().unpin(&self.blk)
+    }
+    // my own extends
+    fn block(&self) -> Option<&BlockId> {
+        Some(&self.blk)
+    }
+}
+impl SetTimestampRecord {
+    pub fn new(p: Page) -> Result<Self> {
+        let tpos = mem::size_of::<i32>();
+        let txnum = p.get_i32(tpos)?;
+        let fpos = tpos + mem::size_of::<i32>();
+        let filename = p.get_string(fpos)?;
+        let bpos = fpos + Page::max_length(filename.len());
+        let blknum = p.get_i32(bpos)?;
+        let blk = BlockId::new(&filename, blknum);
+        let opos = bpos + mem::size_of::<i32>();
+        let offset = p.get_i32(opos)?;
+        let vpos = opos + mem::size_of::<i32>();
+        let old_val = p.get_timestamp(vpos)?;
+        let npos = vpos + mem::size_of::<i64>();
+        let new_val = p.get_timestamp(npos)?;
+
+        Ok(Self {
+            txnum,
+            offset,
+            old_val,
+            new_val,
+            blk,
+        })
+    }
+    pub fn write_to_log(
+        lm: Arc<Mutex<LogMgr>>,
+        txnum: i32,
+        blk: &BlockId,
+        offset: i32,
+        old_val: NaiveDateTime,
+        new_val: NaiveDateTime,
+    ) -> Result<i32> {
+        let tpos = mem::size_of::<i32>();
+        let fpos = tpos + mem::size_of::<i32>();
+        let bpos = fpos + Page::max_length(blk.file_name().len());
+        let opos = bpos + mem::size_of::<i32>();
+        let vpos = opos + mem::size_of::<i32>();
+        let npos = vpos + mem::size_of::<i64>();
+        let reclen = npos + mem::size_of::<i64>();
+
+        let mut p = Page::new_from_size(reclen as usize);
+        p.set_i32(0, TxType::SETTIMESTAMP as i32)?;
+        p.set_i32(tpos, txnum)?;
+        p.set_string(fpos, blk.file_name())?;
+        p.set_i32(bpos, blk.number())?;
+        p.set_i32(opos, offset)?;
+        p.set_timestamp(vpos, old_val)?;
+        p.set_timestamp(npos, new_val)?;
+
+        lm.lock().unwrap().append(p.contents())
+    }
+}
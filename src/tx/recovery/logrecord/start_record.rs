@@ -29,6 +29,11 @@ impl LogRecord for StartRecord {
         // nop
         Ok(())
     }
+    // my own extends
+    fn redo(&mut self, _tx: Arc<Mutex<Transaction>>) -> Result<()> {
+        // nop
+        Ok(())
+    }
 }
 impl StartRecord {
     pub fn new(p: Page) -> Result<Self> {
@@ -1,16 +1,24 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use core::fmt;
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
 use super::logrecord::{
     self, checkpoint_record::CheckpointRecord, commit_record::CommitRecord,
-    rollback_record::RollbackRecord, set_bool_record::SetBoolRecord,
-    set_date_record::SetDateRecord, set_i16_record::SetI16Record, set_i32_record::SetI32Record,
-    set_string_record::SetStringRecord, TxType,
+    delete_file_record::DeleteFileRecord, nq_checkpoint_record::NqCheckpointRecord,
+    rename_file_record::RenameFileRecord, rollback_record::RollbackRecord,
+    rollback_to_record::RollbackToRecord, savepoint_record::SavepointRecord,
+    set_bool_record::SetBoolRecord, set_bytes_record::SetBytesRecord,
+    set_date_record::SetDateRecord, set_f64_record::SetF64Record, set_i16_record::SetI16Record,
+    set_i32_record::SetI32Record, set_i64_record::SetI64Record, set_string_record::SetStringRecord,
+    set_time_record::SetTimeRecord, set_timestamp_record::SetTimestampRecord, TxType,
 };
 use crate::{
     buffer::{buffer::Buffer, manager::BufferMgr},
+    file::block_id::BlockId,
     log::manager::LogMgr,
     tx::recovery::logrecord::start_record::StartRecord,
     tx::transaction::Transaction,
@@ -51,24 +59,73 @@ impl RecoveryMgr {
 
         Ok(Self { lm, bm, tx, txnum })
     }
+    // no-force: the modified buffers are written later, and redone by recovery after a crash.
     pub fn commit(&mut self) -> Result<()> {
-        self.bm.lock().unwrap().flush_all(self.txnum)?;
         let lsn = CommitRecord::write_to_log(Arc::clone(&self.lm), self.txnum)?;
         self.lm.lock().unwrap().flush(lsn)
     }
     pub fn rollback(&mut self) -> Result<()> {
-        self.do_rollback()?;
-        self.bm.lock().unwrap().flush_all(self.txnum)?;
+        let blks = self.do_rollback(None)?;
+        // recovery neither undoes nor redoes a rolled back transaction, so its undo must be on disk.
+        // with record locks, another transaction may have modified a block since the undo,
+        // so the blocks are flushed by name rather than by the transaction of the buffer.
+        let mut bm = self.bm.lock().unwrap();
+        for blk in blks.iter() {
+            bm.flush_block(blk)?;
+        }
+        drop(bm);
         let lsn = RollbackRecord::write_to_log(Arc::clone(&self.lm), self.txnum)?;
         self.lm.lock().unwrap().flush(lsn)
     }
+    // my own extends
+    // a nested top action: its updates are logged under a number of their own,
+    // and are not undone with the transaction once the action has committed.
+    pub fn begin_nested(&self, txnum: i32) -> Result<RecoveryMgr> {
+        RecoveryMgr::new(
+            Arc::clone(&self.tx),
+            txnum,
+            Arc::clone(&self.lm),
+            Arc::clone(&self.bm),
+        )
+    }
+    // my own extends
+    // not flushed: a later update that depends on the action is logged after it,
+    // and its own commit or the page it is written to flushes the log that far.
+    pub fn commit_nested(&mut self) -> Result<()> {
+        CommitRecord::write_to_log(Arc::clone(&self.lm), self.txnum)?;
+
+        Ok(())
+    }
+    // my own extends
+    pub fn savepoint(&mut self, name: &str) -> Result<()> {
+        SavepointRecord::write_to_log(Arc::clone(&self.lm), self.txnum, name)?;
+
+        Ok(())
+    }
+    // my own extends
+    // partial rollback: undoes the updates logged since the savepoint, which the caller knows is set.
+    // the transaction goes on, so nothing is flushed but the marker of the undone records;
+    // recovery undoes them again.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        self.do_rollback(Some(name))?;
+        let lsn = RollbackToRecord::write_to_log(Arc::clone(&self.lm), self.txnum, name)?;
+        self.lm.lock().unwrap().flush(lsn)
+    }
     pub fn recover(&mut self) -> Result<()> {
         self.do_recover()?;
         self.bm.lock().unwrap().flush_all(self.txnum)?;
         let lsn = CheckpointRecord::write_to_log(Arc::clone(&self.lm))?;
         self.lm.lock().unwrap().flush(lsn)
     }
-    pub fn set_i16(&mut self, buff: &mut Buffer, offset: i32, _new_val: i16) -> Result<i32> {
+    // my own extends
+    // non-quiescent checkpoint: the transactions keep running while the buffers are flushed.
+    // txnums must include the caller, so that recovery always goes back before the flush.
+    pub fn checkpoint(&mut self, txnums: &[i32]) -> Result<()> {
+        self.bm.lock().unwrap().flush_all_txs()?;
+        let lsn = NqCheckpointRecord::write_to_log(Arc::clone(&self.lm), txnums)?;
+        self.lm.lock().unwrap().flush(lsn)
+    }
+    pub fn set_i16(&mut self, buff: &mut Buffer, offset: i32, new_val: i16) -> Result<i32> {
         let old_val = buff.contents().get_i16(offset as usize)?;
         if let Some(blk) = buff.block() {
             return SetI16Record::write_to_log(
@@ -77,6 +134,7 @@ impl RecoveryMgr {
                 blk,
                 offset,
                 old_val,
+                new_val,
             );
         }
 
@@ -84,7 +142,7 @@ impl RecoveryMgr {
             "set_i16".to_string(),
         )))
     }
-    pub fn set_i32(&mut self, buff: &mut Buffer, offset: i32, _new_val: i32) -> Result<i32> {
+    pub fn set_i32(&mut self, buff: &mut Buffer, offset: i32, new_val: i32) -> Result<i32> {
         let old_val = buff.contents().get_i32(offset as usize)?;
         if let Some(blk) = buff.block() {
             return SetI32Record::write_to_log(
@@ -93,6 +151,7 @@ impl RecoveryMgr {
                 blk,
                 offset,
                 old_val,
+                new_val,
             );
         }
 
@@ -100,7 +159,64 @@ impl RecoveryMgr {
             "set_i32".to_string(),
         )))
     }
-    pub fn set_string(&mut self, buff: &mut Buffer, offset: i32, _new_val: &str) -> Result<i32> {
+    // my own extends
+    pub fn set_i64(&mut self, buff: &mut Buffer, offset: i32, new_val: i64) -> Result<i32> {
+        let old_val = buff.contents().get_i64(offset as usize)?;
+        if let Some(blk) = buff.block() {
+            return SetI64Record::write_to_log(
+                Arc::clone(&self.lm),
+                self.txnum,
+                blk,
+                offset,
+                old_val,
+                new_val,
+            );
+        }
+
+        Err(From::from(RecoveryMgrError::BufferFailed(
+            "set_i64".to_string(),
+        )))
+    }
+    // my own extends
+    pub fn set_f64(&mut self, buff: &mut Buffer, offset: i32, new_val: f64) -> Result<i32> {
+        let old_val = buff.contents().get_f64(offset as usize)?;
+        if let Some(blk) = buff.block() {
+            return SetF64Record::write_to_log(
+                Arc::clone(&self.lm),
+                self.txnum,
+                blk,
+                offset,
+                old_val,
+                new_val,
+            );
+        }
+
+        Err(From::from(RecoveryMgrError::BufferFailed(
+            "set_f64".to_string(),
+        )))
+    }
+    // my own extends
+    pub fn set_bytes(&mut self, buff: &mut Buffer, offset: i32, new_val: &[u8]) -> Result<i32> {
+        let old_val = buff
+            .contents()
+            .get_raw_bytes(offset as usize, new_val.len())?
+            .to_vec();
+        if let Some(blk) = buff.block() {
+            return SetBytesRecord::write_to_log(
+                Arc::clone(&self.lm),
+                self.txnum,
+                blk,
+                offset,
+                old_val,
+                new_val.to_vec(),
+            );
+        }
+
+        Err(From::from(RecoveryMgrError::BufferFailed(
+            "set_bytes".to_string(),
+        )))
+    }
+    pub fn set_string(&mut self, buff: &mut Buffer, offset: i32, new_val: &str) -> Result<i32> {
         let old_val = buff.contents().get_string(offset as usize)?;
         if let Some(blk) = buff.block() {
             return SetStringRecord::write_to_log(
@@ -109,6 +225,7 @@ impl RecoveryMgr {
                 blk,
                 offset,
                 old_val,
+                new_val.to_string(),
             );
         }
 
@@ -116,7 +233,7 @@ impl RecoveryMgr {
             "set_string".to_string(),
         )))
     }
-    pub fn set_bool(&mut self, buff: &mut Buffer, offset: i32, _new_val: bool) -> Result<i32> {
+    pub fn set_bool(&mut self, buff: &mut Buffer, offset: i32, new_val: bool) -> Result<i32> {
         let old_val = buff.contents().get_bool(offset as usize)?;
         if let Some(blk) = buff.block() {
             return SetBoolRecord::write_to_log(
@@ -125,6 +242,7 @@ impl RecoveryMgr {
                 blk,
                 offset,
                 old_val,
+                new_val,
             );
         }
 
@@ -132,7 +250,7 @@ impl RecoveryMgr {
             "set_bool".to_string(),
         )))
     }
-    pub fn set_date(&mut self, buff: &mut Buffer, offset: i32, _new_val: NaiveDate) -> Result<i32> {
+    pub fn set_date(&mut self, buff: &mut Buffer, offset: i32, new_val: NaiveDate) -> Result<i32> {
         let old_val = buff.contents().get_date(offset as usize)?;
         if let Some(blk) = buff.block() {
             return SetDateRecord::write_to_log(
@@ -141,6 +259,7 @@ impl RecoveryMgr {
                 blk,
                 offset,
                 old_val,
+                new_val,
             );
         }
 
@@ -148,37 +267,167 @@ impl RecoveryMgr {
             "set_date".to_string(),
         )))
     }
-    fn do_rollback(&mut self) -> Result<()> {
+    // my own extends
+    pub fn set_timestamp(
+        &mut self,
+        buff: &mut Buffer,
+        offset: i32,
+        new_val: NaiveDateTime,
+    ) -> Result<i32> {
+        let old_val = buff.contents().get_timestamp(offset as usize)?;
+        if let Some(blk) = buff.block() {
+            return SetTimestampRecord::write_to_log(
+                Arc::clone(&self.lm),
+                self.txnum,
+                blk,
+                offset,
+                old_val,
+                new_val,
+            );
+        }
+
+        Err(From::from(RecoveryMgrError::BufferFailed(
+            "set_timestamp".to_string(),
+        )))
+    }
+    // my own extends
+    pub fn set_time(&mut self, buff: &mut Buffer, offset: i32, new_val: NaiveTime) -> Result<i32> {
+        let old_val = buff.contents().get_time(offset as usize)?;
+        if let Some(blk) = buff.block() {
+            return SetTimeRecord::write_to_log(
+                Arc::clone(&self.lm),
+                self.txnum,
+                blk,
+                offset,
+                old_val,
+                new_val,
+            );
+        }
+
+        Err(From::from(RecoveryMgrError::BufferFailed(
+            "set_time".to_string(),
+        )))
+    }
+    // my own extends
+    // the rename must be on disk in the log before the file is moved.
+    pub fn rename_file(&mut self, from: &str, to: &str) -> Result<()> {
+        let lsn = RenameFileRecord::write_to_log(Arc::clone(&self.lm), self.txnum, from, to)?;
+        self.lm.lock().unwrap().flush(lsn)
+    }
+    // my own extends
+    pub fn delete_file(&mut self, filename: &str) -> Result<()> {
+        let lsn = DeleteFileRecord::write_to_log(Arc::clone(&self.lm), self.txnum, filename)?;
+        self.lm.lock().unwrap().flush(lsn)
+    }
+    // walks back to the start of the transaction, or to the savepoint.
+    // the records between a savepoint and a rollback to it have already been undone.
+    // returns the blocks of the records walked, including those undone earlier.
+    fn do_rollback(&mut self, savepoint: Option<&str>) -> Result<HashSet<BlockId>> {
+        let mut blks = HashSet::new();
+        let mut undone_to: Option<String> = None;
         let mut iter = self.lm.lock().unwrap().iterator()?;
         while let Some(bytes) = iter.next() {
             let mut rec = logrecord::create_log_record(bytes)?;
-            if rec.tx_number() == self.txnum {
-                if rec.op() == TxType::START {
-                    return Ok(());
+            if rec.tx_number() != self.txnum {
+                continue;
+            }
+            match rec.op() {
+                TxType::START => return Ok(blks),
+                TxType::ROLLBACKTO => {
+                    if undone_to.is_none() {
+                        undone_to = rec.savepoint().map(|name| name.to_string());
+                    }
+                }
+                // a savepoint set after the one rolled back to
+                TxType::SAVEPOINT
+                    if undone_to.is_some() && undone_to.as_deref() != rec.savepoint() => {}
+                TxType::SAVEPOINT => {
+                    undone_to = None;
+                    if savepoint.is_some() && savepoint == rec.savepoint() {
+                        return Ok(blks);
+                    }
+                }
+                _ => {
+                    if undone_to.is_none() {
+                        rec.undo(Arc::clone(&self.tx))?;
+                    }
+                    if let Some(blk) = rec.block() {
+                        blks.insert(blk.clone());
+                    }
                 }
-
-                rec.undo(Arc::clone(&self.tx))?;
             }
         }
 
-        Ok(())
+        Ok(blks)
     }
+    // undo pass: walks the log backwards to the checkpoint, undoing unfinished transactions
+    // and collecting the updates of committed ones.
+    // redo pass: replays those updates forwards, because commit does not flush the buffers.
     fn do_recover(&mut self) -> Result<()> {
-        let mut finished_txs = vec![];
+        let mut committed_txs = vec![];
+        let mut rolledback_txs = vec![];
+        let mut replaced_files: HashSet<String> = HashSet::new();
+        let mut redo_recs = vec![];
+        let mut waiting_txs: Option<Vec<i32>> = None;
+        let mut undone_to: HashMap<i32, String> = HashMap::new();
         let mut iter = self.lm.lock().unwrap().iterator()?;
         while let Some(bytes) = iter.next() {
             let mut rec = logrecord::create_log_record(bytes)?;
             match rec.op() {
-                TxType::CHECKPOINT => return Ok(()),
-                TxType::COMMIT | TxType::ROLLBACK => {
-                    finished_txs.push(rec.tx_number());
+                TxType::CHECKPOINT => break,
+                TxType::NQCHECKPOINT => {
+                    if waiting_txs.is_none() {
+                        waiting_txs = Some(rec.active_tx_numbers());
+                    }
+                }
+                TxType::START => {
+                    if let Some(txs) = waiting_txs.as_mut() {
+                        txs.retain(|t| *t != rec.tx_number());
+                    }
+                }
+                TxType::COMMIT => committed_txs.push(rec.tx_number()),
+                TxType::ROLLBACK => rolledback_txs.push(rec.tx_number()),
+                TxType::ROLLBACKTO => {
+                    if let Some(name) = rec.savepoint() {
+                        undone_to.entry(rec.tx_number()).or_insert(name.to_string());
+                    }
+                }
+                TxType::SAVEPOINT => {
+                    if undone_to.get(&rec.tx_number()).map(|n| n.as_str()) == rec.savepoint() {
+                        undone_to.remove(&rec.tx_number());
+                    }
                 }
                 _ => {
-                    if !finished_txs.contains(&rec.tx_number()) {
+                    replaced_files.extend(rec.replaced_files());
+                    let replaced = rec
+                        .block()
+                        .is_some_and(|blk| replaced_files.contains(&blk.file_name()));
+                    if rolledback_txs.contains(&rec.tx_number()) {
+                        // undone and flushed by the rollback
+                    } else if undone_to.contains_key(&rec.tx_number()) {
+                        // rolled back to a savepoint without flushing or logging the undo,
+                        // so a page written before that may still have the update.
+                        // undone again, even if the transaction committed.
+                        if !replaced {
+                            rec.undo(Arc::clone(&self.tx))?;
+                        }
+                    } else if committed_txs.contains(&rec.tx_number()) {
+                        if rec.block().is_some() && !replaced {
+                            redo_recs.push(rec);
+                        }
+                    } else {
                         rec.undo(Arc::clone(&self.tx))?;
                     }
                 }
             }
+            // every transaction active at the last non-quiescent checkpoint has been reached
+            if waiting_txs.as_ref().is_some_and(|txs| txs.is_empty()) {
+                break;
+            }
+        }
+
+        for rec in redo_recs.iter_mut().rev() {
+            rec.redo(Arc::clone(&self.tx))?;
         }
 
         Ok(())
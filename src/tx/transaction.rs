@@ -1,5 +1,7 @@
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use core::fmt;
+use log::debug;
 use std::{
     sync::{Arc, Mutex},
     usize,
@@ -7,21 +9,44 @@ use std::{
 
 use super::{
     bufferlist::BufferList,
-    concurrency::{locktable::LockTable, manager::ConcurrencyMgr},
-    recovery::manager::RecoveryMgr,
+    concurrency::{isolation::IsolationLevel, locktable::LockTable, manager::ConcurrencyMgr},
+    mvcc::{
+        snapshot::Snapshot,
+        versionstore::{Row, VersionStore, VersionStoreError, VisibleVersion},
+    },
+    recovery::{logrecord::set_bytes_record::SetBytesRecord, manager::RecoveryMgr},
 };
 use crate::{
     buffer::manager::BufferMgr,
-    file::{block_id::BlockId, manager::FileMgr},
+    file::{block_id::BlockId, manager::FileMgr, page::Page},
     log::manager::LogMgr,
+    query::constant::Constant,
 };
 
 static END_OF_FILE: i32 = -1;
 
+#[derive(Debug)]
+enum TransactionError {
+    SavepointNotFound(String),
+}
+
+impl std::error::Error for TransactionError {}
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionError::SavepointNotFound(name) => {
+                write!(f, "savepoint not found: {}", name)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Transaction {
     // static member (shared by all Transaction)
     next_tx_num: Arc<Mutex<i32>>,
+    // my own extends
+    active_txs: Arc<Mutex<Vec<i32>>>,
 
     recovery_mgr: Option<Arc<Mutex<RecoveryMgr>>>,
     concur_mgr: ConcurrencyMgr,
@@ -29,33 +54,69 @@ pub struct Transaction {
     fm: Arc<Mutex<FileMgr>>,
     txnum: i32,
     mybuffers: BufferList,
+    // my own extends
+    // files dropped by this transaction, removed when it commits
+    pending_deletes: Vec<String>,
+    // my own extends
+    // savepoints in the order they were set, with the files pending deletion at that time
+    savepoints: Vec<(String, Vec<String>)>,
+    // my own extends
+    // only in MVCC mode
+    versions: Option<Arc<Mutex<VersionStore>>>,
+    snapshot: Option<Snapshot>,
+    // my own extends
+    // the number and the log of the running page action, if any
+    page_action: Option<(i32, Arc<Mutex<RecoveryMgr>>)>,
 }
 
 impl Transaction {
     pub fn new(
         next_tx_num: Arc<Mutex<i32>>,
+        active_txs: Arc<Mutex<Vec<i32>>>,
         locktbl: Arc<Mutex<LockTable>>,
 
         fm: Arc<Mutex<FileMgr>>,
         lm: Arc<Mutex<LogMgr>>,
         bm: Arc<Mutex<dyn BufferMgr>>,
+        versions: Option<Arc<Mutex<VersionStore>>>,
     ) -> Result<Self> {
         let mut tran = Self {
             next_tx_num,
-            recovery_mgr: None, // dummy
-            concur_mgr: ConcurrencyMgr::new(locktbl),
+            active_txs,
+            recovery_mgr: None,                                       // dummy
+            concur_mgr: ConcurrencyMgr::new(Arc::clone(&locktbl), 0), // dummy
             bm: Arc::clone(&bm),
             fm,
             txnum: 0, // dummy
             mybuffers: BufferList::new(Arc::clone(&bm)),
+            pending_deletes: vec![],
+            savepoints: vec![],
+            versions,
+            snapshot: None, // dummy
+            page_action: None,
         };
 
         // update txnum
-        let next_tx_num = tran.next_tx_number();
+        // numbered and registered under one lock, so that a snapshot knows every running transaction
+        let tx_num_lock = Arc::clone(&tran.next_tx_num);
+        let mut last_tx_num = tx_num_lock.lock().unwrap();
+        *last_tx_num += 1;
+        let next_tx_num = *last_tx_num;
         tran.txnum = next_tx_num;
+        tran.concur_mgr = ConcurrencyMgr::new(locktbl, next_tx_num);
         // update recovery_mgr field (cyclic reference)
         let tx = Arc::new(Mutex::new(tran.clone()));
         tran.recovery_mgr = Arc::new(Mutex::new(RecoveryMgr::new(tx, next_tx_num, lm, bm)?)).into();
+        // registered after its START record, so that a checkpoint lists every transaction with updates
+        tran.active_txs.lock().unwrap().push(next_tx_num);
+        if let Some(versions) = tran.versions.as_ref() {
+            let mut active = tran.active_txs.lock().unwrap().clone();
+            active.retain(|t| *t != next_tx_num);
+            let snapshot = Snapshot::new(next_tx_num, active);
+            versions.lock().unwrap().begin(snapshot.clone());
+            tran.snapshot = Some(snapshot);
+        }
+        drop(last_tx_num);
 
         Ok(tran)
     }
@@ -66,8 +127,15 @@ impl Transaction {
             .lock()
             .unwrap()
             .commit()?;
+        for filename in std::mem::take(&mut self.pending_deletes) {
+            self.delete_file_now(&filename)?;
+        }
+        if let Some(versions) = self.versions.as_ref() {
+            versions.lock().unwrap().finish(self.txnum, true);
+        }
         self.concur_mgr.release()?;
         self.mybuffers.unpin_all()?;
+        self.unregister();
         println!("transaction {} committed", self.txnum);
 
         Ok(())
@@ -79,12 +147,71 @@ impl Transaction {
             .lock()
             .unwrap()
             .rollback()?;
+        self.pending_deletes.clear();
+        // before the locks are released, so that no writer sees the versions of this transaction
+        if let Some(versions) = self.versions.as_ref() {
+            versions.lock().unwrap().finish(self.txnum, false);
+        }
         self.concur_mgr.release()?;
         self.mybuffers.unpin_all()?;
+        self.unregister();
         println!("transaction {} rolled back", self.txnum);
 
         Ok(())
     }
+    // my own extends
+    // a savepoint of the same name is replaced.
+    pub fn savepoint(&mut self, name: &str) -> Result<()> {
+        self.recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .savepoint(name)?;
+        self.savepoints.retain(|(n, _)| n != name);
+        self.savepoints
+            .push((name.to_string(), self.pending_deletes.clone()));
+
+        Ok(())
+    }
+    // my own extends
+    // the updates since the savepoint are undone, but their locks are kept until the end.
+    // the savepoint itself stays, the later ones are released.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        let pos = match self.savepoints.iter().position(|(n, _)| n == name) {
+            Some(pos) => pos,
+            None => {
+                return Err(From::from(TransactionError::SavepointNotFound(
+                    name.to_string(),
+                )))
+            }
+        };
+        self.recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .rollback_to_savepoint(name)?;
+        self.savepoints.truncate(pos + 1);
+        self.pending_deletes = self.savepoints[pos].1.clone();
+        debug!(
+            "transaction {} rolled back to savepoint {}",
+            self.txnum, name
+        );
+
+        Ok(())
+    }
+    // my own extends
+    // non-quiescent checkpoint, run by this transaction while the others go on.
+    pub fn checkpoint(&mut self) -> Result<()> {
+        let txnums = self.active_txs.lock().unwrap().clone();
+        self.recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .checkpoint(&txnums)
+    }
     pub fn recover(&mut self) -> Result<()> {
         self.bm.lock().unwrap().flush_all(self.txnum)?;
         self.recovery_mgr
@@ -100,6 +227,16 @@ impl Transaction {
     pub fn unpin(&mut self, blk: &BlockId) -> Result<()> {
         self.mybuffers.unpin(blk)
     }
+    // my own extends
+    // an index page stays S-locked only while it is pinned: the keys in it are locked instead.
+    pub fn unpin_index_page(&mut self, blk: &BlockId) -> Result<()> {
+        self.mybuffers.unpin(blk)?;
+        if self.mybuffers.get_bufer(blk).is_none() {
+            self.concur_mgr.release_s_lock(blk)?;
+        }
+
+        Ok(())
+    }
     pub fn get_i16(&mut self, blk: &BlockId, offset: i32) -> Result<i16> {
         self.concur_mgr.s_lock(blk)?;
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
@@ -107,10 +244,11 @@ impl Transaction {
     }
     pub fn set_i16(&mut self, blk: &BlockId, offset: i32, val: i16, ok_to_log: bool) -> Result<()> {
         self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
         let mut lsn: i32 = -1;
         if ok_to_log {
-            let mut rm = self.recovery_mgr.as_ref().unwrap().lock().unwrap();
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
             lsn = rm.set_i16(&mut buff, offset, val)?.try_into().unwrap();
         }
         let p = buff.contents();
@@ -126,10 +264,11 @@ impl Transaction {
     }
     pub fn set_i32(&mut self, blk: &BlockId, offset: i32, val: i32, ok_to_log: bool) -> Result<()> {
         self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
         let mut lsn: i32 = -1;
         if ok_to_log {
-            let mut rm = self.recovery_mgr.as_ref().unwrap().lock().unwrap();
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
             lsn = rm.set_i32(&mut buff, offset, val)?.try_into().unwrap();
         }
         let p = buff.contents();
@@ -138,6 +277,50 @@ impl Transaction {
 
         Ok(())
     }
+    // my own extends
+    pub fn get_i64(&mut self, blk: &BlockId, offset: i32) -> Result<i64> {
+        self.concur_mgr.s_lock(blk)?;
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        buff.contents().get_i64(offset as usize)
+    }
+    // my own extends
+    pub fn set_i64(&mut self, blk: &BlockId, offset: i32, val: i64, ok_to_log: bool) -> Result<()> {
+        self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        let mut lsn: i32 = -1;
+        if ok_to_log {
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
+            lsn = rm.set_i64(&mut buff, offset, val)?;
+        }
+        let p = buff.contents();
+        p.set_i64(offset as usize, val)?;
+        buff.set_modified(self.txnum, lsn);
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_f64(&mut self, blk: &BlockId, offset: i32) -> Result<f64> {
+        self.concur_mgr.s_lock(blk)?;
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        buff.contents().get_f64(offset as usize)
+    }
+    // my own extends
+    pub fn set_f64(&mut self, blk: &BlockId, offset: i32, val: f64, ok_to_log: bool) -> Result<()> {
+        self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        let mut lsn: i32 = -1;
+        if ok_to_log {
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
+            lsn = rm.set_f64(&mut buff, offset, val)?;
+        }
+        let p = buff.contents();
+        p.set_f64(offset as usize, val)?;
+        buff.set_modified(self.txnum, lsn);
+
+        Ok(())
+    }
     pub fn get_string(&mut self, blk: &BlockId, offset: i32) -> Result<String> {
         self.concur_mgr.s_lock(blk)?;
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
@@ -151,10 +334,11 @@ impl Transaction {
         ok_to_log: bool,
     ) -> Result<()> {
         self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
         let mut lsn: i32 = -1;
         if ok_to_log {
-            let mut rm = self.recovery_mgr.as_ref().unwrap().lock().unwrap();
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
             lsn = rm.set_string(&mut buff, offset, val)?.try_into().unwrap();
         }
         let p = buff.contents();
@@ -163,6 +347,33 @@ impl Transaction {
 
         Ok(())
     }
+    // my own extends
+    // the bytes are logged piece by piece, so that every log record fits in a log page.
+    pub fn set_bytes(
+        &mut self,
+        blk: &BlockId,
+        offset: i32,
+        val: &[u8],
+        ok_to_log: bool,
+    ) -> Result<()> {
+        self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
+        let piece = SetBytesRecord::max_bytes(self.block_size(), blk).max(1);
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        for (i, chunk) in val.chunks(piece).enumerate() {
+            let pos = offset + (i * piece) as i32;
+            let mut lsn: i32 = -1;
+            if ok_to_log {
+                let mut rm = log.as_ref().unwrap().lock().unwrap();
+                lsn = rm.set_bytes(&mut buff, pos, chunk)?;
+            }
+            let p = buff.contents();
+            p.set_raw_bytes(pos as usize, chunk)?;
+            buff.set_modified(self.txnum, lsn);
+        }
+
+        Ok(())
+    }
     pub fn get_bool(&mut self, blk: &BlockId, offset: i32) -> Result<bool> {
         self.concur_mgr.s_lock(blk)?;
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
@@ -176,10 +387,11 @@ impl Transaction {
         ok_to_log: bool,
     ) -> Result<()> {
         self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
         let mut lsn: i32 = -1;
         if ok_to_log {
-            let mut rm = self.recovery_mgr.as_ref().unwrap().lock().unwrap();
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
             lsn = rm.set_bool(&mut buff, offset, val)?.try_into().unwrap();
         }
         let p = buff.contents();
@@ -201,10 +413,11 @@ impl Transaction {
         ok_to_log: bool,
     ) -> Result<()> {
         self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
         let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
         let mut lsn: i32 = -1;
         if ok_to_log {
-            let mut rm = self.recovery_mgr.as_ref().unwrap().lock().unwrap();
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
             lsn = rm.set_date(&mut buff, offset, val)?.try_into().unwrap();
         }
         let p = buff.contents();
@@ -213,16 +426,327 @@ impl Transaction {
 
         Ok(())
     }
+    // my own extends
+    pub fn get_timestamp(&mut self, blk: &BlockId, offset: i32) -> Result<NaiveDateTime> {
+        self.concur_mgr.s_lock(blk)?;
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        buff.contents().get_timestamp(offset as usize)
+    }
+    // my own extends
+    pub fn set_timestamp(
+        &mut self,
+        blk: &BlockId,
+        offset: i32,
+        val: NaiveDateTime,
+        ok_to_log: bool,
+    ) -> Result<()> {
+        self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        let mut lsn: i32 = -1;
+        if ok_to_log {
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
+            lsn = rm.set_timestamp(&mut buff, offset, val)?;
+        }
+        let p = buff.contents();
+        p.set_timestamp(offset as usize, val)?;
+        buff.set_modified(self.txnum, lsn);
+
+        Ok(())
+    }
+    // my own extends
+    pub fn get_time(&mut self, blk: &BlockId, offset: i32) -> Result<NaiveTime> {
+        self.concur_mgr.s_lock(blk)?;
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        buff.contents().get_time(offset as usize)
+    }
+    // my own extends
+    pub fn set_time(
+        &mut self,
+        blk: &BlockId,
+        offset: i32,
+        val: NaiveTime,
+        ok_to_log: bool,
+    ) -> Result<()> {
+        self.concur_mgr.x_lock(blk)?;
+        let log = self.update_log();
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        let mut lsn: i32 = -1;
+        if ok_to_log {
+            let mut rm = log.as_ref().unwrap().lock().unwrap();
+            lsn = rm.set_time(&mut buff, offset, val)?;
+        }
+        let p = buff.contents();
+        p.set_time(offset as usize, val)?;
+        buff.set_modified(self.txnum, lsn);
+
+        Ok(())
+    }
     pub fn size(&mut self, filename: &str) -> Result<i32> {
         let dummyblk = BlockId::new(filename, END_OF_FILE);
-        self.concur_mgr.s_lock(&dummyblk)?;
+        // a snapshot does not see the blocks appended by the others anyway
+        if !self.is_mvcc() {
+            self.concur_mgr.s_lock(&dummyblk)?;
+        }
+        self.reuse_deleted_file(filename)?;
         self.fm.lock().unwrap().length(filename)
     }
+    // my own extends
+    // the size without the lock on the end of the file, which a scan takes when it gets there.
+    pub fn peek_size(&mut self, filename: &str) -> Result<i32> {
+        self.reuse_deleted_file(filename)?;
+        self.fm.lock().unwrap().length(filename)
+    }
+    // my own extends
+    // no block can be appended to the file until this transaction finishes.
+    pub fn s_lock_end_of_file(&mut self, filename: &str) -> Result<()> {
+        let dummyblk = BlockId::new(filename, END_OF_FILE);
+        self.concur_mgr.s_lock_end_of_file(&dummyblk)
+    }
     pub fn append(&mut self, filename: &str) -> Result<BlockId> {
         let dummyblk = BlockId::new(filename, END_OF_FILE);
         self.concur_mgr.x_lock(&dummyblk)?;
+        self.reuse_deleted_file(filename)?;
         self.fm.lock().unwrap().append(filename)
     }
+    // my own extends
+    // the file is only removed at commit, so that a rollback still finds it.
+    pub fn delete_file(&mut self, filename: &str) -> Result<()> {
+        self.concur_mgr.x_lock_table(filename)?;
+        if !self.pending_deletes.iter().any(|f| f == filename) {
+            self.pending_deletes.push(filename.to_string());
+        }
+
+        Ok(())
+    }
+    // my own extends
+    // a file dropped and then recreated in the same transaction must start empty,
+    // so the old one is moved aside. it is removed on commit, or moved back on rollback.
+    fn reuse_deleted_file(&mut self, filename: &str) -> Result<()> {
+        if let Some(i) = self.pending_deletes.iter().position(|f| f == filename) {
+            self.pending_deletes.remove(i);
+            if self.file_exists(filename) {
+                let mut n = 0;
+                while self.file_exists(&format!("{}~{}", filename, n)) {
+                    n += 1;
+                }
+                let oldfile = format!("{}~{}", filename, n);
+                self.rename_file(filename, &oldfile)?;
+                self.pending_deletes.push(oldfile);
+            }
+        }
+
+        Ok(())
+    }
+    // my own extends
+    pub fn file_exists(&self, filename: &str) -> bool {
+        self.fm.lock().unwrap().exists(filename)
+    }
+    // my own extends
+    // record locks, for the records of table files accessed by RecordPage.
+    pub fn s_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<()> {
+        self.concur_mgr.s_lock_record(blk, slot)
+    }
+    // my own extends
+    pub fn x_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<()> {
+        self.concur_mgr.x_lock_record(blk, slot)
+    }
+    // my own extends
+    pub fn try_x_lock_record(&mut self, blk: &BlockId, slot: i32) -> Result<bool> {
+        self.concur_mgr.try_x_lock_record(blk, slot)
+    }
+    // my own extends
+    // block locks, for the slotted pages whose records are moved within the block.
+    pub fn try_x_lock_block(&mut self, blk: &BlockId) -> Result<bool> {
+        self.concur_mgr.try_x_lock_block(blk)
+    }
+    // my own extends
+    pub fn release_x_lock_block(&mut self, blk: &BlockId) -> Result<()> {
+        self.concur_mgr.release_x_lock_block(blk)
+    }
+    // my own extends
+    // a page action changes the directory and the free space of a slotted page under a latch.
+    // its updates are logged as a transaction of their own, which commits when they are done:
+    // the other transactions may use the page as soon as the latch is given back,
+    // so a rollback of this transaction must not undo them.
+    pub fn begin_page_action(&mut self, blk: &BlockId) -> Result<()> {
+        self.concur_mgr.latch_page(blk)?;
+        let tx_num_lock = Arc::clone(&self.next_tx_num);
+        let mut last_tx_num = tx_num_lock.lock().unwrap();
+        *last_tx_num += 1;
+        let txnum = *last_tx_num;
+        let nested = self
+            .recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .begin_nested(txnum);
+        let rm = match nested {
+            Ok(rm) => rm,
+            Err(e) => {
+                self.concur_mgr.unlatch_page(blk)?;
+                return Err(e);
+            }
+        };
+        self.active_txs.lock().unwrap().push(txnum);
+        drop(last_tx_num);
+        self.page_action = Some((txnum, Arc::new(Mutex::new(rm))));
+
+        Ok(())
+    }
+    // my own extends
+    // an action that failed halfway is rolled back.
+    pub fn end_page_action(&mut self, blk: &BlockId, done: bool) -> Result<()> {
+        let (txnum, rm) = self.page_action.take().unwrap();
+        let mut rm = rm.lock().unwrap();
+        let result = if done {
+            rm.commit_nested()
+        } else {
+            rm.rollback()
+        };
+        drop(rm);
+        self.active_txs.lock().unwrap().retain(|t| *t != txnum);
+        self.concur_mgr.unlatch_page(blk)?;
+
+        result
+    }
+    // my own extends
+    pub fn instant_is_lock_block(&mut self, blk: &BlockId) -> Result<()> {
+        self.concur_mgr.instant_is_lock_block(blk)
+    }
+    // my own extends
+    // key-range locks of an index, for next-key locking.
+    // a snapshot sees no phantoms, so in MVCC mode only the writers lock keys.
+    pub fn s_lock_key(&mut self, filename: &str, key: Option<&Constant>) -> Result<()> {
+        if self.is_mvcc() {
+            return Ok(());
+        }
+        self.concur_mgr
+            .s_lock_key(filename, key.map(|k| k.to_string()))
+    }
+    // my own extends
+    pub fn x_lock_key(&mut self, filename: &str, key: Option<&Constant>) -> Result<()> {
+        self.concur_mgr
+            .x_lock_key(filename, key.map(|k| k.to_string()))
+    }
+    // my own extends
+    pub fn instant_x_lock_key(&mut self, filename: &str, key: Option<&Constant>) -> Result<()> {
+        if self.is_mvcc() {
+            return Ok(());
+        }
+        self.concur_mgr
+            .instant_x_lock_key(filename, key.map(|k| k.to_string()))
+    }
+    // my own extends
+    // in MVCC mode every level reads the snapshot, which is at least repeatable read.
+    pub fn isolation_level(&self) -> IsolationLevel {
+        self.concur_mgr.isolation_level()
+    }
+    // my own extends
+    pub fn set_isolation_level(&mut self, isolation: IsolationLevel) {
+        self.concur_mgr.set_isolation_level(isolation);
+    }
+    // my own extends
+    pub fn is_mvcc(&self) -> bool {
+        self.versions.is_some()
+    }
+    // my own extends
+    // reads a pinned page without any lock, for snapshot reads.
+    pub fn peek<T, F>(&mut self, blk: &BlockId, f: F) -> Result<T>
+    where
+        F: FnOnce(&Page) -> Result<T>,
+    {
+        let mut buff = self.mybuffers.get_bufer(blk).unwrap().lock().unwrap();
+        f(buff.contents())
+    }
+    // my own extends
+    pub fn visible_version(&self, blk: &BlockId, slot: i32) -> VisibleVersion {
+        let versions = self.versions.as_ref().unwrap();
+        let snapshot = self.snapshot.as_ref().unwrap();
+        versions.lock().unwrap().visible(blk, slot, snapshot)
+    }
+    // my own extends
+    pub fn version_epoch(&self) -> u64 {
+        self.versions.as_ref().unwrap().lock().unwrap().epoch()
+    }
+    // my own extends
+    // keeps the current image of a record before this transaction changes it for the first time.
+    // the first committer wins: a record changed by a transaction this snapshot does not see
+    // cannot be updated or deleted.
+    pub fn save_version<F>(
+        &mut self,
+        blk: &BlockId,
+        slot: i32,
+        inserting: bool,
+        preimage: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut Transaction) -> Result<Option<Row>>,
+    {
+        let versions = match self.versions.as_ref() {
+            Some(versions) => Arc::clone(versions),
+            None => return Ok(()),
+        };
+        // the other writers wait here until this transaction finishes
+        self.concur_mgr.x_lock_record(blk, slot)?;
+        let creator = versions.lock().unwrap().last_creator(blk, slot);
+        if creator == self.txnum {
+            return Ok(());
+        }
+        if !inserting && !self.snapshot.as_ref().unwrap().is_visible(creator) {
+            return Err(From::from(VersionStoreError::WriteConflict(creator)));
+        }
+        let row = preimage(self)?;
+        versions.lock().unwrap().save(blk, slot, self.txnum, row);
+
+        Ok(())
+    }
+    // my own extends
+    // moves a file aside, e.g. to rebuild a table under the same name.
+    // the rename is logged, so a rollback moves the file back.
+    pub fn rename_file(&mut self, from: &str, to: &str) -> Result<()> {
+        for filename in [from, to] {
+            self.concur_mgr.x_lock_table(filename)?;
+        }
+        // the pages of the old file must reach the disk before it is moved,
+        // including those of committed transactions that are not flushed yet.
+        self.bm.lock().unwrap().flush_file(from)?;
+        self.recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .rename_file(from, to)?;
+        self.bm.lock().unwrap().discard_file(from);
+        self.fm.lock().unwrap().rename_file(from, to)
+    }
+    // my own extends
+    // undo of rename_file: whatever was built under the old name is thrown away.
+    // if the file is not under the new name, the rename never happened (the crash came right
+    // after its log record) or was already undone, and the file under the old name is the one.
+    pub fn restore_file(&mut self, from: &str, to: &str) -> Result<()> {
+        if !self.file_exists(to) {
+            return Ok(());
+        }
+        self.remove_file(from)?;
+        self.bm.lock().unwrap().discard_file(to);
+        self.fm.lock().unwrap().rename_file(to, from)
+    }
+    // the deletion is logged, so that recovery does not redo older updates into the file.
+    fn delete_file_now(&mut self, filename: &str) -> Result<()> {
+        self.recovery_mgr
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .delete_file(filename)?;
+        self.remove_file(filename)
+    }
+    fn remove_file(&mut self, filename: &str) -> Result<()> {
+        self.bm.lock().unwrap().discard_file(filename);
+        self.fm.lock().unwrap().delete_file(filename)
+    }
     pub fn block_size(&self) -> i32 {
         self.fm.lock().unwrap().block_size()
     }
@@ -232,18 +756,24 @@ impl Transaction {
     pub fn tx_num(&self) -> i32 {
         self.txnum
     }
-    fn next_tx_number(&mut self) -> i32 {
-        let mut next_tx_num = self.next_tx_num.lock().unwrap();
-        *next_tx_num += 1;
-
-        *next_tx_num
+    // my own extends
+    // the updates of a page action are logged by the action
+    fn update_log(&self) -> Option<Arc<Mutex<RecoveryMgr>>> {
+        match self.page_action.as_ref() {
+            Some((_, rm)) => Some(Arc::clone(rm)),
+            None => self.recovery_mgr.clone(),
+        }
+    }
+    // my own extends
+    fn unregister(&mut self) {
+        self.active_txs.lock().unwrap().retain(|t| *t != self.txnum);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::server::simpledb::SimpleDB;
+    use crate::{file::page::Page, server::simpledb::SimpleDB};
 
     use std::fs;
     use std::path::Path;
@@ -338,4 +868,429 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn block_lock_test() -> Result<()> {
+        if Path::new("_test/tx/block_lock").exists() {
+            fs::remove_dir_all("_test/tx/block_lock")?;
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tx/block_lock", 400, 8);
+        let blk1 = BlockId::new("testfile", 1);
+        let blk2 = BlockId::new("testfile", 2);
+
+        // a write to a record locked exclusively leaves the other records of the block alone
+        let mut tx1 = simpledb.new_tx()?;
+        tx1.pin(&blk1)?;
+        tx1.x_lock_record(&blk1, 0)?;
+        tx1.set_i32(&blk1, 80, 1, true)?;
+        let mut tx2 = simpledb.new_tx()?;
+        assert!(tx2.try_x_lock_record(&blk1, 1)?);
+        tx2.rollback()?;
+        tx1.commit()?;
+
+        // any other write locks the whole block, even under the intention lock of a reader
+        let mut tx3 = simpledb.new_tx()?;
+        tx3.pin(&blk2)?;
+        tx3.s_lock_record(&blk2, 0)?;
+        tx3.set_i32(&blk2, 80, 1, true)?;
+        let mut tx4 = simpledb.new_tx()?;
+        assert!(!tx4.try_x_lock_block(&blk2)?);
+        tx4.rollback()?;
+        tx3.commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn recovery_test() -> Result<()> {
+        if Path::new("_test/tx/recovery").exists() {
+            fs::remove_dir_all("_test/tx/recovery")?;
+        }
+
+        let blk1 = BlockId::new("testfile", 1);
+        let blk2 = BlockId::new("testfile", 2);
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/recovery", 400, 8);
+
+            // committed, but its buffer is never written to the disk
+            let mut tx1 = simpledb.new_tx()?;
+            tx1.pin(&blk1)?;
+            tx1.set_i32(&blk1, 80, 1, true)?;
+            tx1.set_string(&blk1, 40, "one", true)?;
+            tx1.commit()?;
+
+            // not committed, but its buffer is written to the disk
+            let mut tx2 = simpledb.new_tx()?;
+            tx2.pin(&blk2)?;
+            tx2.set_i32(&blk2, 80, 9999, true)?;
+            simpledb
+                .buffer_mgr()
+                .lock()
+                .unwrap()
+                .flush_all(tx2.tx_num())?;
+
+            let fm = simpledb.file_mgr();
+            let mut p = Page::new_from_size(400);
+            fm.lock().unwrap().read(&blk1, &mut p)?;
+            println!(
+                "before crash: {} at location 80 of {}",
+                p.get_i32(80)?,
+                blk1
+            );
+            assert_eq!(0, p.get_i32(80)?);
+            fm.lock().unwrap().read(&blk2, &mut p)?;
+            println!(
+                "before crash: {} at location 80 of {}",
+                p.get_i32(80)?,
+                blk2
+            );
+            assert_eq!(9999, p.get_i32(80)?);
+            // crash: the buffers are lost
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tx/recovery", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        fm.lock().unwrap().read(&blk1, &mut p)?;
+        println!(
+            "after recovery: {} at location 80 of {}",
+            p.get_i32(80)?,
+            blk1
+        );
+        assert_eq!(1, p.get_i32(80)?);
+        assert_eq!("one".to_string(), p.get_string(40)?);
+        fm.lock().unwrap().read(&blk2, &mut p)?;
+        println!(
+            "after recovery: {} at location 80 of {}",
+            p.get_i32(80)?,
+            blk2
+        );
+        assert_eq!(0, p.get_i32(80)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn savepoint_test() -> Result<()> {
+        if Path::new("_test/tx/savepoint").exists() {
+            fs::remove_dir_all("_test/tx/savepoint")?;
+        }
+
+        let blk = BlockId::new("testfile", 1);
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/savepoint", 400, 8);
+
+            let mut tx = simpledb.new_tx()?;
+            tx.pin(&blk)?;
+            tx.set_i32(&blk, 80, 1, true)?;
+            tx.savepoint("a")?;
+            tx.set_i32(&blk, 80, 2, true)?;
+            tx.savepoint("b")?;
+            tx.set_i32(&blk, 80, 3, true)?;
+            tx.rollback_to_savepoint("b")?;
+            println!("rolled back to b: {}", tx.get_i32(&blk, 80)?);
+            assert_eq!(2, tx.get_i32(&blk, 80)?);
+
+            tx.set_i32(&blk, 84, 7, true)?;
+            tx.rollback_to_savepoint("a")?;
+            println!(
+                "rolled back to a: {}, {}",
+                tx.get_i32(&blk, 80)?,
+                tx.get_i32(&blk, 84)?
+            );
+            assert_eq!(1, tx.get_i32(&blk, 80)?);
+            assert_eq!(0, tx.get_i32(&blk, 84)?);
+            // b was set after a, so it is gone
+            assert!(tx.rollback_to_savepoint("b").is_err());
+
+            tx.set_i32(&blk, 80, 4, true)?;
+            tx.rollback_to_savepoint("a")?;
+            assert_eq!(1, tx.get_i32(&blk, 80)?);
+            tx.set_i32(&blk, 88, 5, true)?;
+            tx.commit()?;
+            // crash: the buffers are lost
+        }
+
+        // the updates rolled back to a savepoint are not redone
+        let simpledb = SimpleDB::new_with("_test/tx/savepoint", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        fm.lock().unwrap().read(&blk, &mut p)?;
+        println!(
+            "after recovery: {}, {}, {} at location 80, 84, 88 of {}",
+            p.get_i32(80)?,
+            p.get_i32(84)?,
+            p.get_i32(88)?,
+            blk
+        );
+        assert_eq!(1, p.get_i32(80)?);
+        assert_eq!(0, p.get_i32(84)?);
+        assert_eq!(5, p.get_i32(88)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rename_recovery_test() -> Result<()> {
+        if Path::new("_test/tx/rename_recovery").exists() {
+            fs::remove_dir_all("_test/tx/rename_recovery")?;
+        }
+
+        let blk = BlockId::new("testfile", 1);
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/rename_recovery", 400, 8);
+
+            let mut tx1 = simpledb.new_tx()?;
+            tx1.pin(&blk)?;
+            tx1.set_i32(&blk, 80, 1, true)?;
+            simpledb
+                .buffer_mgr()
+                .lock()
+                .unwrap()
+                .flush_all(tx1.tx_num())?;
+            tx1.commit()?;
+
+            // the rename is logged, but the crash comes before the file is moved
+            let tx2 = simpledb.new_tx()?;
+            tx2.recovery_mgr
+                .as_ref()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .rename_file("testfile", "testfile~0")?;
+            // crash: the buffers are lost
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tx/rename_recovery", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+        assert!(tx.file_exists("testfile"));
+        assert!(!tx.file_exists("testfile~0"));
+
+        // the file renamed by a transaction which did not commit is moved back
+        let mut tx3 = simpledb.new_tx()?;
+        tx3.rename_file("testfile", "testfile~0")?;
+        assert!(!tx3.file_exists("testfile"));
+        drop(tx3);
+        let simpledb = SimpleDB::new_with("_test/tx/rename_recovery", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+        assert!(!tx.file_exists("testfile~0"));
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        fm.lock().unwrap().read(&blk, &mut p)?;
+        println!(
+            "after recovery: {} at location 80 of {}",
+            p.get_i32(80)?,
+            blk
+        );
+        assert_eq!(1, p.get_i32(80)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn savepoint_flushed_test() -> Result<()> {
+        if Path::new("_test/tx/savepoint_flushed").exists() {
+            fs::remove_dir_all("_test/tx/savepoint_flushed")?;
+        }
+
+        let blk1 = BlockId::new("testfile", 1);
+        let blk2 = BlockId::new("testfile", 2);
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/savepoint_flushed", 400, 8);
+
+            // the page is written to the disk with the update, before it is rolled back
+            for (blk, commit) in [(&blk1, true), (&blk2, false)] {
+                let mut tx = simpledb.new_tx()?;
+                tx.pin(blk)?;
+                tx.set_i32(blk, 80, 1, true)?;
+                tx.savepoint("a")?;
+                tx.set_i32(blk, 84, 7, true)?;
+                simpledb
+                    .buffer_mgr()
+                    .lock()
+                    .unwrap()
+                    .flush_all(tx.tx_num())?;
+                tx.rollback_to_savepoint("a")?;
+                assert_eq!(0, tx.get_i32(blk, 84)?);
+                if commit {
+                    tx.commit()?;
+                }
+            }
+            // crash: the buffers are lost
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tx/savepoint_flushed", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        for (blk, expected) in [(&blk1, 1), (&blk2, 0)] {
+            fm.lock().unwrap().read(blk, &mut p)?;
+            println!(
+                "after recovery: {}, {} at location 80, 84 of {}",
+                p.get_i32(80)?,
+                p.get_i32(84)?,
+                blk
+            );
+            assert_eq!(expected, p.get_i32(80)?);
+            assert_eq!(0, p.get_i32(84)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rollback_flushed_test() -> Result<()> {
+        if Path::new("_test/tx/rollback_flushed").exists() {
+            fs::remove_dir_all("_test/tx/rollback_flushed")?;
+        }
+
+        let blk = BlockId::new("testfile", 1);
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/rollback_flushed", 400, 8);
+
+            let mut tx1 = simpledb.new_tx()?;
+            tx1.pin(&blk)?;
+            tx1.savepoint("a")?;
+            tx1.set_i32(&blk, 80, 9999, true)?;
+            simpledb
+                .buffer_mgr()
+                .lock()
+                .unwrap()
+                .flush_all(tx1.tx_num())?;
+            tx1.rollback_to_savepoint("a")?;
+            // another transaction modifies a different record of the block,
+            // as record locks allow, so the buffer is no longer tagged with tx1.
+            let tx2 = simpledb.new_tx()?;
+            let bm = simpledb.buffer_mgr();
+            let buff = bm.lock().unwrap().pin(&blk)?;
+            buff.lock().unwrap().set_modified(tx2.tx_num(), -1);
+            bm.lock().unwrap().unpin(buff)?;
+            tx1.rollback()?;
+            // crash: the buffers are lost
+        }
+
+        // a rolled back transaction is not undone again, so its undo must be on the disk
+        let simpledb = SimpleDB::new_with("_test/tx/rollback_flushed", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        fm.lock().unwrap().read(&blk, &mut p)?;
+        println!(
+            "after recovery: {} at location 80 of {}",
+            p.get_i32(80)?,
+            blk
+        );
+        assert_eq!(0, p.get_i32(80)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoint_test() -> Result<()> {
+        if Path::new("_test/tx/checkpoint").exists() {
+            fs::remove_dir_all("_test/tx/checkpoint")?;
+        }
+
+        let blks: Vec<BlockId> = (0..4).map(|i| BlockId::new("testfile", i)).collect();
+        {
+            let simpledb = SimpleDB::new_with("_test/tx/checkpoint", 400, 8);
+
+            let mut tx0 = simpledb.new_tx()?;
+            tx0.pin(&blks[0])?;
+            tx0.set_i32(&blks[0], 80, 7, true)?;
+            tx0.commit()?;
+
+            // still active at the checkpoint
+            let mut tx1 = simpledb.new_tx()?;
+            tx1.pin(&blks[1])?;
+            tx1.set_i32(&blks[1], 80, 9999, true)?;
+
+            let mut tx2 = simpledb.new_tx()?;
+            tx2.pin(&blks[2])?;
+            tx2.set_i32(&blks[2], 80, 2, true)?;
+            tx2.commit()?;
+
+            simpledb.checkpoint()?;
+
+            let mut tx3 = simpledb.new_tx()?;
+            tx3.pin(&blks[3])?;
+            tx3.set_i32(&blks[3], 80, 3, true)?;
+            tx3.commit()?;
+
+            // recovery must stop at the START of tx1, so tx0 is never looked at again.
+            // wipe its block to check it.
+            let mut p = Page::new_from_size(400);
+            simpledb
+                .file_mgr()
+                .lock()
+                .unwrap()
+                .write(&blks[0], &mut p)?;
+            // crash: the buffers are lost
+        }
+
+        let simpledb = SimpleDB::new_with("_test/tx/checkpoint", 400, 8);
+        let mut tx = simpledb.new_tx()?;
+        tx.recover()?;
+
+        let fm = simpledb.file_mgr();
+        let mut p = Page::new_from_size(400);
+        let mut vals = vec![];
+        for blk in blks.iter() {
+            fm.lock().unwrap().read(blk, &mut p)?;
+            println!(
+                "after recovery: {} at location 80 of {}",
+                p.get_i32(80)?,
+                blk
+            );
+            vals.push(p.get_i32(80)?);
+        }
+        assert_eq!(vec![0, 0, 2, 3], vals);
+
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoint_if_needed_test() -> Result<()> {
+        if Path::new("_test/tx/checkpoint_if_needed").exists() {
+            fs::remove_dir_all("_test/tx/checkpoint_if_needed")?;
+        }
+
+        let mut simpledb = SimpleDB::new_with("_test/tx/checkpoint_if_needed", 400, 8);
+        simpledb.set_checkpoint_log_records(10);
+        let blk = BlockId::new("testfile", 0);
+        let update = |simpledb: &SimpleDB, n: i32| -> Result<()> {
+            let mut tx = simpledb.new_tx()?;
+            tx.pin(&blk)?;
+            for i in 0..n {
+                tx.set_i32(&blk, 4 * i, i, true)?;
+            }
+            tx.commit()
+        };
+
+        // START, 3 updates and COMMIT
+        update(&simpledb, 3)?;
+        assert!(!simpledb.checkpoint_if_needed()?);
+        update(&simpledb, 3)?;
+        assert!(simpledb.checkpoint_if_needed()?);
+        // counted from the checkpoint
+        assert!(!simpledb.checkpoint_if_needed()?);
+        update(&simpledb, 3)?;
+        assert!(!simpledb.checkpoint_if_needed()?);
+
+        Ok(())
+    }
 }